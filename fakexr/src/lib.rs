@@ -1,18 +1,20 @@
 pub mod vulkan;
 
 mod monado_xdev;
-pub use monado_xdev::add_trackers;
+pub use monado_xdev::{add_trackers, add_trackers_with_serials};
 
 use crossbeam_utils::atomic::AtomicCell;
 use glam::{Affine3A, Quat, Vec3};
 use openxr_sys as xr;
 use paste::paste;
 use slotmap::{DefaultKey, Key, KeyData, SlotMap};
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::f32::consts::FRAC_PI_4;
 use std::ffi::{CStr, CString, c_char};
 use std::sync::{
     Arc, LazyLock, Mutex, MutexGuard, OnceLock, RwLock, Weak,
-    atomic::{AtomicBool, AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering},
     mpsc,
 };
 
@@ -49,7 +51,7 @@ pub fn set_action_state_with_time(
         UserPath::RightHand => {
             d.right = Some((state, time));
         }
-        UserPath::LeftHand => {
+        UserPath::LeftHand | UserPath::Eyes => {
             d.left = Some((state, time));
         }
     }
@@ -87,6 +89,9 @@ pub enum UserPath {
     LeftHand,
     /// /user/hand/right
     RightHand,
+    /// /user/eyes_ext
+    /// Actions bound to the eyes are never bound to the hands, so they share the left hand's state.
+    Eyes,
 }
 
 impl UserPath {
@@ -94,6 +99,7 @@ impl UserPath {
         match s {
             "/user/hand/left" => Some(Self::LeftHand),
             "/user/hand/right" => Some(Self::RightHand),
+            "/user/eyes_ext" => Some(Self::Eyes),
             _ => None,
         }
     }
@@ -102,6 +108,7 @@ impl UserPath {
         match self {
             Self::LeftHand => "/user/hand/left",
             Self::RightHand => "/user/hand/right",
+            Self::Eyes => "/user/eyes_ext",
         }
     }
 }
@@ -110,6 +117,7 @@ fn get_hand_data(hand: UserPath, session: &Session) -> &HandData {
     match hand {
         UserPath::RightHand => &session.right_hand,
         UserPath::LeftHand => &session.left_hand,
+        UserPath::Eyes => &session.eyes,
     }
 }
 
@@ -128,6 +136,70 @@ pub fn set_aim(session: xr::Session, path: UserPath, pose: xr::Posef) {
     get_hand_data(path, &session).aim_pose.store(pose);
 }
 
+/// Makes the grip pose move at the given velocity, so that it's at the pose passed to [`set_grip`]
+/// at time 0 and moves linearly from there.
+pub fn set_grip_velocity(session: xr::Session, path: UserPath, velocity: xr::Vector3f) {
+    let session = session.to_handle().unwrap();
+    get_hand_data(path, &session).grip_velocity.store(velocity);
+}
+
+/// Makes the action spaces of a hand (un)locatable, as if it lost tracking.
+pub fn set_tracked(session: xr::Session, path: UserPath, tracked: bool) {
+    let session = session.to_handle().unwrap();
+    get_hand_data(path, &session).tracked.store(tracked);
+}
+
+/// Sets the pose of /user/eyes_ext/input/gaze_ext/pose.
+pub fn set_gaze(session: xr::Session, pose: xr::Posef) {
+    let session = session.to_handle().unwrap();
+    session.eyes.aim_pose.store(pose);
+}
+
+/// Sets the pose of the VIEW reference space, relative to LOCAL.
+pub fn set_view(session: xr::Session, pose: xr::Posef) {
+    session.to_handle().unwrap().view_pose.store(pose);
+}
+
+/// Sets the pose (relative to VIEW) and FOV of each eye, for faking canted or asymmetric displays.
+pub fn set_eye_views(session: xr::Session, views: [(xr::Posef, xr::Fovf); 2]) {
+    session.to_handle().unwrap().eye_views.store(views);
+}
+
+/// Sets the bounds of the STAGE reference space, or makes them unavailable if None.
+pub fn set_stage_bounds(session: xr::Session, bounds: Option<xr::Extent2Df>) {
+    session.to_handle().unwrap().stage_bounds.store(bounds);
+}
+
+/// Transitions the session to the given state, as if the runtime had changed it.
+pub fn set_session_state(session: xr::Session, state: xr::SessionState) {
+    let s = session.to_handle().unwrap();
+    s.state.store(state);
+    send_event(
+        &s.event_sender,
+        xr::EventDataSessionStateChanged {
+            ty: xr::EventDataSessionStateChanged::TYPE,
+            next: std::ptr::null(),
+            session,
+            state,
+            time: xr::Time::from_nanos(0),
+        },
+        None,
+    );
+}
+
+/// Sets the data source hand trackers for this hand will report.
+/// If None, hand joints will not be active.
+pub fn set_hand_tracking_source(
+    session: xr::Session,
+    path: UserPath,
+    source: Option<xr::HandTrackingDataSourceEXT>,
+) {
+    let session = session.to_handle().unwrap();
+    get_hand_data(path, &session)
+        .hand_tracking_source
+        .store(source);
+}
+
 #[track_caller]
 pub fn check_no_suggested_bindings(action: xr::Action, profile: xr::Path) -> bool {
     let action = xr::Action::to_handle(action).unwrap();
@@ -161,6 +233,46 @@ pub fn session_frame_state(session: xr::Session) -> FrameState {
     session.frame_state.load()
 }
 
+/// The structure types of the layers submitted in the last xrEndFrame call.
+pub fn submitted_layers(session: xr::Session) -> Vec<xr::StructureType> {
+    let session = session.to_handle().unwrap();
+    session.submitted_layers.lock().unwrap().clone()
+}
+
+/// The flags of the layers submitted in the last xrEndFrame call, in the same order as
+/// [`submitted_layers`].
+pub fn submitted_layer_flags(session: xr::Session) -> Vec<xr::CompositionLayerFlags> {
+    let session = session.to_handle().unwrap();
+    session.submitted_layer_flags.lock().unwrap().clone()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SubmittedDepth {
+    pub min_depth: f32,
+    pub max_depth: f32,
+    pub near_z: f32,
+    pub far_z: f32,
+}
+
+/// The depth info chained to each view of the projection layer submitted in the last xrEndFrame
+/// call, if any.
+pub fn submitted_depth(session: xr::Session) -> Vec<Option<SubmittedDepth>> {
+    let session = session.to_handle().unwrap();
+    session.submitted_depth.lock().unwrap().clone()
+}
+
+/// The number of times xrLocateSpace has been called with spaces from this session.
+pub fn locate_space_count(session: xr::Session) -> usize {
+    let session = session.to_handle().unwrap();
+    session.locate_count.load(Ordering::Relaxed)
+}
+
+/// The number of times xrSyncActions has been called on this session.
+pub fn sync_actions_count(session: xr::Session) -> usize {
+    let session = session.to_handle().unwrap();
+    session.sync_count.load(Ordering::Relaxed)
+}
+
 macro_rules! fn_unimplemented_impl {
     ($($param:ident),+) => {
         fn_unimplemented_impl!($($param),+  -> []);
@@ -303,7 +415,7 @@ pub unsafe extern "system" fn get_instance_proc_addr(
                     (EnumerateViewConfigurations),
                     (EnumerateEnvironmentBlendModes),
                     (GetViewConfigurationProperties),
-                    (EnumerateViewConfigurationViews),
+                    EnumerateViewConfigurationViews,
                     BeginFrame,
                     EndFrame,
                     WaitFrame,
@@ -312,7 +424,7 @@ pub unsafe extern "system" fn get_instance_proc_addr(
                     (PollEvent),
                     StringToPath,
                     PathToString,
-                    (GetReferenceSpaceBoundsRect),
+                    GetReferenceSpaceBoundsRect,
                     GetActionStateBoolean,
                     GetActionStateFloat,
                     GetActionStateVector2f,
@@ -327,6 +439,27 @@ pub unsafe extern "system" fn get_instance_proc_addr(
                     SyncActions,
                     (EnumerateBoundSourcesForAction),
                     (GetInputSourceLocalizedName),
+                    CreateHandTrackerEXT,
+                    DestroyHandTrackerEXT,
+                    LocateHandJointsEXT,
+                    EnumerateDisplayRefreshRatesFB,
+                    GetDisplayRefreshRateFB,
+                    RequestDisplayRefreshRateFB,
+                    GetVisibilityMaskKHR,
+                    CreatePassthroughFB,
+                    DestroyPassthroughFB,
+                    (PassthroughStartFB),
+                    (PassthroughPauseFB),
+                    CreatePassthroughLayerFB,
+                    DestroyPassthroughLayerFB,
+                    (PassthroughLayerPauseFB),
+                    (PassthroughLayerResumeFB),
+                    (PassthroughLayerSetStyleFB),
+                    (CreateGeometryInstanceFB),
+                    (DestroyGeometryInstanceFB),
+                    (GeometryInstanceSetTransformFB),
+                    EnumerateViveTrackerPathsHTCX,
+                    ConvertTimespecTimeToTimeKHR,
                     {mndx::CreateXDevListMNDX},
                     {mndx::GetXDevListGenerationNumberMNDX},
                     {mndx::EnumerateXDevsMNDX},
@@ -347,6 +480,42 @@ pub unsafe extern "system" fn get_instance_proc_addr(
     xr::Result::SUCCESS
 }
 
+/// Every extension fakexr implements.
+static EXTENSIONS: &[&CStr] = &[
+    c"XR_KHR_vulkan_enable",
+    c"XR_MNDX_xdev_space",
+    c"XR_HTC_vive_focus3_controller_interaction",
+    c"XR_FB_touch_controller_pro",
+    c"XR_BD_controller_interaction",
+    c"XR_EXT_hand_tracking",
+    c"XR_EXT_hand_tracking_data_source",
+    c"XR_FB_display_refresh_rate",
+    c"XR_EXT_hp_mixed_reality_controller",
+    c"XR_FB_passthrough",
+    c"XR_EXT_eye_gaze_interaction",
+    c"XR_KHR_visibility_mask",
+    c"XR_KHR_composition_layer_depth",
+    c"XR_KHR_composition_layer_color_scale_bias",
+    c"XR_HTCX_vive_tracker_interaction",
+    c"XR_KHR_convert_timespec_time",
+];
+
+thread_local! {
+    static DISABLED_EXTENSIONS: RefCell<Vec<&'static CStr>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Stops advertising the given extensions on this thread, so tests can check how a runtime
+/// without them is handled. Tests each run on their own thread, so this only affects the caller.
+pub fn disable_extensions(extensions: &[&'static CStr]) {
+    for extension in extensions {
+        assert!(
+            EXTENSIONS.contains(extension),
+            "{extension:?} isn't a fakexr extension"
+        );
+    }
+    DISABLED_EXTENSIONS.with_borrow_mut(|disabled| disabled.extend_from_slice(extensions));
+}
+
 extern "system" fn enumerate_instance_extension_properties(
     layer_name: *const c_char,
     property_capacity_input: u32,
@@ -354,43 +523,33 @@ extern "system" fn enumerate_instance_extension_properties(
     properties: *mut xr::ExtensionProperties,
 ) -> xr::Result {
     assert!(layer_name.is_null());
-    unsafe { *property_count_output = 3 };
-    if property_capacity_input >= 3 {
-        let props =
-            unsafe { std::slice::from_raw_parts_mut(properties, property_capacity_input as usize) };
+    let extensions: Vec<&CStr> = DISABLED_EXTENSIONS.with_borrow(|disabled| {
+        EXTENSIONS
+            .iter()
+            .copied()
+            .filter(|extension| !disabled.contains(extension))
+            .collect()
+    });
+    unsafe { *property_count_output = extensions.len() as u32 };
+    if property_capacity_input == 0 {
+        return xr::Result::SUCCESS;
+    }
+    if (property_capacity_input as usize) < extensions.len() {
+        return xr::Result::ERROR_SIZE_INSUFFICIENT;
+    }
 
-        props[0] = xr::ExtensionProperties {
-            ty: xr::ExtensionProperties::TYPE,
-            next: std::ptr::null_mut(),
-            extension_name: [0 as c_char; xr::MAX_EXTENSION_NAME_SIZE],
-            extension_version: 1,
-        };
-        let name = xr::KHR_VULKAN_ENABLE_EXTENSION_NAME;
+    let props = unsafe { std::slice::from_raw_parts_mut(properties, extensions.len()) };
+    for (prop, extension) in props.iter_mut().zip(extensions) {
+        let name = extension.to_bytes_with_nul();
         let name =
             unsafe { std::slice::from_raw_parts(name.as_ptr() as *const c_char, name.len()) };
-        props[0].extension_name[..name.len()].copy_from_slice(name);
-
-        props[1] = xr::ExtensionProperties {
+        *prop = xr::ExtensionProperties {
             ty: xr::ExtensionProperties::TYPE,
             next: std::ptr::null_mut(),
             extension_name: [0 as c_char; xr::MAX_EXTENSION_NAME_SIZE],
             extension_version: 1,
         };
-        let name = openxr_mndx_xdev_space::XR_MNDX_XDEV_SPACE_EXTENSION_NAME;
-        let name =
-            unsafe { std::slice::from_raw_parts(name.as_ptr() as *const c_char, name.len()) };
-        props[1].extension_name[..name.len()].copy_from_slice(name);
-
-        props[2] = xr::ExtensionProperties {
-            ty: xr::ExtensionProperties::TYPE,
-            next: std::ptr::null_mut(),
-            extension_name: [0 as c_char; xr::MAX_EXTENSION_NAME_SIZE],
-            extension_version: 1,
-        };
-        let name = xr::HTC_VIVE_FOCUS3_CONTROLLER_INTERACTION_EXTENSION_NAME;
-        let name =
-            unsafe { std::slice::from_raw_parts(name.as_ptr() as *const c_char, name.len()) };
-        props[2].extension_name[..name.len()].copy_from_slice(name);
+        prop.extension_name[..name.len()].copy_from_slice(name);
     }
     xr::Result::SUCCESS
 }
@@ -465,9 +624,28 @@ struct Instance {
     action_sets: Mutex<HashSet<xr::ActionSet>>,
     left_hand_key: DefaultKey,
     right_hand_key: DefaultKey,
+    /// Persistent and role paths of the trackers from XR_HTCX_vive_tracker_interaction.
+    vive_trackers: Mutex<Vec<(String, String)>>,
+    /// The time in nanoseconds reported by xrConvertTimespecTimeToTimeKHR, regardless of the
+    /// timespec passed in, so that tests control what "now" is.
+    now: AtomicI64,
 }
 
 impl Instance {
+    fn string_to_path(&self, s: &str) -> xr::Path {
+        let mut string_to_path = self.string_to_path.lock().unwrap();
+        let key = match string_to_path.get(s) {
+            Some(p) => *p,
+            None => {
+                let mut paths = self.paths.lock().unwrap();
+                let key = paths.insert(s.to_string());
+                string_to_path.insert(s.to_string(), key);
+                key
+            }
+        };
+        xr::Path::from_raw(key.data().as_ffi())
+    }
+
     fn get_path_value(&self, path: xr::Path) -> Result<Option<String>, ()> {
         if path == xr::Path::NULL {
             Ok(None)
@@ -494,7 +672,12 @@ struct HandData {
     pending_profile: AtomicCell<Option<xr::Path>>,
     profile: AtomicCell<xr::Path>,
     grip_pose: AtomicCell<xr::Posef>,
+    /// Linear velocity of the grip pose, in meters per second.
+    grip_velocity: AtomicCell<xr::Vector3f>,
     aim_pose: AtomicCell<xr::Posef>,
+    /// Whether the hand's action spaces can be located.
+    tracked: AtomicCell<bool>,
+    hand_tracking_source: AtomicCell<Option<xr::HandTrackingDataSourceEXT>>,
 }
 
 impl Default for HandData {
@@ -503,7 +686,10 @@ impl Default for HandData {
             pending_profile: Default::default(),
             profile: Default::default(),
             grip_pose: xr::Posef::IDENTITY.into(),
+            grip_velocity: Default::default(),
             aim_pose: xr::Posef::IDENTITY.into(),
+            tracked: true.into(),
+            hand_tracking_source: Default::default(),
         }
     }
 }
@@ -522,12 +708,25 @@ struct Session {
     attached_sets: OnceLock<Box<[xr::ActionSet]>>,
     left_hand: HandData,
     right_hand: HandData,
+    eyes: HandData,
     spaces: Mutex<HashSet<DefaultKey>>,
     state: AtomicCell<xr::SessionState>,
     state_synced: AtomicBool,
     should_render: AtomicBool,
     frame_state: AtomicCell<FrameState>,
-    with_trackers: AtomicBool,
+    /// The name and serial of each fake xdev tracker.
+    trackers: Mutex<Vec<(CString, CString)>>,
+    xdev_generation: AtomicU64,
+    refresh_rate: AtomicCell<f32>,
+    locate_count: AtomicUsize,
+    sync_count: AtomicUsize,
+    view_pose: AtomicCell<xr::Posef>,
+    /// Each eye's pose relative to the VIEW space, and its FOV.
+    eye_views: AtomicCell<[(xr::Posef, xr::Fovf); 2]>,
+    stage_bounds: AtomicCell<Option<xr::Extent2Df>>,
+    submitted_layers: Mutex<Vec<xr::StructureType>>,
+    submitted_layer_flags: Mutex<Vec<xr::CompositionLayerFlags>>,
+    submitted_depth: Mutex<Vec<Option<SubmittedDepth>>>,
 }
 
 impl Session {
@@ -641,7 +840,7 @@ struct Space {
 }
 
 impl Space {
-    fn get_pose_relative_to_local(&self) -> Result<xr::SpaceLocation, xr::Result> {
+    fn get_pose_relative_to_local(&self, time: xr::Time) -> Result<xr::SpaceLocation, xr::Result> {
         let default = || xr::SpaceLocation {
             ty: xr::SpaceLocation::TYPE,
             next: std::ptr::null_mut(),
@@ -654,7 +853,10 @@ impl Space {
             .ok_or(xr::Result::ERROR_SESSION_LOST)?;
 
         let SpaceType::Action { hand, action } = &self.ty else {
-            let pose = xr::Posef::IDENTITY;
+            let pose = match self.ty {
+                SpaceType::Reference(xr::ReferenceSpaceType::VIEW) => session.view_pose.load(),
+                _ => xr::Posef::IDENTITY,
+            };
             let mat = pose_to_mat(pose);
             let offset = pose_to_mat(self.offset);
 
@@ -670,11 +872,11 @@ impl Space {
 
         // Check if this hand has an interaction profile
         let hand = hand.unwrap_or(UserPath::LeftHand);
-        let hand_data = match hand {
-            UserPath::LeftHand => &session.left_hand,
-            UserPath::RightHand => &session.right_hand,
-        };
+        let hand_data = get_hand_data(hand, &session);
         let hand_path = hand.as_path();
+        if !hand_data.tracked.load() {
+            return Ok(default());
+        }
         let profile = match hand_data.profile.load() {
             xr::Path::NULL => {
                 // no profile - no data
@@ -692,7 +894,7 @@ impl Space {
 
         // Check if this action has been synced
         let state = match hand {
-            UserPath::LeftHand => &action.state.left,
+            UserPath::LeftHand | UserPath::Eyes => &action.state.left,
             UserPath::RightHand => &action.state.right,
         };
 
@@ -719,8 +921,16 @@ impl Space {
             .unwrap_or_else(|| panic!("expected binding for space for action {:?}", action.name));
 
         let pose = match binding.strip_prefix(hand.as_path()).unwrap() {
-            "/input/grip/pose" => hand_data.grip_pose.load(),
-            "/input/aim/pose" => hand_data.aim_pose.load(),
+            "/input/grip/pose" => {
+                let mut pose = hand_data.grip_pose.load();
+                let velocity = hand_data.grip_velocity.load();
+                let seconds = time.as_nanos() as f32 / 1e9;
+                pose.position.x += velocity.x * seconds;
+                pose.position.y += velocity.y * seconds;
+                pose.position.z += velocity.z * seconds;
+                pose
+            }
+            "/input/aim/pose" | "/input/gaze_ext/pose" => hand_data.aim_pose.load(),
             other => panic!(
                 "unrecognized pose binding {other} for action {:?}",
                 action.name
@@ -772,7 +982,7 @@ impl Action {
     fn get_hand_state(&self, path: xr::Path) -> ActionStateData {
         let instance = self.instance.upgrade().expect("Failed to get instance");
         match instance.get_user_path(path).unwrap() {
-            None | Some(UserPath::LeftHand) => self.state.left.load(),
+            None | Some(UserPath::LeftHand) | Some(UserPath::Eyes) => self.state.left.load(),
             Some(UserPath::RightHand) => self.state.right.load(),
         }
     }
@@ -795,12 +1005,30 @@ struct Swapchain {
     image_acquired: AtomicBool,
 }
 
+struct HandTracker {
+    session: Weak<Session>,
+    hand: UserPath,
+    /// Data sources requested through XR_EXT_hand_tracking_data_source.
+    requested_sources: Option<Vec<xr::HandTrackingDataSourceEXT>>,
+}
+
+struct Passthrough {
+    session: Weak<Session>,
+}
+
+struct PassthroughLayer {
+    _passthrough: Arc<Passthrough>,
+}
+
 impl_handle!(Instance, xr::Instance);
 impl_handle!(Session, xr::Session);
 impl_handle!(ActionSet, xr::ActionSet);
 impl_handle!(Action, xr::Action);
 impl_handle!(Space, xr::Space);
 impl_handle!(Swapchain, xr::Swapchain);
+impl_handle!(HandTracker, xr::HandTrackerEXT);
+impl_handle!(Passthrough, xr::PassthroughFB);
+impl_handle!(PassthroughLayer, xr::PassthroughLayerFB);
 
 fn destroy_handle<T: XrType>(xr: T) -> xr::Result {
     T::Handle::instances().remove(DefaultKey::from(KeyData::from_ffi(T::TO_RAW(xr))));
@@ -835,6 +1063,8 @@ extern "system" fn create_instance(
         action_sets: Default::default(),
         left_hand_key,
         right_hand_key,
+        vive_trackers: Default::default(),
+        now: 1.into(),
     });
     unsafe {
         *instance = inst.to_xr();
@@ -866,12 +1096,24 @@ extern "system" fn create_session(
         attached_sets: OnceLock::new(),
         left_hand: Default::default(),
         right_hand: Default::default(),
+        eyes: Default::default(),
         spaces: Default::default(),
         state: xr::SessionState::READY.into(),
         state_synced: true.into(),
         should_render: false.into(),
         frame_state: FrameState::Ended.into(),
-        with_trackers: false.into(),
+        trackers: Mutex::default(),
+        // monado starts (and currently stays) at 1
+        xdev_generation: AtomicU64::new(1),
+        refresh_rate: DEFAULT_REFRESH_RATE.into(),
+        locate_count: AtomicUsize::new(0),
+        sync_count: AtomicUsize::new(0),
+        view_pose: AtomicCell::new(xr::Posef::IDENTITY),
+        eye_views: AtomicCell::new([(xr::Posef::IDENTITY, DEFAULT_FOV); 2]),
+        stage_bounds: AtomicCell::new(None),
+        submitted_layers: Mutex::default(),
+        submitted_layer_flags: Mutex::default(),
+        submitted_depth: Mutex::default(),
     });
 
     let tx = sess.event_sender.clone();
@@ -1083,6 +1325,79 @@ extern "system" fn get_system(
     xr::Result::SUCCESS
 }
 
+/// The recommended and maximum per-eye image sizes.
+pub const RECOMMENDED_VIEW_SIZE: (u32, u32) = (1024, 1024);
+pub const MAX_VIEW_SIZE: (u32, u32) = (2048, 2048);
+
+extern "system" fn enumerate_view_configuration_views(
+    _: xr::Instance,
+    _: xr::SystemId,
+    _: xr::ViewConfigurationType,
+    capacity: u32,
+    output: *mut u32,
+    views: *mut xr::ViewConfigurationView,
+) -> xr::Result {
+    unsafe {
+        output.write(2);
+    }
+    if capacity >= 2 {
+        let views = unsafe { std::slice::from_raw_parts_mut(views, 2) };
+        for view in views {
+            view.recommended_image_rect_width = RECOMMENDED_VIEW_SIZE.0;
+            view.recommended_image_rect_height = RECOMMENDED_VIEW_SIZE.1;
+            view.max_image_rect_width = MAX_VIEW_SIZE.0;
+            view.max_image_rect_height = MAX_VIEW_SIZE.1;
+            view.recommended_swapchain_sample_count = 1;
+            view.max_swapchain_sample_count = 1;
+        }
+    } else if capacity != 0 {
+        return xr::Result::ERROR_SIZE_INSUFFICIENT;
+    }
+
+    xr::Result::SUCCESS
+}
+
+/// A square mask in the corners of the view, as a triangle mesh or a line loop.
+extern "system" fn get_visibility_mask_k_h_r(
+    session: xr::Session,
+    _: xr::ViewConfigurationType,
+    _: u32,
+    ty: xr::VisibilityMaskTypeKHR,
+    mask: *mut xr::VisibilityMaskKHR,
+) -> xr::Result {
+    get_handle!(session);
+    const VERTICES: [xr::Vector2f; 4] = [
+        xr::Vector2f { x: -1.0, y: -1.0 },
+        xr::Vector2f { x: 1.0, y: -1.0 },
+        xr::Vector2f { x: 1.0, y: 1.0 },
+        xr::Vector2f { x: -1.0, y: 1.0 },
+    ];
+    let indices: &[u32] = match ty {
+        xr::VisibilityMaskTypeKHR::LINE_LOOP => &[0, 1, 2, 3],
+        _ => &[0, 1, 2, 0, 2, 3],
+    };
+
+    let mask = unsafe { mask.as_mut() }.unwrap();
+    mask.vertex_count_output = VERTICES.len() as u32;
+    mask.index_count_output = indices.len() as u32;
+    if mask.vertex_capacity_input == 0 && mask.index_capacity_input == 0 {
+        return xr::Result::SUCCESS;
+    }
+    if (mask.vertex_capacity_input as usize) < VERTICES.len()
+        || (mask.index_capacity_input as usize) < indices.len()
+    {
+        return xr::Result::ERROR_SIZE_INSUFFICIENT;
+    }
+
+    unsafe {
+        mask.vertices
+            .copy_from_nonoverlapping(VERTICES.as_ptr(), VERTICES.len());
+        mask.indices
+            .copy_from_nonoverlapping(indices.as_ptr(), indices.len());
+    }
+    xr::Result::SUCCESS
+}
+
 fn send_event<T: Copy>(
     tx: &mpsc::Sender<EventDataBuffer>,
     event: T,
@@ -1157,18 +1472,8 @@ extern "system" fn string_to_path(
 ) -> xr::Result {
     let instance = get_handle!(instance);
     let s = unsafe { CStr::from_ptr(string) }.to_str().unwrap();
-    let mut string_to_path = instance.string_to_path.lock().unwrap();
-    let key = match string_to_path.get(s) {
-        Some(p) => *p,
-        None => {
-            let mut paths = instance.paths.lock().unwrap();
-            let key = paths.insert(s.to_string());
-            string_to_path.insert(s.to_string(), key);
-            key
-        }
-    };
 
-    unsafe { path.write(xr::Path::from_raw(key.data().as_ffi())) };
+    unsafe { path.write(instance.string_to_path(s)) };
 
     xr::Result::SUCCESS
 }
@@ -1281,7 +1586,8 @@ extern "system" fn sync_actions(
     info: *const xr::ActionsSyncInfo,
 ) -> xr::Result {
     let session = get_handle!(session_xr);
-    for hand in [&session.left_hand, &session.right_hand] {
+    session.sync_count.fetch_add(1, Ordering::Relaxed);
+    for hand in [&session.left_hand, &session.right_hand, &session.eyes] {
         if let Some(profile) = hand.pending_profile.load() {
             hand.profile.store(profile);
             send_event(
@@ -1498,6 +1804,7 @@ extern "system" fn get_current_interaction_profile(
     let profile = match val.as_deref() {
         Some("/user/hand/left") => session.left_hand.profile.load(),
         Some("/user/hand/right") => session.right_hand.profile.load(),
+        Some("/user/eyes_ext") => session.eyes.profile.load(),
         _ => xr::Path::NULL,
     };
 
@@ -1512,19 +1819,82 @@ extern "system" fn get_current_interaction_profile(
     xr::Result::SUCCESS
 }
 
+/// Sets the current time, as returned by xrConvertTimespecTimeToTimeKHR.
+pub fn set_now(instance: xr::Instance, time: xr::Time) {
+    let instance = instance.to_handle().unwrap();
+    instance.now.store(time.as_nanos(), Ordering::Relaxed);
+}
+
+extern "system" fn convert_timespec_time_to_time_khr(
+    instance: xr::Instance,
+    _timespec_time: *const xr::timespec,
+    time: *mut xr::Time,
+) -> xr::Result {
+    let instance = get_handle!(instance);
+    unsafe { time.write(xr::Time::from_nanos(instance.now.load(Ordering::Relaxed))) };
+    xr::Result::SUCCESS
+}
+
+/// Replaces the trackers reported by xrEnumerateViveTrackerPathsHTCX with ones with the given
+/// persistent and role paths, sending a connected event for each.
+pub fn set_vive_trackers(instance: xr::Instance, trackers: &[(&str, &str)]) {
+    let instance = instance.to_handle().unwrap();
+    *instance.vive_trackers.lock().unwrap() = trackers
+        .iter()
+        .map(|&(persistent, role)| (persistent.to_string(), role.to_string()))
+        .collect();
+    for _ in trackers {
+        send_event(
+            &instance.event_sender,
+            xr::EventDataViveTrackerConnectedHTCX {
+                ty: xr::EventDataViveTrackerConnectedHTCX::TYPE,
+                next: std::ptr::null(),
+                // Nothing reads the paths from the event, they're enumerated instead.
+                paths: std::ptr::null_mut(),
+            },
+            None,
+        );
+    }
+}
+
+extern "system" fn enumerate_vive_tracker_paths_h_t_c_x(
+    instance: xr::Instance,
+    capacity: u32,
+    output: *mut u32,
+    paths: *mut xr::ViveTrackerPathsHTCX,
+) -> xr::Result {
+    let instance = get_handle!(instance);
+    let trackers = instance.vive_trackers.lock().unwrap();
+    unsafe { output.write(trackers.len() as u32) };
+    if capacity == 0 {
+        return xr::Result::SUCCESS;
+    }
+    if (capacity as usize) < trackers.len() {
+        return xr::Result::ERROR_SIZE_INSUFFICIENT;
+    }
+
+    let paths = unsafe { std::slice::from_raw_parts_mut(paths, trackers.len()) };
+    for ((persistent, role), out) in trackers.iter().zip(paths) {
+        out.persistent_path = instance.string_to_path(persistent);
+        out.role_path = instance.string_to_path(role);
+    }
+
+    xr::Result::SUCCESS
+}
+
 extern "system" fn locate_space(
     space: xr::Space,
     base_space: xr::Space,
-    _time: xr::Time,
+    time: xr::Time,
     location: *mut xr::SpaceLocation,
 ) -> xr::Result {
     let base_space = get_handle!(base_space);
     assert!(
         !matches!(
             base_space.ty,
-            SpaceType::Reference(xr::ReferenceSpaceType::STAGE | xr::ReferenceSpaceType::VIEW),
+            SpaceType::Reference(xr::ReferenceSpaceType::VIEW),
         ),
-        "stage/view locate unimplemented"
+        "view locate unimplemented"
     );
 
     let space = get_handle!(space);
@@ -1532,6 +1902,9 @@ extern "system" fn locate_space(
         space.ty,
         SpaceType::Reference(xr::ReferenceSpaceType::LOCAL)
     ));
+    if let Some(session) = space.session.upgrade() {
+        session.locate_count.fetch_add(1, Ordering::Relaxed);
+    }
     let next = unsafe { (*location).next };
     let mut out_loc = xr::SpaceLocation {
         ty: xr::SpaceLocation::TYPE,
@@ -1560,19 +1933,22 @@ extern "system" fn locate_space(
         base_space.ty,
         SpaceType::Reference(xr::ReferenceSpaceType::LOCAL)
     ) {
-        match space.get_pose_relative_to_local() {
+        match space.get_pose_relative_to_local(time) {
             Ok(loc) => {
                 out_loc = loc;
+                // LOCAL spaces can have an offset too (i.e., after recentering)
+                let base_offset = pose_to_mat(base_space.offset);
+                out_loc.pose = mat_to_pose(base_offset.inverse() * pose_to_mat(loc.pose));
             }
             Err(e) => return e,
         };
     } else {
-        let base_loc = match base_space.get_pose_relative_to_local() {
+        let base_loc = match base_space.get_pose_relative_to_local(time) {
             Ok(loc) => loc,
             Err(e) => return e,
         };
 
-        let target_loc = match space.get_pose_relative_to_local() {
+        let target_loc = match space.get_pose_relative_to_local(time) {
             Ok(loc) => loc,
             Err(e) => return e,
         };
@@ -1593,6 +1969,235 @@ extern "system" fn locate_space(
 
     xr::Result::SUCCESS
 }
+extern "system" fn create_hand_tracker_e_x_t(
+    session: xr::Session,
+    info: *const xr::HandTrackerCreateInfoEXT,
+    hand_tracker: *mut xr::HandTrackerEXT,
+) -> xr::Result {
+    let session = get_handle!(session);
+    let info = unsafe { info.as_ref() }.unwrap();
+    let hand = match info.hand {
+        xr::HandEXT::LEFT => UserPath::LeftHand,
+        xr::HandEXT::RIGHT => UserPath::RightHand,
+        _ => return xr::Result::ERROR_VALIDATION_FAILURE,
+    };
+
+    let mut requested_sources = None;
+    let mut next = info.next as *const xr::BaseInStructure;
+    while let Some(header) = unsafe { next.as_ref() } {
+        if header.ty == xr::HandTrackingDataSourceInfoEXT::TYPE {
+            let source_info = unsafe { &*(next as *const xr::HandTrackingDataSourceInfoEXT) };
+            if source_info.requested_data_source_count == 0 {
+                return xr::Result::ERROR_VALIDATION_FAILURE;
+            }
+            requested_sources = Some(
+                unsafe {
+                    std::slice::from_raw_parts(
+                        source_info.requested_data_sources,
+                        source_info.requested_data_source_count as usize,
+                    )
+                }
+                .to_vec(),
+            );
+        }
+        next = header.next;
+    }
+
+    let tracker = Arc::new(HandTracker {
+        session: Arc::downgrade(&session),
+        hand,
+        requested_sources,
+    });
+    unsafe {
+        hand_tracker.write(tracker.to_xr());
+    }
+    xr::Result::SUCCESS
+}
+
+extern "system" fn destroy_hand_tracker_e_x_t(hand_tracker: xr::HandTrackerEXT) -> xr::Result {
+    destroy_handle(hand_tracker)
+}
+
+extern "system" fn locate_hand_joints_e_x_t(
+    hand_tracker: xr::HandTrackerEXT,
+    _info: *const xr::HandJointsLocateInfoEXT,
+    locations: *mut xr::HandJointLocationsEXT,
+) -> xr::Result {
+    let tracker = get_handle!(hand_tracker);
+    let Some(session) = tracker.session.upgrade() else {
+        return xr::Result::ERROR_SESSION_LOST;
+    };
+    let locations = unsafe { locations.as_mut() }.unwrap();
+    if locations.joint_count != xr::HAND_JOINT_COUNT_EXT as u32 {
+        return xr::Result::ERROR_VALIDATION_FAILURE;
+    }
+
+    // Sources the tracker didn't ask for are never reported.
+    let source = get_hand_data(tracker.hand, &session)
+        .hand_tracking_source
+        .load()
+        .filter(|source| {
+            tracker
+                .requested_sources
+                .as_ref()
+                .is_none_or(|requested| requested.contains(source))
+        });
+
+    locations.is_active = source.is_some().into();
+    let joints = unsafe {
+        std::slice::from_raw_parts_mut(locations.joint_locations, locations.joint_count as usize)
+    };
+    for (idx, joint) in joints.iter_mut().enumerate() {
+        *joint = xr::HandJointLocationEXT {
+            location_flags: if source.is_some() {
+                *LOCATION_FLAGS_TRACKED
+            } else {
+                xr::SpaceLocationFlags::EMPTY
+            },
+            pose: xr::Posef {
+                position: xr::Vector3f {
+                    x: 0.0,
+                    y: 0.0,
+                    z: -0.01 * idx as f32,
+                },
+                ..xr::Posef::IDENTITY
+            },
+            radius: 0.01,
+        };
+    }
+
+    let mut next = locations.next as *mut xr::BaseOutStructure;
+    while let Some(header) = unsafe { next.as_mut() } {
+        if header.ty == xr::HandTrackingDataSourceStateEXT::TYPE {
+            let state = unsafe { &mut *(next as *mut xr::HandTrackingDataSourceStateEXT) };
+            state.is_active = source.is_some().into();
+            if let Some(source) = source {
+                state.data_source = source;
+            }
+        }
+        next = header.next;
+    }
+
+    xr::Result::SUCCESS
+}
+
+/// Refresh rates reported through XR_FB_display_refresh_rate.
+pub const REFRESH_RATES: [f32; 3] = [72.0, 90.0, 120.0];
+const DEFAULT_REFRESH_RATE: f32 = 90.0;
+
+extern "system" fn enumerate_display_refresh_rates_f_b(
+    session: xr::Session,
+    capacity: u32,
+    output: *mut u32,
+    rates: *mut f32,
+) -> xr::Result {
+    get_handle!(session);
+    unsafe {
+        output.write(REFRESH_RATES.len() as u32);
+    }
+    if capacity as usize >= REFRESH_RATES.len() {
+        let rates = unsafe { std::slice::from_raw_parts_mut(rates, REFRESH_RATES.len()) };
+        rates.copy_from_slice(&REFRESH_RATES);
+    } else if capacity != 0 {
+        return xr::Result::ERROR_SIZE_INSUFFICIENT;
+    }
+
+    xr::Result::SUCCESS
+}
+
+extern "system" fn get_reference_space_bounds_rect(
+    session: xr::Session,
+    ty: xr::ReferenceSpaceType,
+    bounds: *mut xr::Extent2Df,
+) -> xr::Result {
+    let session = get_handle!(session);
+    let stage_bounds = session.stage_bounds.load();
+    let (value, result) = match stage_bounds {
+        Some(b) if ty == xr::ReferenceSpaceType::STAGE => (b, xr::Result::SUCCESS),
+        _ => (
+            xr::Extent2Df::default(),
+            xr::Result::SPACE_BOUNDS_UNAVAILABLE,
+        ),
+    };
+    unsafe {
+        bounds.write(value);
+    }
+    result
+}
+
+extern "system" fn get_display_refresh_rate_f_b(
+    session: xr::Session,
+    rate: *mut f32,
+) -> xr::Result {
+    let session = get_handle!(session);
+    unsafe {
+        rate.write(session.refresh_rate.load());
+    }
+    xr::Result::SUCCESS
+}
+
+extern "system" fn request_display_refresh_rate_f_b(session: xr::Session, rate: f32) -> xr::Result {
+    let session = get_handle!(session);
+    // 0 means the runtime should pick
+    let rate = if rate == 0.0 {
+        DEFAULT_REFRESH_RATE
+    } else {
+        rate
+    };
+    if !REFRESH_RATES.contains(&rate) {
+        return xr::Result::ERROR_DISPLAY_REFRESH_RATE_UNSUPPORTED_FB;
+    }
+    session.refresh_rate.store(rate);
+    xr::Result::SUCCESS
+}
+
+extern "system" fn create_passthrough_f_b(
+    session: xr::Session,
+    _info: *const xr::PassthroughCreateInfoFB,
+    passthrough: *mut xr::PassthroughFB,
+) -> xr::Result {
+    let session = get_handle!(session);
+    let pt = Arc::new(Passthrough {
+        session: Arc::downgrade(&session),
+    });
+    unsafe {
+        *passthrough = pt.to_xr();
+    }
+    xr::Result::SUCCESS
+}
+
+extern "system" fn destroy_passthrough_f_b(passthrough: xr::PassthroughFB) -> xr::Result {
+    destroy_handle(passthrough)
+}
+
+extern "system" fn create_passthrough_layer_f_b(
+    session: xr::Session,
+    info: *const xr::PassthroughLayerCreateInfoFB,
+    layer: *mut xr::PassthroughLayerFB,
+) -> xr::Result {
+    let session = get_handle!(session);
+    let info = unsafe { info.as_ref() }.unwrap();
+    let passthrough = get_handle!(info.passthrough);
+    if !passthrough
+        .session
+        .upgrade()
+        .is_some_and(|s| Arc::ptr_eq(&s, &session))
+    {
+        return xr::Result::ERROR_HANDLE_INVALID;
+    }
+    let l = Arc::new(PassthroughLayer {
+        _passthrough: passthrough,
+    });
+    unsafe {
+        *layer = l.to_xr();
+    }
+    xr::Result::SUCCESS
+}
+
+extern "system" fn destroy_passthrough_layer_f_b(layer: xr::PassthroughLayerFB) -> xr::Result {
+    destroy_handle(layer)
+}
+
 extern "system" fn create_swapchain(
     _session: xr::Session,
     info: *const xr::SwapchainCreateInfo,
@@ -1712,26 +2317,68 @@ extern "system" fn begin_frame(
     xr::Result::SUCCESS
 }
 
-extern "system" fn end_frame(session: xr::Session, _info: *const xr::FrameEndInfo) -> xr::Result {
+extern "system" fn end_frame(session: xr::Session, info: *const xr::FrameEndInfo) -> xr::Result {
     let session = get_handle!(session);
     if let Err(e) = transition_frame_state(&session.frame_state, FrameState::Ended) {
         return e;
     }
+    let info = unsafe { info.as_ref() }.unwrap();
+    let layers = if info.layer_count == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(info.layers, info.layer_count as usize) }
+    };
+    *session.submitted_layers.lock().unwrap() =
+        layers.iter().map(|layer| unsafe { (**layer).ty }).collect();
+    *session.submitted_layer_flags.lock().unwrap() = layers
+        .iter()
+        .map(|layer| unsafe { (**layer).layer_flags })
+        .collect();
+    *session.submitted_depth.lock().unwrap() = layers
+        .iter()
+        .find(|layer| unsafe { (***layer).ty } == xr::CompositionLayerProjection::TYPE)
+        .map(|layer| {
+            let layer = unsafe { &*(*layer).cast::<xr::CompositionLayerProjection>() };
+            let views =
+                unsafe { std::slice::from_raw_parts(layer.views, layer.view_count as usize) };
+            views
+                .iter()
+                .map(|view| {
+                    let depth = view.next.cast::<xr::CompositionLayerDepthInfoKHR>();
+                    unsafe { depth.as_ref() }
+                        .filter(|depth| depth.ty == xr::CompositionLayerDepthInfoKHR::TYPE)
+                        .map(|depth| SubmittedDepth {
+                            min_depth: depth.min_depth,
+                            max_depth: depth.max_depth,
+                            near_z: depth.near_z,
+                            far_z: depth.far_z,
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
     if session.state.load() == xr::SessionState::READY {
         session.synchronized();
     }
     xr::Result::SUCCESS
 }
 
+const DEFAULT_FOV: xr::Fovf = xr::Fovf {
+    angle_left: -FRAC_PI_4,
+    angle_right: FRAC_PI_4,
+    angle_up: FRAC_PI_4,
+    angle_down: -FRAC_PI_4,
+};
+
 extern "system" fn locate_views(
     session: xr::Session,
-    _info: *const xr::ViewLocateInfo,
+    info: *const xr::ViewLocateInfo,
     state: *mut xr::ViewState,
     capacity: u32,
     output: *mut u32,
     views: *mut xr::View,
 ) -> xr::Result {
-    let _session = get_handle!(session);
+    let session = get_handle!(session);
     if !state.is_null() {
         unsafe {
             state.write(xr::ViewState {
@@ -1751,15 +2398,28 @@ extern "system" fn locate_views(
         if capacity < 2 {
             return xr::Result::ERROR_SIZE_INSUFFICIENT;
         }
-        let views = unsafe { std::slice::from_raw_parts_mut(views, capacity as usize) };
-        let view = xr::View {
-            ty: xr::View::TYPE,
-            next: std::ptr::null_mut(),
-            pose: xr::Posef::default(),
-            fov: xr::Fovf::default(),
+        let base = unsafe { (*info).space };
+        let base = get_handle!(base);
+        let view_to_base = match base.ty {
+            SpaceType::Reference(xr::ReferenceSpaceType::VIEW) => Affine3A::IDENTITY,
+            _ => {
+                let Ok(base) = base.get_pose_relative_to_local(unsafe { (*info).display_time })
+                else {
+                    return xr::Result::ERROR_SESSION_LOST;
+                };
+                pose_to_mat(base.pose).inverse() * pose_to_mat(session.view_pose.load())
+            }
         };
-        views[0] = view;
-        views[1] = view;
+
+        let views = unsafe { std::slice::from_raw_parts_mut(views, capacity as usize) };
+        for (view, (pose, fov)) in views.iter_mut().zip(session.eye_views.load()) {
+            *view = xr::View {
+                ty: xr::View::TYPE,
+                next: std::ptr::null_mut(),
+                pose: mat_to_pose(view_to_base * pose_to_mat(pose)),
+                fov,
+            };
+        }
     }
 
     xr::Result::SUCCESS
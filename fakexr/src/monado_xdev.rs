@@ -3,7 +3,7 @@ use crate::XrType;
 use super::{Handle, Space, SpaceType, destroy_handle, get_handle, impl_handle};
 use openxr_mndx_xdev_space::bindings::XDevIdMNDX;
 use openxr_sys as xr;
-use std::ffi::{CString, c_char};
+use std::ffi::{CStr, CString, c_char};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 
@@ -22,9 +22,36 @@ pub(super) struct XDev {
 
 impl_handle!(XDevListMNDX, openxr_mndx_xdev_space::bindings::XDevListMNDX);
 
-pub fn add_trackers(session: xr::Session) {
+/// Replaces the generic trackers in the session's xdev list with ones with the given names.
+pub fn add_trackers(session: xr::Session, names: &[&CStr]) {
+    let trackers: Vec<_> = (0..)
+        .zip(names)
+        .map(|(i, &name)| {
+            let serial = if i == 0 {
+                c"FAKEXR-SERIAL".to_owned()
+            } else {
+                CString::new(format!("FAKEXR-SERIAL-{i}")).unwrap()
+            };
+            (name.to_owned(), serial)
+        })
+        .collect();
+    set_trackers(session, trackers);
+}
+
+/// Replaces the generic trackers in the session's xdev list with ones with the given names and
+/// serials.
+pub fn add_trackers_with_serials(session: xr::Session, trackers: &[(&CStr, &CStr)]) {
+    let trackers = trackers
+        .iter()
+        .map(|&(name, serial)| (name.to_owned(), serial.to_owned()))
+        .collect();
+    set_trackers(session, trackers);
+}
+
+fn set_trackers(session: xr::Session, trackers: Vec<(CString, CString)>) {
     let session = session.to_handle().unwrap();
-    session.with_trackers.store(true, Ordering::Relaxed);
+    *session.trackers.lock().unwrap() = trackers;
+    session.xdev_generation.fetch_add(1, Ordering::Relaxed);
 }
 
 pub(super) extern "system" fn create_x_dev_list_m_n_d_x(
@@ -33,20 +60,19 @@ pub(super) extern "system" fn create_x_dev_list_m_n_d_x(
     xdev_list: *mut openxr_mndx_xdev_space::bindings::XDevListMNDX,
 ) -> xr::Result {
     let session = get_handle!(session);
-    let xdevs = if session.with_trackers.load(Ordering::Relaxed) {
-        vec![XDev {
+    let xdevs = (0..)
+        .zip(session.trackers.lock().unwrap().iter())
+        .map(|(i, (name, serial))| XDev {
             // monado starts counting xdevs at 43
             // https://gitlab.freedesktop.org/monado/monado/-/blob/main/src/xrt/state_trackers/oxr/oxr_xdev.c#L170
-            id: XDevIdMNDX::from_raw(43u64),
+            id: XDevIdMNDX::from_raw(43 + i),
             can_create_space: true,
-            name: c"FAKEXR-TRACKER".to_owned(),
-            serial: c"FAKEXR-SERIAL".to_owned(),
-        }]
-    } else {
-        vec![]
-    };
+            name: name.clone(),
+            serial: serial.clone(),
+        })
+        .collect();
     let list = Arc::new(XDevListMNDX {
-        generation_number: 1, // monado always sets this at 1
+        generation_number: session.xdev_generation.load(Ordering::Relaxed),
         xdevs,
     });
 
@@ -152,13 +178,12 @@ pub(super) extern "system" fn create_x_dev_space_m_n_d_x(
     space: *mut xr::Space,
 ) -> xr::Result {
     let s = get_handle!(session);
-    unsafe {
-        if (*create_info).xdev_id != XDevIdMNDX::from_raw(43u64) {
-            return xr::Result::ERROR_INDEX_OUT_OF_RANGE;
-        }
+    let create_info = unsafe { create_info.as_ref().unwrap() };
+    let tracker_count = s.trackers.lock().unwrap().len() as u64;
+    if !(43..43 + tracker_count).any(|id| create_info.xdev_id == XDevIdMNDX::from_raw(id)) {
+        return xr::Result::ERROR_INDEX_OUT_OF_RANGE;
     }
 
-    let create_info = unsafe { create_info.as_ref().unwrap() };
     let xdev_space = Arc::new(Space {
         ty: SpaceType::Reference(xr::ReferenceSpaceType::VIEW),
         offset: create_info.offset,
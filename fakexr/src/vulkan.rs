@@ -44,6 +44,8 @@ pub unsafe extern "system" fn get_instance_proc_addr(
     } else {
         get_fn![name =>
             GetPhysicalDeviceQueueFamilyProperties,
+            GetPhysicalDeviceProperties2,
+            EnumeratePhysicalDevices,
             CreateDevice,
             GetDeviceProcAddr,
             GetDeviceQueue,
@@ -150,6 +152,57 @@ extern "system" fn get_physical_device_queue_family_properties(
     }
 }
 
+/// The physical device the runtime wants to be used, which is second in the device list so that
+/// its index isn't mistaken for a default.
+pub const PHYSICAL_DEVICE: u64 = 0x7d0;
+const PHYSICAL_DEVICES: [u64; 2] = [0x7cf, PHYSICAL_DEVICE];
+
+extern "system" fn enumerate_physical_devices(
+    _: vk::Instance,
+    physical_device_count: *mut u32,
+    physical_devices: *mut vk::PhysicalDevice,
+) -> vk::Result {
+    if physical_devices.is_null() {
+        unsafe { *physical_device_count = PHYSICAL_DEVICES.len() as u32 };
+        return vk::Result::SUCCESS;
+    }
+
+    let count = unsafe { *physical_device_count }.min(PHYSICAL_DEVICES.len() as u32);
+    for (idx, device) in PHYSICAL_DEVICES.iter().take(count as usize).enumerate() {
+        unsafe { *physical_devices.add(idx) = vk::PhysicalDevice::from_raw(*device) };
+    }
+    unsafe { *physical_device_count = count };
+    if (count as usize) < PHYSICAL_DEVICES.len() {
+        vk::Result::INCOMPLETE
+    } else {
+        vk::Result::SUCCESS
+    }
+}
+
+/// The LUID reported for the physical device.
+pub const DEVICE_LUID: [u8; vk::LUID_SIZE] = [0x2a, 0, 0, 0, 0, 0, 0, 0];
+/// The LUID reported for the other physical device.
+const OTHER_DEVICE_LUID: [u8; vk::LUID_SIZE] = [0x29, 0, 0, 0, 0, 0, 0, 0];
+
+extern "system" fn get_physical_device_properties2(
+    physical_device: vk::PhysicalDevice,
+    properties: *mut vk::PhysicalDeviceProperties2<'_>,
+) {
+    let mut next = unsafe { (*properties).p_next }.cast::<vk::BaseOutStructure<'_>>();
+    while let Some(s) = unsafe { next.as_mut() } {
+        if s.s_type == vk::StructureType::PHYSICAL_DEVICE_ID_PROPERTIES {
+            let id = unsafe { &mut *next.cast::<vk::PhysicalDeviceIDProperties<'_>>() };
+            id.device_luid = if physical_device.as_raw() == PHYSICAL_DEVICE {
+                DEVICE_LUID
+            } else {
+                OTHER_DEVICE_LUID
+            };
+            id.device_luid_valid = vk::TRUE;
+        }
+        next = s.p_next;
+    }
+}
+
 pub(crate) mod xr {
     use openxr_sys as xr;
     use std::ffi::{CStr, c_char};
@@ -199,8 +252,9 @@ pub(crate) mod xr {
         _: xr::Instance,
         _: xr::SystemId,
         _: xr::platform::VkInstance,
-        _: *mut xr::platform::VkPhysicalDevice,
+        physical_device: *mut xr::platform::VkPhysicalDevice,
     ) -> xr::Result {
+        unsafe { *physical_device = super::PHYSICAL_DEVICE as _ };
         xr::Result::SUCCESS
     }
 
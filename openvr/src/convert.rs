@@ -6,19 +6,37 @@ pub fn space_relation_to_openvr_pose(
     location: xr::SpaceLocation,
     velocity: xr::SpaceVelocity,
 ) -> TrackedDevicePose_t {
-    if !location.location_flags.contains(
+    let flags = location.location_flags;
+    if !flags.contains(
         xr::SpaceLocationFlags::POSITION_VALID | xr::SpaceLocationFlags::ORIENTATION_VALID,
     ) {
+        // A valid orientation means the runtime still knows about the device, it just lost track
+        // of where it is.
+        let tracking_result = if flags.contains(xr::SpaceLocationFlags::ORIENTATION_VALID) {
+            ETrackingResult::Running_OutOfRange
+        } else {
+            ETrackingResult::Uninitialized
+        };
         return TrackedDevicePose_t {
             bPoseIsValid: false,
             bDeviceIsConnected: false,
             mDeviceToAbsoluteTracking: Default::default(),
             vVelocity: Default::default(),
             vAngularVelocity: Default::default(),
-            eTrackingResult: ETrackingResult::Running_OutOfRange,
+            eTrackingResult: tracking_result,
         };
     }
 
+    // Valid but untracked poses are the runtime's best guess (i.e., IMU only), which is what
+    // SteamVR reports as out of range.
+    let tracking_result = if flags.contains(
+        xr::SpaceLocationFlags::POSITION_TRACKED | xr::SpaceLocationFlags::ORIENTATION_TRACKED,
+    ) {
+        ETrackingResult::Running_OK
+    } else {
+        ETrackingResult::Running_OutOfRange
+    };
+
     let location = HmdMatrix34_t::from(location.pose);
     let linear_velo = velocity
         .velocity_flags
@@ -33,7 +51,7 @@ pub fn space_relation_to_openvr_pose(
         mDeviceToAbsoluteTracking: location,
         vVelocity: linear_velo.unwrap_or_default(),
         vAngularVelocity: angular_velo.unwrap_or_default(),
-        eTrackingResult: ETrackingResult::Running_OK,
+        eTrackingResult: tracking_result,
         bPoseIsValid: true,
         bDeviceIsConnected: true,
     }
@@ -148,3 +166,50 @@ impl From<Affine3A> for VRBoneTransform_t {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pose_with_flags(location_flags: xr::SpaceLocationFlags) -> TrackedDevicePose_t {
+        let location = xr::SpaceLocation {
+            location_flags,
+            pose: xr::Posef::IDENTITY,
+        };
+        let velocity = xr::SpaceVelocity {
+            velocity_flags: xr::SpaceVelocityFlags::EMPTY,
+            linear_velocity: Default::default(),
+            angular_velocity: Default::default(),
+        };
+        space_relation_to_openvr_pose(location, velocity)
+    }
+
+    #[test]
+    fn tracking_result_from_location_flags() {
+        let tracked = xr::SpaceLocationFlags::POSITION_VALID
+            | xr::SpaceLocationFlags::ORIENTATION_VALID
+            | xr::SpaceLocationFlags::POSITION_TRACKED
+            | xr::SpaceLocationFlags::ORIENTATION_TRACKED;
+        let pose = pose_with_flags(tracked);
+        assert!(pose.bPoseIsValid);
+        assert_eq!(pose.eTrackingResult, ETrackingResult::Running_OK);
+
+        let pose = pose_with_flags(
+            xr::SpaceLocationFlags::POSITION_VALID
+                | xr::SpaceLocationFlags::ORIENTATION_VALID
+                | xr::SpaceLocationFlags::ORIENTATION_TRACKED,
+        );
+        assert!(pose.bPoseIsValid);
+        assert_eq!(pose.eTrackingResult, ETrackingResult::Running_OutOfRange);
+
+        let pose = pose_with_flags(
+            xr::SpaceLocationFlags::ORIENTATION_VALID | xr::SpaceLocationFlags::ORIENTATION_TRACKED,
+        );
+        assert!(!pose.bPoseIsValid);
+        assert_eq!(pose.eTrackingResult, ETrackingResult::Running_OutOfRange);
+
+        let pose = pose_with_flags(xr::SpaceLocationFlags::EMPTY);
+        assert!(!pose.bPoseIsValid);
+        assert_eq!(pose.eTrackingResult, ETrackingResult::Uninitialized);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::openxr_data::RealOpenXrData;
+use log::warn;
 use openvr as vr;
+use openxr as xr;
 use std::sync::Arc;
 
 #[derive(macros::InterfaceImpl)]
@@ -17,6 +19,23 @@ impl Chaperone {
             openxr,
         }
     }
+
+    /// The size of the play area. Falls back to 1x1 meters if the runtime doesn't know it, since
+    /// some games refuse to start without a play area.
+    fn stage_bounds(&self) -> xr::Extent2Df {
+        self.openxr
+            .session_data
+            .get()
+            .session
+            .reference_space_bounds_rect(xr::ReferenceSpaceType::STAGE)
+            .inspect_err(|e| warn!("Failed to get stage bounds: {e}"))
+            .ok()
+            .flatten()
+            .unwrap_or(xr::Extent2Df {
+                width: 1.0,
+                height: 1.0,
+            })
+    }
 }
 
 impl vr::IVRChaperone004_Interface for Chaperone {
@@ -55,21 +74,73 @@ impl vr::IVRChaperone004_Interface for Chaperone {
         crate::warn_unimplemented!("ReloadInfo");
     }
     fn GetPlayAreaRect(&self, rect: *mut vr::HmdQuad_t) -> bool {
-        crate::warn_unimplemented!("GetPlayAreaRect");
-        unsafe {
-            *rect = Default::default();
-        }
-        false
+        let Some(rect) = (unsafe { rect.as_mut() }) else {
+            return false;
+        };
+        let bounds = self.stage_bounds();
+
+        // The play area is centered on the standing origin, with the corners in counter-clockwise
+        // order when seen from above.
+        let x = bounds.width / 2.0;
+        let z = bounds.height / 2.0;
+        let corner = |x, z| vr::HmdVector3_t { v: [x, 0.0, z] };
+        rect.vCorners = [corner(-x, -z), corner(-x, z), corner(x, z), corner(x, -z)];
+        true
     }
     fn GetPlayAreaSize(&self, size_x: *mut f32, size_z: *mut f32) -> bool {
-        crate::warn_unimplemented!("GetPlayAreaSize");
-        unsafe {
-            *size_x = 1.0;
-            *size_z = 1.0;
-        };
+        let bounds = self.stage_bounds();
+        if let Some(size_x) = unsafe { size_x.as_mut() } {
+            *size_x = bounds.width;
+        }
+        if let Some(size_z) = unsafe { size_z.as_mut() } {
+            *size_z = bounds.height;
+        }
         true
     }
     fn GetCalibrationState(&self) -> vr::ChaperoneCalibrationState {
         vr::ChaperoneCalibrationState::OK
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clientcore::Injector, openxr_data::OpenXrData};
+    use vr::IVRChaperone004_Interface;
+
+    #[test]
+    fn play_area_from_stage_bounds() {
+        let xr = Arc::new(OpenXrData::new(&Injector::default()).unwrap());
+        let chaperone = Chaperone::new(xr.clone());
+        let session = xr.session_data.get().session.as_raw();
+
+        let (mut x, mut z) = (0.0, 0.0);
+        let mut rect = vr::HmdQuad_t::default();
+        assert!(chaperone.GetPlayAreaSize(&mut x, &mut z));
+        assert_eq!((x, z), (1.0, 1.0));
+        assert!(chaperone.GetPlayAreaRect(&mut rect));
+        assert_eq!(rect.vCorners[2].v, [0.5, 0.0, 0.5]);
+
+        fakexr::set_stage_bounds(
+            session,
+            Some(xr::Extent2Df {
+                width: 3.0,
+                height: 2.0,
+            }),
+        );
+        assert!(chaperone.GetPlayAreaSize(&mut x, &mut z));
+        assert_eq!((x, z), (3.0, 2.0));
+
+        assert!(chaperone.GetPlayAreaRect(&mut rect));
+        let corners = rect.vCorners.map(|c| c.v);
+        assert_eq!(
+            corners,
+            [
+                [-1.5, 0.0, -1.0],
+                [-1.5, 0.0, 1.0],
+                [1.5, 0.0, 1.0],
+                [1.5, 0.0, -1.0]
+            ]
+        );
+    }
+}
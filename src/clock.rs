@@ -0,0 +1,30 @@
+/// The current time, for animating things like finger smoothing and fades. Tests can move this
+/// forward with `advance` instead of sleeping.
+#[cfg(not(test))]
+#[inline]
+pub fn now() -> std::time::Instant {
+    std::time::Instant::now()
+}
+
+#[cfg(test)]
+pub use fake::*;
+
+#[cfg(test)]
+mod fake {
+    use std::cell::Cell;
+    use std::time::{Duration, Instant};
+
+    thread_local! {
+        // Per thread, so that tests running in parallel don't move each other's clocks.
+        static OFFSET: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    }
+
+    pub fn now() -> Instant {
+        Instant::now() + OFFSET.get()
+    }
+
+    /// Moves the current thread's clock forward.
+    pub fn advance(by: Duration) {
+        OFFSET.set(OFFSET.get() + by);
+    }
+}
@@ -1,28 +1,32 @@
 use crate::{
-    AtomicF64,
     clientcore::{Injected, Injector},
     graphics_backends::{GraphicsBackend, SupportedBackend, supported_backends_enum},
     input::Input,
-    openxr_data::{self, FrameStream, OpenXrData, SessionCreateInfo, SessionData},
+    openxr_data::{self, FrameStream, FrameTiming, OpenXrData, SessionCreateInfo, SessionData},
     overlay::OverlayMan,
     system::System,
     tracy_span,
 };
 
-use log::{debug, info, trace, warn};
+use log::{debug, error, info, trace, warn};
 use openvr as vr;
 use openxr as xr;
+use std::collections::VecDeque;
 use std::mem::offset_of;
-use std::sync::{
-    Arc, Mutex, Once,
-    atomic::{AtomicU32, Ordering},
-};
+use std::sync::{Arc, Mutex, Once, atomic::Ordering};
 use std::time::Instant;
 use std::{ffi::c_char, ops::Deref};
 
 #[derive(Default)]
 pub struct CompositorSessionData(Mutex<Option<DynFrameController>>);
 
+impl CompositorSessionData {
+    /// Stops rendering to a session that has been ended.
+    pub fn clear(&self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
 #[derive(macros::InterfaceImpl)]
 #[interface = "IVRCompositor"]
 #[versions(
@@ -36,9 +40,10 @@ pub struct Compositor {
     /// Stores the backend data in between session restarts.
     tmp_backend: Mutex<Option<AnyTempBackendData>>,
     overlays: Injected<OverlayMan>,
-    metrics: FrameMetrics,
     timing_mode: Mutex<vr::EVRCompositorTimingMode>,
     frame_state: Mutex<FrameState>,
+    pose_predictions: Mutex<PosePredictions>,
+    fades: Mutex<Fades>,
     focused: Once,
 }
 
@@ -67,10 +72,117 @@ impl FrameState {
     }
 }
 
-struct FrameMetrics {
-    system_start: Instant,
-    index: AtomicU32,
-    time: AtomicF64,
+/// The poses handed out by the most recent calls to WaitGetPoses, keyed by prediction ID.
+#[derive(Default)]
+struct PosePredictions {
+    last_id: u32,
+    frames: VecDeque<(u32, Vec<vr::TrackedDevicePose_t>)>,
+}
+
+impl PosePredictions {
+    const MAX_FRAMES: usize = 8;
+
+    /// Starts a new prediction with room for `count` poses. The oldest prediction's buffer is
+    /// reused once enough are stored, so this doesn't allocate every frame.
+    fn push(&mut self, count: usize) -> (u32, &mut [vr::TrackedDevicePose_t]) {
+        self.last_id = self.last_id.wrapping_add(1);
+        let mut poses = match self.frames.len() {
+            Self::MAX_FRAMES => self.frames.pop_front().unwrap().1,
+            _ => Vec::with_capacity(count),
+        };
+        poses.clear();
+        poses.resize(count, Default::default());
+        self.frames.push_back((self.last_id, poses));
+        (self.last_id, &mut self.frames.back_mut().unwrap().1)
+    }
+
+    fn get(&self, id: u32) -> Option<&[vr::TrackedDevicePose_t]> {
+        self.frames
+            .iter()
+            .find_map(|(frame_id, poses)| (*frame_id == id).then_some(poses.as_slice()))
+    }
+}
+
+/// Copies as many poses as will fit into an application provided array. Devices past the stored
+/// poses are reported as invalid.
+fn write_poses(poses: &[vr::TrackedDevicePose_t], array: *mut vr::TrackedDevicePose_t, count: u32) {
+    if array.is_null() || count == 0 {
+        return;
+    }
+    let array = unsafe { std::slice::from_raw_parts_mut(array, count as usize) };
+    let len = array.len().min(poses.len());
+    array[..len].copy_from_slice(&poses[..len]);
+    array[len..].fill(Default::default());
+}
+
+/// A color linearly fading towards a target over time.
+#[derive(Copy, Clone)]
+struct Fade {
+    from: vr::HmdColor_t,
+    to: vr::HmdColor_t,
+    start: Instant,
+    seconds: f32,
+}
+
+impl Fade {
+    fn new() -> Self {
+        Self {
+            from: vr::HmdColor_t::default(),
+            to: vr::HmdColor_t::default(),
+            start: crate::clock::now(),
+            seconds: 0.0,
+        }
+    }
+
+    fn color_at(&self, now: Instant) -> vr::HmdColor_t {
+        let t = if self.seconds > 0.0 {
+            (now.saturating_duration_since(self.start).as_secs_f32() / self.seconds).min(1.0)
+        } else {
+            1.0
+        };
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        vr::HmdColor_t {
+            r: lerp(self.from.r, self.to.r),
+            g: lerp(self.from.g, self.to.g),
+            b: lerp(self.from.b, self.to.b),
+            a: lerp(self.from.a, self.to.a),
+        }
+    }
+
+    fn fade_to(&mut self, color: vr::HmdColor_t, seconds: f32) {
+        let now = crate::clock::now();
+        self.from = self.color_at(now);
+        self.to = color;
+        self.start = now;
+        self.seconds = seconds.max(0.0);
+    }
+}
+
+/// Fades requested by the app through FadeToColor and FadeGrid.
+struct Fades {
+    scene: Fade,
+    background: Fade,
+    /// Only the alpha is used.
+    grid: Fade,
+}
+
+impl Fades {
+    fn new() -> Self {
+        Self {
+            scene: Fade::new(),
+            background: Fade::new(),
+            grid: Fade::new(),
+        }
+    }
+}
+
+/// The colors to fade the frame being submitted with.
+#[derive(Copy, Clone, Default)]
+struct FadeColors {
+    /// Composited over the app's scene.
+    scene: vr::HmdColor_t,
+    /// Composited behind the app's scene.
+    background: vr::HmdColor_t,
 }
 
 struct TempBackendData<G: GraphicsBackend> {
@@ -88,13 +200,10 @@ impl Compositor {
             system: injector.inject(),
             tmp_backend: Mutex::default(),
             overlays: injector.inject(),
-            metrics: FrameMetrics {
-                system_start: Instant::now(),
-                index: 0.into(),
-                time: 0.0.into(),
-            },
             timing_mode: vr::EVRCompositorTimingMode::Implicit.into(),
             frame_state: FrameState::Submitted.into(),
+            pose_predictions: Mutex::default(),
+            fades: Mutex::new(Fades::new()),
             focused: Once::new(),
         }
     }
@@ -306,18 +415,36 @@ impl openxr_data::Compositor for Compositor {
 impl vr::IVRCompositor029_Interface for Compositor {
     fn GetPosesForFrame(
         &self,
-        _unPosePredictionID: u32,
-        _pPoseArray: *mut vr::TrackedDevicePose_t,
-        _unPoseArrayCount: u32,
+        pose_prediction_id: u32,
+        pose_array: *mut vr::TrackedDevicePose_t,
+        pose_array_count: u32,
     ) -> vr::EVRCompositorError {
-        todo!()
+        if pose_array.is_null() {
+            return vr::EVRCompositorError::RequestFailed;
+        }
+
+        let predictions = self.pose_predictions.lock().unwrap();
+        let Some(poses) = predictions.get(pose_prediction_id) else {
+            debug!("No poses stored for prediction ID {pose_prediction_id}");
+            return vr::EVRCompositorError::RequestFailed;
+        };
+
+        write_poses(poses, pose_array, pose_array_count);
+        vr::EVRCompositorError::None
     }
     fn GetLastPosePredictionIDs(
         &self,
-        _pRenderPosePredictionID: *mut u32,
-        _pGamePosePredictionID: *mut u32,
+        render_pose_prediction_id: *mut u32,
+        game_pose_prediction_id: *mut u32,
     ) -> vr::EVRCompositorError {
-        crate::warn_unimplemented!("GetLastPosePredictionIDs");
+        // Render and game poses are the same, see GetLastPoses
+        let id = self.pose_predictions.lock().unwrap().last_id;
+        if !render_pose_prediction_id.is_null() {
+            unsafe { render_pose_prediction_id.write(id) };
+        }
+        if !game_pose_prediction_id.is_null() {
+            unsafe { game_pose_prediction_id.write(id) };
+        }
         vr::EVRCompositorError::None
     }
     fn GetCompositorBenchmarkResults(
@@ -474,10 +601,11 @@ impl vr::IVRCompositor029_Interface for Compositor {
         true
     }
     fn GetLastFrameRenderer(&self) -> u32 {
-        todo!()
+        // We're the only scene application, so we're always the one rendering.
+        std::process::id()
     }
     fn GetCurrentSceneFocusProcess(&self) -> u32 {
-        todo!()
+        std::process::id()
     }
     fn IsFullscreen(&self) -> bool {
         true
@@ -536,9 +664,22 @@ impl vr::IVRCompositor029_Interface for Compositor {
         }
     }
     fn GetCurrentGridAlpha(&self) -> f32 {
-        0.0
-    }
-    fn FadeGrid(&self, _fSeconds: f32, bFadeGridIn: bool) {
+        self.fades
+            .lock()
+            .unwrap()
+            .grid
+            .color_at(crate::clock::now())
+            .a
+    }
+    fn FadeGrid(&self, fSeconds: f32, bFadeGridIn: bool) {
+        self.fades.lock().unwrap().grid.fade_to(
+            vr::HmdColor_t {
+                a: if bFadeGridIn { 1.0 } else { 0.0 },
+                ..Default::default()
+            },
+            fSeconds,
+        );
+
         #[macros::any_graphics(DynFrameController)]
         fn set_fade_grid<G: GraphicsBackend + 'static>(
             ctrl: &mut FrameController<G>,
@@ -557,19 +698,49 @@ impl vr::IVRCompositor029_Interface for Compositor {
             .iter_mut()
             .for_each(|ctrl| ctrl.with_any_graphics_mut::<set_fade_grid>(bFadeGridIn));
     }
-    fn GetCurrentFadeColor(&self, _bBackground: bool) -> vr::HmdColor_t {
-        todo!()
+    fn GetCurrentFadeColor(&self, bBackground: bool) -> vr::HmdColor_t {
+        let fades = self.fades.lock().unwrap();
+        let fade = if bBackground {
+            &fades.background
+        } else {
+            &fades.scene
+        };
+        fade.color_at(crate::clock::now())
     }
     fn FadeToColor(
         &self,
-        _fSeconds: f32,
-        _fRed: f32,
-        _fGreen: f32,
-        _fBlue: f32,
-        _fAlpha: f32,
-        _bBackground: bool,
+        fSeconds: f32,
+        fRed: f32,
+        fGreen: f32,
+        fBlue: f32,
+        fAlpha: f32,
+        bBackground: bool,
     ) {
-        crate::warn_unimplemented!("FadeToColor");
+        if !self
+            .openxr
+            .enabled_extensions
+            .khr_composition_layer_color_scale_bias
+        {
+            crate::warn_once!(
+                "Can't show fades: khr_composition_layer_color_scale_bias unsupported"
+            );
+        }
+
+        let mut fades = self.fades.lock().unwrap();
+        let fade = if bBackground {
+            &mut fades.background
+        } else {
+            &mut fades.scene
+        };
+        fade.fade_to(
+            vr::HmdColor_t {
+                r: fRed,
+                g: fGreen,
+                b: fBlue,
+                a: fAlpha,
+            },
+            fSeconds,
+        );
     }
     fn GetCumulativeStats(
         &self,
@@ -582,66 +753,29 @@ impl vr::IVRCompositor029_Interface for Compositor {
         crate::warn_unimplemented!("GetFrameTimeRemaining");
         0.0
     }
-    fn GetFrameTimings(&self, _pTiming: *mut vr::Compositor_FrameTiming, _nFrames: u32) -> u32 {
-        todo!()
-    }
-    fn GetFrameTiming(&self, timing: *mut vr::Compositor_FrameTiming, _frames_ago: u32) -> bool {
-        if timing.is_null() || !timing.is_aligned() {
-            return false;
-        }
+    fn GetFrameTimings(&self, timing: *mut vr::Compositor_FrameTiming, frames: u32) -> u32 {
+        // Only the first entry's size is set, and it's used as the stride for the rest.
+        let Some(size) = (unsafe { frame_timing_size(timing) }) else {
+            return 0;
+        };
 
-        let size = unsafe { (&raw const (*timing).m_nSize).read() } as usize;
-        fn ptr_size<T>(_: *const T) -> usize {
-            std::mem::size_of::<T>()
+        let timings = self.openxr.frame_timings.lock().unwrap();
+        let mut count = 0;
+        for frame in timings.recent(frames as usize) {
+            unsafe { write_frame_timing(timing.byte_add(count * size), frame) };
+            count += 1;
         }
-        if size
-            < offset_of!(vr::Compositor_FrameTiming, m_HmdPose)
-                + ptr_size(unsafe { &raw const (*timing).m_HmdPose })
-        {
+        count as u32
+    }
+    fn GetFrameTiming(&self, timing: *mut vr::Compositor_FrameTiming, frames_ago: u32) -> bool {
+        if unsafe { frame_timing_size(timing) }.is_none() {
             return false;
         }
-        // We're using raw pointers here because the Compositor_FrameTiming struct can be a
-        // varaible size, so we don't want to create a reference to a struct with an incorrect
-        // (to us) size, because that would be Undefined Behavior.
-        macro_rules! set {
-            ($member:ident, $value:expr) => {{
-                let ptr = &raw mut (*timing).$member;
-                ptr.write_unaligned($value)
-            }};
-        }
-
-        unsafe {
-            // TODO: These values are copy/pasted from OpenComposite, determine if real values are
-            // necessary/better
-            set!(m_nFrameIndex, self.metrics.index.load(Ordering::Relaxed));
-            set!(m_nNumFramePresents, 1);
-            set!(m_nNumMisPresented, 0);
-            set!(m_nReprojectionFlags, 0);
-            set!(m_flSystemTimeInSeconds, self.metrics.time.load());
-            set!(m_flPreSubmitGpuMs, 8.0);
-            set!(m_flPostSubmitGpuMs, 1.0);
-            set!(m_flTotalRenderGpuMs, 9.0);
-
-            set!(m_flCompositorRenderGpuMs, 1.5);
-            set!(m_flCompositorRenderCpuMs, 3.0);
-            set!(m_flCompositorIdleCpuMs, 0.1);
-
-            set!(
-                m_flClientFrameIntervalMs,
-                1000.0 / self.openxr.get_refresh_rate()
-            );
-            set!(m_flPresentCallCpuMs, 0.0);
-            set!(m_flWaitForPresentCpuMs, 0.0);
-            set!(m_flSubmitFrameMs, 0.0);
-
-            set!(m_flWaitGetPosesCalledMs, 0.0);
-            set!(m_flNewPosesReadyMs, 0.0);
-            set!(m_flNewFrameReadyMs, 0.0); // second call to IVRCompositor::Submit
-            set!(m_flCompositorUpdateStartMs, 0.0);
-            set!(m_flCompositorUpdateEndMs, 0.0);
-            set!(m_flCompositorRenderStartMs, 0.0);
-        }
 
+        let Some(frame) = self.openxr.frame_timings.lock().unwrap().get(frames_ago) else {
+            return false;
+        };
+        unsafe { write_frame_timing(timing, &frame) };
         true
     }
     fn PostPresentHandoff(&self) {
@@ -652,11 +786,15 @@ impl vr::IVRCompositor029_Interface for Compositor {
             system: &System,
             display_time: xr::Time,
             overlays: Option<&OverlayMan>,
-        ) where
+            fades: FadeColors,
+        ) -> bool
+        where
             for<'b> &'b crate::overlay::AnySwapchainMap:
                 TryInto<&'b crate::overlay::SwapchainMap<G::Api>, Error: std::fmt::Display>,
+            for<'b> &'b openxr_data::GraphicalSession:
+                TryInto<&'b openxr_data::Session<G::Api>, Error: std::fmt::Display>,
         {
-            ctrl.end_frame(session_data, system, display_time, overlays)
+            ctrl.end_frame(session_data, system, display_time, overlays, fades)
         }
 
         let session_data = self.openxr.session_data.get();
@@ -674,23 +812,41 @@ impl vr::IVRCompositor029_Interface for Compositor {
         let system = self.system.force(|i| System::new(self.openxr.clone(), i));
         let display_time = self.openxr.display_time.get();
         let overlays = self.overlays.get();
+        let fades = if self
+            .openxr
+            .enabled_extensions
+            .khr_composition_layer_color_scale_bias
+        {
+            let fades = self.fades.lock().unwrap();
+            let now = crate::clock::now();
+            FadeColors {
+                scene: fades.scene.color_at(now),
+                background: fades.background.color_at(now),
+            }
+        } else {
+            FadeColors::default()
+        };
 
-        ctrl.with_any_graphics_mut::<end_frame>((
+        let present_start = Instant::now();
+        let ended = ctrl.with_any_graphics_mut::<end_frame>((
             &session_data,
             &system,
             display_time,
             overlays.as_deref(),
+            fades,
         ));
+        self.openxr.frame_timings.lock().unwrap().push(
+            Instant::now(),
+            present_start.elapsed().as_secs_f32() * 1000.0,
+            self.openxr.display_period_nanos.load(Ordering::Relaxed),
+            ended,
+        );
 
         self.frame_state
             .lock()
             .unwrap()
             .advance_to(FrameState::Submitted);
 
-        self.metrics.index.fetch_add(1, Ordering::Relaxed);
-        self.metrics
-            .time
-            .store(self.metrics.system_start.elapsed().as_secs_f64());
         #[cfg(feature = "tracing")]
         {
             tracy_client::frame_mark();
@@ -778,6 +934,7 @@ impl vr::IVRCompositor029_Interface for Compositor {
             session_data: &SessionData,
             eye: vr::EVREye,
             texture: &vr::Texture_t,
+            depth: Option<&vr::VRTextureDepthInfo_t>,
             bounds: vr::VRTextureBounds_t,
             flags: vr::EVRSubmitFlags,
         ) -> xr::Result<(), vr::EVRCompositorError>
@@ -788,20 +945,33 @@ impl vr::IVRCompositor029_Interface for Compositor {
         {
             let real_texture =
                 G::get_texture(texture).ok_or(vr::EVRCompositorError::InvalidTexture)?;
+            let depth = depth.and_then(|info| {
+                let depth_texture = vr::Texture_t {
+                    handle: info.handle,
+                    ..*texture
+                };
+                G::get_texture(&depth_texture).map(|texture| (texture, info))
+            });
             ctrl.submit_impl(
                 session_data,
                 eye,
                 real_texture,
+                depth,
                 texture.eColorSpace,
                 bounds,
                 flags,
             )
         }
 
+        let depth = (self.openxr.enabled_extensions.khr_composition_layer_depth
+            && (submit_flags & vr::EVRSubmitFlags::TextureWithDepth).0 > 0)
+            .then(|| unsafe { texture_depth_info(texture, submit_flags) });
+
         if let Err(e) = ctrl.with_any_graphics_mut::<submit>((
             &session_lock,
             eye,
             texture,
+            depth,
             bounds,
             submit_flags,
         )) {
@@ -907,12 +1077,21 @@ impl vr::IVRCompositor029_Interface for Compositor {
             input.frame_start_update();
         }
 
-        self.GetLastPoses(
-            render_pose_array,
-            render_pose_count,
-            game_pose_array,
-            game_pose_count,
-        )
+        // Only the poses the game asked for are located.
+        let count = render_pose_count
+            .max(game_pose_count)
+            .min(vr::k_unMaxTrackedDeviceCount) as usize;
+        let mut predictions = self.pose_predictions.lock().unwrap();
+        let (id, poses) = predictions.push(count);
+        self.input
+            .force(|_| Input::new(self.openxr.clone()))
+            .get_poses(poses, None);
+        trace!("new pose prediction ID: {id}");
+
+        // See GetLastPoses for game poses
+        write_poses(poses, render_pose_array, render_pose_count);
+        write_poses(poses, game_pose_array, game_pose_count);
+        vr::EVRCompositorError::None
     }
 
     fn GetTrackingSpace(&self) -> vr::ETrackingUniverseOrigin {
@@ -1017,6 +1196,32 @@ impl vr::IVRCompositor009On011 for Compositor {
 struct SubmittedEye {
     extent: xr::Extent2Di,
     flip_vertically: bool,
+    depth: Option<SubmittedDepth>,
+}
+
+#[derive(Copy, Clone)]
+struct SubmittedDepth {
+    extent: xr::Extent2Di,
+    min_depth: f32,
+    max_depth: f32,
+    near_z: f32,
+    far_z: f32,
+}
+
+impl SubmittedDepth {
+    fn new(extent: xr::Extent2Di, info: &vr::VRTextureDepthInfo_t) -> Self {
+        // Recover the clip planes from the game's projection matrix (see
+        // IVRSystem::GetProjectionMatrix). This also works out for reversed depth (near > far),
+        // and for an infinite far plane, once the sign of the resulting infinity is fixed up.
+        let m = info.mProjection.m;
+        Self {
+            extent,
+            min_depth: info.vRange.v[0],
+            max_depth: info.vRange.v[1],
+            near_z: (m[2][3] / m[2][2]).abs(),
+            far_z: (m[2][3] / (m[2][2] + 1.0)).abs(),
+        }
+    }
 }
 
 struct SwapchainData<G: xr::Graphics> {
@@ -1031,6 +1236,10 @@ struct FrameController<G: GraphicsBackend> {
     swapchain_data: Option<SwapchainData<G::Api>>,
     image_index: usize,
     image_acquired: bool,
+    depth_swapchain_data: Option<SwapchainData<G::Api>>,
+    depth_image_index: usize,
+    depth_image_acquired: bool,
+    fade_swapchain: Option<xr::Swapchain<G::Api>>,
     should_render: bool,
     app_suspend_render: bool,
     app_fade_grid: bool,
@@ -1120,6 +1329,10 @@ impl<G: GraphicsBackend> FrameController<G> {
             swapchain_data,
             image_index: 0,
             image_acquired: false,
+            depth_swapchain_data: None,
+            depth_image_index: 0,
+            depth_image_acquired: false,
+            fade_swapchain: None,
             should_render: false,
             app_suspend_render: false,
             app_fade_grid: false,
@@ -1172,6 +1385,131 @@ impl<G: GraphicsBackend> FrameController<G> {
         self.image_acquired = true;
     }
 
+    /// Copies the depth texture for an eye into the depth swapchain, (re)creating it if necessary.
+    /// Returns None if the depth texture can't be submitted.
+    fn submit_depth(
+        &mut self,
+        session_data: &SessionData,
+        eye: vr::EVREye,
+        texture: G::OpenVrTexture,
+        info: &vr::VRTextureDepthInfo_t,
+        bounds: vr::VRTextureBounds_t,
+    ) -> Option<SubmittedDepth>
+    where
+        for<'b> &'b openxr_data::GraphicalSession:
+            TryInto<&'b openxr_data::Session<G::Api>, Error: std::fmt::Display>,
+        <G::Api as xr::Graphics>::Format: PartialEq + std::fmt::Debug,
+    {
+        let new_info = self
+            .backend
+            .depth_swapchain_info_for_texture(texture, bounds)
+            .filter(is_valid_swapchain_info)?;
+
+        if !self
+            .depth_swapchain_data
+            .as_ref()
+            .is_some_and(|data| is_usable_swapchain(&data.info, data.initial_format, &new_info))
+        {
+            if !session_data.supports_format::<G>(new_info.format) {
+                crate::warn_once!(
+                    "Runtime does not support depth format {:?}, not submitting depth",
+                    G::to_nice_format(new_info.format)
+                );
+                return None;
+            }
+
+            info!("recreating depth swapchain (for {eye:?})");
+            let swapchain = session_data
+                .create_swapchain(&new_info)
+                .inspect_err(|e| error!("Failed to create depth swapchain: {e}"))
+                .ok()?;
+            let images = swapchain
+                .enumerate_images()
+                .expect("Failed to enumerate depth swapchain images");
+            self.backend.store_depth_swapchain_images(images);
+
+            self.depth_swapchain_data = Some(SwapchainData {
+                swapchain,
+                initial_format: new_info.format,
+                info: new_info,
+            });
+            self.depth_image_acquired = false;
+        }
+
+        if !self.depth_image_acquired {
+            let swapchain = &mut self.depth_swapchain_data.as_mut().unwrap().swapchain;
+            self.depth_image_index = swapchain
+                .acquire_image()
+                .expect("Failed to acquire depth swapchain image")
+                as usize;
+            swapchain
+                .wait_image(xr::Duration::INFINITE)
+                .expect("Failed to wait for depth swapchain image");
+            self.depth_image_acquired = true;
+        }
+
+        let extent =
+            self.backend
+                .copy_depth_to_swapchain(eye, texture, bounds, self.depth_image_index);
+        Some(SubmittedDepth::new(extent, info))
+    }
+
+    fn release_depth_image(&mut self) {
+        if self.depth_image_acquired {
+            self.depth_swapchain_data
+                .as_mut()
+                .expect("Depth image is acquired, yet we have no depth swapchain?")
+                .swapchain
+                .release_image()
+                .unwrap();
+            self.depth_image_acquired = false;
+        }
+    }
+
+    /// Creates the swapchain backing fade layers. Its contents are never shown, since fades only
+    /// use the color bias.
+    fn ensure_fade_swapchain(&mut self, session_data: &SessionData)
+    where
+        for<'b> &'b openxr_data::GraphicalSession:
+            TryInto<&'b openxr_data::Session<G::Api>, Error: std::fmt::Display>,
+    {
+        if self.fade_swapchain.is_some() {
+            return;
+        }
+
+        let info = xr::SwapchainCreateInfo {
+            create_flags: xr::SwapchainCreateFlags::STATIC_IMAGE,
+            usage_flags: xr::SwapchainUsageFlags::COLOR_ATTACHMENT
+                | xr::SwapchainUsageFlags::SAMPLED,
+            format: session_data.preferred_swapchain_format::<G>(),
+            sample_count: 1,
+            width: 1,
+            height: 1,
+            face_count: 1,
+            array_size: 1,
+            mip_count: 1,
+        };
+        let mut swapchain = match session_data.create_swapchain(&info) {
+            Ok(swapchain) => swapchain,
+            Err(e) => {
+                error!("Failed to create fade swapchain: {e}");
+                return;
+            }
+        };
+
+        // A swapchain can't be used in a layer until an image has been released.
+        swapchain
+            .acquire_image()
+            .expect("Failed to acquire fade swapchain image");
+        swapchain
+            .wait_image(xr::Duration::INFINITE)
+            .expect("Failed to wait for fade swapchain image");
+        swapchain
+            .release_image()
+            .expect("Failed to release fade swapchain image");
+        self.fade_swapchain = Some(swapchain);
+    }
+
     fn wait_frame(&mut self) -> (xr::Time, i64) {
         let frame_state = {
             tracy_span!("wait frame");
@@ -1199,6 +1537,8 @@ impl<G: GraphicsBackend> FrameController<G> {
             self.acquire_swapchain_image();
         }
 
+        self.release_depth_image();
+
         {
             tracy_span!("begin frame");
             self.stream.begin().expect("Couldn't begin frame");
@@ -1213,6 +1553,7 @@ impl<G: GraphicsBackend> FrameController<G> {
         session_data: &SessionData,
         eye: vr::EVREye,
         texture: G::OpenVrTexture,
+        depth: Option<(G::OpenVrTexture, &vr::VRTextureDepthInfo_t)>,
         color_space: vr::EColorSpace,
         bounds: vr::VRTextureBounds_t,
         submit_flags: vr::EVRSubmitFlags,
@@ -1248,16 +1589,20 @@ impl<G: GraphicsBackend> FrameController<G> {
                         self.recreate_swapchain(session_data, new_info);
                     }
 
+                    let extent = self.backend.copy_texture_to_swapchain(
+                        eye,
+                        texture,
+                        color_space,
+                        bounds,
+                        self.image_index,
+                        submit_flags,
+                    );
                     SubmittedEye {
-                        extent: self.backend.copy_texture_to_swapchain(
-                            eye,
-                            texture,
-                            color_space,
-                            bounds,
-                            self.image_index,
-                            submit_flags,
-                        ),
+                        extent,
                         flip_vertically: bounds.vertically_flipped(),
+                        depth: depth.and_then(|(texture, info)| {
+                            self.submit_depth(session_data, eye, texture, info, bounds)
+                        }),
                     }
                 })
                 .or_else(|| {
@@ -1277,21 +1622,48 @@ impl<G: GraphicsBackend> FrameController<G> {
                 data.swapchain.release_image().unwrap();
             }
             self.image_acquired = false;
+            self.release_depth_image();
         }
 
         Ok(())
     }
 
+    /// Ends the frame, returning whether xrEndFrame succeeded.
     fn end_frame(
         &mut self,
         session_data: &SessionData,
         system: &System,
         display_time: xr::Time,
         overlays: Option<&OverlayMan>,
-    ) where
+        fades: FadeColors,
+    ) -> bool
+    where
         for<'b> &'b crate::overlay::AnySwapchainMap:
             TryInto<&'b crate::overlay::SwapchainMap<G::Api>, Error: std::fmt::Display>,
+        for<'b> &'b openxr_data::GraphicalSession:
+            TryInto<&'b openxr_data::Session<G::Api>, Error: std::fmt::Display>,
     {
+        // Chained to the fade layers, so these must outlive them.
+        let [background_fade, scene_fade] = [fades.background, fades.scene].map(|color| {
+            (color.a > 0.0).then_some(xr::sys::CompositionLayerColorScaleBiasKHR {
+                ty: xr::sys::CompositionLayerColorScaleBiasKHR::TYPE,
+                next: std::ptr::null(),
+                // Scale the quad's contents away, leaving just the (premultiplied) fade color.
+                color_scale: xr::Color4f::default(),
+                color_bias: xr::Color4f {
+                    r: color.r * color.a,
+                    g: color.g * color.a,
+                    b: color.b * color.a,
+                    a: color.a,
+                },
+            })
+        });
+        if background_fade.is_some() || scene_fade.is_some() {
+            self.ensure_fade_swapchain(session_data);
+        }
+
+        // Chained to the projection views, so these must outlive them.
+        let mut depth_infos: [Option<xr::sys::CompositionLayerDepthInfoKHR>; 2] = [None; 2];
         let mut proj_layer_views = Vec::new();
 
         if self.should_render
@@ -1303,6 +1675,31 @@ impl<G: GraphicsBackend> FrameController<G> {
                 .as_ref()
                 .expect("Swapchain data unexpectedly invalid on submit");
 
+            if let Some(depth_data) = self.depth_swapchain_data.as_ref() {
+                for (eye_index, (info, eye)) in
+                    depth_infos.iter_mut().zip(&self.eyes_submitted).enumerate()
+                {
+                    *info = eye.and_then(|eye| eye.depth).map(|depth| {
+                        xr::sys::CompositionLayerDepthInfoKHR {
+                            ty: xr::sys::CompositionLayerDepthInfoKHR::TYPE,
+                            next: std::ptr::null(),
+                            sub_image: xr::sys::SwapchainSubImage {
+                                swapchain: depth_data.swapchain.as_raw(),
+                                image_rect: xr::Rect2Di {
+                                    extent: depth.extent,
+                                    offset: xr::Offset2Di::default(),
+                                },
+                                image_array_index: eye_index as u32,
+                            },
+                            min_depth: depth.min_depth,
+                            max_depth: depth.max_depth,
+                            near_z: depth.near_z,
+                            far_z: depth.far_z,
+                        }
+                    });
+                }
+            }
+
             let crate::system::ViewData { flags, views } =
                 system.get_views(session_data.current_origin_as_reference_space());
             proj_layer_views = views
@@ -1325,6 +1722,7 @@ impl<G: GraphicsBackend> FrameController<G> {
                     let SubmittedEye {
                         extent,
                         flip_vertically,
+                        ..
                     } = self.eyes_submitted[eye_index]
                         .unwrap_or_else(|| panic!("Eye {eye_index} has not been submitted!"));
                     let mut fov = view.fov;
@@ -1340,39 +1738,212 @@ impl<G: GraphicsBackend> FrameController<G> {
                             offset: xr::Offset2Di::default(),
                         });
 
-                    xr::CompositionLayerProjectionView::new()
+                    let view = xr::CompositionLayerProjectionView::new()
                         .fov(fov)
                         .pose(pose)
-                        .sub_image(sub_image)
+                        .sub_image(sub_image);
+                    match &depth_infos[eye_index] {
+                        Some(depth_info) => {
+                            let mut raw = view.into_raw();
+                            raw.next =
+                                (depth_info as *const xr::sys::CompositionLayerDepthInfoKHR).cast();
+                            // SAFETY: depth_infos outlives the projection layer.
+                            unsafe { xr::CompositionLayerProjectionView::from_raw(raw) }
+                        }
+                        None => view,
+                    }
                 })
                 .collect()
         }
 
+        let passthrough = session_data.passthrough.lock().unwrap();
+        let passthrough_layer = passthrough
+            .as_ref()
+            .map(|p| xr::CompositionLayerPassthrough::from_xr_passthrough_layer(&p.layer));
+
         let mut proj_layer = None;
         if !proj_layer_views.is_empty() {
             trace!("projection layer present");
-            proj_layer = Some(
-                xr::CompositionLayerProjection::new()
-                    .space(session_data.tracking_space())
-                    .views(&proj_layer_views),
-            );
+            let mut layer = xr::CompositionLayerProjection::new()
+                .space(session_data.tracking_space())
+                .views(&proj_layer_views);
+            // OpenVR has no submit flag for eye buffer alpha - SteamVR treats the scene as
+            // opaque, and games commonly leave garbage in the alpha channel. The only time
+            // the alpha matters is when there's something underneath the scene.
+            if passthrough_layer.is_some() {
+                // Let the passthrough show through wherever the app didn't draw anything.
+                layer = layer.layer_flags(xr::CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA);
+            }
+            proj_layer = Some(layer);
         }
 
+        let [background_fade_layer, scene_fade_layer] =
+            [&background_fade, &scene_fade].map(|bias| {
+                let bias = bias.as_ref()?;
+                let swapchain = self.fade_swapchain.as_ref()?;
+                Some(fade_layer(session_data, swapchain, bias))
+            });
+
         let mut layers: Vec<&xr::CompositionLayerBase<_>> = Vec::new();
-        if let Some(l) = proj_layer.as_ref() {
+        if let Some(l) = passthrough_layer.as_ref() {
+            layers.push(l);
+        }
+        // The background fade stands in for the scene while the app isn't rendering, and the
+        // projection layer is opaque, so it's only useful without one.
+        match proj_layer.as_ref() {
+            Some(l) => layers.push(l),
+            None => {
+                if let Some(l) = background_fade_layer.as_ref() {
+                    layers.push(l);
+                }
+            }
+        }
+        if let Some(l) = scene_fade_layer.as_ref() {
             layers.push(l);
         }
         let overlay_layers;
         if let Some(overlay_man) = overlays {
-            overlay_layers = overlay_man.get_layers(session_data, self.app_fade_grid);
+            // The skybox stands in for the scene while the app isn't rendering (i.e. loading).
+            let render_skybox = self.app_fade_grid || proj_layer.is_none();
+            overlay_layers = overlay_man.get_layers(session_data, render_skybox);
             layers.extend(overlay_layers.iter().map(Deref::deref));
         }
 
-        self.stream
+        if let Err(e) = self
+            .stream
             .end(display_time, xr::EnvironmentBlendMode::OPAQUE, &layers)
-            .unwrap();
+        {
+            error!("Failed to end frame: {e}");
+            return false;
+        }
 
         trace!("frame submitted");
+        true
+    }
+}
+
+/// Returns a quad covering the user's view, colored entirely by `bias`.
+fn fade_layer<'a, G: xr::Graphics>(
+    session_data: &'a SessionData,
+    swapchain: &'a xr::Swapchain<G>,
+    bias: &'a xr::sys::CompositionLayerColorScaleBiasKHR,
+) -> xr::CompositionLayerQuad<'a, G> {
+    // Large enough to cover any field of view at this distance.
+    const FADE_QUAD_SIZE: f32 = 100.0;
+
+    let layer = xr::CompositionLayerQuad::new()
+        .space(&session_data.view_space)
+        .layer_flags(xr::CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+        .eye_visibility(xr::EyeVisibility::BOTH)
+        .sub_image(
+            xr::SwapchainSubImage::new()
+                .swapchain(swapchain)
+                .image_rect(xr::Rect2Di {
+                    offset: xr::Offset2Di::default(),
+                    extent: xr::Extent2Di {
+                        width: 1,
+                        height: 1,
+                    },
+                }),
+        )
+        .pose(xr::Posef {
+            orientation: xr::Quaternionf::IDENTITY,
+            position: xr::Vector3f {
+                x: 0.0,
+                y: 0.0,
+                z: -1.0,
+            },
+        })
+        .size(xr::Extent2Df {
+            width: FADE_QUAD_SIZE,
+            height: FADE_QUAD_SIZE,
+        });
+
+    let mut raw = layer.into_raw();
+    raw.next = (bias as *const xr::sys::CompositionLayerColorScaleBiasKHR).cast();
+    // SAFETY: The bias has the same lifetime as the rest of the layer.
+    unsafe { xr::CompositionLayerQuad::from_raw(raw) }
+}
+
+/// Returns the depth info of a texture submitted with Submit_TextureWithDepth.
+///
+/// # Safety
+/// `texture` must be the base of the structure described by `flags`.
+unsafe fn texture_depth_info(
+    texture: &vr::Texture_t,
+    flags: vr::EVRSubmitFlags,
+) -> &vr::VRTextureDepthInfo_t {
+    let texture: *const vr::Texture_t = texture;
+    // Submit_TextureWithMotion implies this too, and VRTextureWithMotion_t extends
+    // VRTextureWithPoseAndDepth_t.
+    if (flags & vr::EVRSubmitFlags::TextureWithPose).0 > 0 {
+        unsafe { &(*texture.cast::<vr::VRTextureWithPoseAndDepth_t>()).depth }
+    } else {
+        unsafe { &(*texture.cast::<vr::VRTextureWithDepth_t>()).depth }
+    }
+}
+
+/// Returns the size the application set on a Compositor_FrameTiming, if it's large enough for us to
+/// fill in.
+///
+/// # Safety
+/// `timing` must be null or point to a Compositor_FrameTiming with its size set.
+unsafe fn frame_timing_size(timing: *const vr::Compositor_FrameTiming) -> Option<usize> {
+    if timing.is_null() || !timing.is_aligned() {
+        return None;
+    }
+
+    let size = unsafe { (&raw const (*timing).m_nSize).read() } as usize;
+    fn ptr_size<T>(_: *const T) -> usize {
+        std::mem::size_of::<T>()
+    }
+    (size
+        >= offset_of!(vr::Compositor_FrameTiming, m_HmdPose)
+            + ptr_size(unsafe { &raw const (*timing).m_HmdPose }))
+    .then_some(size)
+}
+
+/// # Safety
+/// `timing` must point to a Compositor_FrameTiming that passed [`frame_timing_size`].
+unsafe fn write_frame_timing(timing: *mut vr::Compositor_FrameTiming, frame: &FrameTiming) {
+    // We're using raw pointers here because the Compositor_FrameTiming struct can be a
+    // varaible size, so we don't want to create a reference to a struct with an incorrect
+    // (to us) size, because that would be Undefined Behavior.
+    macro_rules! set {
+        ($member:ident, $value:expr) => {{
+            let ptr = &raw mut (*timing).$member;
+            ptr.write_unaligned($value)
+        }};
+    }
+
+    unsafe {
+        set!(m_nFrameIndex, frame.index);
+        set!(m_nNumFramePresents, (!frame.dropped).into());
+        set!(m_nNumMisPresented, 0);
+        set!(m_nNumDroppedFrames, frame.dropped.into());
+        set!(m_nReprojectionFlags, 0);
+        set!(m_flSystemTimeInSeconds, frame.system_time);
+
+        // TODO: These values are copy/pasted from OpenComposite, OpenXR doesn't give us any GPU
+        // timing information.
+        set!(m_flPreSubmitGpuMs, 8.0);
+        set!(m_flPostSubmitGpuMs, 1.0);
+        set!(m_flTotalRenderGpuMs, 9.0);
+        set!(m_flCompositorRenderGpuMs, 1.5);
+        set!(m_flCompositorRenderCpuMs, 3.0);
+        set!(m_flCompositorIdleCpuMs, 0.1);
+
+        set!(m_flClientFrameIntervalMs, frame.interval_ms);
+        set!(m_flPresentCallCpuMs, frame.present_ms);
+        set!(m_flWaitForPresentCpuMs, 0.0);
+        set!(m_flSubmitFrameMs, frame.present_ms);
+
+        set!(m_flWaitGetPosesCalledMs, 0.0);
+        set!(m_flNewPosesReadyMs, 0.0);
+        set!(m_flNewFrameReadyMs, 0.0); // second call to IVRCompositor::Submit
+        set!(m_flCompositorUpdateStartMs, 0.0);
+        set!(m_flCompositorUpdateEndMs, 0.0);
+        set!(m_flCompositorRenderStartMs, 0.0);
     }
 }
 
@@ -1519,6 +2090,27 @@ mod tests {
         ) -> openxr::Extent2Di {
             xr::Extent2Di::default()
         }
+
+        fn depth_swapchain_info_for_texture(
+            &self,
+            texture: Self::OpenVrTexture,
+            bounds: openvr::VRTextureBounds_t,
+        ) -> Option<openxr::SwapchainCreateInfo<Self::Api>> {
+            Some(xr::SwapchainCreateInfo {
+                usage_flags: xr::SwapchainUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                ..self.swapchain_info_for_texture(texture, bounds, vr::EColorSpace::Auto)
+            })
+        }
+
+        fn copy_depth_to_swapchain(
+            &self,
+            _eye: openvr::EVREye,
+            _texture: Self::OpenVrTexture,
+            _bounds: openvr::VRTextureBounds_t,
+            _image_index: usize,
+        ) -> openxr::Extent2Di {
+            xr::Extent2Di::default()
+        }
     }
 
     impl FakeGraphicsData {
@@ -1596,6 +2188,22 @@ mod tests {
             assert!(!ctrl.should_render);
         }
 
+        /// Sets up a real session and runs it until the runtime wants frames to be rendered, so
+        /// the next submitted frame will have a projection layer.
+        fn ensure_rendering(&self) {
+            self.ensure_real_session(false);
+            assert_eq!(self.submit(vr::EVREye::Left), None);
+            assert_eq!(self.submit(vr::EVREye::Right), None);
+            assert_eq!(self.wait_get_poses(), None);
+
+            let data = self.comp.openxr.session_data.get();
+            let lock = data.comp_data.0.lock().unwrap();
+            let DynFrameController::Fake(ctrl) = lock.as_ref().unwrap() else {
+                panic!("Frame controller was not set up or not faked!");
+            };
+            assert!(ctrl.should_render);
+        }
+
         #[track_caller]
         fn check_frame_state(&self, state: fakexr::FrameState) {
             let session = self.comp.openxr.session_data.get().session.as_raw();
@@ -1685,6 +2293,15 @@ mod tests {
         assert_eq!(f.submit(vr::EVREye::Left), None);
     }
 
+    #[test]
+    fn scene_focus_is_current_process() {
+        let f = Fixture::new();
+
+        assert!(f.comp.CanRenderScene());
+        assert_eq!(f.comp.GetCurrentSceneFocusProcess(), std::process::id());
+        assert_eq!(f.comp.GetLastFrameRenderer(), std::process::id());
+    }
+
     #[test]
     fn allow_waitgetposes_without_submit() {
         let f = Fixture::new();
@@ -1697,6 +2314,86 @@ mod tests {
         assert_eq!(f.wait_get_poses(), None);
     }
 
+    #[test]
+    fn pose_prediction_ids() {
+        let f = Fixture::new();
+
+        let get_ids = || {
+            let mut render_id = 0;
+            let mut game_id = 0;
+            assert_eq!(
+                vr::IVRCompositor029_Interface::GetLastPosePredictionIDs(
+                    &*f.comp,
+                    &mut render_id,
+                    &mut game_id
+                ),
+                None
+            );
+            assert_eq!(render_id, game_id);
+            render_id
+        };
+
+        let mut poses = [vr::TrackedDevicePose_t::default(); 2];
+        assert_eq!(
+            f.comp
+                .WaitGetPoses(poses.as_mut_ptr(), 2, std::ptr::null_mut(), 0),
+            None
+        );
+        let first_id = get_ids();
+        assert_eq!(f.wait_get_poses(), None);
+        let second_id = get_ids();
+        assert!(second_id > first_id);
+
+        let get_poses_for_frame = |id, poses: &mut [vr::TrackedDevicePose_t]| {
+            vr::IVRCompositor029_Interface::GetPosesForFrame(
+                &*f.comp,
+                id,
+                poses.as_mut_ptr(),
+                poses.len() as u32,
+            )
+        };
+        let mut stored_poses = [vr::TrackedDevicePose_t::default(); 2];
+        assert_eq!(get_poses_for_frame(first_id, &mut stored_poses), None);
+        for (pose, stored) in poses.iter().zip(&stored_poses) {
+            assert_eq!(pose.bPoseIsValid, stored.bPoseIsValid);
+            assert_eq!(
+                pose.mDeviceToAbsoluteTracking.m,
+                stored.mDeviceToAbsoluteTracking.m
+            );
+        }
+
+        assert_eq!(
+            get_poses_for_frame(second_id + 1, &mut stored_poses),
+            RequestFailed
+        );
+
+        // Only the poses that were asked for are stored, the rest are reported as invalid.
+        let mut more_poses = [vr::TrackedDevicePose_t {
+            bPoseIsValid: true,
+            ..Default::default()
+        }; 4];
+        assert_eq!(get_poses_for_frame(first_id, &mut more_poses), None);
+        assert!(more_poses[2..].iter().all(|pose| !pose.bPoseIsValid));
+
+        // Old predictions' buffers are reused.
+        for _ in 0..PosePredictions::MAX_FRAMES {
+            assert_eq!(
+                f.comp
+                    .WaitGetPoses(poses.as_mut_ptr(), 2, std::ptr::null_mut(), 0),
+                None
+            );
+        }
+        let oldest = f.comp.pose_predictions.lock().unwrap().frames[0].1.as_ptr();
+        assert_eq!(
+            f.comp
+                .WaitGetPoses(poses.as_mut_ptr(), 2, std::ptr::null_mut(), 0),
+            None
+        );
+        let predictions = f.comp.pose_predictions.lock().unwrap();
+        assert_eq!(predictions.frames.len(), PosePredictions::MAX_FRAMES);
+        assert_eq!(predictions.frames.back().unwrap().1.as_ptr(), oldest);
+    }
+
     #[test]
     fn recreate_swapchain() {
         let f = Fixture::new();
@@ -1733,26 +2430,74 @@ mod tests {
     #[test]
     fn get_frame_timing() {
         let f = Fixture::new();
-        assert_eq!(f.wait_get_poses(), None);
-        assert_eq!(f.submit(vr::EVREye::Left), None);
-        assert_eq!(f.submit(vr::EVREye::Right), None);
-
         let mut timing = MaybeUninit::new(vr::Compositor_FrameTiming::default());
         unsafe {
             (&raw mut (*timing.as_mut_ptr()).m_nSize)
                 .write(std::mem::size_of::<vr::Compositor_FrameTiming>() as u32);
         }
-        assert!(f.comp.GetFrameTiming(timing.as_mut_ptr(), 1));
+        // Nothing has been presented yet.
+        assert!(!f.comp.GetFrameTiming(timing.as_mut_ptr(), 0));
+
+        assert_eq!(f.wait_get_poses(), None);
+        assert_eq!(f.submit(vr::EVREye::Left), None);
+        assert_eq!(f.submit(vr::EVREye::Right), None);
+        assert_eq!(f.wait_get_poses(), None);
+
+        assert!(f.comp.GetFrameTiming(timing.as_mut_ptr(), 0));
         let small_size = std::mem::offset_of!(vr::Compositor_FrameTiming, m_HmdPose)
             + std::mem::size_of::<vr::TrackedDevicePose_t>();
         unsafe {
             (&raw mut (*timing.as_mut_ptr()).m_nSize).write(small_size as u32);
         }
-        assert!(f.comp.GetFrameTiming(timing.as_mut_ptr(), 1));
+        assert!(f.comp.GetFrameTiming(timing.as_mut_ptr(), 0));
         unsafe {
             (&raw mut (*timing.as_mut_ptr()).m_nSize).write(0);
         }
-        assert!(!f.comp.GetFrameTiming(timing.as_mut_ptr(), 1));
+        assert!(!f.comp.GetFrameTiming(timing.as_mut_ptr(), 0));
+    }
+
+    #[test]
+    fn frame_timing_history() {
+        let f = Fixture::new();
+        let new_timing = || {
+            let mut timing = vr::Compositor_FrameTiming::default();
+            timing.m_nSize = std::mem::size_of::<vr::Compositor_FrameTiming>() as u32;
+            timing
+        };
+
+        for _ in 0..4 {
+            assert_eq!(f.wait_get_poses(), None);
+            assert_eq!(f.submit(vr::EVREye::Left), None);
+            assert_eq!(f.submit(vr::EVREye::Right), None);
+        }
+        assert_eq!(f.wait_get_poses(), None);
+
+        let mut latest = new_timing();
+        assert!(f.comp.GetFrameTiming(&mut latest, 0));
+        assert_ne!(latest.m_nFrameIndex, 0);
+        assert!(latest.m_flClientFrameIntervalMs > 0.0);
+
+        let mut previous = new_timing();
+        assert!(f.comp.GetFrameTiming(&mut previous, 1));
+        assert_eq!(previous.m_nFrameIndex + 1, latest.m_nFrameIndex);
+        assert!(previous.m_flSystemTimeInSeconds <= latest.m_flSystemTimeInSeconds);
+
+        // Asking for more frames than we have fails.
+        let mut oldest = new_timing();
+        assert!(!f.comp.GetFrameTiming(&mut oldest, 1000));
+
+        let mut timings = [new_timing(); 3];
+        assert_eq!(
+            f.comp
+                .GetFrameTimings(timings.as_mut_ptr(), timings.len() as u32),
+            3
+        );
+        assert!(
+            timings
+                .windows(2)
+                .all(|w| w[0].m_nFrameIndex + 1 == w[1].m_nFrameIndex)
+        );
+        assert_eq!(timings[2].m_nFrameIndex, latest.m_nFrameIndex);
     }
 
     #[test]
@@ -1959,6 +2704,132 @@ mod tests {
         assert_eq!(f.submit(vr::EVREye::Right), None);
     }
 
+    #[test]
+    fn passthrough_layer() {
+        let f = Fixture::new();
+        f.ensure_rendering();
+
+        let submit_frame = || {
+            assert_eq!(f.submit(vr::EVREye::Left), None);
+            assert_eq!(f.submit(vr::EVREye::Right), None);
+            assert_eq!(f.wait_get_poses(), None);
+            let session = f.comp.openxr.session_data.get().session.as_raw();
+            fakexr::submitted_layers(session)
+        };
+
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+
+        assert!(f.comp.openxr.set_passthrough(true));
+        assert!(f.comp.openxr.passthrough_enabled());
+        // Passthrough goes under the projection layer.
+        assert_eq!(
+            submit_frame(),
+            [
+                xr::StructureType::COMPOSITION_LAYER_PASSTHROUGH_FB,
+                xr::StructureType::COMPOSITION_LAYER_PROJECTION
+            ]
+        );
+
+        assert!(f.comp.openxr.set_passthrough(false));
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+    }
+
+    #[test]
+    fn projection_layer_alpha_blending() {
+        let f = Fixture::new();
+        f.ensure_rendering();
+
+        let submit_frame = || {
+            assert_eq!(f.submit(vr::EVREye::Left), None);
+            assert_eq!(f.submit(vr::EVREye::Right), None);
+            assert_eq!(f.wait_get_poses(), None);
+            let session = f.comp.openxr.session_data.get().session.as_raw();
+            let layers = fakexr::submitted_layers(session);
+            let flags = fakexr::submitted_layer_flags(session);
+            let proj = layers
+                .iter()
+                .position(|ty| *ty == xr::StructureType::COMPOSITION_LAYER_PROJECTION)
+                .expect("projection layer should be submitted");
+            flags[proj]
+        };
+
+        assert!(!submit_frame().contains(xr::CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA));
+
+        assert!(f.comp.openxr.set_passthrough(true));
+        assert!(submit_frame().contains(xr::CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA));
+
+        assert!(f.comp.openxr.set_passthrough(false));
+        assert!(!submit_frame().contains(xr::CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA));
+    }
+
+    #[test]
+    fn depth_layer() {
+        let f = Fixture::new();
+        f.ensure_rendering();
+
+        let (near_z, far_z) = (0.1, 100.0);
+        let depth = vr::VRTextureDepthInfo_t {
+            handle: FakeGraphicsData::texture(&f.vk).handle,
+            mProjection: vr::HmdMatrix44_t {
+                m: [
+                    [1.0, 0.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0, 0.0],
+                    [
+                        0.0,
+                        0.0,
+                        -far_z / (far_z - near_z),
+                        -far_z * near_z / (far_z - near_z),
+                    ],
+                    [0.0, 0.0, -1.0, 0.0],
+                ],
+            },
+            vRange: vr::HmdVector2_t { v: [0.0, 1.0] },
+        };
+        let submit_with_depth = |eye| {
+            let texture = vr::VRTextureWithDepth_t {
+                _base: FakeGraphicsData::texture(&f.vk),
+                depth,
+            };
+            f.comp.Submit(
+                eye,
+                &texture._base,
+                std::ptr::null(),
+                vr::EVRSubmitFlags::TextureWithDepth,
+            )
+        };
+
+        assert_eq!(submit_with_depth(vr::EVREye::Left), None);
+        assert_eq!(submit_with_depth(vr::EVREye::Right), None);
+        assert_eq!(f.wait_get_poses(), None);
+
+        let session = f.comp.openxr.session_data.get().session.as_raw();
+        assert_eq!(
+            fakexr::submitted_layers(session),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+        let submitted = fakexr::submitted_depth(session);
+        assert_eq!(submitted.len(), 2);
+        for depth in submitted {
+            let depth = depth.expect("Depth info should be chained to each view");
+            assert_eq!(depth.min_depth, 0.0);
+            assert_eq!(depth.max_depth, 1.0);
+            assert!((depth.near_z - near_z).abs() < 1e-4, "{}", depth.near_z);
+            assert!((depth.far_z - far_z).abs() < 1e-1, "{}", depth.far_z);
+        }
+
+        // Plain submissions shouldn't chain any depth.
+        assert_eq!(f.submit(vr::EVREye::Left), None);
+        assert_eq!(f.submit(vr::EVREye::Right), None);
+        assert_eq!(f.wait_get_poses(), None);
+        assert_eq!(fakexr::submitted_depth(session), [None, None]);
+    }
+
     #[test]
     fn submit_overlay_without_projection_layer() {
         use crate::overlay::OverlayMan;
@@ -1989,4 +2860,208 @@ mod tests {
         f.comp.PostPresentHandoff();
         f.check_frame_state(fakexr::FrameState::Ended);
     }
+
+    #[test]
+    fn fade_without_color_scale_bias() {
+        fakexr::disable_extensions(&[c"XR_KHR_composition_layer_color_scale_bias"]);
+        let f = Fixture::new();
+        f.ensure_rendering();
+
+        // Without the extension the fade can't be drawn, but it's still tracked for the game.
+        f.comp.FadeToColor(0.0, 1.0, 0.0, 0.0, 1.0, false);
+        assert_eq!(f.comp.GetCurrentFadeColor(false).a, 1.0);
+        assert_eq!(f.submit(vr::EVREye::Left), None);
+        assert_eq!(f.submit(vr::EVREye::Right), None);
+        assert_eq!(f.wait_get_poses(), None);
+        assert_eq!(
+            fakexr::submitted_layers(f.comp.openxr.session_data.get().session.as_raw()),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+    }
+
+    #[test]
+    fn fade_to_color() {
+        let f = Fixture::new();
+        f.ensure_rendering();
+
+        let session = f.comp.openxr.session_data.get().session.as_raw();
+        let submit_frame = || {
+            assert_eq!(f.submit(vr::EVREye::Left), None);
+            assert_eq!(f.submit(vr::EVREye::Right), None);
+            assert_eq!(f.wait_get_poses(), None);
+            fakexr::submitted_layers(session)
+        };
+
+        assert_eq!(f.comp.GetCurrentFadeColor(false).a, 0.0);
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+
+        f.comp.FadeToColor(10.0, 1.0, 0.0, 0.0, 1.0, false);
+        let mut last_alpha = 0.0;
+        for _ in 0..3 {
+            crate::clock::advance(std::time::Duration::from_millis(10));
+            assert_eq!(
+                submit_frame(),
+                [
+                    xr::StructureType::COMPOSITION_LAYER_PROJECTION,
+                    xr::StructureType::COMPOSITION_LAYER_QUAD
+                ]
+            );
+            let color = f.comp.GetCurrentFadeColor(false);
+            assert!(
+                color.a > last_alpha && color.a < 1.0,
+                "alpha should ramp up (was {last_alpha}, now {})",
+                color.a
+            );
+            assert!(color.r > 0.0);
+            last_alpha = color.a;
+        }
+        // Background is faded separately.
+        assert_eq!(f.comp.GetCurrentFadeColor(true).a, 0.0);
+
+        // Zero duration fades are immediate.
+        f.comp.FadeToColor(0.0, 0.0, 0.0, 0.0, 0.0, false);
+        assert_eq!(f.comp.GetCurrentFadeColor(false).a, 0.0);
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+
+        // The background fade doesn't cover the scene...
+        f.comp.FadeToColor(0.0, 0.0, 0.0, 0.0, 1.0, true);
+        assert_eq!(f.comp.GetCurrentFadeColor(true).a, 1.0);
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+
+        // ...it's only shown when the app doesn't submit one.
+        f.comp.PostPresentHandoff();
+        assert_eq!(
+            fakexr::submitted_layers(session),
+            [xr::StructureType::COMPOSITION_LAYER_QUAD]
+        );
+
+        f.comp.FadeGrid(0.0, true);
+        assert_eq!(f.comp.GetCurrentGridAlpha(), 1.0);
+        f.comp.FadeGrid(0.0, false);
+        assert_eq!(f.comp.GetCurrentGridAlpha(), 0.0);
+    }
+
+    #[test]
+    fn skybox_override() {
+        use crate::overlay::OverlayMan;
+
+        let f = Fixture::new();
+        let overlays = Arc::new(OverlayMan::new(f.comp.openxr.clone(), &Injector::default()));
+        f.comp.overlays.set(Arc::downgrade(&overlays));
+        overlays.compositor.set(Arc::downgrade(&f.comp));
+        f.ensure_rendering();
+
+        let textures = [(); 6].map(|_| FakeGraphicsData::texture(&f.vk));
+        assert_eq!(f.comp.SetSkyboxOverride(textures.as_ptr(), 6), None);
+
+        let session = f.comp.openxr.session_data.get().session.as_raw();
+        // No frames from the app (i.e. it's loading), so the skybox should be shown.
+        assert_eq!(f.wait_get_poses(), None);
+        assert_eq!(
+            fakexr::submitted_layers(session),
+            [xr::StructureType::COMPOSITION_LAYER_QUAD; 6]
+        );
+
+        // The scene replaces the skybox once the app renders.
+        assert_eq!(f.submit(vr::EVREye::Left), None);
+        assert_eq!(f.submit(vr::EVREye::Right), None);
+        assert_eq!(f.wait_get_poses(), None);
+        assert_eq!(
+            fakexr::submitted_layers(session),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+
+        f.comp.ClearSkyboxOverride();
+        assert_eq!(f.wait_get_poses(), None);
+        assert!(fakexr::submitted_layers(session).is_empty());
+    }
+
+    #[test]
+    fn dashboard_overlay_layers() {
+        use crate::overlay::OverlayMan;
+        use vr::IVROverlay027_Interface;
+
+        let f = Fixture::new();
+        let overlays = Arc::new(OverlayMan::new(f.comp.openxr.clone(), &Injector::default()));
+        f.comp.overlays.set(Arc::downgrade(&overlays));
+        overlays.compositor.set(Arc::downgrade(&f.comp));
+        f.ensure_rendering();
+
+        let mut main = 0;
+        let mut thumbnail = 0;
+        assert_eq!(
+            overlays.CreateDashboardOverlay(
+                c"test_dashboard".as_ptr(),
+                c"TestDashboard".as_ptr(),
+                &mut main,
+                &mut thumbnail
+            ),
+            vr::EVROverlayError::None
+        );
+        assert_ne!(main, thumbnail);
+
+        let mut found = 0;
+        assert_eq!(
+            overlays.FindOverlay(c"test_dashboard".as_ptr(), &mut found),
+            vr::EVROverlayError::None
+        );
+        assert_eq!(found, main);
+
+        assert_eq!(f.wait_get_poses(), None);
+        let texture = FakeGraphicsData::texture(&f.vk);
+        assert_eq!(
+            overlays.SetOverlayTexture(main, &texture),
+            vr::EVROverlayError::None
+        );
+        assert_eq!(
+            overlays.SetOverlayTexture(thumbnail, &texture),
+            vr::EVROverlayError::None
+        );
+
+        let submit_frame = || {
+            assert_eq!(f.submit(vr::EVREye::Left), None);
+            assert_eq!(f.submit(vr::EVREye::Right), None);
+            assert_eq!(f.wait_get_poses(), None);
+            let session = f.comp.openxr.session_data.get().session.as_raw();
+            fakexr::submitted_layers(session)
+        };
+
+        // Dashboard overlays aren't shown until the dashboard is, and thumbnails never are.
+        assert!(!overlays.IsDashboardVisible());
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+
+        overlays.ShowDashboard(c"test_dashboard".as_ptr());
+        assert!(overlays.IsDashboardVisible());
+        assert!(overlays.IsActiveDashboardOverlay(main));
+        assert_eq!(
+            submit_frame(),
+            [
+                xr::StructureType::COMPOSITION_LAYER_PROJECTION,
+                xr::StructureType::COMPOSITION_LAYER_QUAD
+            ]
+        );
+
+        assert_eq!(
+            overlays.DestroyOverlay(thumbnail),
+            vr::EVROverlayError::ThumbnailCantBeDestroyed
+        );
+        assert_eq!(overlays.DestroyOverlay(main), vr::EVROverlayError::None);
+        assert!(!overlays.IsDashboardVisible());
+        assert_eq!(
+            submit_frame(),
+            [xr::StructureType::COMPOSITION_LAYER_PROJECTION]
+        );
+    }
 }
@@ -5,7 +5,7 @@ use derive_more::{From, TryInto};
 pub use gl::GlData;
 use openvr as vr;
 use openxr as xr;
-pub use vulkan::VulkanData;
+pub use vulkan::{VulkanData, physical_device_index, physical_device_luid};
 
 pub trait GraphicsBackend: Into<SupportedBackend> {
     type Api: xr::Graphics + 'static;
@@ -48,6 +48,31 @@ pub trait GraphicsBackend: Into<SupportedBackend> {
         bounds: vr::VRTextureBounds_t,
         image_index: usize,
     ) -> xr::Extent2Di;
+
+    /// Returns None if this backend can't submit the given depth texture.
+    fn depth_swapchain_info_for_texture(
+        &self,
+        _texture: Self::OpenVrTexture,
+        _bounds: vr::VRTextureBounds_t,
+    ) -> Option<xr::SwapchainCreateInfo<Self::Api>> {
+        None
+    }
+
+    fn store_depth_swapchain_images(
+        &mut self,
+        _images: Vec<<Self::Api as xr::Graphics>::SwapchainImage>,
+    ) {
+    }
+
+    fn copy_depth_to_swapchain(
+        &self,
+        _eye: vr::EVREye,
+        _texture: Self::OpenVrTexture,
+        _bounds: vr::VRTextureBounds_t,
+        _image_index: usize,
+    ) -> xr::Extent2Di {
+        unreachable!("Backend does not support depth submission")
+    }
 }
 
 #[derive(macros::Backends, TryInto, From)]
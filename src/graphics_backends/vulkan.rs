@@ -15,6 +15,12 @@ struct RealSessionData {
     overlay_pipeline: Option<PipelineData>,
 }
 
+struct DepthSessionData {
+    images: Vec<vk::Image>,
+    pool: vk::CommandPool,
+    bufs: Vec<vk::CommandBuffer>,
+}
+
 pub struct VulkanData {
     _entry: ash::Entry,
     pub instance: ash::Instance,
@@ -23,6 +29,7 @@ pub struct VulkanData {
     pub queue: vk::Queue,
     pub queue_family_index: u32,
     real_data: Option<RealSessionData>,
+    depth_data: Option<DepthSessionData>,
 }
 
 impl Drop for VulkanData {
@@ -39,6 +46,9 @@ impl Drop for VulkanData {
             // Real session - the handles come from the app, only destroy the command pool we created
             Some(data) => unsafe {
                 self.device.destroy_command_pool(data.pool, None);
+                if let Some(data) = &self.depth_data {
+                    self.device.destroy_command_pool(data.pool, None);
+                }
                 if let Some(data) = &data.overlay_pipeline {
                     self.device.destroy_pipeline(data.pipeline, None);
                     self.device.destroy_pipeline_layout(data.layout, None);
@@ -97,27 +107,7 @@ impl GraphicsBackend for VulkanData {
     }
     fn store_swapchain_images(&mut self, images: Vec<u64>, format: u32) {
         let images: Vec<vk::Image> = images.into_iter().map(vk::Image::from_raw).collect();
-        let pool = unsafe {
-            self.device
-                .create_command_pool(
-                    &vk::CommandPoolCreateInfo::default()
-                        .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
-                        .queue_family_index(self.queue_family_index),
-                    None,
-                )
-                .unwrap()
-        };
-        let bufs = unsafe {
-            self.device
-                .allocate_command_buffers(
-                    &vk::CommandBufferAllocateInfo::default()
-                        .command_pool(pool)
-                        .level(vk::CommandBufferLevel::PRIMARY)
-                        // We have to copy 2 eyes per swapchain image
-                        .command_buffer_count(images.len() as u32 * 2),
-                )
-                .unwrap()
-        };
+        let (pool, bufs) = self.create_eye_command_buffers(images.len());
 
         if let Some(data) = self.real_data.replace(RealSessionData {
             images,
@@ -311,6 +301,139 @@ impl GraphicsBackend for VulkanData {
         }
     }
 
+    fn depth_swapchain_info_for_texture(
+        &self,
+        texture: *const vr::VRVulkanTextureData_t,
+        bounds: vr::VRTextureBounds_t,
+    ) -> Option<xr::SwapchainCreateInfo<Self::Api>> {
+        let texture = unsafe { texture.as_ref() }.unwrap();
+        if texture.m_nSampleCount > 1 {
+            crate::warn_once!("Multisampled depth textures are not supported");
+            return None;
+        }
+
+        let (extent, _) = texture_extent_from_bounds(texture, bounds);
+        Some(xr::SwapchainCreateInfo {
+            create_flags: xr::SwapchainCreateFlags::EMPTY,
+            usage_flags: xr::SwapchainUsageFlags::DEPTH_STENCIL_ATTACHMENT
+                | xr::SwapchainUsageFlags::TRANSFER_DST,
+            format: texture.m_nFormat,
+            sample_count: 1,
+            width: extent.width,
+            height: extent.height,
+            face_count: 1,
+            array_size: 2,
+            mip_count: 1,
+        })
+    }
+
+    fn store_depth_swapchain_images(&mut self, images: Vec<u64>) {
+        let images: Vec<vk::Image> = images.into_iter().map(vk::Image::from_raw).collect();
+        let (pool, bufs) = self.create_eye_command_buffers(images.len());
+
+        if let Some(data) = self
+            .depth_data
+            .replace(DepthSessionData { images, pool, bufs })
+        {
+            unsafe {
+                self.device.destroy_command_pool(data.pool, None);
+            }
+        }
+    }
+
+    fn copy_depth_to_swapchain(
+        &self,
+        eye: vr::EVREye,
+        texture: *const vr::VRVulkanTextureData_t,
+        bounds: vr::VRTextureBounds_t,
+        image_index: usize,
+    ) -> xr::Extent2Di {
+        let texture = unsafe { &*texture };
+        let data = self.depth_data.as_ref().unwrap();
+        let swapchain_image = data.images[image_index];
+        let buf = data.bufs[2 * image_index + eye as usize];
+
+        let (extent, offset) = texture_extent_from_bounds(texture, bounds);
+        let format = vk::Format::from_raw(texture.m_nFormat as _);
+
+        self.record_commands(buf, || unsafe {
+            // Layout transitions have to cover the stencil aspect too, if there is one.
+            let swapchain_res = vk::ImageSubresourceRange {
+                aspect_mask: depth_format_aspects(format),
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: eye as u32,
+                layer_count: 1,
+            };
+
+            self.device.cmd_pipeline_barrier(
+                buf,
+                vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[vk::ImageMemoryBarrier {
+                    src_access_mask: vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                    dst_access_mask: vk::AccessFlags::TRANSFER_WRITE,
+                    old_layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                    new_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    image: swapchain_image,
+                    subresource_range: swapchain_res,
+                    ..Default::default()
+                }],
+            );
+
+            let subresource = vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::DEPTH,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            };
+
+            self.device.cmd_copy_image(
+                buf,
+                vk::Image::from_raw(texture.m_nImage),
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                swapchain_image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[vk::ImageCopy {
+                    src_subresource: subresource,
+                    src_offset: offset,
+                    dst_subresource: vk::ImageSubresourceLayers {
+                        base_array_layer: eye as u32,
+                        ..subresource
+                    },
+                    dst_offset: vk::Offset3D::default(),
+                    extent,
+                }],
+            );
+
+            self.device.cmd_pipeline_barrier(
+                buf,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[vk::ImageMemoryBarrier {
+                    src_access_mask: vk::AccessFlags::TRANSFER_WRITE,
+                    dst_access_mask: vk::AccessFlags::empty(),
+                    old_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    new_layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                    image: swapchain_image,
+                    subresource_range: swapchain_res,
+                    ..Default::default()
+                }],
+            );
+        });
+
+        xr::Extent2Di {
+            width: extent.width as _,
+            height: extent.height as _,
+        }
+    }
+
     fn copy_overlay_to_swapchain(
         &mut self,
         texture: *const vr::VRVulkanTextureData_t,
@@ -462,6 +585,35 @@ impl GraphicsBackend for VulkanData {
     }
 }
 impl VulkanData {
+    /// Creates a command pool with a buffer for each eye of each swapchain image.
+    fn create_eye_command_buffers(
+        &self,
+        image_count: usize,
+    ) -> (vk::CommandPool, Vec<vk::CommandBuffer>) {
+        let pool = unsafe {
+            self.device
+                .create_command_pool(
+                    &vk::CommandPoolCreateInfo::default()
+                        .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+                        .queue_family_index(self.queue_family_index),
+                    None,
+                )
+                .unwrap()
+        };
+        let bufs = unsafe {
+            self.device
+                .allocate_command_buffers(
+                    &vk::CommandBufferAllocateInfo::default()
+                        .command_pool(pool)
+                        .level(vk::CommandBufferLevel::PRIMARY)
+                        // We have to copy 2 eyes per swapchain image
+                        .command_buffer_count(image_count as u32 * 2),
+                )
+                .unwrap()
+        };
+        (pool, bufs)
+    }
+
     pub fn record_commands(&self, buf: vk::CommandBuffer, cmds: impl FnOnce()) {
         unsafe {
             self.device
@@ -511,41 +663,15 @@ impl VulkanData {
             queue: vk::Queue::from_raw(data.m_pQueue as _),
             queue_family_index: data.m_nQueueFamilyIndex,
             real_data: Default::default(),
+            depth_data: Default::default(),
         }
     }
 
     pub fn new_temporary(xr_instance: &xr::Instance, system_id: xr::SystemId) -> Self {
         let entry = new_entry();
-
-        let inst_exts = xr_instance
-            .vulkan_legacy_instance_extensions(system_id)
-            .unwrap();
-        let inst_exts: Vec<CString> = inst_exts
-            .split_ascii_whitespace()
-            .map(|ext| CString::new(ext).unwrap())
-            .collect();
-        let inst_exts: Vec<*const c_char> = inst_exts.iter().map(|ext| ext.as_ptr()).collect();
-
-        let instance = unsafe {
-            entry
-                .create_instance(
-                    &vk::InstanceCreateInfo::default()
-                        .application_info(
-                            &vk::ApplicationInfo::default()
-                                .api_version(vk::API_VERSION_1_0)
-                                .application_name(c"XRizer temporary session"),
-                        )
-                        .enabled_extension_names(&inst_exts),
-                    None,
-                )
-                .expect("Failed to create temporary Vulkan instance")
-        };
-
-        let physical_device = vk::PhysicalDevice::from_raw(unsafe {
-            xr_instance
-                .vulkan_graphics_device(system_id, instance.handle().as_raw() as _)
-                .expect("Failed to get temporary Vulkan physical device") as _
-        });
+        let instance =
+            create_temporary_instance(&entry, xr_instance, system_id, vk::API_VERSION_1_0);
+        let physical_device = temporary_physical_device(&instance, xr_instance, system_id);
         let dev_exts = xr_instance
             .vulkan_legacy_device_extensions(system_id)
             .unwrap();
@@ -591,6 +717,7 @@ impl VulkanData {
             queue,
             queue_family_index,
             real_data: Default::default(),
+            depth_data: Default::default(),
         }
     }
 }
@@ -874,6 +1001,17 @@ fn get_colorspace_corrected_format(format: vk::Format, color_space: vr::EColorSp
     }
 }
 
+fn depth_format_aspects(format: vk::Format) -> vk::ImageAspectFlags {
+    match format {
+        vk::Format::D16_UNORM_S8_UINT
+        | vk::Format::D24_UNORM_S8_UINT
+        | vk::Format::D32_SFLOAT_S8_UINT => {
+            vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL
+        }
+        _ => vk::ImageAspectFlags::DEPTH,
+    }
+}
+
 fn texture_extent_from_bounds(
     texture: &vr::VRVulkanTextureData_t,
     bounds: vr::VRTextureBounds_t,
@@ -897,6 +1035,91 @@ fn texture_extent_from_bounds(
     )
 }
 
+/// The LUID of the physical device the runtime wants us to render with, which is how D3D
+/// applications identify the adapter to use. None if the driver doesn't report one.
+pub fn physical_device_luid(xr_instance: &xr::Instance, system_id: xr::SystemId) -> Option<u64> {
+    let entry = new_entry();
+    // vkGetPhysicalDeviceProperties2 is core in 1.1
+    let instance = create_temporary_instance(&entry, xr_instance, system_id, vk::API_VERSION_1_1);
+    let physical_device = temporary_physical_device(&instance, xr_instance, system_id);
+
+    let luid = device_luid(&instance, physical_device);
+    unsafe { instance.destroy_instance(None) };
+    luid
+}
+
+/// The index of the adapter with the runtime's physical device's LUID, out of the Vulkan devices
+/// (which is what DXVK and VKD3D enumerate as DXGI adapters). None if the driver doesn't report
+/// LUIDs.
+pub fn physical_device_index(xr_instance: &xr::Instance, system_id: xr::SystemId) -> Option<usize> {
+    let entry = new_entry();
+    let instance = create_temporary_instance(&entry, xr_instance, system_id, vk::API_VERSION_1_1);
+    let physical_device = temporary_physical_device(&instance, xr_instance, system_id);
+
+    let index = device_luid(&instance, physical_device).and_then(|luid| {
+        unsafe { instance.enumerate_physical_devices() }
+            .inspect_err(|e| warn!("Failed to enumerate physical devices: {e}"))
+            .unwrap_or_default()
+            .into_iter()
+            .position(|device| device_luid(&instance, device) == Some(luid))
+    });
+    unsafe { instance.destroy_instance(None) };
+    index
+}
+
+/// Requires a Vulkan 1.1 instance.
+fn device_luid(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> Option<u64> {
+    let mut id_properties = vk::PhysicalDeviceIDProperties::default();
+    let mut properties = vk::PhysicalDeviceProperties2::default().push_next(&mut id_properties);
+    unsafe { instance.get_physical_device_properties2(physical_device, &mut properties) };
+
+    (id_properties.device_luid_valid == vk::TRUE)
+        .then(|| u64::from_ne_bytes(id_properties.device_luid))
+}
+
+fn create_temporary_instance(
+    entry: &ash::Entry,
+    xr_instance: &xr::Instance,
+    system_id: xr::SystemId,
+    api_version: u32,
+) -> ash::Instance {
+    let inst_exts = xr_instance
+        .vulkan_legacy_instance_extensions(system_id)
+        .unwrap();
+    let inst_exts: Vec<CString> = inst_exts
+        .split_ascii_whitespace()
+        .map(|ext| CString::new(ext).unwrap())
+        .collect();
+    let inst_exts: Vec<*const c_char> = inst_exts.iter().map(|ext| ext.as_ptr()).collect();
+
+    unsafe {
+        entry
+            .create_instance(
+                &vk::InstanceCreateInfo::default()
+                    .application_info(
+                        &vk::ApplicationInfo::default()
+                            .api_version(api_version)
+                            .application_name(c"XRizer temporary session"),
+                    )
+                    .enabled_extension_names(&inst_exts),
+                None,
+            )
+            .expect("Failed to create temporary Vulkan instance")
+    }
+}
+
+fn temporary_physical_device(
+    instance: &ash::Instance,
+    xr_instance: &xr::Instance,
+    system_id: xr::SystemId,
+) -> vk::PhysicalDevice {
+    vk::PhysicalDevice::from_raw(unsafe {
+        xr_instance
+            .vulkan_graphics_device(system_id, instance.handle().as_raw() as _)
+            .expect("Failed to get temporary Vulkan physical device") as _
+    })
+}
+
 fn new_entry() -> ash::Entry {
     #[cfg(not(test))]
     unsafe {
@@ -1,29 +1,35 @@
 mod action_manifest;
 mod custom_bindings;
 mod devices;
+mod eye_gaze;
 mod legacy;
 mod profiles;
 mod skeletal;
+mod tracker_role;
+mod vive_trackers;
 
 #[cfg(test)]
 mod tests;
 
 pub use devices::TrackedDeviceType;
+pub use eye_gaze::EYE_GAZE_INTERACTION_PROFILE;
 pub use profiles::InteractionProfile;
 
-use devices::{SubactionPaths, TrackedDevice, TrackedDeviceList};
+use devices::{PropertyOverrides, SubactionPaths, TrackedDevice, TrackedDeviceList};
 use skeletal::FingerState;
 use skeletal::SkeletalInputActionData;
 
 use crate::input::devices::ProfileData;
-use crate::input::profiles::RunWithProfile;
+use crate::input::profiles::{RunWithProfile, paths::DynSubpath};
 use crate::{
     AtomicF32,
     openxr_data::{self, Hand, OpenXrData, SessionData},
+    settings::XrizerSettings,
     tracy_span,
 };
+use action_manifest::{AnalogRemap, BindingInfo, BindingReport};
 use custom_bindings::{BoolBindingData, GrabActions};
-use glam::Quat;
+use glam::{Mat4, Quat, Vec3};
 use legacy::LegacyActionData;
 use log::{debug, info, trace, warn};
 use openvr as vr;
@@ -34,8 +40,8 @@ use std::ffi::{CStr, CString, c_char, c_void};
 use std::mem::ManuallyDrop;
 use std::ops::Deref;
 use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicU32};
 use std::sync::{Arc, Mutex, OnceLock, RwLock, RwLockReadGuard};
 
 new_key_type! {
@@ -44,6 +50,14 @@ new_key_type! {
     struct ActionSetKey;
 }
 
+/// How many frames to wait between syncing the info set while no controllers are connected.
+const INFO_SET_SYNC_INTERVAL: u32 = 10;
+/// How many frames to wait between checking for generic trackers being plugged in or removed.
+#[cfg(feature = "monado")]
+const TRACKER_POLL_INTERVAL: u32 = 30;
+/// Furthest ahead (or behind) of the current time, in seconds, that pose actions will be predicted.
+const MAX_POSE_PREDICTION: f32 = 1.0;
+
 #[derive(macros::InterfaceImpl)]
 #[interface = "IVRInput"]
 #[versions(011, 010, 007, 006, 005, 004)]
@@ -55,19 +69,45 @@ pub struct Input<C: openxr_data::Compositor> {
     right_hand_key: InputSourceKey,
     action_map: RwLock<SlotMap<ActionKey, Action>>,
     set_map: RwLock<SlotMap<ActionSetKey, String>>,
-    loaded_actions_path: OnceLock<PathBuf>,
+    /// Actions whose sets were only activated for a single hand in the last UpdateActionState.
+    restricted_actions: RwLock<SecondaryMap<ActionKey, Hand>>,
+    /// Actions whose inputs were claimed by a higher priority set in the last UpdateActionState.
+    /// None means the action is suppressed on both hands.
+    suppressed_actions: RwLock<SecondaryMap<ActionKey, Option<Hand>>>,
+    /// Sets that were active in the last UpdateActionState.
+    active_sets: Mutex<HashSet<ActionSetKey>>,
+    /// Preferred when both hands provide state for an action. Until the application sets one,
+    /// the left hand is checked first.
+    dominant_hand: RwLock<Option<Hand>>,
+    /// Whether the application's left hand is driven by the right controller and vice versa.
+    hands_swapped: AtomicBool,
+    /// Radius of the radial deadzone applied to thumbsticks, to hide stick drift. Zero disables it.
+    thumbstick_deadzone: AtomicF32,
+    /// Whether UpdateActionState activates every loaded set when the application passes none,
+    /// for games that never activate their sets.
+    activate_all_sets: AtomicBool,
+    /// Whether manifests set after the first one are merged with it, for mods that add their own
+    /// actions to the game's.
+    merge_action_manifests: AtomicBool,
+    loaded_actions_paths: Mutex<Vec<PathBuf>>,
+    manifest_cache: Mutex<HashMap<PathBuf, Arc<action_manifest::CachedManifest>>>,
     legacy_state: legacy::LegacyState,
-    skeletal_tracking_level: RwLock<vr::EVRSkeletalTrackingLevel>,
     estimated_finger_state: [Mutex<FingerState>; 2],
     subaction_paths: SubactionPaths,
     events: Mutex<VecDeque<InputEvent>>,
     loading_actions: AtomicBool,
+    /// Frames since controllers were last connected, used to throttle syncing the info set.
+    disconnected_frames: AtomicU32,
+    #[cfg(feature = "monado")]
+    tracker_poll_frames: AtomicU32,
+    property_overrides: RwLock<PropertyOverrides>,
+    tracker_roles: RwLock<tracker_role::TrackerRoleOverrides>,
 }
 
 struct InputEvent {
     ty: vr::EVREventType,
     index: vr::TrackedDeviceIndex_t,
-    data: vr::VREvent_Controller_t,
+    data: vr::VREvent_Data_t,
 }
 
 #[derive(Debug)]
@@ -108,6 +148,7 @@ impl<C: openxr_data::Compositor> Input<C> {
             &openxr.instance,
             subaction_paths.left,
             subaction_paths.right,
+            openxr.enabled_extensions.ext_eye_gaze_interaction,
         );
         openxr
             .session_data
@@ -117,17 +158,28 @@ impl<C: openxr_data::Compositor> Input<C> {
             .set(pose_data)
             .unwrap_or_else(|_| panic!("PoseData already setup"));
 
+        let settings = XrizerSettings::get();
         Self {
             openxr,
             vtables: Default::default(),
             input_source_map: RwLock::new(map),
             action_map: Default::default(),
             set_map: Default::default(),
-            loaded_actions_path: OnceLock::new(),
+            restricted_actions: Default::default(),
+            suppressed_actions: Default::default(),
+            active_sets: Default::default(),
+            dominant_hand: RwLock::new(None),
+            hands_swapped: settings.swap_hands.into(),
+            thumbstick_deadzone: AtomicF32::new(Self::clamp_thumbstick_deadzone(
+                settings.thumbstick_deadzone,
+            )),
+            activate_all_sets: settings.activate_all_sets.into(),
+            merge_action_manifests: settings.merge_action_manifests.into(),
+            loaded_actions_paths: Default::default(),
+            manifest_cache: Default::default(),
             left_hand_key,
             right_hand_key,
             legacy_state: Default::default(),
-            skeletal_tracking_level: RwLock::new(vr::EVRSkeletalTrackingLevel::Estimated),
             estimated_finger_state: [
                 Mutex::new(FingerState::new()),
                 Mutex::new(FingerState::new()),
@@ -135,6 +187,11 @@ impl<C: openxr_data::Compositor> Input<C> {
             subaction_paths,
             events: Mutex::default(),
             loading_actions: false.into(),
+            disconnected_frames: AtomicU32::new(0),
+            #[cfg(feature = "monado")]
+            tracker_poll_frames: AtomicU32::new(0),
+            property_overrides: RwLock::new(PropertyOverrides::load()),
+            tracker_roles: RwLock::new(tracker_role::TrackerRoleOverrides::load()),
         }
     }
 
@@ -149,39 +206,244 @@ impl<C: openxr_data::Compositor> Input<C> {
         if handle == vr::k_ulInvalidInputValueHandle {
             Some(xr::Path::NULL)
         } else {
-            match InputSourceKey::from(KeyData::from_ffi(handle)) {
-                x if x == self.left_hand_key => Some(self.get_subaction_path(Hand::Left)),
-                x if x == self.right_hand_key => Some(self.get_subaction_path(Hand::Right)),
-                _ => None,
+            self.hand_from_handle(handle)
+                .map(|hand| self.get_subaction_path(self.physical_hand(hand)))
+        }
+    }
+
+    /// Maps between the hand the application sees and the controller that drives it.
+    /// Swapping is its own inverse, so this also maps controllers back to application hands.
+    fn physical_hand(&self, hand: Hand) -> Hand {
+        if !self.hands_swapped.load(Ordering::Relaxed) {
+            return hand;
+        }
+        match hand {
+            Hand::Left => Hand::Right,
+            Hand::Right => Hand::Left,
+        }
+    }
+
+    pub fn hands_swapped(&self) -> bool {
+        self.hands_swapped.load(Ordering::Relaxed)
+    }
+
+    pub fn thumbstick_deadzone(&self) -> f32 {
+        self.thumbstick_deadzone.load()
+    }
+
+    pub fn set_thumbstick_deadzone(&self, deadzone: f32) {
+        debug!("Setting thumbstick deadzone to {deadzone}");
+        self.thumbstick_deadzone
+            .store(Self::clamp_thumbstick_deadzone(deadzone));
+    }
+
+    fn clamp_thumbstick_deadzone(deadzone: f32) -> f32 {
+        deadzone.clamp(0.0, 0.99)
+    }
+
+    /// Zeroes stick positions inside the deadzone, and rescales the rest so the stick still
+    /// ramps up from zero at the edge of the deadzone.
+    fn apply_thumbstick_deadzone(&self, value: xr::Vector2f) -> xr::Vector2f {
+        let deadzone = self.thumbstick_deadzone.load();
+        if deadzone <= 0.0 {
+            return value;
+        }
+
+        let radius = value.x.hypot(value.y);
+        if radius <= deadzone {
+            return xr::Vector2f::default();
+        }
+
+        let scale = ((radius - deadzone) / (1.0 - deadzone)).min(1.0) / radius;
+        xr::Vector2f {
+            x: value.x * scale,
+            y: value.y * scale,
+        }
+    }
+
+    /// Swaps which controller drives each of the application's hands, for left-handed players
+    /// holding the controllers in opposite hands.
+    pub fn set_hands_swapped(&self, swapped: bool) {
+        if self.hands_swapped.swap(swapped, Ordering::Relaxed) == swapped {
+            return;
+        }
+        debug!("Setting hands swapped to {swapped}");
+
+        // Both controllers now report the opposite role.
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+        for hand in [Hand::Left, Hand::Right] {
+            if let Some(index) = devices.get_controller_index(hand) {
+                self.queue_event(vr::EVREventType::TrackedDeviceRoleChanged, index);
             }
         }
     }
 
+    fn hand_from_handle(&self, handle: vr::VRInputValueHandle_t) -> Option<Hand> {
+        match InputSourceKey::from(KeyData::from_ffi(handle)) {
+            x if x == self.left_hand_key => Some(Hand::Left),
+            x if x == self.right_hand_key => Some(Hand::Right),
+            _ => None,
+        }
+    }
+
+    fn hand_handle(&self, hand: Hand) -> vr::VRInputValueHandle_t {
+        match hand {
+            Hand::Left => self.left_hand_key,
+            Hand::Right => self.right_hand_key,
+        }
+        .data()
+        .as_ffi()
+    }
+
+    /// Applies the device restriction of the action's set to the given restriction.
+    /// Returns None if the action's set is not active for the requested device.
+    fn restrict_to_active_hand(
+        &self,
+        action: vr::VRActionHandle_t,
+        restrict_to_device: vr::VRInputValueHandle_t,
+    ) -> Option<vr::VRInputValueHandle_t> {
+        let key = ActionKey::from(KeyData::from_ffi(action));
+        let Some(&hand) = self.restricted_actions.read().unwrap().get(key) else {
+            return Some(restrict_to_device);
+        };
+
+        let hand_handle = self.hand_handle(hand);
+
+        (restrict_to_device == vr::k_ulInvalidInputValueHandle || restrict_to_device == hand_handle)
+            .then_some(hand_handle)
+    }
+
+    fn is_suppressed(
+        &self,
+        action: vr::VRActionHandle_t,
+        restrict_to_device: vr::VRInputValueHandle_t,
+    ) -> bool {
+        let key = ActionKey::from(KeyData::from_ffi(action));
+        match self.suppressed_actions.read().unwrap().get(key) {
+            None => false,
+            Some(None) => true,
+            Some(Some(hand)) => self.hand_from_handle(restrict_to_device) == Some(*hand),
+        }
+    }
+
+    /// Finds the actions bound to an input that an action in a higher priority set is also bound
+    /// to, as SteamVR only lets the highest priority set use an input.
+    fn get_suppressed_actions(
+        &self,
+        session_data: &SessionData,
+        loaded: &ManifestLoadedActions,
+        set_priorities: &[(&str, i32)],
+    ) -> SecondaryMap<ActionKey, Option<Hand>> {
+        let mut suppressed = SecondaryMap::new();
+        if set_priorities
+            .iter()
+            .all(|(_, priority)| *priority == set_priorities[0].1)
+        {
+            return suppressed;
+        }
+
+        let action_map = self.action_map.read().unwrap();
+        let priority_of = |key| {
+            let path = &action_map.get(key)?.path;
+            set_priorities
+                .iter()
+                .filter(|(set, _)| {
+                    path.strip_prefix(set)
+                        .is_some_and(|rest| rest.starts_with('/'))
+                })
+                .map(|(_, priority)| *priority)
+                .max()
+        };
+
+        let devices = session_data.input_data.devices.read().unwrap();
+        let mut bound_hands: SecondaryMap<ActionKey, Vec<Hand>> = SecondaryMap::new();
+        for hand in [Hand::Left, Hand::Right] {
+            let Some(infos) = devices
+                .get_controller(hand)
+                .filter(|c| c.connected)
+                .and_then(|c| loaded.per_profile_binding_info.get(&c.profile_path))
+            else {
+                continue;
+            };
+
+            let bound: Vec<(ActionKey, i32, profiles::DynInputPath)> = infos
+                .iter()
+                .filter_map(|(key, infos)| Some((key, priority_of(key)?, infos)))
+                .flat_map(|(key, priority, infos)| {
+                    infos
+                        .iter()
+                        .filter(|info| info.path.hand == hand)
+                        .map(move |info| (key, priority, info.path))
+                })
+                .collect();
+
+            for (key, priority, path) in &bound {
+                if let Some(hands) = bound_hands.get_mut(*key) {
+                    if !hands.contains(&hand) {
+                        hands.push(hand);
+                    }
+                } else {
+                    bound_hands.insert(*key, vec![hand]);
+                }
+
+                let claimed = bound
+                    .iter()
+                    .any(|(_, other, other_path)| other > priority && other_path == path);
+                if !claimed {
+                    continue;
+                }
+                match suppressed.get_mut(*key) {
+                    Some(suppressed_hand) if *suppressed_hand != Some(hand) => {
+                        *suppressed_hand = None
+                    }
+                    Some(_) => {}
+                    None => {
+                        suppressed.insert(*key, Some(hand));
+                    }
+                }
+            }
+        }
+
+        // An action that is only bound on the suppressed hand is fully suppressed.
+        for (key, suppressed_hand) in suppressed.iter_mut() {
+            if bound_hands.get(key).is_some_and(|hands| hands.len() == 1) {
+                *suppressed_hand = None;
+            }
+        }
+
+        suppressed
+    }
+
     fn state_from_bindings_left_right(
         &self,
         action: vr::VRActionHandle_t,
     ) -> Option<(xr::ActionState<bool>, vr::VRInputValueHandle_t)> {
         debug_assert!(self.left_hand_key.0.as_ffi() != 0);
         debug_assert!(self.right_hand_key.0.as_ffi() != 0);
-        let left_state = self.state_from_bindings(action, self.left_hand_key.0.as_ffi());
+        let (dominant_key, other_key) = match *self.dominant_hand.read().unwrap() {
+            None | Some(Hand::Left) => (self.left_hand_key, self.right_hand_key),
+            Some(Hand::Right) => (self.right_hand_key, self.left_hand_key),
+        };
+        let dominant_state = self.state_from_bindings(action, dominant_key.0.as_ffi());
 
-        match left_state {
-            None => self.state_from_bindings(action, self.right_hand_key.0.as_ffi()),
-            Some((left, _)) => {
-                if left.is_active && left.current_state {
-                    return left_state;
+        match dominant_state {
+            None => self.state_from_bindings(action, other_key.0.as_ffi()),
+            Some((dominant, _)) => {
+                if dominant.is_active && dominant.current_state {
+                    return dominant_state;
                 }
-                let right_state = self.state_from_bindings(action, self.right_hand_key.0.as_ffi());
-                match right_state {
-                    None => left_state,
-                    Some((right, _)) => {
-                        if right.is_active && right.current_state {
-                            return right_state;
+                let other_state = self.state_from_bindings(action, other_key.0.as_ffi());
+                match other_state {
+                    None => dominant_state,
+                    Some((other, _)) => {
+                        if other.is_active && other.current_state {
+                            return other_state;
                         }
-                        if left.is_active {
-                            return left_state;
+                        if dominant.is_active {
+                            return dominant_state;
                         }
-                        right_state
+                        other_state
                     }
                 }
             }
@@ -215,7 +477,12 @@ impl<C: openxr_data::Compositor> Input<C> {
         let mut best_state: Option<xr::ActionState<bool>> = None;
 
         for x in bindings.iter() {
-            let Ok(Some(state)) = x.state(&session, extra_data, subaction) else {
+            let Ok(Some(state)) = x.state(
+                &session,
+                extra_data,
+                subaction,
+                self.openxr.display_time.get(),
+            ) else {
                 continue;
             };
 
@@ -232,6 +499,253 @@ impl<C: openxr_data::Compositor> Input<C> {
 
         best_state.map(|x| (x, restrict_to_device))
     }
+
+    /// Whether the given action is bound on the current interaction profile of the given hand.
+    fn is_action_bound_for_hand(
+        &self,
+        session_data: &SessionData,
+        loaded: &ManifestLoadedActions,
+        action: vr::VRActionHandle_t,
+        hand: Hand,
+    ) -> bool {
+        let devices = session_data.input_data.devices.read().unwrap();
+        let Some(controller) = devices.get_controller(hand).filter(|c| c.connected) else {
+            return false;
+        };
+        let profile = controller.profile_path;
+        let subaction = self.get_subaction_path(hand);
+
+        if loaded
+            .try_get_bindings(action, profile)
+            .is_ok_and(|bindings| bindings.iter().any(|b| b.hand == subaction))
+        {
+            return true;
+        }
+
+        let session = &session_data.session;
+        match loaded.try_get_action(action) {
+            Ok(ActionData::Bool(action)) => {
+                action.state(session, subaction).is_ok_and(|s| s.is_active)
+            }
+            Ok(ActionData::Vector1 { action, .. }) => {
+                action.state(session, subaction).is_ok_and(|s| s.is_active)
+            }
+            Ok(ActionData::Vector2 { action, .. }) => {
+                action.state(session, subaction).is_ok_and(|s| s.is_active)
+            }
+            Ok(ActionData::Pose) => {
+                loaded
+                    .try_get_pose(action, profile)
+                    .is_ok_and(|bound| match hand {
+                        Hand::Left => bound.left.is_some(),
+                        Hand::Right => bound.right.is_some(),
+                    })
+            }
+            Ok(ActionData::Skeleton(skeleton_hand)) => *skeleton_hand == hand,
+            // There's no way to query if a haptic action is bound, so assume any connected
+            // controller can vibrate.
+            Ok(ActionData::Haptic(_)) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// Reads a float action, applying the deadzone and gain of the bindings on the controllers
+    /// it is read from.
+    fn remapped_float_state(
+        &self,
+        session_data: &SessionData,
+        loaded: &ManifestLoadedActions,
+        key: ActionKey,
+        action: &xr::Action<f32>,
+        subaction_path: xr::Path,
+    ) -> xr::ActionState<f32> {
+        let state = action.state(&session_data.session, subaction_path).unwrap();
+        let devices = session_data.input_data.devices.read().unwrap();
+        let remap_for = |hand: Hand| {
+            let controller = devices.get_controller(hand).filter(|c| c.connected)?;
+            loaded
+                .per_profile_analog_remaps
+                .get(&controller.profile_path)?
+                .get(key)?
+                .iter()
+                .find(|remap| remap.hand == hand)
+                .copied()
+        };
+
+        let hands = [Hand::Left, Hand::Right];
+        if subaction_path != xr::Path::NULL {
+            let remap = hands
+                .into_iter()
+                .find(|hand| self.get_subaction_path(*hand) == subaction_path)
+                .and_then(remap_for);
+            return match remap {
+                Some(remap) => xr::ActionState {
+                    current_state: remap.apply(state.current_state),
+                    ..state
+                },
+                None => state,
+            };
+        }
+
+        if !hands.into_iter().any(|hand| remap_for(hand).is_some()) {
+            return state;
+        }
+
+        // The hands may be remapped differently, so read them separately and take the largest
+        // magnitude like OpenXR would.
+        let current_state = hands
+            .into_iter()
+            .filter_map(|hand| {
+                let state = action
+                    .state(&session_data.session, self.get_subaction_path(hand))
+                    .ok()?;
+                state.is_active.then(|| {
+                    remap_for(hand).map_or(state.current_state, |remap| {
+                        remap.apply(state.current_state)
+                    })
+                })
+            })
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+            .unwrap_or(state.current_state);
+        xr::ActionState {
+            current_state,
+            ..state
+        }
+    }
+
+    /// Reads a 2D action, applying the thumbstick deadzone if it's read from a thumbstick.
+    fn vector2_state(
+        &self,
+        session_data: &SessionData,
+        loaded: &ManifestLoadedActions,
+        key: ActionKey,
+        action: &xr::Action<xr::Vector2f>,
+        subaction_path: xr::Path,
+    ) -> xr::ActionState<xr::Vector2f> {
+        let mut state = action.state(&session_data.session, subaction_path).unwrap();
+        let devices = session_data.input_data.devices.read().unwrap();
+        let on_thumbstick = |hand: Hand| {
+            devices
+                .get_controller(hand)
+                .filter(|c| c.connected)
+                .and_then(|c| {
+                    loaded
+                        .per_profile_thumbstick_hands
+                        .get(&c.profile_path)?
+                        .get(key)
+                })
+                .is_some_and(|hands| hands.contains(&hand))
+        };
+
+        // Without a hand, OpenXR could be reporting either one, so apply the deadzone if either
+        // of them is a thumbstick.
+        let thumbstick = [Hand::Left, Hand::Right].into_iter().any(|hand| {
+            (subaction_path == xr::Path::NULL || self.get_subaction_path(hand) == subaction_path)
+                && on_thumbstick(hand)
+        });
+        if thumbstick {
+            state.current_state = self.apply_thumbstick_deadzone(state.current_state);
+        }
+        state
+    }
+
+    /// Returns the problems found while loading the bindings of the action manifest, if one has
+    /// been loaded.
+    pub fn binding_report(&self) -> Option<BindingReport> {
+        let session_data = self.openxr.session_data.get();
+        let loaded = session_data.input_data.get_loaded_actions()?;
+        Some(loaded.binding_report.clone())
+    }
+
+    fn binding_dump_path() -> PathBuf {
+        std::env::temp_dir().join(format!("xrizer-bindings-{}.txt", std::process::id()))
+    }
+
+    /// Writes the bindings of the loaded actions for every interaction profile to a text file,
+    /// optionally restricted to the actions of a single action set.
+    fn write_binding_dump(&self, set_name: Option<&str>) -> std::io::Result<PathBuf> {
+        use std::fmt::Write;
+
+        let report = self.binding_report().unwrap_or_default();
+        let session_data = self.openxr.session_data.get();
+        let Some(loaded) = session_data.input_data.get_loaded_actions() else {
+            return Err(std::io::Error::other("no action manifest loaded"));
+        };
+        let action_map = self.action_map.read().unwrap();
+        let in_set = |path: &str| {
+            set_name.is_none_or(|set| {
+                path.strip_prefix(set)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+        };
+
+        let mut profiles: Vec<_> = loaded
+            .per_profile_binding_info
+            .iter()
+            .filter_map(|(profile, infos)| {
+                let profile = self.openxr.instance.path_to_string(*profile).ok()?;
+                Some((profile, infos))
+            })
+            .collect();
+        profiles.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut dump = String::new();
+        for (profile, infos) in profiles {
+            let mut lines: Vec<String> = infos
+                .iter()
+                .filter_map(|(key, infos)| {
+                    let Action { path } = action_map.get(key)?;
+                    in_set(path).then_some((path, infos))
+                })
+                .flat_map(|(path, infos)| {
+                    infos.iter().map(move |info| {
+                        format!("  {path}: {} ({}, {})", info.path, info.mode, info.slot)
+                    })
+                })
+                .collect();
+
+            if lines.is_empty() {
+                continue;
+            }
+            lines.sort();
+            writeln!(dump, "{profile}").unwrap();
+            for line in lines {
+                writeln!(dump, "{line}").unwrap();
+            }
+        }
+
+        let unbound: Vec<_> = report
+            .unbound_actions
+            .iter()
+            .filter(|action| in_set(action))
+            .collect();
+        if !unbound.is_empty() {
+            writeln!(dump, "unbound actions").unwrap();
+            for action in unbound {
+                writeln!(dump, "  {action}").unwrap();
+            }
+        }
+        for (profile, actions) in &report.unknown_actions {
+            let actions: Vec<_> = actions.iter().filter(|action| in_set(action)).collect();
+            if actions.is_empty() {
+                continue;
+            }
+            writeln!(dump, "unknown actions ({profile})").unwrap();
+            for action in actions {
+                writeln!(dump, "  {action}").unwrap();
+            }
+        }
+        for (profile, inputs) in &report.invalid_inputs {
+            writeln!(dump, "invalid inputs ({profile})").unwrap();
+            for input in inputs {
+                writeln!(dump, "  {input}").unwrap();
+            }
+        }
+
+        let path = Self::binding_dump_path();
+        std::fs::write(&path, dump)?;
+        Ok(path)
+    }
 }
 
 #[derive(Default)]
@@ -240,9 +754,18 @@ pub struct InputSessionData {
     estimated_skeleton_actions: OnceLock<SkeletalInputActionData>,
     pose_data: OnceLock<PoseData>,
     devices: RwLock<TrackedDeviceList>,
+    /// The tracking level of the last skeleton we returned for each hand.
+    skeletal_tracking_levels: [RwLock<Option<vr::EVRSkeletalTrackingLevel>>; 2],
 }
 
 impl InputSessionData {
+    /// Drops the poses located so far this frame, for when the spaces they're relative to change.
+    pub fn clear_pose_caches(&self) {
+        for device in self.devices.read().unwrap().iter() {
+            device.clear_pose_cache();
+        }
+    }
+
     #[inline]
     fn get_loaded_actions(&self) -> Option<&ManifestLoadedActions> {
         match self.actions.get()? {
@@ -258,6 +781,18 @@ impl InputSessionData {
         }
     }
 
+    fn get_skeletal_tracking_level(&self, hand: Hand) -> Option<vr::EVRSkeletalTrackingLevel> {
+        *self.skeletal_tracking_levels[hand as usize - 1]
+            .read()
+            .unwrap()
+    }
+
+    fn set_skeletal_tracking_level(&self, hand: Hand, level: vr::EVRSkeletalTrackingLevel) {
+        *self.skeletal_tracking_levels[hand as usize - 1]
+            .write()
+            .unwrap() = Some(level);
+    }
+
     pub(crate) fn interaction_profile_changed(&self) {
         if let Some(data) = self.pose_data.get() {
             // If the interaction profile changes the offsets must be updated too
@@ -281,12 +816,34 @@ enum ActionData {
     Haptic(xr::Action<xr::Haptic>),
 }
 
+impl ActionData {
+    /// Makes the next analog delta zero, instead of relative to a value from before the action's
+    /// set was deactivated.
+    fn reset_delta(&self) {
+        match self {
+            ActionData::Vector1 { last_value, .. } => last_value.store(f32::NAN),
+            ActionData::Vector2 { last_value, .. } => {
+                last_value.0.store(f32::NAN);
+                last_value.1.store(f32::NAN);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn analog_delta(last_value: &AtomicF32, current: f32) -> f32 {
+    let last = last_value.swap(current);
+    if last.is_nan() { 0.0 } else { current - last }
+}
+
 #[derive(Default)]
 struct ExtraActionData {
     toggle_action: Option<xr::Action<bool>>,
     analog_action: Option<xr::Action<f32>>,
     double_action: Option<xr::Action<bool>>,
+    long_press_action: Option<xr::Action<bool>>,
     vector2_action: Option<xr::Action<xr::Vector2f>>,
+    scroll_action: Option<xr::Action<xr::Vector2f>>,
     grab_actions: Option<GrabActions<custom_bindings::Actions>>,
 }
 
@@ -294,6 +851,8 @@ struct ExtraActionData {
 struct BoundPose {
     left: Option<BoundPoseType>,
     right: Option<BoundPoseType>,
+    /// Bound to the eye gaze, which isn't tied to either hand.
+    gaze: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -307,10 +866,59 @@ enum BoundPoseType {
     /// "If you provide /pose/tip in your rendermodel you should set it to the position and rotation that are appropriate for pointing (i.e. with a laser pointer) with your controller."
     /// ~https://github.com/ValveSoftware/openvr/wiki/Input-Profiles#pose-components
     Tip,
-    /// Not sure why games still use this, but having it be equivalent to raw seems to work fine.
+    /// "Canonical coordinate system of the gdc 2015 wired controller, provided for backwards compatibility"
+    /// ~openvr.h, k_pch_Controller_Component_GDC2015
     Gdc2015,
 }
 
+impl BoundPoseType {
+    /// The transform from the raw pose to this pose type, if it isn't the raw pose itself.
+    fn offset_from_raw(self, profile: Option<&ProfileData>, hand: Hand) -> Option<Mat4> {
+        match self {
+            Self::Raw => None,
+            // ToDo: Check if render model has a tip pose otherwise use raw pose
+            // For now, just use the raw pose
+            Self::Tip => None,
+            Self::Gdc2015 => profile.map(|profile| profile.gdc2015_offset(hand)),
+        }
+    }
+}
+
+/// Moves a pose to a point rigidly attached to the device. The point's linear velocity picks up
+/// the tangential velocity from the device's rotation around its raw origin.
+fn offset_pose(pose: &mut vr::TrackedDevicePose_t, offset: Mat4) {
+    let raw = xr::Posef::from(pose.mDeviceToAbsoluteTracking);
+    let raw_rotation = Quat::from_xyzw(
+        raw.orientation.x,
+        raw.orientation.y,
+        raw.orientation.z,
+        raw.orientation.w,
+    );
+    let raw_position = Vec3::new(raw.position.x, raw.position.y, raw.position.z);
+    let (_, rotation, translation) = offset.to_scale_rotation_translation();
+    let lever = raw_rotation * translation;
+    let position = raw_position + lever;
+    let orientation = raw_rotation * rotation;
+    pose.mDeviceToAbsoluteTracking = xr::Posef {
+        position: xr::Vector3f {
+            x: position.x,
+            y: position.y,
+            z: position.z,
+        },
+        orientation: xr::Quaternionf {
+            x: orientation.x,
+            y: orientation.y,
+            z: orientation.z,
+            w: orientation.w,
+        },
+    }
+    .into();
+
+    let angular_velocity = Vec3::from_array(pose.vAngularVelocity.v);
+    let velocity = Vec3::from_array(pose.vVelocity.v) + angular_velocity.cross(lever);
+    pose.vVelocity.v = velocity.to_array();
+}
+
 macro_rules! get_action_from_handle {
     ($self:expr, $handle:expr, $session_data:ident, $action:ident) => {
         get_action_from_handle!($self, $handle, $session_data, $action, loaded)
@@ -356,32 +964,147 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
     fn OpenBindingUI(
         &self,
         _: *const c_char,
-        _: vr::VRActionSetHandle_t,
+        action_set: vr::VRActionSetHandle_t,
         _: vr::VRInputValueHandle_t,
         _: bool,
     ) -> vr::EVRInputError {
-        crate::warn_unimplemented!("OpenBindingUI");
+        // There's no binding UI to open, so dump the current bindings somewhere the user can
+        // look at them instead.
+        let set_map = self.set_map.read().unwrap();
+        let set_name = if action_set == vr::k_ulInvalidActionSetHandle {
+            None
+        } else {
+            match set_map.get(ActionSetKey::from(KeyData::from_ffi(action_set))) {
+                Some(name) => Some(name.as_str()),
+                None => return vr::EVRInputError::InvalidHandle,
+            }
+        };
+
+        match self.write_binding_dump(set_name) {
+            Ok(path) => info!("Current bindings written to {}", path.display()),
+            Err(e) => warn!("Failed to write current bindings: {e}"),
+        }
         vr::EVRInputError::None
     }
     fn IsUsingLegacyInput(&self) -> bool {
-        return self
-            .openxr
+        self.openxr
             .session_data
             .get()
             .input_data
             .get_legacy_actions()
-            .is_some();
+            .is_some()
     }
     fn GetComponentStateForBinding(
         &self,
-        _: *const c_char,
-        _: *const c_char,
-        _: *const vr::InputBindingInfo_t,
-        _: u32,
-        _: u32,
-        _: *mut vr::RenderModel_ComponentState_t,
+        render_model_name: *const c_char,
+        component_name: *const c_char,
+        origin_info: *const vr::InputBindingInfo_t,
+        origin_info_size: u32,
+        origin_info_count: u32,
+        component_state: *mut vr::RenderModel_ComponentState_t,
     ) -> vr::EVRInputError {
-        todo!()
+        if render_model_name.is_null() || component_name.is_null() || component_state.is_null() {
+            return vr::EVRInputError::InvalidParam;
+        }
+        let origin_infos: &[vr::InputBindingInfo_t] = if origin_info.is_null() {
+            &[]
+        } else {
+            if origin_info_size as usize != std::mem::size_of::<vr::InputBindingInfo_t>() {
+                return vr::EVRInputError::InvalidParam;
+            }
+            unsafe { std::slice::from_raw_parts(origin_info, origin_info_count as usize) }
+        };
+
+        let render_model_name = unsafe { CStr::from_ptr(render_model_name) };
+        let component_name = unsafe { CStr::from_ptr(component_name) };
+        let Some(mut state) =
+            crate::rendermodels::static_component_state(render_model_name, component_name)
+        else {
+            debug!(
+                "no component {component_name:?} on render model {render_model_name:?} for binding state"
+            );
+            return vr::EVRInputError::InvalidParam;
+        };
+
+        let session_data = self.openxr.session_data.get();
+        let Some(loaded) = session_data.input_data.get_loaded_actions() else {
+            unsafe {
+                component_state.write(state);
+            }
+            return vr::EVRInputError::None;
+        };
+        let devices = session_data.input_data.devices.read().unwrap();
+        let to_str = |s: &[c_char]| unsafe { CStr::from_ptr(s.as_ptr()) }.to_string_lossy();
+        // Our default component stands in for the whole controller, but the others only show
+        // their own input.
+        let component_subpath = crate::rendermodels::component_input(component_name)
+            .and_then(DynSubpath::from_openvr_str);
+
+        for origin in origin_infos {
+            let hand = match to_str(&origin.rchDevicePathName).as_ref() {
+                "/user/hand/left" => Hand::Left,
+                "/user/hand/right" => Hand::Right,
+                _ => continue,
+            };
+            let Some(subpath) = to_str(&origin.rchInputPathName)
+                .strip_prefix("/input/")
+                .and_then(DynSubpath::from_openvr_str)
+            else {
+                continue;
+            };
+            if component_subpath.is_some_and(|component| component != subpath) {
+                continue;
+            }
+            let Some(infos) = devices
+                .get_controller(hand)
+                .and_then(|c| loaded.per_profile_binding_info.get(&c.profile_path))
+            else {
+                continue;
+            };
+            let slot = to_str(&origin.rchSlotName);
+            let subaction_path = self.get_subaction_path(hand);
+
+            let bound = infos.iter().filter(|(_, infos)| {
+                infos.iter().any(|info| {
+                    info.path.hand == hand && info.path.subpath == subpath && info.slot == slot
+                })
+            });
+            for (key, _) in bound {
+                // Only the actions' own state is read here - evaluating custom bindings would
+                // eat the change events the application is waiting for.
+                let flag = match loaded.try_get_action(key.data().as_ffi()) {
+                    Ok(ActionData::Bool(action)) => action
+                        .state(&session_data.session, subaction_path)
+                        .is_ok_and(|s| s.is_active && s.current_state)
+                        .then_some(if slot == "touch" {
+                            vr::EVRComponentProperty::IsTouched
+                        } else {
+                            vr::EVRComponentProperty::IsPressed
+                        }),
+                    // Analog inputs count as touched while they're away from their rest position.
+                    Ok(ActionData::Vector1 { action, .. }) => action
+                        .state(&session_data.session, subaction_path)
+                        .is_ok_and(|s| s.is_active && s.current_state != 0.0)
+                        .then_some(vr::EVRComponentProperty::IsTouched),
+                    Ok(ActionData::Vector2 { action, .. }) => action
+                        .state(&session_data.session, subaction_path)
+                        .is_ok_and(|s| {
+                            s.is_active && (s.current_state.x != 0.0 || s.current_state.y != 0.0)
+                        })
+                        .then_some(vr::EVRComponentProperty::IsTouched),
+                    _ => None,
+                };
+
+                if let Some(flag) = flag {
+                    state.uProperties |= flag.0;
+                }
+            }
+        }
+
+        unsafe {
+            component_state.write(state);
+        }
+        vr::EVRInputError::None
     }
     fn ShowBindingsForActionSet(
         &self,
@@ -390,27 +1113,70 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
         _: u32,
         _: vr::VRInputValueHandle_t,
     ) -> vr::EVRInputError {
-        todo!()
+        // There's no binding UI to show, but games may call this from a settings menu.
+        crate::warn_unimplemented!("ShowBindingsForActionSet");
+        vr::EVRInputError::None
     }
     fn ShowActionOrigins(
         &self,
         _: vr::VRActionSetHandle_t,
         _: vr::VRActionHandle_t,
     ) -> vr::EVRInputError {
-        todo!()
+        crate::warn_unimplemented!("ShowActionOrigins");
+        vr::EVRInputError::None
     }
     fn GetActionBindingInfo(
         &self,
-        _: vr::VRActionHandle_t,
-        _: *mut vr::InputBindingInfo_t,
-        _: u32,
-        _: u32,
+        action: vr::VRActionHandle_t,
+        binding_info: *mut vr::InputBindingInfo_t,
+        binding_info_size: u32,
+        binding_info_count: u32,
         returned_binding_info_count: *mut u32,
     ) -> vr::EVRInputError {
-        crate::warn_unimplemented!("GetActionBindingInfo");
         if !returned_binding_info_count.is_null() {
             unsafe { *returned_binding_info_count = 0 };
         }
+        let infos_out: &mut [vr::InputBindingInfo_t] = if binding_info.is_null() {
+            &mut []
+        } else {
+            assert_eq!(
+                binding_info_size as usize,
+                std::mem::size_of::<vr::InputBindingInfo_t>()
+            );
+            unsafe { std::slice::from_raw_parts_mut(binding_info, binding_info_count as usize) }
+        };
+
+        let session_data = self.openxr.session_data.get();
+        let Some(loaded) = session_data.input_data.get_loaded_actions() else {
+            return vr::EVRInputError::InvalidHandle;
+        };
+        if let Err(e) = loaded.try_get_action(action) {
+            return e;
+        }
+
+        let key = ActionKey::from(KeyData::from_ffi(action));
+        let devices = session_data.input_data.devices.read().unwrap();
+        let bound_infos = [Hand::Left, Hand::Right]
+            .into_iter()
+            .filter_map(|hand| {
+                let controller = devices.get_controller(hand).filter(|c| c.connected)?;
+                let infos = loaded
+                    .per_profile_binding_info
+                    .get(&controller.profile_path)?
+                    .get(key)?;
+                Some(infos.iter().filter(move |info| info.path.hand == hand))
+            })
+            .flatten();
+
+        let mut count = 0;
+        for (out, info) in infos_out.iter_mut().zip(bound_infos) {
+            *out = info.to_openvr();
+            count += 1;
+        }
+
+        if !returned_binding_info_count.is_null() {
+            unsafe { *returned_binding_info_count = count };
+        }
         vr::EVRInputError::None
     }
     fn GetOriginTrackedDeviceInfo(
@@ -453,22 +1219,127 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
     }
     fn GetOriginLocalizedName(
         &self,
-        _: vr::VRInputValueHandle_t,
-        _: *mut c_char,
-        _: u32,
-        _: i32,
+        origin: vr::VRInputValueHandle_t,
+        name_array: *mut c_char,
+        name_array_size: u32,
+        string_sections_to_include: i32,
     ) -> vr::EVRInputError {
-        crate::warn_unimplemented!("GetOriginLocalizedName");
-        vr::EVRInputError::None
+        // EVRInputStringBits
+        const HAND: i32 = 0x01;
+        const CONTROLLER_TYPE: i32 = 0x02;
+        const INPUT_SOURCE: i32 = 0x04;
+
+        if name_array.is_null() {
+            return vr::EVRInputError::InvalidParam;
+        }
+
+        let path = {
+            let map = self.input_source_map.read().unwrap();
+            let Some(path) = map.get(InputSourceKey::from(KeyData::from_ffi(origin))) else {
+                return vr::EVRInputError::InvalidHandle;
+            };
+            path.to_string_lossy().into_owned()
+        };
+
+        let (hand, component) = if let Some(rest) = path.strip_prefix("/user/hand/left") {
+            (Hand::Left, rest)
+        } else if let Some(rest) = path.strip_prefix("/user/hand/right") {
+            (Hand::Right, rest)
+        } else {
+            return vr::EVRInputError::InvalidDevice;
+        };
+
+        // Name the controller the user is actually holding, which differs from the application's
+        // hand when the hands are swapped.
+        let mut sections = Vec::new();
+        if string_sections_to_include & HAND != 0 {
+            sections.push(
+                match self.physical_hand(hand) {
+                    Hand::Left => "Left Hand",
+                    Hand::Right => "Right Hand",
+                }
+                .to_string(),
+            );
+        }
+        if string_sections_to_include & CONTROLLER_TYPE != 0
+            && let Some(profile_name) = self.get_controller_profile_name(hand)
+        {
+            sections.push(profile_name.to_string());
+        }
+        if string_sections_to_include & INPUT_SOURCE != 0
+            && let Some(component) = component.strip_prefix("/input/")
+        {
+            sections.push(component.to_string());
+        }
+
+        let name = sections.join(" ");
+        let buf = unsafe {
+            std::slice::from_raw_parts_mut(name_array as *mut u8, name_array_size as usize)
+        };
+        let Some(max_len) = buf.len().checked_sub(1) else {
+            return vr::EVRInputError::BufferTooSmall;
+        };
+        let len = name.len().min(max_len);
+        buf[..len].copy_from_slice(&name.as_bytes()[..len]);
+        buf[len] = 0;
+
+        if len < name.len() {
+            vr::EVRInputError::BufferTooSmall
+        } else {
+            vr::EVRInputError::None
+        }
     }
     fn GetActionOrigins(
         &self,
-        _: vr::VRActionSetHandle_t,
-        _: vr::VRActionHandle_t,
-        _: *mut vr::VRInputValueHandle_t,
-        _: u32,
+        action_set: vr::VRActionSetHandle_t,
+        action: vr::VRActionHandle_t,
+        origins_out: *mut vr::VRInputValueHandle_t,
+        origin_out_count: u32,
     ) -> vr::EVRInputError {
-        crate::warn_unimplemented!("GetActionOrigins");
+        let origins: &mut [vr::VRInputValueHandle_t] = if origins_out.is_null() {
+            &mut []
+        } else {
+            unsafe { std::slice::from_raw_parts_mut(origins_out, origin_out_count as usize) }
+        };
+        origins.fill(vr::k_ulInvalidInputValueHandle);
+
+        {
+            let set_map = self.set_map.read().unwrap();
+            let Some(set_name) = set_map.get(ActionSetKey::from(KeyData::from_ffi(action_set)))
+            else {
+                return vr::EVRInputError::InvalidHandle;
+            };
+            let action_map = self.action_map.read().unwrap();
+            let Some(Action { path }) = action_map.get(ActionKey::from(KeyData::from_ffi(action)))
+            else {
+                return vr::EVRInputError::InvalidHandle;
+            };
+            if !path
+                .strip_prefix(set_name.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+            {
+                debug!("action {path} is not part of action set {set_name}");
+                return vr::EVRInputError::InvalidHandle;
+            }
+        }
+
+        let session_data = self.openxr.session_data.get();
+        let Some(loaded) = session_data.input_data.get_loaded_actions() else {
+            return vr::EVRInputError::InvalidHandle;
+        };
+
+        let bound_origins = [
+            (Hand::Left, self.left_hand_key),
+            (Hand::Right, self.right_hand_key),
+        ]
+        .into_iter()
+        .filter(|(hand, _)| self.is_action_bound_for_hand(&session_data, loaded, action, *hand))
+        .map(|(_, key)| key.data().as_ffi());
+
+        for (out, origin) in origins.iter_mut().zip(bound_origins) {
+            *out = origin;
+        }
+
         vr::EVRInputError::None
     }
     fn TriggerHapticVibrationAction(
@@ -508,23 +1379,47 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
     }
     fn DecompressSkeletalBoneData(
         &self,
-        _: *const c_void,
-        _: u32,
-        _: vr::EVRSkeletalTransformSpace,
-        _: *mut vr::VRBoneTransform_t,
-        _: u32,
+        compressed_buffer: *const c_void,
+        compressed_buffer_size: u32,
+        transform_space: vr::EVRSkeletalTransformSpace,
+        transform_array: *mut vr::VRBoneTransform_t,
+        transform_array_count: u32,
     ) -> vr::EVRInputError {
-        todo!()
+        if compressed_buffer.is_null() || transform_array.is_null() {
+            return vr::EVRInputError::InvalidParam;
+        }
+        let data = unsafe {
+            std::slice::from_raw_parts(
+                compressed_buffer as *const u8,
+                compressed_buffer_size as usize,
+            )
+        };
+        let transforms = unsafe {
+            std::slice::from_raw_parts_mut(transform_array, transform_array_count as usize)
+        };
+
+        match skeletal::decompress_bones(data, Some(transform_space), transforms) {
+            Ok(_) => vr::EVRInputError::None,
+            Err(e) => e,
+        }
     }
     fn GetSkeletalBoneDataCompressed(
         &self,
-        _: vr::VRActionHandle_t,
-        _: vr::EVRSkeletalMotionRange,
-        _: *mut c_void,
-        _: u32,
-        _: *mut u32,
+        action: vr::VRActionHandle_t,
+        motion_range: vr::EVRSkeletalMotionRange,
+        compressed_data: *mut c_void,
+        compressed_size: u32,
+        required_compressed_size: *mut u32,
     ) -> vr::EVRInputError {
-        todo!()
+        // The current interface always compresses in parent space
+        self.get_compressed_bones(
+            action,
+            vr::EVRSkeletalTransformSpace::Parent,
+            motion_range,
+            compressed_data,
+            compressed_size,
+            required_compressed_size,
+        )
     }
     fn GetSkeletalSummaryData(
         &self,
@@ -550,7 +1445,7 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
         &self,
         handle: vr::VRActionHandle_t,
         transform_space: vr::EVRSkeletalTransformSpace,
-        _motion_range: vr::EVRSkeletalMotionRange,
+        motion_range: vr::EVRSkeletalMotionRange,
         transform_array: *mut vr::VRBoneTransform_t,
         transform_array_count: u32,
     ) -> vr::EVRInputError {
@@ -566,7 +1461,13 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
             return vr::EVRInputError::WrongType;
         };
 
-        self.get_bones_from_hand_tracking(&session_data, transform_space, *hand, transforms);
+        self.get_bones_from_hand_tracking(
+            &session_data,
+            transform_space,
+            motion_range,
+            *hand,
+            transforms,
+        );
         vr::EVRInputError::None
     }
     fn GetSkeletalTrackingLevel(
@@ -588,13 +1489,20 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
             vr::ETrackedDeviceProperty::ControllerType_String,
         );
 
+        let detected = data
+            .input_data
+            .get_skeletal_tracking_level(*hand)
+            .unwrap_or(vr::EVRSkeletalTrackingLevel::Estimated);
+
         unsafe {
-            // Make sure knuckles are always Partial
-            // TODO: Remove in favor of using XR_EXT_hand_tracking_data_source
-            if controller_type.as_deref() == Some(c"knuckles") {
+            // Knuckles can always report finger positions, even if the runtime isn't giving us
+            // hand tracking data for them.
+            if detected == vr::EVRSkeletalTrackingLevel::Estimated
+                && controller_type.as_deref() == Some(c"knuckles")
+            {
                 *level = vr::EVRSkeletalTrackingLevel::Partial;
             } else {
-                *level = *self.skeletal_tracking_level.read().unwrap();
+                *level = detected;
             }
         }
         vr::EVRInputError::None
@@ -628,20 +1536,60 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
     }
     fn GetBoneName(
         &self,
-        _: vr::VRActionHandle_t,
-        _: vr::BoneIndex_t,
-        _: *mut c_char,
-        _: u32,
+        handle: vr::VRActionHandle_t,
+        bone_index: vr::BoneIndex_t,
+        bone_name: *mut c_char,
+        bone_name_size: u32,
     ) -> vr::EVRInputError {
-        todo!()
+        get_action_from_handle!(self, handle, session_data, action);
+        let ActionData::Skeleton(hand) = action else {
+            return vr::EVRInputError::WrongType;
+        };
+        if bone_name.is_null() {
+            return vr::EVRInputError::InvalidParam;
+        }
+        let Some(name) = usize::try_from(bone_index)
+            .ok()
+            .and_then(|index| skeletal::bone_name(index, *hand))
+        else {
+            return vr::EVRInputError::InvalidBoneIndex;
+        };
+
+        let buf = unsafe {
+            std::slice::from_raw_parts_mut(bone_name as *mut u8, bone_name_size as usize)
+        };
+        if buf.len() <= name.len() {
+            return vr::EVRInputError::BufferTooSmall;
+        }
+        buf[..name.len()].copy_from_slice(name.as_bytes());
+        buf[name.len()] = 0;
+
+        vr::EVRInputError::None
     }
     fn GetBoneHierarchy(
         &self,
-        _: vr::VRActionHandle_t,
-        _: *mut vr::BoneIndex_t,
-        _: u32,
+        handle: vr::VRActionHandle_t,
+        parent_indices: *mut vr::BoneIndex_t,
+        index_array_count: u32,
     ) -> vr::EVRInputError {
-        todo!()
+        get_action_from_handle!(self, handle, session_data, action);
+        if !matches!(action, ActionData::Skeleton { .. }) {
+            return vr::EVRInputError::WrongType;
+        }
+        if parent_indices.is_null() {
+            return vr::EVRInputError::InvalidParam;
+        }
+        if index_array_count != skeletal::HandSkeletonBone::Count as u32 {
+            return vr::EVRInputError::InvalidBoneCount;
+        }
+
+        let parents =
+            unsafe { std::slice::from_raw_parts_mut(parent_indices, index_array_count as usize) };
+        for (out, parent) in parents.iter_mut().zip(skeletal::bone_hierarchy()) {
+            *out = parent;
+        }
+
+        vr::EVRInputError::None
     }
     fn GetBoneCount(&self, handle: vr::VRActionHandle_t, count: *mut u32) -> vr::EVRInputError {
         get_action_from_handle!(self, handle, session_data, action);
@@ -656,12 +1604,26 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
 
         vr::EVRInputError::None
     }
-    fn SetDominantHand(&self, _: vr::ETrackedControllerRole) -> vr::EVRInputError {
-        crate::warn_unimplemented!("SetDominantHand");
+    fn SetDominantHand(&self, role: vr::ETrackedControllerRole) -> vr::EVRInputError {
+        let Ok(hand) = Hand::try_from(role) else {
+            debug!("Tried to set dominant hand to invalid role {role:?}");
+            return vr::EVRInputError::InvalidParam;
+        };
+        debug!("Setting dominant hand to {hand:?}");
+        *self.dominant_hand.write().unwrap() = Some(hand);
         vr::EVRInputError::None
     }
-    fn GetDominantHand(&self, _: *mut vr::ETrackedControllerRole) -> vr::EVRInputError {
-        crate::warn_unimplemented!("GetDominantHand");
+    fn GetDominantHand(&self, role: *mut vr::ETrackedControllerRole) -> vr::EVRInputError {
+        let Some(role) = (unsafe { role.as_mut() }) else {
+            return vr::EVRInputError::InvalidParam;
+        };
+        // SteamVR reports the right hand when the user hasn't picked one.
+        *role = self
+            .dominant_hand
+            .read()
+            .unwrap()
+            .unwrap_or(Hand::Right)
+            .into();
         vr::EVRInputError::None
     }
     fn GetSkeletalActionData(
@@ -689,171 +1651,58 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
         };
         let pose_data = data.input_data.pose_data.get().unwrap();
         unsafe {
-            std::ptr::addr_of_mut!((*action_data).bActive).write(
-                pose_data
-                    .grip
-                    .is_active(&data.session, xr::Path::NULL)
-                    .unwrap(),
-            );
-            std::ptr::addr_of_mut!((*action_data).activeOrigin).write(origin);
-        }
-        vr::EVRInputError::None
-    }
-    fn GetPoseActionDataForNextFrame(
-        &self,
-        action: vr::VRActionHandle_t,
-        origin: vr::ETrackingUniverseOrigin,
-        action_data: *mut vr::InputPoseActionData_t,
-        action_data_size: u32,
-        restrict_to_device: vr::VRInputValueHandle_t,
-    ) -> vr::EVRInputError {
-        assert_eq!(
-            action_data_size as usize,
-            std::mem::size_of::<vr::InputPoseActionData_t>()
-        );
-
-        if log::log_enabled!(log::Level::Trace) {
-            let action_map = self.action_map.read().unwrap();
-            let action_key = ActionKey::from(KeyData::from_ffi(action));
-            let input_map = self.input_source_map.read().unwrap();
-            let input_key = InputSourceKey::from(KeyData::from_ffi(restrict_to_device));
-            trace!(
-                "getting pose for {:?} (restrict: {:?})",
-                action_map.get(action_key).map(|a| &a.path),
-                input_map.get(input_key)
-            );
-        }
-
-        let data = self.openxr.session_data.get();
-        let Some(loaded) = data.input_data.get_loaded_actions() else {
-            return vr::EVRInputError::InvalidHandle;
-        };
-
-        macro_rules! no_data {
-            () => {{
-                unsafe {
-                    action_data.write(Default::default());
-                }
-                return vr::EVRInputError::None;
-            }};
-        }
-        let subaction_path = get_subaction_path!(self, restrict_to_device, action_data);
-        let devices = data.input_data.devices.read().unwrap();
-        let get_hand = |hand| {
-            devices
-                .get_controller(hand)
-                .map(|h| (hand, h.profile_path))
-                .unzip()
-        };
-        let (active_origin, hand) = match loaded.try_get_action(action) {
-            Ok(ActionData::Pose) => {
-                let (mut hand, interaction_profile) = match subaction_path {
-                    x if x == self.get_subaction_path(Hand::Left) => get_hand(Hand::Left),
-                    x if x == self.get_subaction_path(Hand::Right) => get_hand(Hand::Right),
-                    x if x == xr::Path::NULL => (None, None),
-                    _ => unreachable!(),
-                };
-
-                let get_hand_pose =
-                    |hand: &TrackedDevice| loaded.try_get_pose(action, hand.profile_path).ok();
-
-                let get_first_bound_hand_profile = || {
-                    devices
-                        .get_controller(Hand::Left)
-                        .and_then(get_hand_pose)
-                        .or_else(|| devices.get_controller(Hand::Right).and_then(get_hand_pose))
-                };
-
-                let Some(bound) = interaction_profile
-                    .and_then(|p| loaded.try_get_pose(action, p).ok())
-                    .or_else(get_first_bound_hand_profile)
-                else {
-                    match hand {
-                        Some(hand) => {
-                            trace!(
-                                "action has no bindings for the {hand:?} hand's interaction profile"
-                            );
-                        }
-                        None => {
-                            trace!("action has no bindings for either hand's interaction profile");
-                        }
-                    }
-
-                    no_data!()
-                };
-
-                let origin = hand.is_some().then_some(restrict_to_device);
-                let pose_type = match hand {
-                    Some(Hand::Left) => bound.left,
-                    Some(Hand::Right) => bound.right,
-                    None => {
-                        hand = Some(Hand::Left);
-                        bound.left.or_else(|| {
-                            hand = Some(Hand::Right);
-                            bound.right
-                        })
-                    }
-                };
-
-                let Some(ty) = pose_type else {
-                    trace!("action has no bindings for the hand {hand:?}");
-                    no_data!()
-                };
-
-                let hand = hand.unwrap();
-                let origin = origin.unwrap_or_else(|| match hand {
-                    Hand::Left => self.left_hand_key.data().as_ffi(),
-                    Hand::Right => self.right_hand_key.data().as_ffi(),
-                });
-
-                match ty {
-                    BoundPoseType::Raw | BoundPoseType::Gdc2015 => (origin, hand),
-                    BoundPoseType::Tip => {
-                        // ToDo: Check if render model has a tip pose otherwise use raw pose
-                        // For now, just use the raw pose
-                        (origin, hand)
-                    }
-                }
-            }
-            Ok(ActionData::Skeleton(hand)) => {
-                if subaction_path != xr::Path::NULL {
-                    return vr::EVRInputError::InvalidDevice;
-                }
-                (0, *hand)
-            }
-            Ok(_) => return vr::EVRInputError::WrongType,
-            Err(e) => return e,
-        };
-
-        drop(devices);
-        drop(data);
-
-        unsafe {
-            let pose = self
-                .get_controller_pose(hand, Some(origin))
-                .unwrap_or_default();
-            action_data.write(vr::InputPoseActionData_t {
-                bActive: true,
-                activeOrigin: active_origin,
-                pose,
-            });
+            std::ptr::addr_of_mut!((*action_data).bActive).write(
+                pose_data
+                    .grip
+                    .is_active(&data.session, xr::Path::NULL)
+                    .unwrap(),
+            );
+            std::ptr::addr_of_mut!((*action_data).activeOrigin).write(origin);
         }
-
         vr::EVRInputError::None
     }
+    fn GetPoseActionDataForNextFrame(
+        &self,
+        action: vr::VRActionHandle_t,
+        origin: vr::ETrackingUniverseOrigin,
+        action_data: *mut vr::InputPoseActionData_t,
+        action_data_size: u32,
+        restrict_to_device: vr::VRInputValueHandle_t,
+    ) -> vr::EVRInputError {
+        self.get_pose_action_data(
+            action,
+            origin,
+            self.openxr.display_time.get(),
+            action_data,
+            action_data_size,
+            restrict_to_device,
+        )
+    }
 
     fn GetPoseActionDataRelativeToNow(
         &self,
         action: vr::VRActionHandle_t,
         origin: vr::ETrackingUniverseOrigin,
-        _seconds_from_now: f32,
+        seconds_from_now: f32,
         action_data: *mut vr::InputPoseActionData_t,
         action_data_size: u32,
         restrict_to_device: vr::VRInputValueHandle_t,
     ) -> vr::EVRInputError {
-        self.GetPoseActionDataForNextFrame(
+        let seconds_from_now = if seconds_from_now.is_nan() {
+            0.0
+        } else {
+            seconds_from_now.clamp(-MAX_POSE_PREDICTION, MAX_POSE_PREDICTION)
+        };
+        let time = xr::Time::from_nanos(
+            self.openxr
+                .now()
+                .as_nanos()
+                .saturating_add((seconds_from_now as f64 * 1e9) as i64),
+        );
+        self.get_pose_action_data(
             action,
             origin,
+            time,
             action_data,
             action_data_size,
             restrict_to_device,
@@ -874,12 +1723,22 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
 
         let mut out = WriteOnDrop::new(action_data);
         get_action_from_handle!(self, handle, session_data, action, loaded);
+        let Some(restrict_to_device) = self.restrict_to_active_hand(handle, restrict_to_device)
+        else {
+            return vr::EVRInputError::None;
+        };
         let subaction_path = get_subaction_path!(self, restrict_to_device, action_data);
 
         let mut active_hand = restrict_to_device;
         let (state, delta) = match action {
             ActionData::Vector1 { action, last_value } => {
-                let mut state = action.state(&session_data.session, subaction_path).unwrap();
+                let mut state = self.remapped_float_state(
+                    &session_data,
+                    loaded,
+                    ActionKey::from(KeyData::from_ffi(handle)),
+                    action,
+                    subaction_path,
+                );
 
                 // It's generally not clear how SteamVR handles float actions with multiple bindings;
                 //   so emulate OpenXR, which takes maximum among active actions
@@ -903,7 +1762,7 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
                 }
 
                 let delta = xr::Vector2f {
-                    x: state.current_state - last_value.swap(state.current_state),
+                    x: analog_delta(last_value, state.current_state),
                     y: 0.0,
                 };
                 (
@@ -920,15 +1779,24 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
                 )
             }
             ActionData::Vector2 { action, last_value } => {
-                let state = action.state(&session_data.session, subaction_path).unwrap();
+                let state = self.vector2_state(
+                    &session_data,
+                    loaded,
+                    ActionKey::from(KeyData::from_ffi(handle)),
+                    action,
+                    subaction_path,
+                );
                 let delta = xr::Vector2f {
-                    x: state.current_state.x - last_value.0.swap(state.current_state.x),
-                    y: state.current_state.y - last_value.1.swap(state.current_state.y),
+                    x: analog_delta(&last_value.0, state.current_state.x),
+                    y: analog_delta(&last_value.1, state.current_state.y),
                 };
                 (state, delta)
             }
             _ => return vr::EVRInputError::WrongType,
         };
+        if self.is_suppressed(handle, restrict_to_device) {
+            return vr::EVRInputError::None;
+        }
 
         *out.value = vr::InputAnalogActionData_t {
             bActive: state.is_active,
@@ -958,10 +1826,17 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
         let mut out = WriteOnDrop::new(action_data);
 
         get_action_from_handle!(self, handle, session_data, action);
+        let Some(restrict_to_device) = self.restrict_to_active_hand(handle, restrict_to_device)
+        else {
+            return vr::EVRInputError::None;
+        };
         let subaction_path = get_subaction_path!(self, restrict_to_device, action_data);
         let ActionData::Bool(action) = &action else {
             return vr::EVRInputError::WrongType;
         };
+        if self.is_suppressed(handle, restrict_to_device) {
+            return vr::EVRInputError::None;
+        }
 
         let mut state = action.state(&session_data.session, subaction_path).unwrap();
 
@@ -1019,37 +1894,76 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
             std::mem::size_of::<vr::VRActiveActionSet_t>()
         );
         // alyx
-        if active_set_count == 0 {
+        let activate_all_sets =
+            active_set_count == 0 && self.activate_all_sets.load(Ordering::Relaxed);
+        if active_set_count == 0 && !activate_all_sets {
             return vr::EVRInputError::NoActiveActionSet;
         }
 
-        let active_sets =
-            unsafe { std::slice::from_raw_parts(active_sets, active_set_count as usize) };
-
-        if active_sets
-            .iter()
-            .any(|set| set.ulRestrictedToDevice != vr::k_ulInvalidInputValueHandle)
-        {
-            crate::warn_once!("Per device action set restriction is not implemented yet.");
-        }
-
         let data = self.openxr.session_data.get();
         let Some(actions) = data.input_data.get_loaded_actions() else {
             return vr::EVRInputError::InvalidParam;
         };
 
+        let all_sets: Vec<vr::VRActiveActionSet_t>;
+        let mut active_sets: Vec<_> = if activate_all_sets {
+            trace!("No sets passed to UpdateActionState, activating all of them");
+            all_sets = actions
+                .sets
+                .keys()
+                .map(|key| vr::VRActiveActionSet_t {
+                    ulActionSet: key.data().as_ffi(),
+                    ulRestrictedToDevice: vr::k_ulInvalidInputValueHandle,
+                    ..Default::default()
+                })
+                .collect();
+            all_sets.iter().collect()
+        } else {
+            unsafe { std::slice::from_raw_parts(active_sets, active_set_count as usize) }
+                .iter()
+                .collect()
+        };
+        active_sets.sort_by_key(|set| std::cmp::Reverse(set.nPriority));
+
         let set_map = self.set_map.read().unwrap();
         let mut sync_sets = Vec::with_capacity(active_sets.len() + 3);
+        // None means the set is active for all devices
+        let mut set_restrictions: HashMap<ActionSetKey, Option<Hand>> = HashMap::new();
+        let mut set_priorities: Vec<(&str, i32)> = Vec::with_capacity(active_sets.len());
         {
             tracy_span!("UpdateActionState generate active sets");
-            for set in active_sets {
-                let key = ActionSetKey::from(KeyData::from_ffi(set.ulActionSet));
+            for active_set in active_sets {
+                let key = ActionSetKey::from(KeyData::from_ffi(active_set.ulActionSet));
                 let name = set_map.get(key);
                 let Some(set) = actions.sets.get(key) else {
                     debug!("Application passed invalid action set key: {key:?} ({name:?})");
                     return vr::EVRInputError::InvalidHandle;
                 };
                 debug!("Activating set {}", name.unwrap());
+
+                let restriction = match active_set.ulRestrictedToDevice {
+                    vr::k_ulInvalidInputValueHandle => None,
+                    device => {
+                        let hand = self.hand_from_handle(device);
+                        if hand.is_none() {
+                            warn!(
+                                "Set {} restricted to unsupported device {device}, ignoring restriction",
+                                name.unwrap()
+                            );
+                        }
+                        hand
+                    }
+                };
+                set_restrictions
+                    .entry(key)
+                    .and_modify(|r| {
+                        // Activating a set for both hands is the same as not restricting it
+                        if *r != restriction {
+                            *r = None;
+                        }
+                    })
+                    .or_insert(restriction);
+                set_priorities.push((name.unwrap().as_str(), active_set.nPriority));
                 sync_sets.push(set.into());
             }
 
@@ -1062,6 +1976,64 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
             self.legacy_state.on_action_sync();
         }
 
+        {
+            tracy_span!("UpdateActionState reset reactivated sets");
+            let mut active_sets = self.active_sets.lock().unwrap();
+            let reactivated: Vec<&str> = set_restrictions
+                .keys()
+                .filter(|key| !active_sets.contains(key))
+                .filter_map(|key| set_map.get(*key).map(String::as_str))
+                .collect();
+
+            if !reactivated.is_empty() {
+                let action_map = self.action_map.read().unwrap();
+                for (key, action) in action_map.iter() {
+                    let in_reactivated_set = reactivated.iter().any(|set| {
+                        action
+                            .path
+                            .strip_prefix(set)
+                            .is_some_and(|rest| rest.starts_with('/'))
+                    });
+                    if in_reactivated_set && let Some(data) = actions.actions.get(key) {
+                        data.reset_delta();
+                    }
+                }
+            }
+            *active_sets = set_restrictions.keys().copied().collect();
+        }
+
+        {
+            tracy_span!("UpdateActionState apply device restrictions");
+            let restricted_sets: Vec<(&str, Hand)> = set_restrictions
+                .into_iter()
+                .filter_map(|(key, hand)| Some((set_map.get(key)?.as_str(), hand?)))
+                .collect();
+
+            let mut restricted_actions = SecondaryMap::new();
+            if !restricted_sets.is_empty() {
+                let action_map = self.action_map.read().unwrap();
+                for (key, action) in action_map.iter() {
+                    let hand = restricted_sets.iter().find_map(|(set, hand)| {
+                        action
+                            .path
+                            .strip_prefix(set)
+                            .is_some_and(|rest| rest.starts_with('/'))
+                            .then_some(*hand)
+                    });
+                    if let Some(hand) = hand {
+                        restricted_actions.insert(key, hand);
+                    }
+                }
+            }
+            *self.restricted_actions.write().unwrap() = restricted_actions;
+        }
+
+        {
+            tracy_span!("UpdateActionState apply set priorities");
+            *self.suppressed_actions.write().unwrap() =
+                self.get_suppressed_actions(&data, actions, &set_priorities);
+        }
+
         {
             tracy_span!("xrSyncActions");
             data.session.sync_actions(&sync_sets).unwrap();
@@ -1143,6 +2115,8 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
         action_name: *const c_char,
         handle: *mut vr::VRActionHandle_t,
     ) -> vr::EVRInputError {
+        // Like SteamVR, action names are case insensitive - names that only differ by case will
+        // share a handle.
         let name = unsafe { CStr::from_ptr(action_name) }
             .to_string_lossy()
             .to_lowercase();
@@ -1203,7 +2177,21 @@ impl<C: openxr_data::Compositor> vr::IVRInput011_Interface for Input<C> {
         // We need to restart the session if the legacy actions have already been attached.
         self.loading_actions.store(true, Ordering::Relaxed);
         let mut data = self.openxr.session_data.get();
-        if data.input_data.get_legacy_actions().is_some() {
+        let mut restart = data.input_data.get_legacy_actions().is_some();
+        // Some mods set their own manifest on top of the game's, expecting the actions of both.
+        // Merging them means recreating all of the actions, so the session has to restart too.
+        if data.input_data.get_loaded_actions().is_some()
+            && self.merge_action_manifests.load(Ordering::Relaxed)
+        {
+            match self.merge_action_manifest(path) {
+                Ok(merged) => restart = merged,
+                Err(e) => {
+                    self.loading_actions.store(false, Ordering::Relaxed);
+                    return e;
+                }
+            }
+        }
+        if restart {
             drop(data);
             self.openxr.restart_session();
             data = self.openxr.session_data.get();
@@ -1260,13 +2248,35 @@ impl<C: openxr_data::Compositor> vr::IVRInput004On005 for Input<C> {
     #[inline]
     fn DecompressSkeletalBoneData(
         &self,
-        _compressed_buffer: *mut c_void,
-        _compressed_buffer_size: u32,
-        _transform_space: *mut vr::EVRSkeletalTransformSpace,
-        _transform_array: *mut vr::VRBoneTransform_t,
-        _transform_array_count: u32,
+        compressed_buffer: *mut c_void,
+        compressed_buffer_size: u32,
+        transform_space: *mut vr::EVRSkeletalTransformSpace,
+        transform_array: *mut vr::VRBoneTransform_t,
+        transform_array_count: u32,
     ) -> vr::EVRInputError {
-        todo!()
+        if compressed_buffer.is_null() || transform_array.is_null() {
+            return vr::EVRInputError::InvalidParam;
+        }
+        let data = unsafe {
+            std::slice::from_raw_parts(
+                compressed_buffer as *const u8,
+                compressed_buffer_size as usize,
+            )
+        };
+        let transforms = unsafe {
+            std::slice::from_raw_parts_mut(transform_array, transform_array_count as usize)
+        };
+
+        // Older interfaces decompress into the space the data was compressed with
+        match skeletal::decompress_bones(data, None, transforms) {
+            Ok(space) => {
+                if let Some(transform_space) = unsafe { transform_space.as_mut() } {
+                    *transform_space = space;
+                }
+                vr::EVRInputError::None
+            }
+            Err(e) => e,
+        }
     }
 
     #[inline]
@@ -1276,12 +2286,13 @@ impl<C: openxr_data::Compositor> vr::IVRInput004On005 for Input<C> {
         name_array: *mut c_char,
         name_array_size: u32,
     ) -> vr::EVRInputError {
+        // Older versions always include every section (VRInputString_All)
         <Self as vr::IVRInput010_Interface>::GetOriginLocalizedName(
             self,
             origin,
             name_array,
             name_array_size,
-            0,
+            -1,
         )
     }
 
@@ -1334,25 +2345,220 @@ impl<C: openxr_data::Compositor> vr::IVRInput004On005 for Input<C> {
     #[inline]
     fn GetSkeletalBoneDataCompressed(
         &self,
-        _action: vr::VRActionHandle_t,
-        _transform_space: vr::EVRSkeletalTransformSpace,
-        _motion_range: vr::EVRSkeletalMotionRange,
-        _compressed_data: *mut c_void,
-        _compressed_size: u32,
-        _required_compressed_size: *mut u32,
-        _restrict_to_device: vr::VRInputValueHandle_t,
+        action: vr::VRActionHandle_t,
+        transform_space: vr::EVRSkeletalTransformSpace,
+        motion_range: vr::EVRSkeletalMotionRange,
+        compressed_data: *mut c_void,
+        compressed_size: u32,
+        required_compressed_size: *mut u32,
+        restrict_to_device: vr::VRInputValueHandle_t,
     ) -> vr::EVRInputError {
-        todo!()
+        // restrict_to_device not supported for skeletal actions
+        if restrict_to_device != vr::k_ulInvalidInputValueHandle {
+            return vr::EVRInputError::NoData;
+        }
+
+        self.get_compressed_bones(
+            action,
+            transform_space,
+            motion_range,
+            compressed_data,
+            compressed_size,
+            required_compressed_size,
+        )
     }
 }
 
 impl<C: openxr_data::Compositor> Input<C> {
+    /// Gets a pose action's data, predicted for the given time.
+    fn get_pose_action_data(
+        &self,
+        action: vr::VRActionHandle_t,
+        origin: vr::ETrackingUniverseOrigin,
+        time: xr::Time,
+        action_data: *mut vr::InputPoseActionData_t,
+        action_data_size: u32,
+        restrict_to_device: vr::VRInputValueHandle_t,
+    ) -> vr::EVRInputError {
+        assert_eq!(
+            action_data_size as usize,
+            std::mem::size_of::<vr::InputPoseActionData_t>()
+        );
+
+        if log::log_enabled!(log::Level::Trace) {
+            let action_map = self.action_map.read().unwrap();
+            let action_key = ActionKey::from(KeyData::from_ffi(action));
+            let input_map = self.input_source_map.read().unwrap();
+            let input_key = InputSourceKey::from(KeyData::from_ffi(restrict_to_device));
+            trace!(
+                "getting pose for {:?} (restrict: {:?})",
+                action_map.get(action_key).map(|a| &a.path),
+                input_map.get(input_key)
+            );
+        }
+
+        let data = self.openxr.session_data.get();
+        let Some(loaded) = data.input_data.get_loaded_actions() else {
+            return vr::EVRInputError::InvalidHandle;
+        };
+
+        macro_rules! no_data {
+            () => {{
+                unsafe {
+                    action_data.write(Default::default());
+                }
+                return vr::EVRInputError::None;
+            }};
+        }
+        let subaction_path = get_subaction_path!(self, restrict_to_device, action_data);
+
+        // The gaze isn't tied to either hand, so it can't be restricted to one.
+        if loaded.is_gaze_pose(action) {
+            drop(data);
+            let pose = (subaction_path == xr::Path::NULL)
+                .then(|| self.get_eye_gaze_pose(Some(origin)))
+                .flatten();
+            let Some(pose) = pose else { no_data!() };
+            unsafe {
+                action_data.write(vr::InputPoseActionData_t {
+                    bActive: true,
+                    activeOrigin: vr::k_ulInvalidInputValueHandle,
+                    pose,
+                });
+            }
+            return vr::EVRInputError::None;
+        }
+
+        let devices = data.input_data.devices.read().unwrap();
+        let (active_origin, hand, offset) = match loaded.try_get_action(action) {
+            Ok(ActionData::Pose) => {
+                let mut hand = match subaction_path {
+                    x if x == self.get_subaction_path(Hand::Left) => Some(Hand::Left),
+                    x if x == self.get_subaction_path(Hand::Right) => Some(Hand::Right),
+                    x if x == xr::Path::NULL => None,
+                    _ => unreachable!(),
+                };
+
+                let get_hand_pose =
+                    |hand: &TrackedDevice| loaded.try_get_pose(action, hand.profile_path).ok();
+
+                let get_first_bound_hand_profile = || {
+                    devices
+                        .get_controller(Hand::Left)
+                        .and_then(get_hand_pose)
+                        .or_else(|| devices.get_controller(Hand::Right).and_then(get_hand_pose))
+                };
+
+                let bound = match hand {
+                    // A restricted hand prefers its own profile (or the last one it had, if it
+                    // has since lost tracking), and only borrows the other hand's bindings if
+                    // its own profile has none.
+                    Some(h) => devices.get_controller(h).and_then(|controller| {
+                        [controller.profile_path, controller.last_profile_path]
+                            .into_iter()
+                            .filter(|p| *p != xr::Path::NULL)
+                            .find_map(|p| loaded.try_get_pose(action, p).ok())
+                            .or_else(get_first_bound_hand_profile)
+                    }),
+                    None => get_first_bound_hand_profile(),
+                };
+
+                let Some(bound) = bound else {
+                    match hand {
+                        Some(hand) => {
+                            trace!(
+                                "action has no bindings for the {hand:?} hand's interaction profile"
+                            );
+                        }
+                        None => {
+                            trace!("action has no bindings for either hand's interaction profile");
+                        }
+                    }
+
+                    no_data!()
+                };
+
+                let origin = hand.is_some().then_some(restrict_to_device);
+                let pose_type = match hand {
+                    Some(Hand::Left) => bound.left,
+                    Some(Hand::Right) => bound.right,
+                    None => {
+                        hand = Some(Hand::Left);
+                        bound.left.or_else(|| {
+                            hand = Some(Hand::Right);
+                            bound.right
+                        })
+                    }
+                };
+
+                let Some(ty) = pose_type else {
+                    trace!("action has no bindings for the hand {hand:?}");
+                    no_data!()
+                };
+
+                // The swap to the controller the pose comes from happens when the pose is read.
+                let hand = hand.unwrap();
+                let origin = origin.unwrap_or_else(|| self.hand_handle(hand));
+
+                let profile = devices
+                    .get_controller(hand)
+                    .and_then(|controller| controller.profile_data.as_ref());
+                (origin, hand, ty.offset_from_raw(profile, hand))
+            }
+            Ok(ActionData::Skeleton(hand)) => {
+                if subaction_path != xr::Path::NULL {
+                    return vr::EVRInputError::InvalidDevice;
+                }
+                (0, *hand, None)
+            }
+            Ok(_) => return vr::EVRInputError::WrongType,
+            Err(e) => return e,
+        };
+
+        drop(devices);
+        drop(data);
+
+        // Without a controller (or a pose for it) there's nothing to report, and a default pose
+        // would put the hand at the origin.
+        let Some(mut pose) = self.get_controller_pose_at_time(hand, Some(origin), time) else {
+            trace!("no pose for the {hand:?} controller, returning an invalid pose");
+            no_data!()
+        };
+        // Unlocatable poses are zeroed out, so don't offset them away from that.
+        if pose.bPoseIsValid
+            && let Some(offset) = offset
+        {
+            offset_pose(&mut pose, offset);
+        }
+        unsafe {
+            action_data.write(vr::InputPoseActionData_t {
+                bActive: true,
+                activeOrigin: active_origin,
+                pose,
+            });
+        }
+
+        vr::EVRInputError::None
+    }
+
+    /// A Vive tracker was connected. Trackers that already have a role won't necessarily get an
+    /// interaction profile change, so pick them up here.
+    pub fn vive_tracker_connected(&self, session_data: &SessionData) {
+        let mut devices = session_data.input_data.devices.write().unwrap();
+        if let Err(e) =
+            devices.create_htcx_generic_trackers(session_data, &self.tracker_roles.read().unwrap())
+        {
+            warn!("Failed to update Vive trackers: {e}");
+        }
+    }
+
     pub fn interaction_profile_changed(&self, session_data: &SessionData) {
         let mut devices = session_data.input_data.devices.write().unwrap();
 
         let mut devices_to_create = vec![];
 
         for hand in [Hand::Left, Hand::Right] {
+            let controller_index = devices.get_controller_index(hand);
             let mut controller = devices.get_controller_mut(hand);
             let subaction_path = self.get_subaction_path(hand);
 
@@ -1362,7 +2568,21 @@ impl<C: openxr_data::Compositor> Input<C> {
                 .unwrap();
 
             if let Some(controller) = controller.as_mut() {
+                // Switching between profiles without disconnecting (i.e., touch -> touch plus)
+                // won't send any activation events, so let games know to requery properties.
+                if controller.profile_path != xr::Path::NULL
+                    && profile_path != xr::Path::NULL
+                    && controller.profile_path != profile_path
+                {
+                    self.queue_event(
+                        vr::EVREventType::TrackedDeviceRoleChanged,
+                        controller_index.unwrap(),
+                    );
+                }
                 controller.profile_path = profile_path;
+                if profile_path != xr::Path::NULL {
+                    controller.last_profile_path = profile_path;
+                }
             }
 
             let profile_name = match profile_path {
@@ -1404,13 +2624,12 @@ impl<C: openxr_data::Compositor> Input<C> {
 
             profiles::run_for_all_profiles(&mut data);
 
-            if let Some(data) = data.data {
+            if let Some(mut data) = data.data {
+                data.overrides = self.property_overrides.read().unwrap().get(&profile_name);
                 if let Some(controller) = controller.as_mut() {
                     controller.profile_data = Some(data);
                 } else {
-                    let hand_tracker = session_data
-                        .session
-                        .create_hand_tracker(hand.into())
+                    let hand_tracker = devices::create_hand_tracker(&session_data.session, hand)
                         .inspect_err(|e| {
                             if !matches!(
                                 *e,
@@ -1454,15 +2673,57 @@ impl<C: openxr_data::Compositor> Input<C> {
             });
         }
 
+        let tracker_roles = self.tracker_roles.read().unwrap();
         #[cfg(feature = "monado")]
         devices
-            .create_monado_generic_trackers(&self.openxr, session_data)
+            .create_monado_generic_trackers(&self.openxr, session_data, &tracker_roles)
             .unwrap();
+        // Trackers being assigned a role or changing roles changes their interaction profile,
+        // so they only need to be looked at here.
+        if let Err(e) = devices.create_htcx_generic_trackers(session_data, &tracker_roles) {
+            warn!("Failed to update Vive trackers: {e}");
+        }
+    }
+
+    /// Picks up trackers being plugged in or removed. They're first created along with the
+    /// controllers, so this does nothing until then.
+    #[cfg(feature = "monado")]
+    fn update_generic_trackers(&self, session_data: &SessionData) {
+        // Getting the xdev list is too slow to do every frame.
+        let frames = self.tracker_poll_frames.fetch_add(1, Ordering::Relaxed);
+        if frames % TRACKER_POLL_INTERVAL != 0 {
+            return;
+        }
+
+        let generation = {
+            let devices = session_data.input_data.devices.read().unwrap();
+            if !devices.has_xdev_trackers() {
+                return;
+            }
+            devices.xdev_generation()
+        };
+        if let Err(e) = devices::with_xdev_trackers(
+            &self.openxr,
+            session_data,
+            generation,
+            |generation, xdevs| {
+                session_data
+                    .input_data
+                    .devices
+                    .write()
+                    .unwrap()
+                    .update_xdev_trackers(generation, xdevs, &self.tracker_roles.read().unwrap())
+            },
+        ) {
+            warn!("Failed to update generic trackers: {e}");
+        }
     }
 
     pub fn frame_start_update(&self) {
         tracy_span!();
         let data = self.openxr.session_data.get();
+        #[cfg(feature = "monado")]
+        self.update_generic_trackers(&data);
         let devices = data.input_data.devices.read().unwrap();
 
         for device in devices.iter() {
@@ -1479,13 +2740,20 @@ impl<C: openxr_data::Compositor> Input<C> {
             // don't actually call UpdateActionState if no controllers are reported as connected,
             // and interaction profiles are only updated after xrSyncActions is called. So here, we
             // do an action sync to try and get the runtime to update the interaction profile.
+            // Runtimes take a while to report a profile anyway, so this doesn't need to happen
+            // every frame.
             if (left_hand.is_none_or(|hand| !hand.connected))
                 && (right_hand.is_none_or(|hand| !hand.connected))
             {
-                debug!("no controllers connected - syncing info set");
-                data.session
-                    .sync_actions(&[xr::ActiveActionSet::new(&loaded.info_set)])
-                    .unwrap();
+                let frames = self.disconnected_frames.fetch_add(1, Ordering::Relaxed);
+                if frames % INFO_SET_SYNC_INTERVAL == 0 {
+                    debug!("no controllers connected - syncing info set");
+                    data.session
+                        .sync_actions(&[xr::ActiveActionSet::new(&loaded.info_set)])
+                        .unwrap();
+                }
+            } else {
+                self.disconnected_frames.store(0, Ordering::Relaxed);
             }
             return;
         }
@@ -1539,13 +2807,32 @@ impl<C: openxr_data::Compositor> Input<C> {
                 &self.openxr.instance,
                 self.subaction_paths.left,
                 self.subaction_paths.right,
+                self.openxr.enabled_extensions.ext_eye_gaze_interaction,
             ))
             .unwrap_or_else(|_| panic!("PoseData already setup"));
-        if let Some(path) = self.loaded_actions_path.get() {
-            let _ = self.load_action_manifest(data, path);
+        if !self.loaded_actions_paths.lock().unwrap().is_empty() {
+            let _ = self.load_action_manifests(data);
         }
     }
 
+    /// Queues an event with no data to be returned from [`Self::get_next_event`].
+    pub fn queue_event(&self, ty: vr::EVREventType, index: vr::TrackedDeviceIndex_t) {
+        self.queue_event_with_data(ty, index, Default::default());
+    }
+
+    /// Queues an event to be returned from [`Self::get_next_event`].
+    pub fn queue_event_with_data(
+        &self,
+        ty: vr::EVREventType,
+        index: vr::TrackedDeviceIndex_t,
+        data: vr::VREvent_Data_t,
+    ) {
+        self.events
+            .lock()
+            .unwrap()
+            .push_back(InputEvent { ty, index, data });
+    }
+
     pub fn get_next_event(&self, size: u32, out: *mut vr::VREvent_t) -> bool {
         const FUNC: &str = "get_next_event";
         if out.is_null() {
@@ -1589,11 +2876,19 @@ impl<C: openxr_data::Compositor> Input<C> {
             // VREvent_t can be different sizes depending on the OpenVR version,
             // so we use raw pointers to avoid creating a reference, because if the
             // size doesn't match our VREvent_t's size, we are in UB land
+            // The data union is smaller in older versions as well, so only write the part of it
+            // that fits.
+            let data_size = (size as usize - std::mem::offset_of!(vr::VREvent_t, data))
+                .min(std::mem::size_of::<vr::VREvent_Data_t>());
             unsafe {
                 (&raw mut (*out).eventType).write(event.ty as u32);
                 (&raw mut (*out).trackedDeviceIndex).write(event.index);
                 (&raw mut (*out).eventAgeSeconds).write(0.0);
-                (&raw mut (*out).data.controller).write(event.data);
+                std::ptr::copy_nonoverlapping(
+                    (&raw const event.data).cast::<u8>(),
+                    (&raw mut (*out).data).cast::<u8>(),
+                    data_size,
+                );
             }
             true
         } else {
@@ -1614,6 +2909,11 @@ struct ManifestLoadedActions {
     actions_with_custom_bindings: HashSet<ActionKey>,
     per_profile_pose_bindings: HashMap<xr::Path, SecondaryMap<ActionKey, BoundPose>>,
     per_profile_bindings: HashMap<xr::Path, SecondaryMap<ActionKey, Vec<BoolBindingData>>>,
+    per_profile_binding_info: HashMap<xr::Path, SecondaryMap<ActionKey, Vec<BindingInfo>>>,
+    per_profile_analog_remaps: HashMap<xr::Path, SecondaryMap<ActionKey, Vec<AnalogRemap>>>,
+    /// The hands whose thumbstick is bound to a 2D action, for the thumbstick deadzone.
+    per_profile_thumbstick_hands: HashMap<xr::Path, SecondaryMap<ActionKey, Vec<Hand>>>,
+    binding_report: BindingReport,
     info_set: xr::ActionSet,
     _info_action: xr::Action<bool>,
     haptic_set: xr::ActionSet,
@@ -1655,6 +2955,14 @@ impl ManifestLoadedActions {
             .ok_or(vr::EVRInputError::InvalidHandle)
     }
 
+    /// Whether the pose action is bound to the eye gaze for any interaction profile.
+    fn is_gaze_pose(&self, handle: vr::VRActionHandle_t) -> bool {
+        let key = ActionKey::from(KeyData::from_ffi(handle));
+        self.per_profile_pose_bindings
+            .values()
+            .any(|bindings| bindings.get(key).is_some_and(|bound| bound.gaze))
+    }
+
     fn try_get_pose(
         &self,
         handle: vr::VRActionHandle_t,
@@ -1674,19 +2982,34 @@ struct PoseData {
     grip: xr::Action<xr::Posef>,
     left_space: HandSpace,
     right_space: HandSpace,
+    eye_gaze: Option<eye_gaze::EyeGazeData>,
+    vive_trackers: Option<vive_trackers::ViveTrackerData>,
 }
 
 impl PoseData {
-    fn new(instance: &xr::Instance, left_path: xr::Path, right_path: xr::Path) -> Self {
+    fn new(
+        instance: &xr::Instance,
+        left_path: xr::Path,
+        right_path: xr::Path,
+        eye_gaze: bool,
+    ) -> Self {
         let set = instance
             .create_action_set("xrizer-pose-data", "xrizer pose data", 0)
             .unwrap();
         let grip = set
             .create_action("grip-pose", "Grip Pose", &[left_path, right_path])
             .unwrap();
+        let eye_gaze = eye_gaze.then(|| eye_gaze::EyeGazeData::new(instance, &set));
+        let vive_trackers = instance
+            .exts()
+            .htcx_vive_tracker_interaction
+            .is_some()
+            .then(|| vive_trackers::ViveTrackerData::new(instance, &set));
         Self {
             set,
             grip,
+            eye_gaze,
+            vive_trackers,
             left_space: HandSpace {
                 hand: Hand::Left,
                 hand_path: left_path,
@@ -1702,6 +3025,9 @@ impl PoseData {
     fn reset_spaces(&self) {
         self.left_space.reset_raw();
         self.right_space.reset_raw();
+        if let Some(eye_gaze) = &self.eye_gaze {
+            eye_gaze.reset_space();
+        }
     }
 }
 
@@ -3,20 +3,48 @@ mod bindings;
 mod context;
 
 pub(super) use actions::ControllerType;
-pub(super) use bindings::{ClickThresholdParams, GrabParameters};
+pub(super) use bindings::{
+    AnalogRemap, BindingInfo, ClickThresholdParams, GrabParameters, ScrollParameters,
+};
 
 use crate::input::InteractionProfile;
 use crate::input::action_manifest::context::BindingsLoadContext;
 use crate::input::profiles::LegalPathsT;
 use crate::input::{ActionKey, Input, profiles::RunWithProfile, skeletal::SkeletalInputActionData};
 use crate::openxr_data::{self, Hand, SessionData};
+use crate::settings::XrizerSettings;
 use log::{debug, error, info, warn};
 use openvr as vr;
 use openxr as xr;
 use slotmap::{SecondaryMap, SlotMap};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env::current_dir;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// A parsed action manifest and its bindings, kept so that session restarts only have to
+/// recreate the OpenXR actions.
+pub(super) struct CachedManifest {
+    path: PathBuf,
+    /// Every file the manifest was loaded from or could have been, with its modification time
+    /// (None if it didn't exist), so the cache is dropped if any of them change.
+    sources: Vec<(PathBuf, Option<SystemTime>)>,
+    manifest: actions::ActionManifest,
+    bindings: Vec<(actions::ControllerType, bindings::Bindings)>,
+}
+
+/// Problems found while loading the bindings of an action manifest, for checking manifests
+/// without having to dig through the logs.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BindingReport {
+    /// Actions declared in the manifest that no binding for any profile refers to.
+    pub unbound_actions: BTreeSet<String>,
+    /// Actions that bindings refer to, but aren't declared in the manifest, by interaction profile.
+    pub unknown_actions: BTreeMap<String, BTreeSet<String>>,
+    /// Bound input paths that don't exist on their interaction profile, by interaction profile.
+    pub invalid_inputs: BTreeMap<String, BTreeSet<String>>,
+}
 
 fn action_map_to_secondary<T>(
     act_guard: &mut SlotMap<ActionKey, super::Action>,
@@ -34,20 +62,209 @@ fn action_map_to_secondary<T>(
         .collect()
 }
 
+/// Reads and parses the bindings files of a manifest, preferring user provided bindings.
+fn read_bindings(
+    parent_path: &Path,
+    bindings: &[actions::DefaultBindings],
+) -> Vec<(actions::ControllerType, bindings::Bindings)> {
+    // Each controller falls back to its next bindings if the previous ones couldn't be loaded.
+    let profiles: Vec<&[actions::DefaultBindings]> = bindings
+        .chunk_by(|a, b| a.controller_type == b.controller_type)
+        .collect();
+
+    // Parsing is the slow part for manifests with a lot of bindings, and controllers don't depend
+    // on each other, so each one is read on its own thread. Suggesting the bindings afterwards
+    // stays sequential, since it goes through the instance.
+    std::thread::scope(|scope| {
+        let threads: Vec<_> = profiles
+            .iter()
+            .map(|files| scope.spawn(|| read_profile_bindings(parent_path, files)))
+            .collect();
+        profiles
+            .iter()
+            .zip(threads)
+            .filter_map(|(files, thread)| {
+                let bindings = thread.join().unwrap()?;
+                Some((files[0].controller_type.clone(), bindings))
+            })
+            .collect()
+    })
+}
+
+/// Reads the first of a controller's bindings files that can be loaded.
+fn read_profile_bindings(
+    parent_path: &Path,
+    files: &[actions::DefaultBindings],
+) -> Option<bindings::Bindings> {
+    let mut files = files.iter();
+    let parsed = files
+        .by_ref()
+        .find_map(|b| read_bindings_file(parent_path, b));
+    for b in files {
+        info!("skipping bindings in {:?}", b.binding_url);
+    }
+    parsed
+}
+
+/// The bindings for one controller type from all loaded manifests, by action set.
+type MergedBindings<'a> = BTreeMap<&'a str, &'a bindings::ActionSetBinding>;
+
+/// Combines the bindings of all loaded manifests by controller type. If an action set is bound
+/// by more than one manifest, the bindings from the manifest that was loaded first win.
+fn merge_bindings(
+    manifests: &[Arc<CachedManifest>],
+) -> Vec<(&actions::ControllerType, MergedBindings<'_>)> {
+    let mut ret: Vec<(&actions::ControllerType, MergedBindings)> = Vec::new();
+    for manifest in manifests {
+        for (controller_type, bindings) in &manifest.bindings {
+            let idx = ret
+                .iter()
+                .position(|(ty, _)| *ty == controller_type)
+                .unwrap_or_else(|| {
+                    ret.push((controller_type, BTreeMap::new()));
+                    ret.len() - 1
+                });
+            let merged = &mut ret[idx].1;
+            for (set_name, set_bindings) in &bindings.bindings {
+                if merged.contains_key(set_name.as_str()) {
+                    warn!(
+                        "Ignoring conflicting bindings for {set_name} ({controller_type:?}) from {}",
+                        manifest.path.display()
+                    );
+                    continue;
+                }
+                merged.insert(set_name, set_bindings);
+            }
+        }
+    }
+    ret
+}
+
+/// Directory users can drop their own bindings into, which take priority over the game's.
+fn custom_bindings_dir() -> PathBuf {
+    if let Some(custom_dir) = &XrizerSettings::get().custom_bindings_dir {
+        custom_dir.clone()
+    } else {
+        current_dir().unwrap().join("xrizer")
+    }
+}
+
+/// Resolves a binding URL relative to the manifest's directory. Manifests are written for Windows,
+/// so URLs may use backslashes and won't necessarily match the case of the files on disk.
+fn resolve_bindings_path(parent_path: &Path, binding_url: &Path) -> PathBuf {
+    let url = binding_url.to_string_lossy().replace('\\', "/");
+    let mut path = parent_path.to_path_buf();
+    for component in Path::new(&url).components() {
+        let next = path.join(component);
+        if next.exists() {
+            path = next;
+            continue;
+        }
+
+        let name = component.as_os_str();
+        let found = std::fs::read_dir(&path).ok().and_then(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .find(|entry| entry.file_name().eq_ignore_ascii_case(name))
+        });
+        path = found.map(|entry| entry.path()).unwrap_or(next);
+    }
+    path
+}
+
+/// Where the user's own bindings for a controller type would be.
+fn custom_bindings_path(controller_type: &actions::ControllerType) -> PathBuf {
+    custom_bindings_dir().join(format!("{controller_type:?}.json").to_lowercase())
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The files a manifest's bindings may be read from, which the cache needs to watch.
+fn bindings_sources(parent_path: &Path, bindings: &[actions::DefaultBindings]) -> Vec<PathBuf> {
+    bindings
+        .iter()
+        .flat_map(|b| {
+            [
+                custom_bindings_path(&b.controller_type),
+                resolve_bindings_path(parent_path, &b.binding_url),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+thread_local! {
+    /// How many times a manifest and its bindings were actually read on this thread.
+    pub(super) static MANIFEST_READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn read_bindings_file(
+    parent_path: &Path,
+    actions::DefaultBindings {
+        binding_url,
+        controller_type,
+    }: &actions::DefaultBindings,
+) -> Option<bindings::Bindings> {
+    let custom_path = custom_bindings_path(controller_type);
+    let bindings_path = match custom_path.exists() {
+        true => custom_path,
+        false => resolve_bindings_path(parent_path, binding_url),
+    };
+    debug!(
+        "Reading bindings for {controller_type:?} (at {})",
+        bindings_path.display()
+    );
+
+    let data = std::fs::read(bindings_path)
+        .inspect_err(|e| error!("Couldn't load bindings for {controller_type:?}: {e}"))
+        .ok()?;
+
+    serde_json::from_slice(&data)
+        .inspect_err(|e| error!("Failed to parse bindings for {controller_type:?}: {e}"))
+        .ok()
+}
+
 impl<C: openxr_data::Compositor> Input<C> {
+    /// The directories the loaded action manifests and their bindings are read from.
+    /// Empty if no manifest has been loaded.
+    pub fn app_container_file_paths(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for path in self.loaded_actions_paths.lock().unwrap().iter() {
+            let Some(dir) = std::path::absolute(path)
+                .ok()
+                .and_then(|path| path.parent().map(Path::to_path_buf))
+            else {
+                continue;
+            };
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+
+        if !dirs.is_empty() {
+            let custom_dir = custom_bindings_dir();
+            if custom_dir.is_dir() && !dirs.contains(&custom_dir) {
+                dirs.push(custom_dir);
+            }
+        }
+
+        dirs
+    }
+
     pub(super) fn load_action_manifest(
         &self,
         session_data: &SessionData,
         manifest_path: &Path,
     ) -> Result<(), vr::EVRInputError> {
-        match self.loaded_actions_path.get() {
-            Some(p) => {
-                assert_eq!(p, manifest_path);
+        {
+            let mut paths = self.loaded_actions_paths.lock().unwrap();
+            if paths.iter().any(|p| p == manifest_path) {
                 if session_data.input_data.actions.get().is_some() {
                     return Ok(());
                 }
-            }
-            None => {
+            } else {
                 if let Some(loaded) = session_data.input_data.actions.get() {
                     error!(
                         "{} actions are already loaded!",
@@ -59,45 +276,99 @@ impl<C: openxr_data::Compositor> Input<C> {
                     );
                     return Err(vr::EVRInputError::MismatchedActionManifest);
                 }
-                self.loaded_actions_path
-                    .set(manifest_path.to_path_buf())
-                    .unwrap();
+                paths.push(manifest_path.to_path_buf());
             }
         }
 
-        let data = std::fs::read(manifest_path).map_err(|e| {
-            error!("Failed to read manifest {}: {e}", manifest_path.display());
-            vr::EVRInputError::InvalidParam
-        })?;
+        self.load_action_manifests(session_data)
+    }
 
-        let manifest: actions::ActionManifest = serde_json::from_slice(&data).map_err(|e| {
-            error!("Failed to parse action manifest: {e}");
-            vr::EVRInputError::InvalidParam
-        })?;
+    /// Adds a manifest to be merged with the ones that are already loaded, for mods that set
+    /// their own manifest on top of the game's. A manifest with the same action sets as the loaded
+    /// ones replaces them instead. Returns false if the manifest was already loaded.
+    /// The actions are only created on the next session restart.
+    pub(super) fn merge_action_manifest(
+        &self,
+        manifest_path: &Path,
+    ) -> Result<bool, vr::EVRInputError> {
+        let mut paths = self.loaded_actions_paths.lock().unwrap();
+        if paths.iter().any(|p| p == manifest_path) {
+            return Ok(false);
+        }
 
-        // TODO: support non english localization?
-        let english = manifest
-            .localization
-            .and_then(|l| l.into_iter().find(|l| l.language_tag == "en_US"));
+        // Make sure the manifest is usable before it gets loaded along with the others.
+        let manifest = self.get_cached_manifest(manifest_path)?;
+        let set_paths = |manifest: &CachedManifest| {
+            manifest
+                .manifest
+                .action_sets
+                .iter()
+                .map(|set| set.path().to_owned())
+                .collect::<BTreeSet<_>>()
+        };
+        let mut loaded_sets = BTreeSet::new();
+        for path in paths.iter() {
+            loaded_sets.extend(set_paths(&self.get_cached_manifest(path)?));
+        }
+        if set_paths(&manifest) == loaded_sets {
+            info!(
+                "Action manifest {} replaces {paths:?}",
+                manifest_path.display()
+            );
+            *paths = vec![manifest_path.to_path_buf()];
+            return Ok(true);
+        }
 
-        let mut sets = actions::load_action_sets(
-            &self.openxr.instance,
-            english.as_ref(),
-            manifest.action_sets,
-        )?;
-        debug!("Loaded {} action sets.", sets.len());
+        info!(
+            "Merging action manifest {} into {paths:?}",
+            manifest_path.display()
+        );
+        paths.push(manifest_path.to_path_buf());
+        Ok(true)
+    }
+
+    /// Creates the actions and bindings of all loaded manifests, in the order they were loaded.
+    pub(super) fn load_action_manifests(
+        &self,
+        session_data: &SessionData,
+    ) -> Result<(), vr::EVRInputError> {
+        let paths = self.loaded_actions_paths.lock().unwrap().clone();
+        let manifests = paths
+            .iter()
+            .map(|path| self.get_cached_manifest(path))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let left_hand_subaction_path = self.get_subaction_path(Hand::Left);
         let right_hand_subaction_path = self.get_subaction_path(Hand::Right);
 
-        let actions = actions::load_actions(
-            &self.openxr.instance,
-            english.as_ref(),
-            &mut sets,
-            manifest.actions,
-            left_hand_subaction_path,
-            right_hand_subaction_path,
-        )?;
+        let mut sets = HashMap::new();
+        let mut actions = HashMap::new();
+        for cached in &manifests {
+            let manifest = &cached.manifest;
+
+            // TODO: support non english localization?
+            let english = manifest
+                .localization
+                .as_ref()
+                .and_then(|l| l.iter().find(|l| l.language_tag == "en_US"));
+
+            actions::load_action_sets(
+                &self.openxr.instance,
+                english,
+                &manifest.action_sets,
+                &mut sets,
+            )?;
+            actions::load_actions(
+                &self.openxr.instance,
+                english,
+                &mut sets,
+                &manifest.actions,
+                left_hand_subaction_path,
+                right_hand_subaction_path,
+                &mut actions,
+            )?;
+        }
+        debug!("Loaded {} action sets.", sets.len());
         debug!("Loaded {} actions.", actions.len());
 
         let skeletal_input = session_data
@@ -135,6 +406,7 @@ impl<C: openxr_data::Compositor> Input<C> {
             )
             .unwrap();
 
+        let declared_actions: Vec<String> = actions.keys().cloned().collect();
         let mut binding_context = context::BindingsLoadContext::new(
             &sets,
             actions,
@@ -144,20 +416,35 @@ impl<C: openxr_data::Compositor> Input<C> {
             skeletal_input,
         );
 
-        self.load_bindings(
-            manifest_path.parent().unwrap(),
-            manifest.default_bindings,
-            &mut binding_context,
-        );
+        self.load_bindings(&merge_bindings(&manifests), &mut binding_context);
 
         let context::BindingsLoadContext {
             actions,
             extra_actions,
             per_profile_bindings,
             per_profile_pose_bindings,
+            per_profile_binding_info,
+            per_profile_analog_remaps,
+            per_profile_thumbstick_hands,
+            report,
+            referenced_actions,
             ..
         } = binding_context;
 
+        let mut binding_report = report.into_inner();
+        let referenced_actions = referenced_actions.into_inner();
+        binding_report.unbound_actions = declared_actions
+            .into_iter()
+            .filter(|action| !referenced_actions.contains(action))
+            .collect();
+        if !binding_report.unbound_actions.is_empty() {
+            info!(
+                "{} actions have no bindings: {:?}",
+                binding_report.unbound_actions.len(),
+                binding_report.unbound_actions
+            );
+        }
+
         let xr_sets: Vec<_> = sets
             .values()
             .chain([
@@ -226,6 +513,21 @@ impl<C: openxr_data::Compositor> Input<C> {
             .map(|(k, v)| (k, action_map_to_secondary(&mut act_guard, v)))
             .collect();
 
+        let per_profile_binding_info = per_profile_binding_info
+            .into_iter()
+            .map(|(k, v)| (k, action_map_to_secondary(&mut act_guard, v)))
+            .collect();
+
+        let per_profile_analog_remaps = per_profile_analog_remaps
+            .into_iter()
+            .map(|(k, v)| (k, action_map_to_secondary(&mut act_guard, v)))
+            .collect();
+
+        let per_profile_thumbstick_hands = per_profile_thumbstick_hands
+            .into_iter()
+            .map(|(k, v)| (k, action_map_to_secondary(&mut act_guard, v)))
+            .collect();
+
         let loaded = super::ManifestLoadedActions {
             sets,
             actions,
@@ -233,6 +535,10 @@ impl<C: openxr_data::Compositor> Input<C> {
             extra_actions,
             per_profile_bindings,
             per_profile_pose_bindings,
+            per_profile_binding_info,
+            per_profile_analog_remaps,
+            per_profile_thumbstick_hands,
+            binding_report,
             _info_action: info_action,
             info_set,
             haptic_action,
@@ -249,68 +555,86 @@ impl<C: openxr_data::Compositor> Input<C> {
 }
 
 impl<C: openxr_data::Compositor> Input<C> {
-    fn load_bindings(
+    /// Returns the parsed manifest at the given path, only reading it and its bindings again if
+    /// the manifest or any of its bindings files changed since it was last loaded.
+    fn get_cached_manifest(
         &self,
-        parent_path: &Path,
-        bindings: Vec<actions::DefaultBindings>,
-        context: &mut context::BindingsLoadContext,
-    ) {
-        let mut it = bindings.into_iter().peekable();
-        while let Some(actions::DefaultBindings {
-            binding_url,
-            controller_type,
-        }) = it.next()
-        {
-            let custom_path = if let Ok(custom_dir) = std::env::var("XRIZER_CUSTOM_BINDINGS_DIR") {
-                PathBuf::from(custom_dir)
-            } else {
-                current_dir().unwrap().join("xrizer")
-            }
-            .join(format!("{controller_type:?}.json").to_lowercase());
-            let bindings_path = match custom_path.exists() {
-                true => custom_path,
-                false => parent_path.join(binding_url),
-            };
-            debug!(
-                "Reading bindings for {controller_type:?} (at {})",
-                bindings_path.display()
-            );
+        manifest_path: &Path,
+    ) -> Result<Arc<CachedManifest>, vr::EVRInputError> {
+        let modified = modified_time(manifest_path);
+        let mut cache = self.manifest_cache.lock().unwrap();
+        if let Some(cached) = cache.get(manifest_path).filter(|c| {
+            modified.is_some()
+                && c.sources
+                    .iter()
+                    .all(|(path, modified)| modified_time(path) == *modified)
+        }) {
+            debug!("Reusing cached action manifest {}", manifest_path.display());
+            return Ok(cached.clone());
+        }
 
-            let data = match std::fs::read(bindings_path) {
-                Ok(data) => data,
-                Err(e) => {
-                    error!("Couldn't load bindings for {controller_type:?}: {e}");
-                    continue;
-                }
-            };
+        #[cfg(test)]
+        MANIFEST_READS.with(|reads| reads.set(reads.get() + 1));
 
-            let bindings = match serde_json::from_slice(&data) {
-                Ok(bindings) => bindings,
-                Err(e) => {
-                    error!("Failed to parse bindings for {controller_type:?}: {e}");
-                    continue;
-                }
-            };
+        let data = std::fs::read(manifest_path).map_err(|e| {
+            error!("Failed to read manifest {}: {e}", manifest_path.display());
+            vr::EVRInputError::InvalidParam
+        })?;
+
+        let manifest: actions::ActionManifest = serde_json::from_slice(&data).map_err(|e| {
+            error!("Failed to parse action manifest: {e}");
+            vr::EVRInputError::InvalidParam
+        })?;
+
+        // Note the modification times before reading the bindings, so changes made while they're
+        // being read are picked up next time.
+        let parent_path = manifest_path.parent().unwrap();
+        let sources = std::iter::once((manifest_path.to_path_buf(), modified))
+            .chain(
+                bindings_sources(parent_path, &manifest.default_bindings)
+                    .into_iter()
+                    .map(|path| {
+                        let modified = modified_time(&path);
+                        (path, modified)
+                    }),
+            )
+            .collect();
+        let bindings = read_bindings(parent_path, &manifest.default_bindings);
+
+        let cached = Arc::new(CachedManifest {
+            path: manifest_path.to_path_buf(),
+            sources,
+            manifest,
+            bindings,
+        });
+        cache.insert(manifest_path.to_path_buf(), cached.clone());
+        Ok(cached)
+    }
 
+    fn load_bindings(
+        &self,
+        bindings: &[(&actions::ControllerType, MergedBindings)],
+        context: &mut context::BindingsLoadContext,
+    ) {
+        for (controller_type, bindings) in bindings {
             match controller_type {
-                actions::ControllerType::Unknown(ref other) => {
+                actions::ControllerType::Unknown(other) => {
                     info!("Ignoring bindings for unknown profile {other}")
                 }
-                ref other => {
+                other => {
                     let mut runner = Runner(self, context, bindings);
                     other.run_for_profile(&mut runner);
 
                     // lifetime moment
-                    struct Runner<'a, 'b, 'c, C: openxr_data::Compositor>(
+                    struct Runner<'a, 'b, 'c, 'd, C: openxr_data::Compositor>(
                         &'a Input<C>,
                         &'b mut BindingsLoadContext<'c>,
-                        bindings::Bindings,
+                        &'d MergedBindings<'d>,
                     );
-                    impl<C: openxr_data::Compositor> RunWithProfile for Runner<'_, '_, '_, C> {
+                    impl<C: openxr_data::Compositor> RunWithProfile for Runner<'_, '_, '_, '_, C> {
                         fn run<P: super::InteractionProfile>(&mut self) {
                             if let Some(mut context) = self.1.for_profile::<C, P>(self.0) {
-                                self.0
-                                    .load_bindings_for_profile::<P>(&self.2.bindings, &mut context);
+                                self.0.load_bindings_for_profile::<P>(self.2, &mut context);
                             } else {
                                 warn!(
                                     "Couldn't create context for profile {}?",
@@ -321,16 +645,12 @@ impl<C: openxr_data::Compositor> Input<C> {
                     }
                 }
             }
-
-            while let Some(b) = it.next_if(|b| b.controller_type == controller_type) {
-                info!("skipping bindings in {:?}", b.binding_url);
-            }
         }
     }
 
     fn load_bindings_for_profile<P: InteractionProfile>(
         &self,
-        bindings: &HashMap<String, bindings::ActionSetBinding>,
+        bindings: &MergedBindings,
         context: &mut context::BindingsProfileLoadContext<'_>,
     ) {
         info!("loading bindings for {}", P::profile_path());
@@ -349,7 +669,9 @@ impl<C: openxr_data::Compositor> Input<C> {
                 .or_else(|| P::translate_path(path))
         };
 
-        for (action_set_name, bindings) in bindings.iter() {
+        // The sets are sorted, so the names of any actions created for the bindings don't change
+        // between loads.
+        for (action_set_name, bindings) in bindings {
             let Some(set) = context.get_action_set(action_set_name) else {
                 warn!("Action set {action_set_name} missing.");
                 continue;
@@ -376,6 +698,16 @@ impl<C: openxr_data::Compositor> Input<C> {
                 &set,
                 &bindings.sources,
             );
+
+            if let Some(chords) = &bindings.chords {
+                bindings::handle_chord_bindings(
+                    &path_validator,
+                    context,
+                    action_set_name,
+                    &set,
+                    chords,
+                );
+            }
         }
 
         let info_action_binding = *legacy_bindings
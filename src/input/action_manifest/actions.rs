@@ -2,14 +2,14 @@
 
 use super::bindings::ActionPath;
 use crate::openxr_data::Hand;
-use log::{error, trace, warn};
+use log::{debug, error, trace, warn};
 use openvr as vr;
 use openxr as xr;
 use serde::{
     Deserialize,
     de::{Error, Unexpected},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 /**
@@ -51,6 +51,12 @@ pub struct ActionSetJson {
     path: String,
 }
 
+impl ActionSetJson {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
 pub enum ActionType {
@@ -122,20 +128,25 @@ fn create_action_set(
         })
 }
 
+/// Creates the given action sets, skipping any that were already created for another manifest.
 pub fn load_action_sets(
     instance: &xr::Instance,
     english: Option<&Localization>,
-    sets: Vec<ActionSetJson>,
-) -> Result<HashMap<String, xr::ActionSet>, vr::EVRInputError> {
-    let mut action_sets = HashMap::new();
+    sets: &[ActionSetJson],
+    action_sets: &mut HashMap<String, xr::ActionSet>,
+) -> Result<(), vr::EVRInputError> {
     for ActionSetJson { path } in sets {
-        let localized = english.and_then(|e| e.localized_names.get(&path));
+        let localized = english.and_then(|e| e.localized_names.get(path));
 
         let path = path.to_lowercase();
+        if action_sets.contains_key(&path) {
+            debug!("Action set {path} was already loaded, reusing it");
+            continue;
+        }
         let set = create_action_set(instance, &path, localized.map(String::as_str))?;
         action_sets.insert(path, set);
     }
-    Ok(action_sets)
+    Ok(())
 }
 
 fn create_action<T: xr::ActionTy>(
@@ -190,17 +201,48 @@ fn create_action<T: xr::ActionTy>(
 }
 
 pub type LoadedActionDataMap = HashMap<String, crate::input::ActionData>;
+/// Creates the given actions, skipping any that were already created for another manifest.
 pub fn load_actions(
     instance: &xr::Instance,
     english: Option<&Localization>,
     sets: &mut HashMap<String, xr::ActionSet>,
-    actions: Vec<ActionType>,
+    actions: &[ActionType],
     left_hand: xr::Path,
     right_hand: xr::Path,
-) -> Result<LoadedActionDataMap, vr::EVRInputError> {
-    let mut ret = HashMap::with_capacity(actions.len());
-    let mut long_name_idx = 0;
+    ret: &mut LoadedActionDataMap,
+) -> Result<(), vr::EVRInputError> {
+    ret.reserve(actions.len());
+    // Every long name before this point got an index lower than the number of loaded actions,
+    // so starting here keeps the names unique across manifests.
+    let mut long_name_idx = ret.len();
+    let mut declared = HashSet::new();
     for action in actions {
+        let name = match action {
+            ActionType::Boolean(data)
+            | ActionType::Vector1(data)
+            | ActionType::Vector2(data)
+            | ActionType::Vector3(data)
+            | ActionType::Vibration(data)
+            | ActionType::Pose(data)
+            | ActionType::Skeleton(SkeletonData { data, .. }) => &data.name,
+        };
+        // Action paths are lowercased, so names differing only by case refer to the same action.
+        if !declared.insert(name.path.as_str()) {
+            warn!(
+                "Action {} is declared more than once (action names are case insensitive), \
+                 only the first declaration will be used",
+                name.path
+            );
+            continue;
+        }
+        if ret.contains_key(&name.path) {
+            warn!(
+                "Action {} was already declared by another manifest, skipping it",
+                name.path
+            );
+            continue;
+        }
+
         let paths = &[left_hand, right_hand];
         macro_rules! create_action {
             ($ty:ty, $data:expr) => {
@@ -209,7 +251,7 @@ pub fn load_actions(
             };
         }
         use crate::input::ActionData::*;
-        let (path, action) = match &action {
+        let (path, action) = match action {
             ActionType::Boolean(data) => (&data.name, Bool(create_action!(bool, data))),
             ActionType::Vector1(data) => (
                 &data.name,
@@ -241,5 +283,5 @@ pub fn load_actions(
         };
         ret.insert(path.path.clone(), action);
     }
-    Ok(ret)
+    Ok(())
 }
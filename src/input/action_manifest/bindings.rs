@@ -9,13 +9,15 @@ use crate::{
     input::{
         GrabActions,
         custom_bindings::{
-            DoubleTapData, DpadActions, DpadBindingParams, DpadData, GrabBindingData,
-            ThresholdBindingFloat, ThresholdBindingVector2, ToggleData,
+            ChordBindingParams, ChordData, DoubleTapData, DpadActions, DpadBindingParams, DpadData,
+            GrabBindingData, LongPressData, ScrollBindingData, ThresholdBindingFloat,
+            ThresholdBindingVector2, ToggleData,
         },
     },
     openxr_data::Hand,
 };
 use log::{debug, trace, warn};
+use openvr as vr;
 use openxr as xr;
 use serde::de::value::StringDeserializer;
 use serde::{
@@ -23,6 +25,7 @@ use serde::{
     de::{Error, IgnoredAny, Unexpected},
 };
 use std::collections::HashMap;
+use std::ffi::c_char;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
@@ -41,6 +44,7 @@ pub struct ActionSetBinding {
     pub poses: Option<Vec<PoseBinding>>,
     pub haptics: Option<Vec<SimpleActionBinding>>,
     pub skeleton: Option<Vec<SimpleActionBinding>>,
+    pub chords: Option<Vec<ChordBinding>>,
 }
 
 #[derive(Debug)]
@@ -86,13 +90,25 @@ impl<'de> Deserialize<'de> for ActionPath {
 pub struct PoseBinding {
     output: ActionPath,
     #[serde(deserialize_with = "parse_pose_binding")]
-    path: (Hand, BoundPoseType),
+    path: PoseSource,
 }
 
-fn parse_pose_binding<'de, D: serde::Deserializer<'de>>(
-    d: D,
-) -> Result<(Hand, BoundPoseType), D::Error> {
+/// The HMD's eye tracking component, as registered by drivers with
+/// IVRDriverInput::CreateEyeTrackingComponent. Pose actions bound to it get the eye gaze from
+/// XR_EXT_eye_gaze_interaction.
+const GAZE_POSE_PATH: &str = "/user/head/eyetracking";
+
+#[derive(Clone, Copy)]
+enum PoseSource {
+    Hand(Hand, BoundPoseType),
+    Gaze,
+}
+
+fn parse_pose_binding<'de, D: serde::Deserializer<'de>>(d: D) -> Result<PoseSource, D::Error> {
     let pose_path: &str = Deserialize::deserialize(d)?;
+    if pose_path == GAZE_POSE_PATH {
+        return Ok(PoseSource::Gaze);
+    }
 
     let (hand, pose) = pose_path.rsplit_once('/').ok_or(D::Error::invalid_value(
         Unexpected::Str(pose_path),
@@ -105,7 +121,11 @@ fn parse_pose_binding<'de, D: serde::Deserializer<'de>>(
         _ => {
             return Err(D::Error::unknown_variant(
                 hand,
-                &["/user/hand/left/pose", "/user/hand/right/pose"],
+                &[
+                    "/user/hand/left/pose",
+                    "/user/hand/right/pose",
+                    GAZE_POSE_PATH,
+                ],
             ));
         }
     };
@@ -120,7 +140,14 @@ fn parse_pose_binding<'de, D: serde::Deserializer<'de>>(
         }
     };
 
-    Ok((hand, pose))
+    Ok(PoseSource::Hand(hand, pose))
+}
+
+#[derive(Deserialize)]
+pub struct ChordBinding {
+    output: ActionPath,
+    /// Pairs of input paths and components, i.e. ["/user/hand/left/input/a", "click"]
+    inputs: Vec<(String, String)>,
 }
 
 #[derive(Deserialize)]
@@ -162,6 +189,142 @@ pub enum ActionBinding {
     Joystick(ActionBindingData<Vector2Input, Vector2Parameters>),
 }
 
+impl ActionBinding {
+    /// Returns the mode name, input path, and the (slot, output) pairs of this binding.
+    fn outputs(&self) -> Option<(&'static str, DynInputPath, Vec<(&'static str, &ActionPath)>)> {
+        fn out<C>(output: &Option<ActionBindingOutput<C>>) -> Option<&ActionPath> {
+            output.as_ref().map(|o| &o.output)
+        }
+
+        let (mode, path, slots): (_, _, Vec<(_, Option<&ActionPath>)>) = match self {
+            Self::None(_) => return None,
+            Self::Button(ActionBindingData { path, inputs, .. })
+            | Self::ToggleButton(ActionBindingData { path, inputs, .. }) => (
+                if matches!(self, Self::Button(_)) {
+                    "button"
+                } else {
+                    "toggle_button"
+                },
+                path,
+                vec![
+                    ("click", out(&inputs.click)),
+                    ("touch", out(&inputs.touch)),
+                    ("double", out(&inputs.double)),
+                    ("long", out(&inputs.long)),
+                ],
+            ),
+            Self::Dpad(ActionBindingData { path, inputs, .. }) => (
+                "dpad",
+                path,
+                vec![
+                    ("north", out(&inputs.north)),
+                    ("east", out(&inputs.east)),
+                    ("south", out(&inputs.south)),
+                    ("west", out(&inputs.west)),
+                    ("center", out(&inputs.center)),
+                    ("north_east", out(&inputs.north_east)),
+                    ("south_east", out(&inputs.south_east)),
+                    ("south_west", out(&inputs.south_west)),
+                    ("north_west", out(&inputs.north_west)),
+                ],
+            ),
+            Self::Trigger(ActionBindingData { path, inputs, .. }) => (
+                "trigger",
+                path,
+                vec![
+                    ("pull", out(&inputs.pull)),
+                    ("touch", out(&inputs.touch)),
+                    ("click", out(&inputs.click)),
+                ],
+            ),
+            Self::ScalarConstant(ActionBindingData { path, inputs, .. }) => (
+                "scalar_constant",
+                path,
+                vec![("value", Some(&inputs.value.output))],
+            ),
+            Self::ForceSensor(ActionBindingData { path, inputs, .. }) => (
+                "force_sensor",
+                path,
+                vec![("force", Some(&inputs.force.output))],
+            ),
+            Self::Grab(ActionBindingData { path, inputs, .. }) => {
+                ("grab", path, vec![("grab", Some(&inputs.grab.output))])
+            }
+            Self::Scroll(ActionBindingData { path, inputs, .. }) => (
+                "scroll",
+                path,
+                vec![("scroll", Some(&inputs.scroll.output))],
+            ),
+            Self::Trackpad(ActionBindingData { path, inputs, .. })
+            | Self::Joystick(ActionBindingData { path, inputs, .. }) => (
+                if matches!(self, Self::Trackpad(_)) {
+                    "trackpad"
+                } else {
+                    "joystick"
+                },
+                path,
+                vec![
+                    ("position", out(&inputs.position)),
+                    ("click", out(&inputs.click)),
+                    ("touch", out(&inputs.touch)),
+                ],
+            ),
+        };
+
+        let MaybeInputPath::Valid(path) = path else {
+            return None;
+        };
+
+        Some((
+            mode,
+            *path,
+            slots
+                .into_iter()
+                .filter_map(|(slot, output)| output.map(|o| (slot, o)))
+                .collect(),
+        ))
+    }
+}
+
+/// Where an action is bound in the bindings file, as reported by IVRInput::GetActionBindingInfo.
+pub struct BindingInfo {
+    pub path: DynInputPath,
+    pub mode: &'static str,
+    pub slot: &'static str,
+}
+
+impl BindingInfo {
+    pub fn to_openvr(&self) -> vr::InputBindingInfo_t {
+        fn to_char_array<const N: usize>(s: &str) -> [c_char; N] {
+            let mut ret = [0; N];
+            // Leave room for the null terminator
+            for (c, b) in ret.iter_mut().zip(s.bytes().take(N - 1)) {
+                *c = b as c_char;
+            }
+            ret
+        }
+
+        let device = match self.path.hand {
+            Hand::Left => "/user/hand/left",
+            Hand::Right => "/user/hand/right",
+        };
+        let source_type = match self.path.subpath {
+            paths::DynSubpath::Trackpad => "trackpad",
+            paths::DynSubpath::Thumbstick => "joystick",
+            paths::DynSubpath::Trigger | paths::DynSubpath::Squeeze => "trigger",
+            _ => "button",
+        };
+
+        vr::InputBindingInfo_t {
+            rchDevicePathName: to_char_array(device),
+            rchInputPathName: to_char_array(&format!("/input/{}", self.path.subpath)),
+            rchModeName: to_char_array(self.mode),
+            rchSlotName: to_char_array(self.slot),
+            rchInputSourceType: to_char_array(source_type),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct ActionBindingData<Inputs, Parameters = ()> {
     path: MaybeInputPath,
@@ -176,7 +339,10 @@ struct ValidActionBindingData<'a, Inputs, Parameters> {
 }
 
 impl<Inputs, Parameters> ActionBindingData<Inputs, Parameters> {
-    fn validate_path(&self) -> Option<ValidActionBindingData<'_, Inputs, Parameters>> {
+    fn validate_path(
+        &self,
+        context: &BindingsProfileLoadContext,
+    ) -> Option<ValidActionBindingData<'_, Inputs, Parameters>> {
         match &self.path {
             MaybeInputPath::Valid(path) => Some(ValidActionBindingData {
                 path: *path,
@@ -185,6 +351,7 @@ impl<Inputs, Parameters> ActionBindingData<Inputs, Parameters> {
             }),
             MaybeInputPath::Invalid { path, error } => {
                 warn!("got invalid input path {path} - {error}");
+                context.report_invalid_input(path);
                 None
             }
         }
@@ -210,6 +377,11 @@ impl InvalidActionPath<'_> {
     fn warn(&self) {
         warn!("invalid path {} for {}", self.0, self.1);
     }
+
+    fn report(&self, context: &BindingsProfileLoadContext) {
+        self.warn();
+        context.report_invalid_input(self.0);
+    }
 }
 
 impl<C: Component> ActionBindingOutput<C>
@@ -254,18 +426,49 @@ impl<T> From<T> for FromString<T> {
     }
 }
 
-impl<'de, T: Deserialize<'de> + FromStr> Deserialize<'de> for FromString<T> {
+impl<'de, T: FromStr> Deserialize<'de> for FromString<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let ret = <&str>::deserialize(deserializer)?;
-        ret.parse().map_err(|_| {
-            D::Error::custom(format_args!(
-                "invalid value: expected {}, got {ret}",
-                std::any::type_name::<T>()
-            ))
-        })
+        struct FromStringVisitor<T>(PhantomData<T>);
+
+        impl<T: FromStr> serde::de::Visitor<'_> for FromStringVisitor<T> {
+            type Value = FromString<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a string containing {}", std::any::type_name::<T>())
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(|_| {
+                    E::custom(format_args!(
+                        "invalid value: expected {}, got {v}",
+                        std::any::type_name::<T>()
+                    ))
+                })
+            }
+
+            // SteamVR always writes parameters as strings, but hand written bindings
+            // sometimes don't quote them.
+            fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(FromStringVisitor(PhantomData))
     }
 }
 
@@ -275,12 +478,18 @@ struct ButtonInput {
     /// Click can be overridden to use a different path via the `force_input` parameter.
     click: Option<ActionBindingOutput<paths::Click>>,
     double: Option<ActionBindingOutput<Custom>>,
+    #[serde(alias = "long_press")]
+    long: Option<ActionBindingOutput<Custom>>,
 }
 
 #[derive(Deserialize)]
 pub struct ClickThresholdParams {
     pub click_activate_threshold: Option<FromString<f32>>,
     pub click_deactivate_threshold: Option<FromString<f32>>,
+    /// Portion of the pull, in percent, that is reported as no pull at all.
+    pub deadzone_pct: Option<FromString<u8>>,
+    /// Multiplier for the pull after the deadzone is removed.
+    pub gain: Option<FromString<f32>>,
 }
 
 impl ClickThresholdParams {
@@ -288,8 +497,44 @@ impl ClickThresholdParams {
         Self {
             click_activate_threshold: Some(0.01f32.into()),
             click_deactivate_threshold: Some(0.005f32.into()),
+            deadzone_pct: None,
+            gain: None,
         }
     }
+
+    fn analog_remap(&self, hand: Hand) -> Option<AnalogRemap> {
+        if self.deadzone_pct.is_none() && self.gain.is_none() {
+            return None;
+        }
+
+        Some(AnalogRemap {
+            hand,
+            deadzone: self
+                .deadzone_pct
+                .map_or(0.0, |pct| (*pct).min(99) as f32 / 100.0),
+            gain: self.gain.map_or(1.0, |gain| *gain),
+        })
+    }
+}
+
+/// Deadzone and gain applied to the pull of a trigger bound to a float action.
+#[derive(Copy, Clone, Debug)]
+pub struct AnalogRemap {
+    pub hand: Hand,
+    deadzone: f32,
+    gain: f32,
+}
+
+impl AnalogRemap {
+    /// Applies the remap to the magnitude of the value, so axes bound as a vector1 keep their sign.
+    pub fn apply(&self, value: f32) -> f32 {
+        let magnitude = value.abs();
+        if magnitude <= self.deadzone {
+            return 0.0;
+        }
+        let magnitude = ((magnitude - self.deadzone) / (1.0 - self.deadzone) * self.gain).min(1.0);
+        magnitude.copysign(value)
+    }
 }
 
 #[derive(Deserialize)]
@@ -303,6 +548,10 @@ struct ScalarConstantParameters {
 struct ButtonParameters {
     #[serde(default, deserialize_with = "ButtonForceInput::default_deserialize")]
     force_input: Option<ButtonForceInput>,
+    /// How long the button needs to be held for a long press, in seconds.
+    hold_time: Option<FromString<f32>>,
+    /// The maximum time between the taps of a double tap, in seconds.
+    double_tap_time: Option<FromString<f32>>,
     #[serde(flatten)]
     click_threshold: ClickThresholdParams,
 }
@@ -339,24 +588,31 @@ struct DpadInput {
     north: Option<ActionBindingOutput<Custom>>,
     west: Option<ActionBindingOutput<Custom>>,
     center: Option<ActionBindingOutput<Custom>>,
+    north_east: Option<ActionBindingOutput<Custom>>,
+    south_east: Option<ActionBindingOutput<Custom>>,
+    south_west: Option<ActionBindingOutput<Custom>>,
+    north_west: Option<ActionBindingOutput<Custom>>,
 }
 
 #[derive(Deserialize)]
 #[serde(default)]
 pub struct DpadParameters {
     pub sub_mode: DpadSubMode,
-    pub deadzone_pct: FromString<u8>,
-    pub overlap_pct: FromString<u8>,
+    pub deadzone_pct: Option<FromString<u8>>,
+    pub overlap_pct: Option<FromString<u8>>,
     pub sticky: FromString<bool>,
+    /// Splits the dpad into 8 directions, enabling the diagonal inputs.
+    pub eight_way: FromString<bool>,
 }
 
 impl Default for DpadParameters {
     fn default() -> Self {
         Self {
             sub_mode: DpadSubMode::Touch,
-            deadzone_pct: FromString(50),
-            overlap_pct: FromString(50),
+            deadzone_pct: None,
+            overlap_pct: None,
             sticky: FromString(false),
+            eight_way: FromString(false),
         }
     }
 }
@@ -366,6 +622,9 @@ impl Default for DpadParameters {
 pub enum DpadSubMode {
     Click,
     Touch,
+    /// Not a SteamVR sub mode: activates whenever the input is pushed out of the deadzone, for
+    /// joysticks where requiring a click or touch would be awkward.
+    Deflection,
 }
 
 #[derive(Deserialize)]
@@ -408,11 +667,14 @@ struct ScrollInput {
 }
 
 #[derive(Deserialize)]
-struct ScrollParameters {
+pub struct ScrollParameters {
     #[allow(unused)]
     scroll_mode: Option<String>,
     #[allow(unused)]
     smooth_scroll_multiplier: Option<String>, // float
+    /// How far the finger needs to move before a scroll click is emitted,
+    /// for scrolls bound to boolean actions.
+    pub scroll_distance: Option<FromString<f32>>,
 }
 
 #[derive(Deserialize)]
@@ -444,12 +706,15 @@ pub fn handle_dpad_binding(
         north,
         west,
         center,
+        north_east,
+        south_east,
+        south_west,
+        north_west,
     }: &DpadInput,
     parameters: Option<&DpadParameters>,
 ) {
     // Would love to use the dpad extension here, but it doesn't seem to
     // support touch trackpad dpads.
-    // TODO: actually take the deadzone and overlap into account
 
     // Workaround weird closure lifetime quirks.
     const fn constrain<F>(f: F) -> F
@@ -475,15 +740,27 @@ pub fn handle_dpad_binding(
 
     use DpadDirection::*;
 
+    let eight_way = parameters.is_some_and(|p| *p.eight_way);
+    let diagonals = [
+        (north_east, NorthEast),
+        (south_east, SouthEast),
+        (south_west, SouthWest),
+        (north_west, NorthWest),
+    ];
+    if !eight_way && diagonals.iter().any(|(a, _)| a.is_some()) {
+        warn!("Ignoring diagonal directions on 4-way dpad ({parent_path} in {action_set_name})");
+    }
+
     let bound_actions: Vec<(&ActionPath, DpadDirection)> = [
-        (maybe_find_action(north, North), North),
-        (maybe_find_action(east, East), East),
-        (maybe_find_action(south, South), South),
-        (maybe_find_action(west, West), West),
-        (maybe_find_action(center, Center), Center),
+        (north, North),
+        (east, East),
+        (south, South),
+        (west, West),
+        (center, Center),
     ]
     .into_iter()
-    .flat_map(|(name, direction)| name.zip(Some(direction)))
+    .chain(diagonals.into_iter().filter(|_| eight_way))
+    .flat_map(|(a, direction)| maybe_find_action(a, direction).zip(Some(direction)))
     .collect();
 
     if bound_actions.is_empty() {
@@ -491,6 +768,12 @@ pub fn handle_dpad_binding(
         return;
     }
 
+    let activate_on_deflection =
+        parameters.is_some_and(|p| matches!(p.sub_mode, DpadSubMode::Deflection));
+    if activate_on_deflection && center.is_some() {
+        warn!("Center of deflection dpad can never activate ({parent_path} in {action_set_name})");
+    }
+
     let parent_action_key = format!("{parent_path}-{action_set_name}");
 
     let (xy, click_or_touch_data, haptic_data) = context.get_dpad_parent(
@@ -502,6 +785,12 @@ pub fn handle_dpad_binding(
         parameters,
     );
 
+    let pct = |pct: Option<&FromString<u8>>| pct.map(|pct| **pct as f32 / 100.0);
+    let deadzone =
+        pct(parameters.and_then(|p| p.deadzone_pct.as_ref())).unwrap_or(DpadData::DEFAULT_DEADZONE);
+    let overlap =
+        pct(parameters.and_then(|p| p.overlap_pct.as_ref())).unwrap_or(DpadData::DEFAULT_OVERLAP);
+
     for (path, direction) in bound_actions {
         context.add_custom_binding::<DpadData>(
             path,
@@ -515,6 +804,10 @@ pub fn handle_dpad_binding(
                     haptic: haptic_data.as_ref().map(|d| d.action.clone()),
                 },
                 direction,
+                deadzone,
+                overlap,
+                eight_way,
+                activate_on_deflection,
             }),
         );
     }
@@ -545,6 +838,19 @@ pub fn handle_sources(
     sources: &[ActionBinding],
 ) {
     for mode in sources {
+        if let Some((mode_name, path, outputs)) = mode.outputs() {
+            for (slot, output) in outputs {
+                context.add_binding_info(
+                    output,
+                    BindingInfo {
+                        path,
+                        mode: mode_name,
+                        slot,
+                    },
+                );
+            }
+        }
+
         match mode {
             ActionBinding::None(_) => {}
             ActionBinding::ToggleButton(data) => {
@@ -552,7 +858,7 @@ pub fn handle_sources(
                     path,
                     inputs: ButtonInput { touch, click, .. },
                     parameters: _,
-                }) = data.validate_path()
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
@@ -560,12 +866,13 @@ pub fn handle_sources(
                 if let Some(touch) = touch {
                     let _ = touch
                         .try_bind_with_component(path, context, validate_path)
-                        .inspect_err(InvalidActionPath::warn);
+                        .inspect_err(|e| e.report(context));
                 }
 
                 if let Some(click) = click {
                     let click_path = path.with_component(DynComponent::Click);
                     let Some(click_path) = validate_path(click_path) else {
+                        InvalidActionPath(click_path, &click.output.path).report(context);
                         continue;
                     };
 
@@ -599,9 +906,10 @@ pub fn handle_sources(
                             touch,
                             click,
                             double,
+                            long,
                         },
                     parameters,
-                }) = data.validate_path()
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
@@ -609,21 +917,43 @@ pub fn handle_sources(
                 if let Some(touch) = touch {
                     let _ = touch
                         .try_bind_with_component(path, context, validate_path)
-                        .inspect_err(InvalidActionPath::warn);
+                        .inspect_err(|e| e.report(context));
                 }
 
                 let click_path = path.with_component(DynComponent::Click);
                 if let Some(double) = double
                     && let Ok(complete_path) = validate_path(click_path)
                         .ok_or_else(|| InvalidActionPath(click_path, &double.output.path))
-                        .inspect_err(InvalidActionPath::warn)
+                        .inspect_err(|e| e.report(context))
                 {
                     let name = context.add_custom_binding::<DoubleTapData>(
                         &double.output,
                         complete_path.hand,
                         action_set_name,
                         action_set,
-                        None,
+                        parameters.and_then(|p| p.double_tap_time.as_deref()),
+                    );
+
+                    context.push_binding(
+                        name,
+                        context
+                            .instance
+                            .string_to_path(&complete_path.to_string())
+                            .unwrap(),
+                    );
+                }
+
+                if let Some(long) = long
+                    && let Ok(complete_path) = validate_path(click_path)
+                        .ok_or_else(|| InvalidActionPath(click_path, &long.output.path))
+                        .inspect_err(|e| e.report(context))
+                {
+                    let name = context.add_custom_binding::<LongPressData>(
+                        &long.output,
+                        complete_path.hand,
+                        action_set_name,
+                        action_set,
+                        parameters.and_then(|p| p.hold_time.as_deref()),
                     );
 
                     context.push_binding(
@@ -649,7 +979,14 @@ pub fn handle_sources(
                         ButtonForceInput::Position => path, // No component = 2D binding
                     };
 
-                    let complete_path = match validate_path(complete_path) {
+                    // Few inputs have a force sensor, so read how far they're pulled instead of
+                    // falling all the way back to the click.
+                    let validated = validate_path(complete_path).or_else(|| {
+                        matches!(target, ButtonForceInput::Force)
+                            .then(|| validate_path(path.with_component(DynComponent::Value)))
+                            .flatten()
+                    });
+                    let complete_path = match validated {
                         None
                         // If the translated path we get is just the click component, there's no need
                         // to create and bind to our threshold action.
@@ -669,7 +1006,7 @@ pub fn handle_sources(
                                     context,
                                     validate_path,
                                 )
-                                .inspect_err(InvalidActionPath::warn);
+                                .inspect_err(|e| e.report(context));
                             continue;
                         }
                         Some(path) => path,
@@ -708,13 +1045,13 @@ pub fn handle_sources(
                     path,
                     inputs,
                     parameters,
-                }) = data.validate_path()
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
 
                 if validate_path(path).is_none() {
-                    InvalidActionPath(path, &format!("{inputs:#?}")).warn();
+                    InvalidActionPath(path, &format!("{inputs:#?}")).report(context);
                     continue;
                 }
                 handle_dpad_binding(
@@ -744,22 +1081,54 @@ pub fn handle_sources(
                 let Some(ValidActionBindingData {
                     path,
                     inputs: TriggerInput { pull, touch, click },
-                    parameters: _,
-                }) = data.validate_path()
+                    parameters,
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
 
                 if let Some(pull) = pull {
+                    if let Some(remap) = parameters.and_then(|p| p.analog_remap(path.hand)) {
+                        context.add_analog_remap(&pull.output, remap);
+                    }
                     let _ = pull
                         .try_bind_with_component(path, context, validate_path)
-                        .inspect_err(InvalidActionPath::warn);
+                        .inspect_err(|e| e.report(context));
                 }
 
                 if let Some(click) = click {
-                    let _ = click
-                        .try_bind_with_component(path, context, validate_path)
-                        .inspect_err(InvalidActionPath::warn);
+                    // Custom click thresholds are applied to the pull rather than the
+                    // runtime's own click point.
+                    let value_path = validate_path(path.with_component(DynComponent::Value))
+                        .filter(|p| p.component == Some(DynComponent::Value));
+                    if let Some(value_path) = value_path
+                        && parameters.is_some_and(|p| {
+                            p.click_activate_threshold.is_some()
+                                || p.click_deactivate_threshold.is_some()
+                        })
+                    {
+                        if context.find_action(&click.output.path) {
+                            let float_name_with_as = context
+                                .add_custom_binding::<ThresholdBindingFloat>(
+                                    &click.output,
+                                    value_path.hand,
+                                    action_set_name,
+                                    action_set,
+                                    parameters,
+                                );
+                            context.push_binding(
+                                float_name_with_as,
+                                context
+                                    .instance
+                                    .string_to_path(&value_path.to_string())
+                                    .unwrap(),
+                            );
+                        }
+                    } else {
+                        let _ = click
+                            .try_bind_with_component(path, context, validate_path)
+                            .inspect_err(|e| e.report(context));
+                    }
                 }
 
                 if let Some(touch) = touch
@@ -803,56 +1172,50 @@ pub fn handle_sources(
                     path,
                     inputs: ScalarConstantInput { value },
                     ..
-                }) = data.validate_path()
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
 
                 let _ = value
                     .try_bind_with_component(path, context, validate_path)
-                    .inspect_err(InvalidActionPath::warn);
+                    .inspect_err(|e| e.report(context));
             }
             ActionBinding::ForceSensor(data) => {
                 let Some(ValidActionBindingData {
                     path,
                     inputs: ForceSensorInput { force },
                     ..
-                }) = data.validate_path()
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
 
                 let _ = force
                     .try_bind_with_component(path, context, validate_path)
-                    .inspect_err(InvalidActionPath::warn);
+                    .inspect_err(|e| e.report(context));
             }
             ActionBinding::Grab(data) => {
                 let Some(ValidActionBindingData {
                     path,
                     inputs: GrabInput { grab },
                     parameters,
-                }) = data.validate_path()
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
 
-                let force_path = path.with_component(DynComponent::Force);
                 let value_path = path.with_component(DynComponent::Value);
-
-                let Ok((force_path, value_path)) = validate_path(force_path)
-                    .ok_or_else(|| InvalidActionPath(force_path, &grab.output.path))
-                    .and_then(|force| {
-                        Ok((
-                            force,
-                            validate_path(value_path)
-                                .ok_or_else(|| InvalidActionPath(value_path, &grab.output.path))?,
-                        ))
-                    })
-                    .inspect_err(InvalidActionPath::warn)
-                else {
+                let Some(value_path) = validate_path(value_path) else {
+                    InvalidActionPath(value_path, &grab.output.path).report(context);
                     continue;
                 };
 
+                // Controllers without a force sensor in the grip (or that just translate force to
+                // the squeeze value) grab based on the value alone.
+                let force_path = validate_path(path.with_component(DynComponent::Force))
+                    .filter(|p| p.component == Some(DynComponent::Force));
+
                 if !context.find_action(&grab.output.path) {
                     continue;
                 }
@@ -868,16 +1231,17 @@ pub fn handle_sources(
                     parameters,
                 );
 
-                trace!(
-                    "suggesting {force_path} and {value_path} for {force_action} (grab binding)"
-                );
-                context.push_binding(
-                    force_action,
-                    context
-                        .instance
-                        .string_to_path(&force_path.to_string())
-                        .unwrap(),
-                );
+                if let Some(force_path) = force_path {
+                    trace!("suggesting {force_path} for {force_action} (grab binding)");
+                    context.push_binding(
+                        force_action,
+                        context
+                            .instance
+                            .string_to_path(&force_path.to_string())
+                            .unwrap(),
+                    );
+                }
+                trace!("suggesting {value_path} for {value_action} (grab binding)");
                 context.push_binding(
                     value_action,
                     context
@@ -890,19 +1254,50 @@ pub fn handle_sources(
                 let Some(ValidActionBindingData {
                     inputs,
                     path,
-                    parameters: _,
-                }) = data.validate_path()
+                    parameters,
+                }) = data.validate_path(context)
                 else {
                     continue;
                 };
                 let ScrollInput { scroll } = inputs;
+
+                // Boolean actions get a click every time the finger moves far enough
+                if matches!(
+                    context.actions.get(&scroll.output.path),
+                    Some(ActionData::Bool(_))
+                ) {
+                    let Some(complete_path) = validate_path(path) else {
+                        InvalidActionPath(path, &scroll.output.path).report(context);
+                        continue;
+                    };
+
+                    let name = context.add_custom_binding::<ScrollBindingData>(
+                        &scroll.output,
+                        complete_path.hand,
+                        action_set_name,
+                        action_set,
+                        parameters,
+                    );
+
+                    trace!("suggesting {complete_path} for {name} (scroll)");
+                    context.push_binding(
+                        name,
+                        context
+                            .instance
+                            .string_to_path(&complete_path.to_string())
+                            .unwrap(),
+                    );
+                    continue;
+                }
+
                 // TODO: custom scrolling for trackpads
                 let _ = scroll
                     .try_bind_with_component(path, context, validate_path)
-                    .inspect_err(InvalidActionPath::warn);
+                    .inspect_err(|e| e.report(context));
             }
             ActionBinding::Trackpad(data) | ActionBinding::Joystick(data) => {
-                let Some(ValidActionBindingData { path, inputs, .. }) = data.validate_path() else {
+                let Some(ValidActionBindingData { path, inputs, .. }) = data.validate_path(context)
+                else {
                     continue;
                 };
 
@@ -922,12 +1317,66 @@ pub fn handle_sources(
 
                 if let Some(position) = position {
                     let _ = position.try_bind_with_component(path, context, validate_path);
+                    if validate_path(path)
+                        .is_some_and(|p| p.subpath == paths::DynSubpath::Thumbstick)
+                    {
+                        context.add_thumbstick_binding(&position.output, path.hand);
+                    }
                 }
             }
         }
     }
 }
 
+pub fn handle_chord_bindings(
+    validate_path: &dyn PathValidator,
+    context: &mut BindingsProfileLoadContext,
+    action_set_name: &str,
+    action_set: &xr::ActionSet,
+    chords: &[ChordBinding],
+) {
+    'chords: for ChordBinding { output, inputs } in chords {
+        if !context.find_action(&output.path) {
+            continue;
+        }
+
+        let mut hand = None;
+        let mut chord_inputs = Vec::with_capacity(inputs.len());
+        for (path, component) in inputs {
+            let input = format!("{path}/{component}");
+            let Some(input_path) = input.parse::<DynInputPath>().ok().and_then(validate_path)
+            else {
+                warn!("invalid chord input {input} for {}", output.path);
+                context.report_invalid_input(&input);
+                continue 'chords;
+            };
+
+            hand.get_or_insert(input_path.hand);
+            chord_inputs.push(context.get_chord_input(input_path, action_set_name, action_set));
+        }
+
+        let Some(hand) = hand else {
+            warn!("Chord for {} has no inputs", output.path);
+            continue;
+        };
+
+        trace!(
+            "binding chord of {} inputs for {}",
+            chord_inputs.len(),
+            output.path
+        );
+        context.add_custom_binding::<ChordData>(
+            output,
+            hand,
+            action_set_name,
+            action_set,
+            Some(&ChordBindingParams {
+                inputs: chord_inputs,
+            }),
+        );
+    }
+}
+
 pub fn handle_skeleton_bindings(
     context: &BindingsProfileLoadContext,
     bindings: &[SimpleActionBinding],
@@ -948,6 +1397,7 @@ pub fn handle_skeleton_bindings(
                             "Got invalid skeleton binding {other} for action {}",
                             output.path
                         );
+                        context.report_invalid_input(other);
                         continue;
                     }
                 };
@@ -978,6 +1428,7 @@ pub fn handle_haptic_bindings(
             "/user/hand/left/output/haptic" | "/user/hand/right/output/haptic",
         ) {
             warn!("invalid haptic path {path} for {}", output.path);
+            context.report_invalid_input(path);
             continue;
         };
         if !context.find_action(&output.path) {
@@ -998,11 +1449,7 @@ pub fn handle_haptic_bindings(
 }
 
 pub fn handle_pose_bindings(context: &mut BindingsProfileLoadContext, bindings: &[PoseBinding]) {
-    for PoseBinding {
-        output,
-        path: (hand, pose_ty),
-    } in bindings
-    {
+    for PoseBinding { output, path } in bindings {
         if !context.find_action(&output.path) {
             continue;
         };
@@ -1021,14 +1468,22 @@ pub fn handle_pose_bindings(context: &mut BindingsProfileLoadContext, bindings:
             .entry(output.path.clone())
             .or_default();
 
+        let (hand, pose_ty) = match *path {
+            PoseSource::Hand(hand, pose_ty) => (hand, pose_ty),
+            PoseSource::Gaze => {
+                bound.gaze = true;
+                trace!("bound eye gaze to pose {}", output.path);
+                continue;
+            }
+        };
         let b = match hand {
             Hand::Left => &mut bound.left,
             Hand::Right => &mut bound.right,
         };
-        *b = Some(*pose_ty);
+        *b = Some(pose_ty);
         trace!(
             "bound {:?} to pose {} for hand {hand:?}",
-            *pose_ty, output.path
+            pose_ty, output.path
         );
     }
 }
@@ -1,5 +1,6 @@
+use super::BindingReport;
 use super::actions::LoadedActionDataMap;
-use super::bindings::{ActionPath, DpadParameters, DpadSubMode};
+use super::bindings::{ActionPath, AnalogRemap, BindingInfo, DpadParameters, DpadSubMode};
 use crate::input::custom_bindings::{
     AsActionData, AsIter, BoolBindingData, CustomBindingHelper, Names,
 };
@@ -9,7 +10,10 @@ use crate::input::{ActionData, BoundPose, ExtraActionData, Input, InteractionPro
 use crate::openxr_data::{self, Hand};
 use log::{info, trace, warn};
 use openxr as xr;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
 
 pub(super) struct BindingsLoadContext<'a> {
     pub action_sets: &'a HashMap<String, xr::ActionSet>,
@@ -17,10 +21,16 @@ pub(super) struct BindingsLoadContext<'a> {
     pub extra_actions: HashMap<String, ExtraActionData>,
     pub per_profile_bindings: HashMap<xr::Path, HashMap<String, Vec<BoolBindingData>>>,
     pub per_profile_pose_bindings: HashMap<xr::Path, HashMap<String, BoundPose>>,
+    pub per_profile_binding_info: HashMap<xr::Path, HashMap<String, Vec<BindingInfo>>>,
+    pub per_profile_analog_remaps: HashMap<xr::Path, HashMap<String, Vec<AnalogRemap>>>,
+    pub per_profile_thumbstick_hands: HashMap<xr::Path, HashMap<String, Vec<Hand>>>,
     pub grip_action: &'a xr::Action<xr::Posef>,
     pub info_action: &'a xr::Action<bool>,
     pub haptic_action: &'a xr::Action<xr::Haptic>,
     pub skeletal_input: &'a SkeletalInputActionData,
+    pub report: RefCell<BindingReport>,
+    /// Manifest actions that at least one binding refers to.
+    pub referenced_actions: RefCell<HashSet<String>>,
 }
 
 impl<'a> BindingsLoadContext<'a> {
@@ -38,10 +48,15 @@ impl<'a> BindingsLoadContext<'a> {
             extra_actions: Default::default(),
             per_profile_bindings: Default::default(),
             per_profile_pose_bindings: Default::default(),
+            per_profile_binding_info: Default::default(),
+            per_profile_analog_remaps: Default::default(),
+            per_profile_thumbstick_hands: Default::default(),
             grip_action,
             info_action,
             haptic_action,
             skeletal_input,
+            report: Default::default(),
+            referenced_actions: Default::default(),
         }
     }
 }
@@ -80,16 +95,34 @@ impl BindingsLoadContext<'_> {
             .per_profile_pose_bindings
             .entry(interaction_profile)
             .or_default();
+        let binding_info = self
+            .per_profile_binding_info
+            .entry(interaction_profile)
+            .or_default();
+        let analog_remaps = self
+            .per_profile_analog_remaps
+            .entry(interaction_profile)
+            .or_default();
+        let thumbstick_hands = self
+            .per_profile_thumbstick_hands
+            .entry(interaction_profile)
+            .or_default();
         Some(BindingsProfileLoadContext {
             action_sets: self.action_sets,
             actions: &mut self.actions,
             extra_actions: &mut self.extra_actions,
             bindings_parsed,
             pose_bindings,
+            binding_info,
+            analog_remaps,
+            thumbstick_hands,
             grip_action: self.grip_action,
             info_action: self.info_action,
             haptic_action: self.haptic_action,
             skeletal_input: self.skeletal_input,
+            profile: P::profile_path(),
+            report: &self.report,
+            referenced_actions: &self.referenced_actions,
             instance,
             hands,
             bindings: Vec::new(),
@@ -104,10 +137,17 @@ pub(super) struct BindingsProfileLoadContext<'a> {
     extra_actions: &'a mut HashMap<String, ExtraActionData>,
     bindings_parsed: &'a mut HashMap<String, Vec<BoolBindingData>>,
     pub pose_bindings: &'a mut HashMap<String, BoundPose>,
+    binding_info: &'a mut HashMap<String, Vec<BindingInfo>>,
+    analog_remaps: &'a mut HashMap<String, Vec<AnalogRemap>>,
+    /// The hands whose thumbstick is bound to each 2D action.
+    thumbstick_hands: &'a mut HashMap<String, Vec<Hand>>,
     pub grip_action: &'a xr::Action<xr::Posef>,
     pub info_action: &'a xr::Action<bool>,
     pub haptic_action: &'a xr::Action<xr::Haptic>,
     pub skeletal_input: &'a SkeletalInputActionData,
+    profile: &'static str,
+    pub report: &'a RefCell<BindingReport>,
+    referenced_actions: &'a RefCell<HashSet<String>>,
     pub instance: &'a xr::Instance,
     pub hands: [xr::Path; 2],
     pub bindings: Vec<(String, xr::Path)>,
@@ -148,6 +188,19 @@ action_match!(bool, ActionData::Bool(_) | ActionData::Vector1 { .. });
 action_match!(f32, ActionData::Vector1 { .. });
 action_match!(xr::Vector2f, ActionData::Vector2 { .. });
 
+pub(super) fn record_invalid_input(
+    report: &RefCell<BindingReport>,
+    profile: &str,
+    path: impl Display,
+) {
+    report
+        .borrow_mut()
+        .invalid_inputs
+        .entry(profile.to_string())
+        .or_default()
+        .insert(path.to_string());
+}
+
 fn hand_to_path(hand: Hand) -> &'static str {
     match hand {
         Hand::Left => "/user/hand/left",
@@ -156,23 +209,38 @@ fn hand_to_path(hand: Hand) -> &'static str {
 }
 
 impl BindingsProfileLoadContext<'_> {
-    pub fn get_action_set(&self, p0: &String) -> Option<&xr::ActionSet> {
+    pub fn get_action_set(&self, p0: &str) -> Option<&xr::ActionSet> {
         self.action_sets.get(p0)
     }
 
     #[track_caller]
     pub fn find_action(&self, name: &str) -> bool {
         let ret = self.actions.contains_key(name);
-        if !ret {
+        if ret {
+            self.referenced_actions
+                .borrow_mut()
+                .insert(name.to_string());
+        } else {
             let caller = std::panic::Location::caller();
             warn!(
                 "Couldn't find action {name}, skipping (line {})",
                 caller.line()
             );
+            self.report
+                .borrow_mut()
+                .unknown_actions
+                .entry(self.profile.to_string())
+                .or_default()
+                .insert(name.to_string());
         }
         ret
     }
 
+    /// Records an input path that doesn't exist on the profile being loaded.
+    pub fn report_invalid_input(&self, path: impl Display) {
+        record_invalid_input(self.report, self.profile, path);
+    }
+
     pub fn try_suggest_binding<T: WithActionPattern>(
         &mut self,
         action_path: String,
@@ -228,10 +296,87 @@ impl BindingsProfileLoadContext<'_> {
         T::ExtraActions::from_iter(full_names)
     }
 
+    pub fn add_binding_info(&mut self, output: &ActionPath, info: BindingInfo) {
+        if self.actions.contains_key(&output.path) {
+            self.binding_info
+                .entry(output.path.clone())
+                .or_default()
+                .push(info);
+        }
+    }
+
+    pub fn add_analog_remap(&mut self, output: &ActionPath, remap: AnalogRemap) {
+        if matches!(
+            self.actions.get(&output.path),
+            Some(ActionData::Vector1 { .. })
+        ) {
+            self.analog_remaps
+                .entry(output.path.clone())
+                .or_default()
+                .push(remap);
+        }
+    }
+
+    pub fn add_thumbstick_binding(&mut self, output: &ActionPath, hand: Hand) {
+        if matches!(
+            self.actions.get(&output.path),
+            Some(ActionData::Vector2 { .. })
+        ) {
+            let hands = self
+                .thumbstick_hands
+                .entry(output.path.clone())
+                .or_default();
+            if !hands.contains(&hand) {
+                hands.push(hand);
+            }
+        }
+    }
+
     pub fn push_binding(&mut self, action: String, path: xr::Path) {
         self.bindings.push((action, path));
     }
 
+    /// Returns the (shared) action for an input of a chord, and the hand it should be read from.
+    pub fn get_chord_input(
+        &mut self,
+        input_path: DynInputPath,
+        action_set_name: &str,
+        action_set: &xr::ActionSet,
+    ) -> (xr::Action<bool>, xr::Path) {
+        let key = format!("{input_path}-{action_set_name}-chord");
+        let len = self.actions.len();
+        let action = match self.actions.entry(key) {
+            Entry::Occupied(entry) => {
+                let ActionData::Bool(action) = entry.get() else {
+                    unreachable!();
+                };
+                action.clone()
+            }
+            Entry::Vacant(entry) => {
+                let name = format!("xrizer-chord-input{len}");
+                let localized = format!("XRizer chord input ({input_path})");
+                let action = action_set
+                    .create_action::<bool>(&name, &localized, &self.hands)
+                    .unwrap();
+                trace!("created new chord input ({})", entry.key());
+
+                let binding = self
+                    .instance
+                    .string_to_path(&input_path.to_string())
+                    .unwrap();
+                self.bindings.push((entry.key().clone(), binding));
+                entry.insert(ActionData::Bool(action.clone()));
+                action
+            }
+        };
+
+        let hand = match input_path.hand {
+            Hand::Left => self.hands[0],
+            Hand::Right => self.hands[1],
+        };
+        (action, hand)
+    }
+
     pub fn get_dpad_parent(
         &mut self,
         string_to_path: &impl Fn(&str) -> Option<xr::Path>,
@@ -291,6 +436,8 @@ impl BindingsProfileLoadContext<'_> {
                     DpadSubMode::Touch => {
                         format!("{parent_path}/touch")
                     }
+                    // Only the position of the parent matters.
+                    DpadSubMode::Deflection => return None,
                 };
                 string_to_path(&name).map(|p| (name, p))
             })
@@ -1,10 +1,10 @@
-use super::action_manifest::{ClickThresholdParams, GrabParameters};
+use super::action_manifest::{ClickThresholdParams, GrabParameters, ScrollParameters};
 use crate::AtomicF32;
 use crate::input::{ActionData, ExtraActionData};
 use crate::openxr_data::SessionData;
 use log::error;
 use openxr as xr;
-use std::f32::consts::{FRAC_PI_4, PI};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_8, PI};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -116,6 +116,7 @@ pub(super) trait BoolCustomBinding: Sized {
         actions: &Self::ExtraActions<Actions>,
         session: &xr::Session<xr::AnyGraphics>,
         subaction_path: xr::Path,
+        display_time: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>>;
 }
 
@@ -126,6 +127,11 @@ pub(super) enum DpadDirection {
     South,
     West,
     Center,
+    // Only used by 8-way dpads
+    NorthEast,
+    SouthEast,
+    SouthWest,
+    NorthWest,
 }
 
 #[derive(Clone)]
@@ -138,18 +144,49 @@ pub(super) struct DpadActions {
 pub(super) struct DpadBindingParams {
     pub actions: DpadActions,
     pub direction: DpadDirection,
+    /// Radius of the center zone, from 0 to 1
+    pub deadzone: f32,
+    /// How much each direction's wedge extends into its neighbors, from 0 to 1
+    pub overlap: f32,
+    /// Whether the diagonal directions get their own wedges
+    pub eight_way: bool,
+    /// Whether the dpad is active whenever the input is outside of the deadzone, instead of
+    /// requiring a click or touch
+    pub activate_on_deflection: bool,
 }
 
 pub(super) struct DpadData {
     actions: DpadActions,
     direction: DpadDirection,
+    deadzone: f32,
+    overlap: f32,
+    eight_way: bool,
+    activate_on_deflection: bool,
     last_state: AtomicBool,
     active: AtomicBool,
     changed: AtomicBool,
 }
 
+impl DpadDirection {
+    /// The angle at the center of this direction's wedge, if it has one
+    fn angle(self) -> Option<f32> {
+        match self {
+            DpadDirection::North => Some(FRAC_PI_2),
+            DpadDirection::East => Some(0.0),
+            DpadDirection::South => Some(-FRAC_PI_2),
+            DpadDirection::West => Some(PI),
+            DpadDirection::NorthEast => Some(FRAC_PI_4),
+            DpadDirection::SouthEast => Some(-FRAC_PI_4),
+            DpadDirection::SouthWest => Some(-3.0 * FRAC_PI_4),
+            DpadDirection::NorthWest => Some(3.0 * FRAC_PI_4),
+            DpadDirection::Center => None,
+        }
+    }
+}
+
 impl DpadData {
-    const CENTER_ZONE: f32 = 0.5;
+    pub const DEFAULT_DEADZONE: f32 = 0.5;
+    pub const DEFAULT_OVERLAP: f32 = 0.0;
 
     // Thresholds for force-activated dpads, experimentally chosen to match SteamVR
     const DPAD_CLICK_THRESHOLD: f32 = 0.33;
@@ -172,10 +209,21 @@ impl BoolCustomBinding for DpadData {
     ) -> Self::ExtraActions<Actions> {
     }
     fn create_binding_data(params: Option<&Self::BindingParams>) -> BoolBindingType {
-        let DpadBindingParams { actions, direction } = params.unwrap();
+        let DpadBindingParams {
+            actions,
+            direction,
+            deadzone,
+            overlap,
+            eight_way,
+            activate_on_deflection,
+        } = params.unwrap();
         BoolBindingType::Dpad(DpadData {
             actions: actions.clone(),
             direction: *direction,
+            deadzone: *deadzone,
+            overlap: *overlap,
+            eight_way: *eight_way,
+            activate_on_deflection: *activate_on_deflection,
             last_state: false.into(),
             active: false.into(),
             changed: false.into(),
@@ -187,6 +235,7 @@ impl BoolCustomBinding for DpadData {
         _: &(),
         session: &xr::Session<xr::AnyGraphics>,
         subaction_path: xr::Path,
+        _: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>> {
         let action = &self.actions;
         let parent_state = action.xy.state(session, subaction_path)?;
@@ -204,17 +253,22 @@ impl BoolCustomBinding for DpadData {
             Self::DPAD_CLICK_THRESHOLD
         };
 
-        let active = action
-            .click_or_touch
-            .as_ref()
-            .map(|a| {
-                // If this action isn't bound in the current interaction profile,
-                // is_active will be false - in this case, it's probably a joystick touch dpad, in
-                // which case we still want to read the current state.
-                a.state(session, subaction_path)
-                    .map(|s| !s.is_active || s.current_state > active_threshold)
-            })
-            .unwrap_or(Ok(true))?;
+        let active = if self.activate_on_deflection {
+            let xr::Vector2f { x, y } = parent_state.current_state;
+            x.hypot(y) >= self.deadzone
+        } else {
+            action
+                .click_or_touch
+                .as_ref()
+                .map(|a| {
+                    // If this action isn't bound in the current interaction profile,
+                    // is_active will be false - in this case, it's probably a joystick touch dpad, in
+                    // which case we still want to read the current state.
+                    a.state(session, subaction_path)
+                        .map(|s| !s.is_active || s.current_state > active_threshold)
+                })
+                .unwrap_or(Ok(true))?
+        };
 
         if !active {
             let changed = self
@@ -231,24 +285,15 @@ impl BoolCustomBinding for DpadData {
         let radius = x.hypot(y);
         let angle = y.atan2(x);
 
-        // pi/2 wedges, no overlap
-        let in_bounds = match self.direction {
-            DpadDirection::North => {
-                radius >= Self::CENTER_ZONE && (FRAC_PI_4..=3.0 * FRAC_PI_4).contains(&angle)
+        // pi/2 wedges (pi/4 for 8-way dpads), widened by the overlap
+        let half_wedge = if self.eight_way { FRAC_PI_8 } else { FRAC_PI_4 };
+        let in_bounds = match self.direction.angle() {
+            Some(center) => {
+                // atan2 is disjoint at pi, so wrap the difference into [-pi, pi]
+                let difference = (angle - center + PI).rem_euclid(2.0 * PI) - PI;
+                radius >= self.deadzone && difference.abs() <= half_wedge * (1.0 + self.overlap)
             }
-            DpadDirection::East => {
-                radius >= Self::CENTER_ZONE && (-FRAC_PI_4..=FRAC_PI_4).contains(&angle)
-            }
-            DpadDirection::South => {
-                radius >= Self::CENTER_ZONE && (-3.0 * FRAC_PI_4..=-FRAC_PI_4).contains(&angle)
-            }
-            // west section is disjoint with atan2
-            DpadDirection::West => {
-                radius >= Self::CENTER_ZONE
-                    && ((3.0 * FRAC_PI_4..=PI).contains(&angle)
-                        || (-PI..=-3.0 * FRAC_PI_4).contains(&angle))
-            }
-            DpadDirection::Center => radius < Self::CENTER_ZONE,
+            None => radius < self.deadzone,
         };
 
         ret_state.current_state = in_bounds;
@@ -391,15 +436,17 @@ impl BoolCustomBinding for GrabBindingData {
         grabs: &Self::ExtraActions<Actions>,
         session: &xr::Session<xr::AnyGraphics>,
         subaction_path: xr::Path,
+        _: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>> {
         let force_state = grabs.force_action.state(session, subaction_path)?;
         let value_state = grabs.value_action.state(session, subaction_path)?;
-        if !force_state.is_active || !value_state.is_active {
+        if !value_state.is_active {
             self.last_state.store(false, Ordering::Relaxed);
             Ok(None)
         } else {
             let prev_grabbed = self.last_state.load(Ordering::Relaxed);
-            let value = if force_state.current_state > 0.0 {
+            // The force action is inactive on controllers that have no force sensor.
+            let value = if force_state.is_active && force_state.current_state > 0.0 {
                 force_state.current_state + 1.0
             } else {
                 value_state.current_state
@@ -414,7 +461,11 @@ impl BoolCustomBinding for GrabBindingData {
             Ok(Some(xr::ActionState {
                 current_state: grabbed,
                 changed_since_last_sync,
-                last_change_time: force_state.last_change_time,
+                last_change_time: if force_state.is_active {
+                    force_state.last_change_time
+                } else {
+                    value_state.last_change_time
+                },
                 is_active: true,
             }))
         }
@@ -463,6 +514,7 @@ impl BoolCustomBinding for ToggleData {
         action: &xr::Action<bool>,
         session: &xr::Session<xr::AnyGraphics>,
         subaction_path: xr::Path,
+        _: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>> {
         let state = action.state(session, subaction_path)?;
         if !state.is_active {
@@ -621,6 +673,7 @@ impl<T: ThresholdType> BoolCustomBinding for ThresholdBindingData<T> {
         action: &Self::ExtraActions<Actions>,
         session: &xr::Session<xr::AnyGraphics>,
         subaction_path: xr::Path,
+        _: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>> {
         let state = T::state(action, session, subaction_path)?;
         if !state.is_active {
@@ -654,6 +707,105 @@ impl<T: ThresholdType> BoolCustomBinding for ThresholdBindingData<T> {
     }
 }
 
+#[derive(Default)]
+struct ScrollState {
+    last_position: Option<xr::Vector2f>,
+    distance: xr::Vector2f,
+    clicked: bool,
+}
+
+pub(super) struct ScrollBindingData {
+    scroll_distance: f32,
+    state: Mutex<ScrollState>,
+}
+
+impl ScrollBindingData {
+    const DEFAULT_SCROLL_DISTANCE: f32 = 0.2;
+
+    pub fn new(scroll_distance: Option<f32>) -> Self {
+        Self {
+            scroll_distance: scroll_distance.unwrap_or(Self::DEFAULT_SCROLL_DISTANCE),
+            state: Default::default(),
+        }
+    }
+}
+
+impl BoolCustomBinding for ScrollBindingData {
+    type ExtraActions<M: ActionsMarker> = Action<xr::Vector2f, M>;
+    type BindingParams = ScrollParameters;
+
+    fn extra_action_names(cleaned_action_name: &str) -> Self::ExtraActions<Names> {
+        [cleaned_action_name, "_scroll"].concat()
+    }
+
+    fn get_actions(
+        extra_actions: &mut ExtraActionData,
+    ) -> Option<&mut Option<Self::ExtraActions<Actions>>> {
+        Some(&mut extra_actions.scroll_action)
+    }
+
+    fn create_actions(
+        action_name: &Self::ExtraActions<Names>,
+        action_set: &xr::ActionSet,
+        subaction_paths: &[xr::Path],
+    ) -> Self::ExtraActions<Actions> {
+        action_set
+            .create_action(
+                action_name,
+                &format!("{action_name} (scroll)"),
+                subaction_paths,
+            )
+            .unwrap()
+    }
+
+    fn create_binding_data(params: Option<&Self::BindingParams>) -> BoolBindingType {
+        BoolBindingType::Scroll(ScrollBindingData::new(
+            params.and_then(|x| x.scroll_distance.as_deref()).copied(),
+        ))
+    }
+
+    fn state(
+        &self,
+        action: &Self::ExtraActions<Actions>,
+        session: &xr::Session<xr::AnyGraphics>,
+        subaction_path: xr::Path,
+        _: xr::Time,
+    ) -> xr::Result<Option<xr::ActionState<bool>>> {
+        let state = action.state(session, subaction_path)?;
+        let mut scroll = self.state.lock().unwrap();
+        if !state.is_active {
+            *scroll = Default::default();
+            return Ok(None);
+        }
+
+        let was_clicked = scroll.clicked;
+        let position = state.current_state;
+        // A resting position means the finger was lifted, so the next touch shouldn't
+        // count as movement.
+        if position.x == 0.0 && position.y == 0.0 {
+            scroll.last_position = None;
+            scroll.distance = Default::default();
+        } else if let Some(last) = scroll.last_position.replace(position) {
+            scroll.distance.x += position.x - last.x;
+            scroll.distance.y += position.y - last.y;
+        }
+
+        // Clicks are momentary, so always release for at least one sync.
+        scroll.clicked =
+            !was_clicked && scroll.distance.x.hypot(scroll.distance.y) >= self.scroll_distance;
+        if scroll.clicked {
+            scroll.distance = Default::default();
+        }
+
+        Ok(Some(xr::ActionState {
+            current_state: scroll.clicked,
+            changed_since_last_sync: scroll.clicked != was_clicked,
+            last_change_time: state.last_change_time,
+            is_active: true,
+        }))
+    }
+}
+
 mod atomic_time {
     use openxr as xr;
     use std::sync::atomic::{AtomicI64, Ordering};
@@ -677,6 +829,7 @@ mod atomic_time {
 use atomic_time::AtomicTime;
 
 pub(super) struct DoubleTapData {
+    timeout_ms: u128,
     clicked_once: AtomicBool,
     first_release_time: AtomicTime,
     active: AtomicBool,
@@ -688,7 +841,8 @@ impl DoubleTapData {
 
 impl BoolCustomBinding for DoubleTapData {
     type ExtraActions<M: ActionsMarker> = Action<bool, M>;
-    type BindingParams = ();
+    /// The maximum time between taps, in seconds
+    type BindingParams = f32;
 
     fn extra_action_names(cleaned_action_name: &str) -> Self::ExtraActions<Names> {
         format!("{cleaned_action_name}_dbl")
@@ -714,8 +868,11 @@ impl BoolCustomBinding for DoubleTapData {
             .unwrap()
     }
 
-    fn create_binding_data(_: Option<&Self::BindingParams>) -> BoolBindingType {
+    fn create_binding_data(params: Option<&Self::BindingParams>) -> BoolBindingType {
         BoolBindingType::DoubleTap(DoubleTapData {
+            timeout_ms: params
+                .map(|secs| (secs * 1000.0) as u128)
+                .unwrap_or(Self::TIMEOUT_MS),
             clicked_once: false.into(),
             active: false.into(),
             first_release_time: AtomicTime::new(0),
@@ -727,6 +884,7 @@ impl BoolCustomBinding for DoubleTapData {
         action: &Self::ExtraActions<Actions>,
         session: &xr::Session<xr::AnyGraphics>,
         subaction_path: xr::Path,
+        _: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>> {
         let state = action.state(session, subaction_path)?;
         if !state.is_active {
@@ -756,7 +914,7 @@ impl BoolCustomBinding for DoubleTapData {
                         .try_into()
                         .expect("XrTime should never be negative"),
                 );
-                elapsed.as_millis() <= Self::TIMEOUT_MS
+                elapsed.as_millis() <= self.timeout_ms
             };
 
             if active {
@@ -776,6 +934,151 @@ impl BoolCustomBinding for DoubleTapData {
     }
 }
 
+pub(super) struct ChordBindingParams {
+    /// The action for each input of the chord, along with the hand it's read from.
+    pub inputs: Vec<(xr::Action<bool>, xr::Path)>,
+}
+
+pub(super) struct ChordData {
+    inputs: Vec<(xr::Action<bool>, xr::Path)>,
+    last_state: AtomicBool,
+}
+
+impl BoolCustomBinding for ChordData {
+    // The input actions can be shared between chords, so we pass them in via the BindingParams.
+    type ExtraActions<M: ActionsMarker> = ();
+    type BindingParams = ChordBindingParams;
+    fn extra_action_names(_: &str) -> Self::ExtraActions<Names> {}
+    fn get_actions(_: &mut ExtraActionData) -> Option<&mut Option<Self::ExtraActions<Actions>>> {
+        None
+    }
+    fn create_actions(
+        _: &Self::ExtraActions<Names>,
+        _: &xr::ActionSet,
+        _: &[xr::Path],
+    ) -> Self::ExtraActions<Actions> {
+    }
+    fn create_binding_data(params: Option<&Self::BindingParams>) -> BoolBindingType {
+        let ChordBindingParams { inputs } = params.unwrap();
+        BoolBindingType::Chord(ChordData {
+            inputs: inputs.clone(),
+            last_state: false.into(),
+        })
+    }
+
+    fn state(
+        &self,
+        _: &(),
+        session: &xr::Session<xr::AnyGraphics>,
+        _: xr::Path,
+        _: xr::Time,
+    ) -> xr::Result<Option<xr::ActionState<bool>>> {
+        let mut current_state = true;
+        let mut last_change_time = xr::Time::from_nanos(0);
+        for (action, hand) in &self.inputs {
+            // Inputs may be on either hand, so read them from the hand they were bound on.
+            let state = action.state(session, *hand)?;
+            if !state.is_active {
+                self.last_state.store(false, Ordering::Relaxed);
+                return Ok(None);
+            }
+
+            current_state &= state.current_state;
+            if state.last_change_time > last_change_time {
+                last_change_time = state.last_change_time;
+            }
+        }
+
+        let changed_since_last_sync =
+            self.last_state.swap(current_state, Ordering::Relaxed) != current_state;
+
+        Ok(Some(xr::ActionState {
+            current_state,
+            changed_since_last_sync,
+            last_change_time,
+            is_active: true,
+        }))
+    }
+}
+
+pub(super) struct LongPressData {
+    hold_time: xr::Duration,
+    last_state: AtomicBool,
+}
+
+impl LongPressData {
+    const DEFAULT_HOLD_TIME_SECS: f32 = 0.5;
+
+    pub fn new(hold_time_secs: Option<f32>) -> Self {
+        let hold_time = hold_time_secs.unwrap_or(Self::DEFAULT_HOLD_TIME_SECS);
+        Self {
+            hold_time: xr::Duration::from_nanos((hold_time as f64 * 1e9) as i64),
+            last_state: false.into(),
+        }
+    }
+}
+
+impl BoolCustomBinding for LongPressData {
+    type ExtraActions<M: ActionsMarker> = Action<bool, M>;
+    /// The hold time, in seconds
+    type BindingParams = f32;
+
+    fn extra_action_names(cleaned_action_name: &str) -> Self::ExtraActions<Names> {
+        format!("{cleaned_action_name}_long")
+    }
+
+    fn get_actions(
+        extra_actions: &mut ExtraActionData,
+    ) -> Option<&mut Option<Self::ExtraActions<Actions>>> {
+        Some(&mut extra_actions.long_press_action)
+    }
+
+    fn create_actions(
+        action_name: &Self::ExtraActions<Names>,
+        action_set: &xr::ActionSet,
+        subaction_paths: &[xr::Path],
+    ) -> Self::ExtraActions<Actions> {
+        action_set
+            .create_action(
+                action_name,
+                &format!("{action_name} (long press)"),
+                subaction_paths,
+            )
+            .unwrap()
+    }
+
+    fn create_binding_data(params: Option<&Self::BindingParams>) -> BoolBindingType {
+        BoolBindingType::LongPress(LongPressData::new(params.copied()))
+    }
+
+    fn state(
+        &self,
+        action: &Self::ExtraActions<Actions>,
+        session: &xr::Session<xr::AnyGraphics>,
+        subaction_path: xr::Path,
+        display_time: xr::Time,
+    ) -> xr::Result<Option<xr::ActionState<bool>>> {
+        let state = action.state(session, subaction_path)?;
+        if !state.is_active {
+            self.last_state.store(false, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        let held_time = display_time - state.last_change_time;
+        let current_state =
+            state.current_state && held_time.as_nanos() >= self.hold_time.as_nanos();
+        let changed_since_last_sync =
+            self.last_state.swap(current_state, Ordering::Relaxed) != current_state;
+
+        Ok(Some(xr::ActionState {
+            current_state,
+            changed_since_last_sync,
+            last_change_time: state.last_change_time,
+            is_active: true,
+        }))
+    }
+}
+
 enum BindingState {
     Unsynced,
     Synced(Option<xr::ActionState<bool>>),
@@ -803,10 +1106,13 @@ pub enum BoolBindingType {
     // This can include actions where behavior is customized via OXR extensions
     Dpad(DpadData),
     DoubleTap(DoubleTapData),
+    LongPress(LongPressData),
     Toggle(ToggleData),
     Grab(GrabBindingData),
     ThresholdFloat(ThresholdBindingFloat),
     ThresholdVec2(ThresholdBindingVector2),
+    Scroll(ScrollBindingData),
+    Chord(ChordData),
 }
 
 impl BoolBindingData {
@@ -819,6 +1125,7 @@ impl BoolBindingData {
         session: &SessionData,
         extra_data: &ExtraActionData,
         subaction_path: xr::Path,
+        display_time: xr::Time,
     ) -> xr::Result<Option<xr::ActionState<bool>>> {
         assert_ne!(subaction_path, xr::Path::NULL);
         macro_rules! get_state {
@@ -826,7 +1133,7 @@ impl BoolBindingData {
                 let Some(action) = extra_data.$action_name.as_ref() else {
                     return Ok(None);
                 };
-                $data.state(action, &session.session, subaction_path)
+                $data.state(action, &session.session, subaction_path, display_time)
             }};
         }
 
@@ -840,7 +1147,12 @@ impl BoolBindingData {
         }
 
         let state = match &self.ty {
-            BoolBindingType::Dpad(dpad) => dpad.state(&(), &session.session, subaction_path),
+            BoolBindingType::Dpad(dpad) => {
+                dpad.state(&(), &session.session, subaction_path, display_time)
+            }
+            BoolBindingType::Chord(chord) => {
+                chord.state(&(), &session.session, subaction_path, display_time)
+            }
             BoolBindingType::Toggle(toggle) => {
                 get_state!(toggle, toggle_action)
             }
@@ -856,6 +1168,12 @@ impl BoolBindingData {
             BoolBindingType::DoubleTap(double) => {
                 get_state!(double, double_action)
             }
+            BoolBindingType::LongPress(long_press) => {
+                get_state!(long_press, long_press_action)
+            }
+            BoolBindingType::Scroll(scroll) => {
+                get_state!(scroll, scroll_action)
+            }
         }?;
 
         *last_state = BindingState::Synced(state);
@@ -869,6 +1187,7 @@ mod tests {
     use crate::input::InteractionProfile;
     use crate::input::profiles::knuckles::Knuckles;
     use crate::input::profiles::oculus_touch::OculusTouch;
+    use crate::input::profiles::oculus_touch_pro::OculusTouchPro;
     use crate::input::profiles::vive_controller::ViveWands;
     use crate::input::tests::{ExtraActionType, Fixture};
     use crate::openxr_data::Hand;
@@ -1056,6 +1375,139 @@ mod tests {
         assert!(state.bChanged);
     }
 
+    #[test]
+    fn dpad_deadzone_and_overlap() {
+        let mut f = Fixture::new();
+
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+
+        f.load_actions(c"actions_dpad_deadzone.json");
+        f.input.openxr.restart_session();
+
+        get_dpad_action!(f, boolact, dpad_data, ViveWands);
+
+        f.set_interaction_profile::<ViveWands>(LeftHand);
+        fakexr::set_action_state(
+            dpad_data.click_or_touch.as_ref().unwrap().as_raw(),
+            fakexr::ActionState::Float(1.0),
+            LeftHand,
+        );
+        let mut check_direction = |x, y, expected| {
+            fakexr::set_action_state(
+                dpad_data.xy.as_raw(),
+                fakexr::ActionState::Vector2(x, y),
+                LeftHand,
+            );
+            f.sync(vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                ..Default::default()
+            });
+
+            let state = f.get_bool_state(boolact).unwrap();
+            assert!(state.bActive);
+            assert_eq!(state.bState, expected, "unexpected state for ({x}, {y})");
+        };
+
+        // Would be in the center zone with the default deadzone
+        check_direction(0.0, 0.2, true);
+        check_direction(0.0, 0.05, false);
+        // With 50% overlap, north extends 22.5 degrees into east and west
+        let (sin, cos) = 30.0_f32.to_radians().sin_cos();
+        check_direction(cos, sin, true);
+        check_direction(-cos, sin, true);
+        let (sin, cos) = 15.0_f32.to_radians().sin_cos();
+        check_direction(cos, sin, false);
+    }
+
+    #[test]
+    fn dpad_deflection_without_activator() {
+        let mut f = Fixture::new();
+
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+
+        f.load_actions(c"actions_dpad_deflection.json");
+        f.input.openxr.restart_session();
+
+        get_dpad_action!(f, boolact, dpad_data, Knuckles);
+        assert!(dpad_data.click_or_touch.is_none());
+
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        let mut check_direction = |x, y, expected| {
+            fakexr::set_action_state(
+                dpad_data.xy.as_raw(),
+                fakexr::ActionState::Vector2(x, y),
+                LeftHand,
+            );
+            f.sync(vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                ..Default::default()
+            });
+
+            let state = f.get_bool_state(boolact).unwrap();
+            assert_eq!(state.bState, expected, "unexpected state for ({x}, {y})");
+        };
+
+        check_direction(0.0, 0.0, false);
+        // Still inside the default deadzone
+        check_direction(0.0, 0.3, false);
+        check_direction(0.0, 0.8, true);
+        check_direction(0.8, 0.0, false);
+        check_direction(0.0, 0.0, false);
+    }
+
+    #[test]
+    fn dpad_input_8way() {
+        let mut f = Fixture::new();
+
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let north = f.get_action_handle(c"/actions/set1/in/boolact");
+        let north_east = f.get_action_handle(c"/actions/set1/in/boolact_ne");
+
+        f.load_actions(c"actions_dpad_8way.json");
+        f.input.openxr.restart_session();
+
+        get_dpad_action!(f, north, dpad_data, ViveWands);
+
+        f.set_interaction_profile::<ViveWands>(LeftHand);
+        fakexr::set_action_state(
+            dpad_data.click_or_touch.as_ref().unwrap().as_raw(),
+            fakexr::ActionState::Float(1.0),
+            LeftHand,
+        );
+        let mut check_direction = |degrees: f32, expected_north, expected_north_east| {
+            let (y, x) = degrees.to_radians().sin_cos();
+            fakexr::set_action_state(
+                dpad_data.xy.as_raw(),
+                fakexr::ActionState::Vector2(x, y),
+                LeftHand,
+            );
+            f.sync(vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                ..Default::default()
+            });
+
+            let state = f.get_bool_state(north).unwrap();
+            assert!(state.bActive);
+            assert_eq!(state.bState, expected_north, "north at {degrees} degrees");
+            let state = f.get_bool_state(north_east).unwrap();
+            assert!(state.bActive);
+            assert_eq!(
+                state.bState, expected_north_east,
+                "north east at {degrees} degrees"
+            );
+        };
+
+        check_direction(90.0, true, false);
+        check_direction(80.0, true, false);
+        // Would be north on a 4-way dpad
+        check_direction(60.0, false, true);
+        check_direction(45.0, false, true);
+        check_direction(30.0, false, true);
+        check_direction(10.0, false, false);
+    }
+
     #[test]
     fn dpad_input_different_sets_have_different_actions() {
         let f = Fixture::new();
@@ -1437,6 +1889,114 @@ mod tests {
         value_state_check(0.0, 1.0, false, false, line!());
     }
 
+    #[test]
+    fn grab_binding_thresholds_per_hand() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        let left = f.get_input_source_handle(c"/user/hand/left");
+        let right = f.get_input_source_handle(c"/user/hand/right");
+        f.load_actions(c"actions_grab_per_hand.json");
+        get_grab_action!(f, boolact, grab_data);
+
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        f.set_interaction_profile::<Knuckles>(RightHand);
+        let mut value_state_check = |left_value, right_value, expected: [bool; 2], line| {
+            for (hand, value) in [(LeftHand, left_value), (RightHand, right_value)] {
+                fakexr::set_action_state(
+                    grab_data.value_action.as_raw(),
+                    fakexr::ActionState::Float(value),
+                    hand,
+                );
+            }
+            f.sync(vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                ..Default::default()
+            });
+
+            for (restrict, state) in [left, right].into_iter().zip(expected) {
+                let s = f.get_bool_state_hand(boolact, restrict).unwrap();
+                assert!(s.bActive, "active failed (line {line})");
+                assert_eq!(s.bState, state, "state failed (line {line})");
+            }
+        };
+
+        // Left: grab at 0.3, release at 0.2. Right: grab at 0.9, release at 0.8.
+        value_state_check(0.5, 0.5, [true, false], line!());
+        value_state_check(0.25, 0.95, [true, true], line!());
+        value_state_check(0.25, 0.85, [true, true], line!());
+        value_state_check(0.2, 0.8, [false, false], line!());
+    }
+
+    #[test]
+    fn grab_binding_force_and_value_paths() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions_grab.json");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        get_grab_action!(f, boolact, grab_data);
+
+        let profile_path = |profile| f.input.openxr.instance.string_to_path(profile).unwrap();
+        let suggested = |profile, action: &xr::Action<f32>| {
+            fakexr::get_suggested_bindings(action.as_raw(), profile_path(profile))
+        };
+
+        // Index controllers have a force sensor in the grip
+        let knuckles = Knuckles::profile_path();
+        assert_eq!(
+            suggested(knuckles, &grab_data.force_action),
+            ["/user/hand/left/input/squeeze/force"]
+        );
+        assert_eq!(
+            suggested(knuckles, &grab_data.value_action),
+            ["/user/hand/left/input/squeeze/value"]
+        );
+
+        // Touch controllers don't, so only the value is bound
+        let touch = OculusTouch::profile_path();
+        assert!(fakexr::check_no_suggested_bindings(
+            grab_data.force_action.as_raw(),
+            profile_path(touch)
+        ));
+        assert_eq!(
+            suggested(touch, &grab_data.value_action),
+            ["/user/hand/left/input/squeeze/value"]
+        );
+    }
+
+    #[test]
+    fn grab_binding_without_force() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        f.load_actions(c"actions_grab.json");
+        get_grab_action!(f, boolact, grab_data);
+
+        f.set_interaction_profile::<OculusTouch>(LeftHand);
+        let mut value_state_check = |value, state, changed, line| {
+            fakexr::set_action_state(
+                grab_data.value_action.as_raw(),
+                fakexr::ActionState::Float(value),
+                LeftHand,
+            );
+            f.sync(vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                ..Default::default()
+            });
+
+            let s = f.get_bool_state(boolact).unwrap();
+            assert_eq!(s.bState, state, "state failed (line {line})");
+            assert!(s.bActive, "active failed (line {line})");
+            assert_eq!(s.bChanged, changed, "changed failed (line {line})");
+        };
+
+        let grab = GrabBindingData::DEFAULT_GRAB_THRESHOLD;
+        let release = GrabBindingData::DEFAULT_RELEASE_THRESHOLD;
+        value_state_check(grab - 0.1, false, false, line!());
+        value_state_check(grab, true, true, line!());
+        value_state_check(release + 0.01, true, false, line!());
+        value_state_check(release, false, true, line!());
+    }
+
     #[test]
     fn toggle_button() {
         let mut f = Fixture::new();
@@ -1626,6 +2186,93 @@ mod tests {
         assert!(s_left.bChanged);
     }
 
+    #[test]
+    fn trigger_force_falls_back_to_value() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        let left = f.get_input_source_handle(c"/user/hand/left");
+
+        // No Touch controller has a force sensor on its trigger.
+        f.load_actions(c"actions_force_click.json");
+        for profile in [OculusTouch::profile_path(), OculusTouchPro::profile_path()] {
+            f.verify_extra_bindings(
+                profile,
+                c"/actions/set1/in/boolact",
+                ExtraActionType::Analog,
+                ["/user/hand/left/input/trigger/value".into()],
+            );
+        }
+        get_analog_action!(f, boolact, analog_data);
+        let act = analog_data.as_raw();
+
+        f.set_interaction_profile::<OculusTouchPro>(LeftHand);
+        let mut state_check = |value, state, line| {
+            fakexr::set_action_state(act, ActionState::Float(value), LeftHand);
+            f.sync(vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                ..Default::default()
+            });
+            let s = f.get_bool_state_hand(boolact, left).unwrap();
+            assert!(s.bActive, "active failed (line {line})");
+            assert_eq!(s.bState, state, "state failed (line {line})");
+        };
+
+        state_check(0.0, false, line!());
+        state_check(1.0, true, line!());
+        state_check(0.0, false, line!());
+    }
+
+    #[test]
+    fn manifest_click_thresholds() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let button = f.get_action_handle(c"/actions/set1/in/boolact");
+        let trigger = f.get_action_handle(c"/actions/set1/in/boolact2");
+
+        f.load_actions(c"actions_click_threshold.json");
+        f.verify_extra_bindings(
+            Knuckles::profile_path(),
+            c"/actions/set1/in/boolact",
+            ExtraActionType::Analog,
+            ["/user/hand/left/input/trigger/value".into()],
+        );
+        f.verify_extra_bindings(
+            Knuckles::profile_path(),
+            c"/actions/set1/in/boolact2",
+            ExtraActionType::Analog,
+            ["/user/hand/right/input/trigger/value".into()],
+        );
+
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        f.set_interaction_profile::<Knuckles>(RightHand);
+
+        for (handle, hand) in [(button, LeftHand), (trigger, RightHand)] {
+            get_analog_action!(f, handle, analog_data);
+            let act = analog_data.as_raw();
+
+            let mut value_state_check = |value, state, changed, line| {
+                fakexr::set_action_state(act, ActionState::Float(value), hand);
+                f.sync(vr::VRActiveActionSet_t {
+                    ulActionSet: set1,
+                    ..Default::default()
+                });
+
+                let s = f.get_bool_state(handle).unwrap();
+                assert!(s.bActive, "active failed (line {line})");
+                assert_eq!(s.bState, state, "state failed (line {line})");
+                assert_eq!(s.bChanged, changed, "changed failed (line {line})");
+            };
+
+            // Well past the default threshold, but not the one from the bindings
+            value_state_check(0.5, false, false, line!());
+            value_state_check(0.89, false, false, line!());
+            value_state_check(0.9, true, true, line!());
+            value_state_check(0.86, true, false, line!());
+            value_state_check(0.84, false, true, line!());
+        }
+    }
+
     #[test]
     fn trigger_no_touch_from_pull_oculus() {
         let f = Fixture::new();
@@ -1751,6 +2398,59 @@ mod tests {
         f.verify_bool_state(boolact, active_state.set_changed());
     }
 
+    #[test]
+    fn double_tap_custom_timeout() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let active_set = vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        };
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        f.load_actions(c"actions_double_tap.json");
+        get_double_action!(f, boolact, double_action);
+        let tap = |state: bool, time_ms: i64| {
+            fakexr::set_action_state_with_time(
+                double_action.as_raw(),
+                fakexr::ActionState::Bool(state),
+                LeftHand,
+                xr::Time::from_nanos(time_ms * 1_000_000),
+            );
+        };
+
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        let inactive_state = BoolState::default().set_active();
+        let active_state = inactive_state.set_state();
+        tap(false, 0);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state);
+
+        // longer than the default timeout, but within the configured one
+        tap(true, 0);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state);
+        tap(false, 0);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state);
+        tap(true, 400);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, active_state.set_changed());
+        tap(false, 500);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state.set_changed());
+
+        // too slow
+        tap(true, 1000);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state);
+        tap(false, 1000);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state);
+        tap(true, 1600);
+        f.sync(active_set);
+        f.verify_bool_state(boolact, inactive_state);
+    }
+
     #[test]
     fn double_tap_bindings() {
         let f = Fixture::new();
@@ -1762,4 +2462,169 @@ mod tests {
             ["/user/hand/left/input/a/click".to_string()],
         );
     }
+
+    #[test]
+    fn long_press() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        f.load_actions(c"actions_long_press.json");
+
+        let long_press = f
+            .get_extra_action(boolact, ExtraActionType::LongPress)
+            .expect("Missing long press action");
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+
+        let active_set = vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        };
+        let secs = |secs: f64| xr::Time::from_nanos((secs * 1e9) as i64);
+        let sync_at = |f: &mut Fixture, time| {
+            f.input.openxr.display_time.set(secs(time));
+            f.sync(active_set);
+        };
+        let press = |state: bool, time| {
+            fakexr::set_action_state_with_time(long_press, state.into(), LeftHand, secs(time));
+        };
+
+        let inactive = BoolState::default().set_active();
+        let held = inactive.set_state();
+
+        press(true, 1.0);
+        sync_at(&mut f, 1.2);
+        f.verify_bool_state(boolact, inactive);
+
+        sync_at(&mut f, 1.6);
+        f.verify_bool_state(boolact, held.set_changed());
+
+        sync_at(&mut f, 1.7);
+        f.verify_bool_state(boolact, held);
+
+        press(false, 1.8);
+        sync_at(&mut f, 1.8);
+        f.verify_bool_state(boolact, inactive.set_changed());
+
+        // short presses should never activate
+        press(true, 2.0);
+        sync_at(&mut f, 2.1);
+        f.verify_bool_state(boolact, inactive);
+        press(false, 2.2);
+        sync_at(&mut f, 2.7);
+        f.verify_bool_state(boolact, inactive);
+    }
+
+    #[test]
+    fn scroll_clicks() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        f.load_actions(c"actions_scroll.json");
+
+        let scroll = f
+            .get_extra_action(boolact, ExtraActionType::Scroll)
+            .expect("Missing scroll action");
+        f.set_interaction_profile::<Knuckles>(RightHand);
+
+        let active_set = vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        };
+        let scroll_to = |f: &mut Fixture, y| {
+            fakexr::set_action_state(scroll, ActionState::Vector2(0.0, y), RightHand);
+            f.sync(active_set);
+        };
+
+        let inactive = BoolState::default().set_active();
+        let clicked = inactive.set_state().set_changed();
+        let released = inactive.set_changed();
+
+        scroll_to(&mut f, 0.1);
+        f.verify_bool_state(boolact, inactive);
+        scroll_to(&mut f, 0.3);
+        f.verify_bool_state(boolact, inactive);
+        // moved 0.35 in total
+        scroll_to(&mut f, 0.45);
+        f.verify_bool_state(boolact, clicked);
+        scroll_to(&mut f, 0.5);
+        f.verify_bool_state(boolact, released);
+        // moved 0.35 since the last click
+        scroll_to(&mut f, 0.8);
+        f.verify_bool_state(boolact, clicked);
+
+        // lifting the finger shouldn't count as movement
+        scroll_to(&mut f, 0.0);
+        f.verify_bool_state(boolact, released);
+        scroll_to(&mut f, 0.5);
+        f.verify_bool_state(boolact, inactive);
+    }
+
+    #[test]
+    fn chord_input() {
+        let mut f = Fixture::new();
+        let set1 = f.get_action_set_handle(c"/actions/set1");
+        let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+        f.load_actions(c"actions_chord.json");
+
+        let input = f.input.clone();
+        let data = input.openxr.session_data.get();
+        let actions = data.input_data.get_loaded_actions().unwrap();
+        let profile = f
+            .input
+            .openxr
+            .instance
+            .string_to_path(Knuckles::profile_path())
+            .unwrap();
+        let bindings = actions.try_get_bindings(boolact, profile).unwrap();
+        let [
+            BoolBindingData {
+                ty: BoolBindingType::Chord(chord),
+                ..
+            },
+        ] = bindings.as_slice()
+        else {
+            panic!("Expected a single chord binding");
+        };
+        let [(a, _), (trigger, _)] = chord.inputs.as_slice() else {
+            panic!("Expected two chord inputs, got {}", chord.inputs.len());
+        };
+
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        f.set_interaction_profile::<Knuckles>(RightHand);
+        let active_set = vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        };
+
+        // only one input held
+        fakexr::set_action_state(a.as_raw(), true.into(), LeftHand);
+        fakexr::set_action_state(trigger.as_raw(), false.into(), RightHand);
+        f.sync(active_set);
+        let state = f.get_bool_state(boolact).unwrap();
+        assert!(state.bActive);
+        assert!(!state.bState);
+        assert!(!state.bChanged);
+
+        // both inputs held
+        fakexr::set_action_state(trigger.as_raw(), true.into(), RightHand);
+        f.sync(active_set);
+        let state = f.get_bool_state(boolact).unwrap();
+        assert!(state.bActive);
+        assert!(state.bState);
+        assert!(state.bChanged);
+
+        f.sync(active_set);
+        let state = f.get_bool_state(boolact).unwrap();
+        assert!(state.bActive);
+        assert!(state.bState);
+        assert!(!state.bChanged);
+
+        // releasing either input releases the chord
+        fakexr::set_action_state(a.as_raw(), false.into(), LeftHand);
+        f.sync(active_set);
+        let state = f.get_bool_state(boolact).unwrap();
+        assert!(state.bActive);
+        assert!(!state.bState);
+        assert!(state.bChanged);
+    }
 }
@@ -1,45 +1,163 @@
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::env::current_dir;
 use std::ffi::{CStr, CString};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-use glam::Mat4;
+use glam::{Mat4, Quat, Vec3};
 use openvr as vr;
 use openxr as xr;
 
 use crate::input::profiles::knuckles::Knuckles;
-#[cfg(feature = "monado")]
+use crate::input::profiles::simple_controller::SimpleController;
 use crate::input::profiles::vive_tracker::ViveTracker;
+use crate::input::tracker_role::{TrackerRole, TrackerRoleOverrides};
+use crate::input::vive_trackers::enumerate_trackers as enumerate_vive_trackers;
 #[cfg(feature = "monado")]
 use openxr_mndx_xdev_space::{SessionXDevExtensionMNDX, XDev, XR_MNDX_XDEV_SPACE_EXTENSION_NAME};
 
 use crate::input::profiles::ProfileProperties;
 use crate::openxr_data::{self, Hand, OpenXrData, SessionData};
+use crate::settings::XrizerSettings;
 use crate::tracy_span;
-use log::trace;
+use log::{info, trace, warn};
+use serde::Deserialize;
+use serde::de::IgnoredAny;
 
-use super::{Input, InteractionProfile, profiles::MainAxisType};
+use super::{Input, InteractionProfile};
 
 pub enum TrackedDeviceType {
     Hmd,
     Controller {
         hand: Hand,
         hand_tracker: Option<xr::HandTracker>,
-        skeleton_cache: Mutex<HashMap<u64, Option<xr::HandJointLocations>>>,
+        skeleton_cache: Mutex<HashMap<u64, Option<HandSkeleton>>>,
     },
-    #[cfg(feature = "monado")]
     GenericTracker {
         space: xr::Space,
         serial: CString,
+        role: TrackerRole,
+        /// SteamVR's registered device type for trackers includes the serial.
+        registered_device_type: CString,
+        /// The role path the space was created from, for trackers from
+        /// XR_HTCX_vive_tracker_interaction rather than the xdev extension.
+        role_path: Option<xr::Path>,
     },
 }
 
+#[derive(Copy, Clone)]
+pub struct HandSkeleton {
+    pub joints: xr::HandJointLocations,
+    pub tracking_level: vr::EVRSkeletalTrackingLevel,
+}
+
+/// Creates a hand tracker for the given hand. If XR_EXT_hand_tracking_data_source is available,
+/// both unobstructed and controller data sources are requested, so the runtime can tell us which
+/// one is actually providing the joints.
+pub fn create_hand_tracker(
+    session: &xr::Session<xr::AnyGraphics>,
+    hand: Hand,
+) -> xr::Result<xr::HandTracker> {
+    let exts = session.instance().exts();
+    let Some(fp) = exts.ext_hand_tracking.as_ref() else {
+        return Err(xr::sys::Result::ERROR_EXTENSION_NOT_PRESENT);
+    };
+    if exts.ext_hand_tracking_data_source.is_none() {
+        return session.create_hand_tracker(hand.into());
+    }
+
+    let mut sources = [
+        xr::sys::HandTrackingDataSourceEXT::UNOBSTRUCTED,
+        xr::sys::HandTrackingDataSourceEXT::CONTROLLER,
+    ];
+    let source_info = xr::sys::HandTrackingDataSourceInfoEXT {
+        ty: xr::sys::HandTrackingDataSourceInfoEXT::TYPE,
+        next: std::ptr::null(),
+        requested_data_source_count: sources.len() as u32,
+        requested_data_sources: sources.as_mut_ptr(),
+    };
+    let info = xr::sys::HandTrackerCreateInfoEXT {
+        ty: xr::sys::HandTrackerCreateInfoEXT::TYPE,
+        next: &raw const source_info as *const _,
+        hand: hand.into(),
+        hand_joint_set: xr::sys::HandJointSetEXT::DEFAULT,
+    };
+    let mut tracker = xr::sys::HandTrackerEXT::NULL;
+    let ret = unsafe { (fp.create_hand_tracker)(session.as_raw(), &info, &mut tracker) };
+    if ret.into_raw() < 0 {
+        return Err(ret);
+    }
+
+    Ok(unsafe { xr::HandTracker::from_raw(session.clone(), tracker) })
+}
+
+fn locate_hand_joints(
+    instance: &xr::Instance,
+    base: &xr::Space,
+    tracker: &xr::HandTracker,
+    time: xr::Time,
+) -> Option<HandSkeleton> {
+    let exts = instance.exts();
+    let fp = exts.ext_hand_tracking.as_ref()?;
+    let has_data_source = exts.ext_hand_tracking_data_source.is_some();
+
+    let mut source_state = xr::sys::HandTrackingDataSourceStateEXT {
+        ty: xr::sys::HandTrackingDataSourceStateEXT::TYPE,
+        next: std::ptr::null_mut(),
+        is_active: false.into(),
+        data_source: xr::sys::HandTrackingDataSourceEXT::UNOBSTRUCTED,
+    };
+    // SAFETY: HandJointLocationEXT is plain old data
+    let mut joints: xr::HandJointLocations = unsafe { std::mem::zeroed() };
+    let mut locations = xr::sys::HandJointLocationsEXT {
+        ty: xr::sys::HandJointLocationsEXT::TYPE,
+        next: if has_data_source {
+            &raw mut source_state as *mut _
+        } else {
+            std::ptr::null_mut()
+        },
+        is_active: false.into(),
+        joint_count: joints.len() as u32,
+        joint_locations: joints.as_mut_ptr(),
+    };
+    let info = xr::sys::HandJointsLocateInfoEXT {
+        ty: xr::sys::HandJointsLocateInfoEXT::TYPE,
+        next: std::ptr::null(),
+        base_space: base.as_raw(),
+        time,
+    };
+
+    let ret = unsafe { (fp.locate_hand_joints)(tracker.as_raw(), &info, &mut locations) };
+    if ret.into_raw() < 0 {
+        trace!("Failed to locate hand joints: {ret}");
+        return None;
+    }
+    if !bool::from(locations.is_active) {
+        return None;
+    }
+
+    // Without the data source extension we have no way of knowing where the joints come from,
+    // so assume they're from actual hand tracking.
+    let tracking_level = if has_data_source
+        && bool::from(source_state.is_active)
+        && source_state.data_source == xr::sys::HandTrackingDataSourceEXT::CONTROLLER
+    {
+        vr::EVRSkeletalTrackingLevel::Partial
+    } else {
+        vr::EVRSkeletalTrackingLevel::Full
+    };
+
+    Some(HandSkeleton {
+        joints,
+        tracking_level,
+    })
+}
+
 impl std::fmt::Debug for TrackedDeviceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TrackedDeviceType::Hmd => write!(f, "HMD"),
             TrackedDeviceType::Controller { hand, .. } => write!(f, "Controller ({:?})", hand),
-            #[cfg(feature = "monado")]
             TrackedDeviceType::GenericTracker { serial, .. } => {
                 write!(f, "Generic Tracker ({})", serial.to_string_lossy())
             }
@@ -47,9 +165,142 @@ impl std::fmt::Debug for TrackedDeviceType {
     }
 }
 
+/// User provided replacements for some of the [`ProfileProperties`], keyed on interaction profile
+/// path. Some games only behave properly if they think they're seeing specific hardware.
+#[derive(Deserialize, Default)]
+#[serde(transparent)]
+pub struct PropertyOverrides(HashMap<String, Arc<ProfilePropertyOverrides>>);
+
+impl PropertyOverrides {
+    /// Loads overrides from the file in [`XrizerSettings::property_overrides`].
+    pub fn load() -> Self {
+        let path = if let Some(path) = &XrizerSettings::get().property_overrides {
+            path.clone()
+        } else {
+            match current_dir() {
+                Ok(dir) => dir.join("xrizer").join("property_overrides.json"),
+                Err(_) => return Self::default(),
+            }
+        };
+
+        let Ok(data) = std::fs::read(&path) else {
+            return Self::default();
+        };
+
+        match Self::from_slice(&data) {
+            Ok(overrides) => {
+                info!("Loaded property overrides from {}", path.display());
+                overrides
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to parse property overrides ({}): {e}",
+                    path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    pub fn from_slice(data: &[u8]) -> serde_json::Result<Self> {
+        let overrides: Self = serde_json::from_slice(data)?;
+        for (profile, overrides) in &overrides.0 {
+            if overrides.controller_type.is_some() {
+                warn!(
+                    "Ignoring controller_type override for {profile}, since the bindings would still be picked for the real controller type"
+                );
+            }
+        }
+        Ok(overrides)
+    }
+
+    pub fn get(&self, profile_path: &str) -> Option<Arc<ProfilePropertyOverrides>> {
+        self.0.get(profile_path).cloned()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilePropertyOverrides {
+    model: Option<PropertyOverride>,
+    /// Not supported: bindings are picked by interaction profile, so the game would be told about
+    /// one controller type while getting the bindings for another.
+    controller_type: Option<IgnoredAny>,
+    render_model_name: Option<PropertyOverride>,
+    /// Applied on top of the profile's grip offset, for games whose controller models don't
+    /// line up with the physical controller.
+    grip_offset: Option<PropertyOverride<GripOffset>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct GripOffset {
+    /// In meters.
+    translation: [f32; 3],
+    /// XYZ Euler angles, in degrees.
+    rotation: [f32; 3],
+}
+
+impl GripOffset {
+    fn to_mat4(&self) -> Mat4 {
+        let [x, y, z] = self.rotation.map(f32::to_radians);
+        Mat4::from_rotation_translation(
+            Quat::from_euler(glam::EulerRot::XYZ, x, y, z),
+            Vec3::from_array(self.translation),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct OverrideString(CString);
+
+impl TryFrom<String> for OverrideString {
+    type Error = std::ffi::NulError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        CString::new(value).map(Self)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PropertyOverride<T = OverrideString> {
+    BothHands(T),
+    PerHand { left: T, right: T },
+}
+
+impl<T> PropertyOverride<T> {
+    fn get(&self, hand: Hand) -> &T {
+        match self {
+            Self::BothHands(property) => property,
+            Self::PerHand { left, right } => match hand {
+                Hand::Left => left,
+                Hand::Right => right,
+            },
+        }
+    }
+}
+
+impl ProfilePropertyOverrides {
+    fn get(&self, property: vr::ETrackedDeviceProperty, hand: Hand) -> Option<&CStr> {
+        match property {
+            vr::ETrackedDeviceProperty::ModelNumber_String => {
+                self.model.as_ref().map(|m| m.get(hand).0.as_c_str())
+            }
+            vr::ETrackedDeviceProperty::RenderModelName_String => self
+                .render_model_name
+                .as_ref()
+                .map(|m| m.get(hand).0.as_c_str()),
+            _ => None,
+        }
+    }
+}
+
 pub struct ProfileData {
     properties: &'static ProfileProperties,
+    pub overrides: Option<Arc<ProfilePropertyOverrides>>,
     get_hand_offset: fn(Hand) -> Mat4,
+    get_gdc2015_offset: fn(Hand) -> Mat4,
     /// For Knuckles, the skeleton thumb tries to accurately match where the physical
     /// thumb is, e.g. the curl depends on which part of the touchpad is being touched,
     /// or how the thumbstick is being pushed, but in GetSkeletalSummaryData with
@@ -57,20 +308,45 @@ pub struct ProfileData {
     /// or thumbstick is being touched, so just use the skeletal input actions to
     /// determine the curl value for the thumb.
     pub force_estimated_thumb: bool,
+    /// The simple controller only has click inputs, so its trigger and grip axes are
+    /// approximated from those clicks, which only ever reads 0 or 1.
+    pub analog_from_click: bool,
 }
 
 impl ProfileData {
     pub fn new<P: InteractionProfile>() -> Self {
         Self {
             properties: P::properties(),
+            overrides: None,
             get_hand_offset: P::offset_grip_pose,
+            get_gdc2015_offset: P::offset_gdc2015_pose,
             force_estimated_thumb: TypeId::of::<P>() == TypeId::of::<Knuckles>(),
+            analog_from_click: TypeId::of::<P>() == TypeId::of::<SimpleController>(),
         }
     }
 
+    #[inline]
+    pub fn display_name(&self) -> &'static str {
+        self.properties.display_name
+    }
+
+    #[inline]
+    pub fn has_joystick(&self) -> bool {
+        self.properties.has_joystick
+    }
+
     #[inline]
     pub fn hand_offset(&self, hand: Hand) -> Mat4 {
-        (self.get_hand_offset)(hand)
+        let offset = (self.get_hand_offset)(hand);
+        match self.overrides.as_ref().and_then(|o| o.grip_offset.as_ref()) {
+            Some(grip_offset) => offset * grip_offset.get(hand).to_mat4(),
+            None => offset,
+        }
+    }
+
+    #[inline]
+    pub fn gdc2015_offset(&self, hand: Hand) -> Mat4 {
+        (self.get_gdc2015_offset)(hand)
     }
 }
 
@@ -78,35 +354,79 @@ pub struct TrackedDevice {
     device_type: TrackedDeviceType,
     pub profile_data: Option<ProfileData>,
     pub profile_path: xr::Path,
+    /// The most recent non-null profile, kept around while the controller is disconnected.
+    pub last_profile_path: xr::Path,
     pub connected: bool,
     pub previous_connected: bool,
-    pose_cache: Mutex<Option<vr::TrackedDevicePose_t>>,
+    /// Poses located this frame, so that repeated queries don't have to locate the device again.
+    pose_cache: Mutex<PoseCache>,
+    /// The latest valid pose located up to the display time for each origin, for estimating
+    /// velocities.
+    previous_poses: Mutex<HashMap<vr::ETrackingUniverseOrigin, PreviousPose>>,
+}
+
+/// Most poses kept in a device's [`PoseCache`].
+const MAX_CACHED_POSES: usize = 32;
+
+/// Poses located during a single display time, keyed by the time they were located at. If the
+/// display time moves on before the cache is cleared (i.e., without a frame update), the cached
+/// poses are stale and get thrown out.
+/// Predictions relative to the current time each get their own key, so the number of cached poses
+/// is capped to keep games polling with many different offsets from growing it unbounded.
+#[derive(Default)]
+struct PoseCache {
+    time: Option<xr::Time>,
+    poses: HashMap<(i64, vr::ETrackingUniverseOrigin), vr::TrackedDevicePose_t>,
+}
+
+impl PoseCache {
+    fn get(
+        &mut self,
+        display_time: xr::Time,
+        time: xr::Time,
+        origin: vr::ETrackingUniverseOrigin,
+    ) -> Option<vr::TrackedDevicePose_t> {
+        if self.time != Some(display_time) {
+            self.time = Some(display_time);
+            self.poses.clear();
+        }
+        self.poses.get(&(time.as_nanos(), origin)).copied()
+    }
+
+    fn insert(
+        &mut self,
+        time: xr::Time,
+        origin: vr::ETrackingUniverseOrigin,
+        pose: vr::TrackedDevicePose_t,
+    ) {
+        if self.poses.len() < MAX_CACHED_POSES {
+            self.poses.insert((time.as_nanos(), origin), pose);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.time = None;
+        self.poses.clear();
+    }
 }
 
 fn get_hmd_pose(
-    xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
     session_data: &SessionData,
     origin: vr::ETrackingUniverseOrigin,
-) -> Option<vr::TrackedDevicePose_t> {
-    let (location, velocity) = {
-        session_data
-            .view_space
-            .relate(
-                session_data.get_space_for_origin(origin),
-                xr_data.display_time.get(),
-            )
-            .ok()?
-    };
-
-    Some(vr::space_relation_to_openvr_pose(location, velocity))
+    time: xr::Time,
+) -> Option<(xr::SpaceLocation, xr::SpaceVelocity)> {
+    session_data
+        .view_space
+        .relate(session_data.get_space_for_origin(origin), time)
+        .ok()
 }
 
 fn get_controller_pose(
-    xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
     session_data: &SessionData,
     controller: &TrackedDevice,
     origin: vr::ETrackingUniverseOrigin,
-) -> Option<vr::TrackedDevicePose_t> {
+    time: xr::Time,
+) -> Option<(xr::SpaceLocation, xr::SpaceVelocity)> {
     let pose_data = session_data.input_data.pose_data.get()?;
 
     let spaces = match controller.get_controller_hand().unwrap() {
@@ -114,41 +434,84 @@ fn get_controller_pose(
         Hand::Right => &pose_data.right_space,
     };
 
-    let (location, velocity) = if let Some(raw) =
-        spaces.try_get_or_init_raw(&controller.profile_data, session_data, pose_data)
+    if let Some(raw) = spaces.try_get_or_init_raw(&controller.profile_data, session_data, pose_data)
     {
-        raw.relate(
-            session_data.get_space_for_origin(origin),
-            xr_data.display_time.get(),
-        )
-        .ok()?
+        raw.relate(session_data.get_space_for_origin(origin), time)
+            .ok()
     } else {
         trace!("Failed to get raw space, returning empty pose");
-        (xr::SpaceLocation::default(), xr::SpaceVelocity::default())
-    };
-
-    Some(vr::space_relation_to_openvr_pose(location, velocity))
+        Some((xr::SpaceLocation::default(), xr::SpaceVelocity::default()))
+    }
 }
 
-#[cfg(feature = "monado")]
 fn get_generic_tracker_pose(
-    xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
     session_data: &SessionData,
     tracker: &TrackedDevice,
     origin: vr::ETrackingUniverseOrigin,
-) -> Option<vr::TrackedDevicePose_t> {
+    time: xr::Time,
+) -> Option<(xr::SpaceLocation, xr::SpaceVelocity)> {
     let TrackedDeviceType::GenericTracker { space, .. } = tracker.get_type() else {
         return None;
     };
 
-    let (location, velocity) = space
-        .relate(
-            session_data.get_space_for_origin(origin),
-            xr_data.display_time.get(),
-        )
-        .ok()?;
+    space
+        .relate(session_data.get_space_for_origin(origin), time)
+        .ok()
+}
+
+/// The last valid pose of a device, used to estimate velocity when the runtime doesn't provide it.
+struct PreviousPose {
+    time: xr::Time,
+    pose: xr::Posef,
+}
+
+impl PreviousPose {
+    /// Fills in any velocities the runtime didn't provide by differencing against this pose.
+    fn estimate_velocity(&self, current: &PreviousPose, velocity: &mut xr::SpaceVelocity) {
+        // Predictions relative to now can come before this pose, which differences just as well.
+        let dt = (current.time.as_nanos() - self.time.as_nanos()) as f32 / 1e9;
+        if dt == 0.0 {
+            return;
+        }
+
+        let vec3 = |v: xr::Vector3f| Vec3::new(v.x, v.y, v.z);
+        let quat = |q: xr::Quaternionf| Quat::from_xyzw(q.x, q.y, q.z, q.w);
+        let to_xr = |v: Vec3| xr::Vector3f {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        };
+
+        if !velocity
+            .velocity_flags
+            .contains(xr::SpaceVelocityFlags::LINEAR_VALID)
+        {
+            let delta = vec3(current.pose.position) - vec3(self.pose.position);
+            velocity.linear_velocity = to_xr(delta / dt);
+            velocity.velocity_flags |= xr::SpaceVelocityFlags::LINEAR_VALID;
+        }
 
-    Some(vr::space_relation_to_openvr_pose(location, velocity))
+        if !velocity
+            .velocity_flags
+            .contains(xr::SpaceVelocityFlags::ANGULAR_VALID)
+        {
+            let mut delta = quat(current.pose.orientation) * quat(self.pose.orientation).inverse();
+            // Take the shortest rotation
+            if delta.w < 0.0 {
+                delta = -delta;
+            }
+            // atan2 stays accurate for the small rotations between frames, unlike acos
+            let axis = delta.xyz();
+            let sin_half_angle = axis.length();
+            let rotation = if sin_half_angle > f32::EPSILON {
+                axis * (2.0 * sin_half_angle.atan2(delta.w) / sin_half_angle)
+            } else {
+                axis * 2.0
+            };
+            velocity.angular_velocity = to_xr(rotation / dt);
+            velocity.velocity_flags |= xr::SpaceVelocityFlags::ANGULAR_VALID;
+        }
+    }
 }
 
 impl TrackedDevice {
@@ -160,10 +523,12 @@ impl TrackedDevice {
         Self {
             profile_data,
             profile_path: profile_path.unwrap_or(xr::Path::NULL),
+            last_profile_path: profile_path.unwrap_or(xr::Path::NULL),
             connected: matches!(device_type, TrackedDeviceType::Hmd),
             device_type,
             previous_connected: false,
-            pose_cache: Mutex::new(None),
+            pose_cache: Default::default(),
+            previous_poses: Mutex::default(),
         }
     }
 
@@ -173,30 +538,78 @@ impl TrackedDevice {
         session_data: &SessionData,
         origin: vr::ETrackingUniverseOrigin,
     ) -> Option<vr::TrackedDevicePose_t> {
+        self.get_pose_at_time(xr_data, session_data, origin, xr_data.display_time.get())
+    }
+
+    /// Like [`Self::get_pose`], but located at the given time rather than the display time, i.e.
+    /// to predict relative to the current time.
+    pub fn get_pose_at_time(
+        &self,
+        xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
+        session_data: &SessionData,
+        origin: vr::ETrackingUniverseOrigin,
+        time: xr::Time,
+    ) -> Option<vr::TrackedDevicePose_t> {
+        let display_time = xr_data.display_time.get();
         let mut pose_cache = self.pose_cache.lock().unwrap();
-        if let Some(pose) = *pose_cache {
+        if let Some(pose) = pose_cache.get(display_time, time, origin) {
             return Some(pose);
         }
 
-        *pose_cache = match self.device_type {
-            TrackedDeviceType::Hmd => get_hmd_pose(xr_data, session_data, origin),
+        let pose = self.locate(session_data, origin, time, display_time)?;
+        pose_cache.insert(time, origin, pose);
+        Some(pose)
+    }
+
+    fn locate(
+        &self,
+        session_data: &SessionData,
+        origin: vr::ETrackingUniverseOrigin,
+        time: xr::Time,
+        display_time: xr::Time,
+    ) -> Option<vr::TrackedDevicePose_t> {
+        let (location, mut velocity) = match self.device_type {
+            TrackedDeviceType::Hmd => get_hmd_pose(session_data, origin, time),
             TrackedDeviceType::Controller { .. } => {
-                get_controller_pose(xr_data, session_data, self, origin)
+                get_controller_pose(session_data, self, origin, time)
             }
-            #[cfg(feature = "monado")]
             TrackedDeviceType::GenericTracker { .. } => {
-                get_generic_tracker_pose(xr_data, session_data, self, origin)
+                get_generic_tracker_pose(session_data, self, origin, time)
             }
-        };
+        }?;
+
+        let mut previous_poses = self.previous_poses.lock().unwrap();
+        // Poses predicted past the display time are extrapolated from the ones before it, so only
+        // the latter are worth keeping around.
+        let update_previous_pose = time.as_nanos() <= display_time.as_nanos()
+            && previous_poses
+                .get(&origin)
+                .is_none_or(|previous| previous.time.as_nanos() <= time.as_nanos());
+        if location.location_flags.contains(
+            xr::SpaceLocationFlags::POSITION_VALID | xr::SpaceLocationFlags::ORIENTATION_VALID,
+        ) {
+            let current = PreviousPose {
+                time,
+                pose: location.pose,
+            };
+            if let Some(previous) = previous_poses.get(&origin) {
+                previous.estimate_velocity(&current, &mut velocity);
+            }
+            if update_previous_pose {
+                previous_poses.insert(origin, current);
+            }
+        } else if update_previous_pose {
+            previous_poses.remove(&origin);
+        }
 
-        *pose_cache
+        Some(vr::space_relation_to_openvr_pose(location, velocity))
     }
 
     pub fn get_hand_skeleton(
         &self,
         xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
         base: &xr::Space,
-    ) -> Option<xr::HandJointLocations> {
+    ) -> Option<HandSkeleton> {
         let TrackedDeviceType::Controller {
             hand_tracker,
             skeleton_cache,
@@ -210,15 +623,18 @@ impl TrackedDevice {
             return *skeleton;
         }
 
-        let joints = base
-            .locate_hand_joints(hand_tracker.as_ref()?, xr_data.display_time.get())
-            .unwrap_or_default();
+        let joints = locate_hand_joints(
+            &xr_data.instance,
+            base,
+            hand_tracker.as_ref()?,
+            xr_data.display_time.get(),
+        );
         skeleton_cache.insert(base.as_raw().into_raw(), joints);
         joints
     }
 
     pub fn clear_pose_cache(&self) {
-        std::mem::take(&mut *self.pose_cache.lock().unwrap());
+        self.pose_cache.lock().unwrap().clear();
         if let TrackedDeviceType::Controller { skeleton_cache, .. } = self.get_type() {
             skeleton_cache.lock().unwrap().clear();
         }
@@ -237,6 +653,14 @@ impl TrackedDevice {
         &self.device_type
     }
 
+    pub fn get_device_class(&self) -> vr::ETrackedDeviceClass {
+        match self.device_type {
+            TrackedDeviceType::Hmd => vr::ETrackedDeviceClass::HMD,
+            TrackedDeviceType::Controller { .. } => vr::ETrackedDeviceClass::Controller,
+            TrackedDeviceType::GenericTracker { .. } => vr::ETrackedDeviceClass::GenericTracker,
+        }
+    }
+
     pub fn get_controller_hand(&self) -> Option<Hand> {
         match self.device_type {
             TrackedDeviceType::Controller { hand, .. } => Some(hand),
@@ -250,7 +674,34 @@ impl TrackedDevice {
             _ => Hand::Left,
         };
 
-        let data = self.profile_data.as_ref()?.properties;
+        let profile_data = self.profile_data.as_ref()?;
+        if let Some(value) = profile_data
+            .overrides
+            .as_ref()
+            .and_then(|o| o.get(property, hand))
+        {
+            return Some(value);
+        }
+
+        if let TrackedDeviceType::GenericTracker {
+            role,
+            registered_device_type,
+            ..
+        } = &self.device_type
+        {
+            match property {
+                // FBT games use this to tell which body part a tracker is attached to
+                vr::ETrackedDeviceProperty::ControllerType_String => {
+                    return Some(role.controller_type());
+                }
+                vr::ETrackedDeviceProperty::RegisteredDeviceType_String => {
+                    return Some(registered_device_type);
+                }
+                _ => {}
+            }
+        }
+
+        let data = profile_data.properties;
 
         match property {
             // Audica likes to apply controller specific tweaks via this property
@@ -272,7 +723,6 @@ impl TrackedDevice {
             // Required for controllers to be acknowledged in I Expect You To Die 3
             vr::ETrackedDeviceProperty::SerialNumber_String => match self.get_type() {
                 TrackedDeviceType::Controller { .. } => Some(*data.serial_number.get(hand)),
-                #[cfg(feature = "monado")]
                 TrackedDeviceType::GenericTracker { serial, .. } => Some(serial.as_c_str()),
                 TrackedDeviceType::Hmd => unreachable!(),
             },
@@ -286,25 +736,37 @@ impl TrackedDevice {
             TrackedDeviceType::Controller { .. } => {
                 let data = self.profile_data.as_ref()?.properties;
 
-                match property {
-                    vr::ETrackedDeviceProperty::Axis0Type_Int32 => match data.main_axis {
-                        MainAxisType::Thumbstick => Some(vr::EVRControllerAxisType::Joystick as _),
-                        MainAxisType::Trackpad => Some(vr::EVRControllerAxisType::TrackPad as _),
-                    },
+                let axis_type = match property {
+                    vr::ETrackedDeviceProperty::Axis0Type_Int32 => {
+                        if data.has_joystick {
+                            vr::EVRControllerAxisType::Joystick
+                        } else if data.has_trackpad {
+                            vr::EVRControllerAxisType::TrackPad
+                        } else {
+                            vr::EVRControllerAxisType::None
+                        }
+                    }
                     vr::ETrackedDeviceProperty::Axis1Type_Int32 => {
-                        Some(vr::EVRControllerAxisType::Trigger as _)
+                        vr::EVRControllerAxisType::Trigger
                     }
                     vr::ETrackedDeviceProperty::Axis2Type_Int32 => {
                         // This is actually the grip, and gets recognized as such
-                        Some(vr::EVRControllerAxisType::Trigger as _)
+                        vr::EVRControllerAxisType::Trigger
+                    }
+                    // Controllers with both a thumbstick and a trackpad (i.e., knuckles) report
+                    // the trackpad after the grip.
+                    vr::ETrackedDeviceProperty::Axis3Type_Int32
+                        if data.has_joystick && data.has_trackpad =>
+                    {
+                        vr::EVRControllerAxisType::TrackPad
                     }
-                    // TODO: report knuckles trackpad?
                     vr::ETrackedDeviceProperty::Axis3Type_Int32
                     | vr::ETrackedDeviceProperty::Axis4Type_Int32 => {
-                        Some(vr::EVRControllerAxisType::None as _)
+                        vr::EVRControllerAxisType::None
                     }
-                    _ => None,
-                }
+                    _ => return None,
+                };
+                Some(axis_type as i32)
             }
             _ => None,
         }
@@ -325,6 +787,35 @@ impl TrackedDevice {
             _ => None,
         }
     }
+
+    // OpenXR has no way of querying battery status, so devices report a default of fully
+    // charged and not charging, to avoid games warning about low batteries.
+    fn get_float_property(&self, property: vr::ETrackedDeviceProperty) -> Option<f32> {
+        match (&self.device_type, property) {
+            (TrackedDeviceType::Hmd, _) => None,
+            (_, vr::ETrackedDeviceProperty::DeviceBatteryPercentage_Float) => Some(1.0),
+            _ => None,
+        }
+    }
+
+    fn get_bool_property(&self, property: vr::ETrackedDeviceProperty) -> Option<bool> {
+        match (&self.device_type, property) {
+            (TrackedDeviceType::Hmd, _) => None,
+            // Controllers and trackers are all wireless, so they have batteries and can be
+            // turned off.
+            (
+                _,
+                vr::ETrackedDeviceProperty::DeviceProvidesBatteryStatus_Bool
+                | vr::ETrackedDeviceProperty::DeviceCanPowerOff_Bool,
+            ) => Some(true),
+            (
+                _,
+                vr::ETrackedDeviceProperty::DeviceIsCharging_Bool
+                | vr::ETrackedDeviceProperty::Firmware_UpdateAvailable_Bool,
+            ) => Some(false),
+            _ => None,
+        }
+    }
 }
 
 pub struct SubactionPaths {
@@ -347,12 +838,69 @@ impl SubactionPaths {
 
 pub struct TrackedDeviceList {
     devices: Vec<TrackedDevice>,
+    /// Generation number of the xdev list the generic trackers were last created from, if they
+    /// have been created yet.
+    #[cfg(feature = "monado")]
+    xdev_generation: Option<u64>,
+}
+
+/// Lists the xdevs that should be generic trackers, along with the serial each is known by, if
+/// the runtime's xdev list changed since `generation`, and passes them to `f`. This doesn't need
+/// the device list, so the (comparatively slow) listing can happen without holding its lock.
+#[cfg(feature = "monado")]
+pub(super) fn with_xdev_trackers<R: Default>(
+    xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
+    session_data: &SessionData,
+    generation: Option<u64>,
+    f: impl FnOnce(u64, Vec<(CString, XDev)>) -> xr::Result<R>,
+) -> xr::Result<R> {
+    if !xr_data
+        .enabled_extensions
+        .other
+        .contains(&XR_MNDX_XDEV_SPACE_EXTENSION_NAME.to_string())
+    {
+        return Ok(R::default());
+    }
+
+    let xdev_list = session_data.session.get_xdev_list()?;
+    let new_generation = xdev_list.get_generation_number()?;
+    if generation == Some(new_generation) {
+        return Ok(R::default());
+    }
+
+    let extra_tracker_serials = &XrizerSettings::get().tracker_serials;
+
+    // Trackers are told apart by their serial, so devices that share one (or have none) are
+    // numbered to keep them from being mistaken for each other.
+    let mut serials: HashMap<String, usize> = HashMap::new();
+    let xdevs = xdev_list
+        .enumerate_xdevs()?
+        .into_iter()
+        .filter(|xdev| {
+            xdev.can_create_space()
+                && (xdev.name().to_lowercase().contains("tracker")
+                    || extra_tracker_serials.contains(&xdev.serial().to_string()))
+        })
+        .map(|xdev| {
+            let count = serials.entry(xdev.serial().to_string()).or_default();
+            *count += 1;
+            let serial = match *count {
+                1 => xdev.serial().to_string(),
+                n => format!("{}#{n}", xdev.serial()),
+            };
+            (CString::new(serial).unwrap(), xdev)
+        })
+        .collect();
+
+    f(new_generation, xdevs)
 }
 
 impl Default for TrackedDeviceList {
     fn default() -> Self {
         Self {
             devices: vec![TrackedDevice::new(TrackedDeviceType::Hmd, None, None)],
+            #[cfg(feature = "monado")]
+            xdev_generation: None,
         }
     }
 }
@@ -393,61 +941,121 @@ impl TrackedDeviceList {
         self.get_device_mut(self.get_controller_index(hand)?)
     }
 
-    fn get_controller_index(&self, hand: Hand) -> Option<vr::TrackedDeviceIndex_t> {
+    pub(super) fn get_controller_index(&self, hand: Hand) -> Option<vr::TrackedDeviceIndex_t> {
         self.iter()
             .enumerate()
             .find(|(_, device)| device.get_controller_hand() == Some(hand))
             .map(|(i, _)| i as vr::TrackedDeviceIndex_t)
     }
 
+    /// Whether the generic trackers have been created from the xdev list yet.
+    #[cfg(feature = "monado")]
+    pub(super) fn has_xdev_trackers(&self) -> bool {
+        self.xdev_generation.is_some()
+    }
+
+    /// The generation number of the xdev list the generic trackers were last updated from.
+    #[cfg(feature = "monado")]
+    pub(super) fn xdev_generation(&self) -> Option<u64> {
+        self.xdev_generation
+    }
+
+    /// Creates trackers for new xdevs and updates whether the existing ones are connected,
+    /// if the xdev list changed since the last call.
     #[cfg(feature = "monado")]
     pub(super) fn create_monado_generic_trackers(
         &mut self,
         xr_data: &OpenXrData<impl crate::openxr_data::Compositor>,
         session_data: &SessionData,
+        role_overrides: &TrackerRoleOverrides,
     ) -> xr::Result<()> {
-        if !xr_data
-            .enabled_extensions
-            .other
-            .contains(&XR_MNDX_XDEV_SPACE_EXTENSION_NAME.to_string())
-        {
+        with_xdev_trackers(
+            xr_data,
+            session_data,
+            self.xdev_generation,
+            |generation, xdevs| self.update_xdev_trackers(generation, xdevs, role_overrides),
+        )
+    }
+
+    /// Applies a new list of xdev trackers, as given by [`with_xdev_trackers`].
+    #[cfg(feature = "monado")]
+    pub(super) fn update_xdev_trackers(
+        &mut self,
+        generation: u64,
+        xdevs: Vec<(CString, XDev)>,
+        role_overrides: &TrackerRoleOverrides,
+    ) -> xr::Result<()> {
+        // Someone else may have gotten to this list first.
+        if self.xdev_generation == Some(generation) {
             return Ok(());
         }
-
-        self.devices.retain(|device| {
-            !matches!(device.device_type, TrackedDeviceType::GenericTracker { .. })
-        });
-
-        let max_generic_trackers = vr::k_unMaxTrackedDeviceCount as usize - self.devices.len();
-        let extra_tracker_serials = std::env::var("XRIZER_TRACKER_SERIALS")
-            .map_or(vec![], |trackers| {
-                trackers.split(";").map(|t| t.to_string()).collect()
-            });
-
-        let mut xdevs: Vec<XDev> = session_data
-            .session
-            .get_xdev_list()?
-            .enumerate_xdevs()?
+        self.xdev_generation = Some(generation);
+
+        // Trackers keep their index once they've been seen, so that disconnecting them instead of
+        // removing them sends the app a deactivated event, and reconnecting them an activated one.
+        for device in &mut self.devices {
+            let TrackedDeviceType::GenericTracker {
+                serial,
+                space,
+                role_path: None,
+                ..
+            } = &mut device.device_type
+            else {
+                continue;
+            };
+            let xdev = xdevs
+                .iter()
+                .find_map(|(xdev_serial, xdev)| (xdev_serial == serial).then_some(xdev));
+            match (xdev, device.connected) {
+                (Some(xdev), false) => {
+                    info!("Tracker {serial:?} reconnected");
+                    // The device may have been recreated by the runtime.
+                    *space = xdev.create_space(xr::Posef::IDENTITY)?;
+                }
+                (None, true) => info!("Tracker {serial:?} disconnected"),
+                _ => {}
+            }
+            device.connected = xdev.is_some();
+        }
+        let mut new_xdevs: Vec<(CString, XDev)> = xdevs
             .into_iter()
-            .filter(|xdev| {
-                xdev.can_create_space()
-                    && (xdev.name().to_lowercase().contains("tracker")
-                        || extra_tracker_serials.contains(&xdev.serial().to_string()))
+            .filter(|(xdev_serial, _)| {
+                !self.devices.iter().any(|device| {
+                    matches!(
+                        &device.device_type,
+                        TrackedDeviceType::GenericTracker { serial, .. }
+                            if serial == xdev_serial
+                    )
+                })
             })
             .collect();
 
-        xdevs.truncate(max_generic_trackers);
+        let max_generic_trackers = vr::k_unMaxTrackedDeviceCount as usize - self.devices.len();
+        new_xdevs.truncate(max_generic_trackers);
 
         log::info!(
             "Creating {} generic trackers via Monado XDev extension",
-            xdevs.len()
+            new_xdevs.len()
         );
 
-        let trackers = xdevs.into_iter().map(|xdev| {
-            let serial = CString::new(xdev.serial()).unwrap();
+        let trackers = new_xdevs.into_iter().map(|(serial, xdev)| {
+            let serial_str = serial.to_string_lossy();
+            let role = role_overrides.get_role(xdev.name(), &serial_str);
+            info!(
+                "Tracker {:?} ({serial_str}) assigned role {role:?}",
+                xdev.name()
+            );
+            let registered_device_type =
+                CString::new(format!("htc/vive_tracker{serial_str}")).unwrap();
             let space = xdev.create_space(xr::Posef::IDENTITY).unwrap();
             let mut tracker = TrackedDevice::new(
-                TrackedDeviceType::GenericTracker { serial, space },
+                TrackedDeviceType::GenericTracker {
+                    serial,
+                    space,
+                    role,
+                    registered_device_type,
+                    role_path: None,
+                },
                 None,
                 Some(ProfileData::new::<ViveTracker>()),
             );
@@ -459,6 +1067,110 @@ impl TrackedDeviceList {
         Ok(())
     }
 
+    /// Creates trackers for the Vive trackers reported by XR_HTCX_vive_tracker_interaction, and
+    /// updates the roles and connection state of the existing ones.
+    pub(super) fn create_htcx_generic_trackers(
+        &mut self,
+        session_data: &SessionData,
+        role_overrides: &TrackerRoleOverrides,
+    ) -> xr::Result<()> {
+        let Some(vive_trackers) = session_data
+            .input_data
+            .pose_data
+            .get()
+            .and_then(|pose_data| pose_data.vive_trackers.as_ref())
+        else {
+            return Ok(());
+        };
+
+        // Runtimes with the xdev extension may expose the same trackers through both, so prefer
+        // the xdevs if there are any.
+        if self.devices.iter().any(|device| {
+            matches!(
+                device.device_type,
+                TrackedDeviceType::GenericTracker {
+                    role_path: None,
+                    ..
+                }
+            )
+        }) {
+            return Ok(());
+        }
+
+        let trackers = enumerate_vive_trackers(session_data.session.instance())?;
+        for device in &mut self.devices {
+            let TrackedDeviceType::GenericTracker {
+                serial,
+                space,
+                role,
+                role_path: Some(role_path),
+                ..
+            } = &mut device.device_type
+            else {
+                continue;
+            };
+            let tracker = trackers
+                .iter()
+                .find(|tracker| tracker.serial.as_bytes() == serial.to_bytes());
+            match (tracker, device.connected) {
+                (Some(_), false) => info!("Tracker {serial:?} reconnected"),
+                (None, true) => info!("Tracker {serial:?} disconnected"),
+                _ => {}
+            }
+            if let Some(tracker) = tracker
+                && (!device.connected || tracker.role_path != *role_path)
+            {
+                *space = vive_trackers.create_space(&session_data.session, tracker.role_path)?;
+                *role_path = tracker.role_path;
+                *role = role_overrides.get_role(&tracker.role_name, &tracker.serial);
+            }
+            device.connected = tracker.is_some();
+        }
+
+        let mut new_trackers: Vec<_> = trackers
+            .into_iter()
+            .filter(|tracker| {
+                !self.devices.iter().any(|device| {
+                    matches!(
+                        &device.device_type,
+                        TrackedDeviceType::GenericTracker { serial, .. }
+                            if serial.to_bytes() == tracker.serial.as_bytes()
+                    )
+                })
+            })
+            .collect();
+
+        let max_generic_trackers = vr::k_unMaxTrackedDeviceCount as usize - self.devices.len();
+        new_trackers.truncate(max_generic_trackers);
+
+        for tracker in new_trackers {
+            let role = role_overrides.get_role(&tracker.role_name, &tracker.serial);
+            info!(
+                "Vive tracker {} ({}) assigned role {role:?}",
+                tracker.serial, tracker.role_name
+            );
+            let mut device = TrackedDevice::new(
+                TrackedDeviceType::GenericTracker {
+                    space: vive_trackers.create_space(&session_data.session, tracker.role_path)?,
+                    registered_device_type: CString::new(format!(
+                        "htc/vive_tracker{}",
+                        tracker.serial
+                    ))
+                    .unwrap(),
+                    serial: CString::new(tracker.serial).unwrap(),
+                    role,
+                    role_path: Some(tracker.role_path),
+                },
+                None,
+                Some(ProfileData::new::<ViveTracker>()),
+            );
+            device.connected = true;
+            self.devices.push(device);
+        }
+
+        Ok(())
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &TrackedDevice> {
         self.devices.iter()
     }
@@ -503,11 +1215,31 @@ impl<C: openxr_data::Compositor> Input<C> {
             .devices
             .read()
             .unwrap()
-            .get_controller_index(hand)?;
+            .get_controller_index(self.physical_hand(hand))?;
 
         self.get_device_pose(controller_index, origin)
     }
 
+    /// Like [`Self::get_controller_pose`], but located at the given time rather than the
+    /// display time.
+    pub fn get_controller_pose_at_time(
+        &self,
+        hand: Hand,
+        origin: Option<vr::ETrackingUniverseOrigin>,
+        time: xr::Time,
+    ) -> Option<vr::TrackedDevicePose_t> {
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+        devices
+            .get_controller(self.physical_hand(hand))?
+            .get_pose_at_time(
+                &self.openxr,
+                &session_data,
+                origin.unwrap_or(session_data.current_origin),
+                time,
+            )
+    }
+
     pub fn get_device_pose(
         &self,
         index: vr::TrackedDeviceIndex_t,
@@ -516,15 +1248,27 @@ impl<C: openxr_data::Compositor> Input<C> {
         tracy_span!();
 
         let session_data = self.openxr.session_data.get();
-        let devices = session_data.input_data.devices.read().unwrap();
-
-        devices.get_device(index)?.get_pose(
-            &self.openxr,
+        self.get_device_pose_in_session(
             &session_data,
+            index,
             origin.unwrap_or(session_data.current_origin),
         )
     }
 
+    /// Like [`Self::get_device_pose`], for callers already holding the session data.
+    pub fn get_device_pose_in_session(
+        &self,
+        session_data: &SessionData,
+        index: vr::TrackedDeviceIndex_t,
+        origin: vr::ETrackingUniverseOrigin,
+    ) -> Option<vr::TrackedDevicePose_t> {
+        let devices = session_data.input_data.devices.read().unwrap();
+
+        devices
+            .get_device(index)?
+            .get_pose(&self.openxr, session_data, origin)
+    }
+
     pub fn is_device_connected(&self, index: vr::TrackedDeviceIndex_t) -> bool {
         let session_data = self.openxr.session_data.get();
         let devices = session_data.input_data.devices.read().unwrap();
@@ -538,16 +1282,85 @@ impl<C: openxr_data::Compositor> Input<C> {
     ) -> Option<vr::ETrackedDeviceClass> {
         let session_data = self.openxr.session_data.get();
         let devices = session_data.input_data.devices.read().unwrap();
-        let device = devices.get_device(index)?;
 
-        match device.get_type() {
-            TrackedDeviceType::Hmd => Some(vr::ETrackedDeviceClass::HMD),
-            TrackedDeviceType::Controller { .. } => Some(vr::ETrackedDeviceClass::Controller),
-            #[cfg(feature = "monado")]
-            TrackedDeviceType::GenericTracker { .. } => {
-                Some(vr::ETrackedDeviceClass::GenericTracker)
-            }
-        }
+        devices
+            .get_device(index)
+            .map(TrackedDevice::get_device_class)
+    }
+
+    /// Returns the indices of all connected devices of the given class, sorted right to left from
+    /// the point of view of `relative_to`, or of the tracking space if it's
+    /// `k_unTrackedDeviceIndexInvalid`. If `relative_to` has no pose, they are sorted by index.
+    pub fn get_sorted_device_indices_of_class(
+        &self,
+        class: vr::ETrackedDeviceClass,
+        relative_to: vr::TrackedDeviceIndex_t,
+    ) -> Vec<vr::TrackedDeviceIndex_t> {
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+        let indices: Vec<vr::TrackedDeviceIndex_t> = devices
+            .iter()
+            .enumerate()
+            .filter(|(_, device)| device.connected && device.get_device_class() == class)
+            .map(|(index, _)| index as vr::TrackedDeviceIndex_t)
+            .collect();
+
+        let get_position = |index| {
+            let pose = devices.get_device(index)?.get_pose(
+                &self.openxr,
+                &session_data,
+                session_data.current_origin,
+            )?;
+            pose.bPoseIsValid
+                .then(|| xr::Posef::from(pose.mDeviceToAbsoluteTracking))
+        };
+        let reference = if relative_to == vr::k_unTrackedDeviceIndexInvalid {
+            xr::Posef::IDENTITY
+        } else {
+            let Some(reference) = get_position(relative_to) else {
+                return indices;
+            };
+            reference
+        };
+
+        let vec3 = |v: xr::Vector3f| Vec3::new(v.x, v.y, v.z);
+        let o = reference.orientation;
+        let to_reference = Quat::from_xyzw(o.x, o.y, o.z, o.w).inverse();
+        // How far to the right of the reference device each device is.
+        let mut offsets: Vec<_> = indices
+            .into_iter()
+            .map(|index| {
+                let offset = get_position(index).map(|pose| {
+                    (to_reference * (vec3(pose.position) - vec3(reference.position))).x
+                });
+                (index, offset)
+            })
+            .collect();
+
+        // Devices without a valid pose are placed last.
+        offsets.sort_by(|(_, a), (_, b)| match (a, b) {
+            (Some(a), Some(b)) => b.total_cmp(a),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        offsets.into_iter().map(|(index, _)| index).collect()
+    }
+
+    /// Returns the role of the tracker with the given registered device type, as it would be
+    /// stored in SteamVR's "trackers" settings section.
+    pub fn get_tracker_role_setting(&self, registered_device_type: &str) -> Option<&'static CStr> {
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+
+        devices.iter().find_map(|device| match device.get_type() {
+            TrackedDeviceType::GenericTracker {
+                role,
+                registered_device_type: ty,
+                ..
+            } if ty.to_bytes() == registered_device_type.as_bytes() => Some(role.setting_name()),
+            _ => None,
+        })
     }
 
     pub fn device_index_to_hand(&self, index: vr::TrackedDeviceIndex_t) -> Option<Hand> {
@@ -555,14 +1368,31 @@ impl<C: openxr_data::Compositor> Input<C> {
         let devices = session_data.input_data.devices.read().unwrap();
         let device = devices.get_device(index)?;
 
-        device.get_controller_hand()
+        device
+            .get_controller_hand()
+            .map(|hand| self.physical_hand(hand))
     }
 
     pub fn get_controller_device_index(&self, hand: Hand) -> Option<vr::TrackedDeviceIndex_t> {
         let session_data = self.openxr.session_data.get();
         let devices = session_data.input_data.devices.read().unwrap();
 
-        devices.get_controller_index(hand)
+        devices.get_controller_index(self.physical_hand(hand))
+    }
+
+    /// The display name of the interaction profile used by the controller in the given hand, if
+    /// it's connected.
+    pub fn get_controller_profile_name(&self, hand: Hand) -> Option<&'static str> {
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+        let controller = devices
+            .get_controller(self.physical_hand(hand))
+            .filter(|controller| controller.connected)?;
+
+        controller
+            .profile_data
+            .as_ref()
+            .map(ProfileData::display_name)
     }
 
     pub fn get_device_string_tracked_property(
@@ -600,12 +1430,220 @@ impl<C: openxr_data::Compositor> Input<C> {
 
         device.get_uint_property(property)
     }
+
+    pub fn get_device_float_tracked_property(
+        &self,
+        index: vr::TrackedDeviceIndex_t,
+        property: vr::ETrackedDeviceProperty,
+    ) -> Option<f32> {
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+        let device = devices.get_device(index)?;
+
+        device.get_float_property(property)
+    }
+
+    pub fn get_device_bool_tracked_property(
+        &self,
+        index: vr::TrackedDeviceIndex_t,
+        property: vr::ETrackedDeviceProperty,
+    ) -> Option<bool> {
+        let session_data = self.openxr.session_data.get();
+        let devices = session_data.input_data.devices.read().unwrap();
+        let device = devices.get_device(index)?;
+
+        device.get_bool_property(property)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::input::{profiles::knuckles::Knuckles, tests::Fixture};
+    use super::{MAX_CACHED_POSES, PoseCache, ProfileData, PropertyOverrides};
+    use crate::input::{InteractionProfile, profiles::knuckles::Knuckles, tests::Fixture};
+    use crate::openxr_data::Hand;
+    use glam::{EulerRot, Mat4, Quat, Vec3};
     use openvr as vr;
+    use openxr as xr;
+
+    #[test]
+    fn pose_cache_is_bounded() {
+        let mut cache = PoseCache::default();
+        let display_time = xr::Time::from_nanos(1);
+        let origin = vr::ETrackingUniverseOrigin::Seated;
+        for nanos in 0..MAX_CACHED_POSES as i64 * 2 {
+            let time = xr::Time::from_nanos(nanos);
+            assert!(cache.get(display_time, time, origin).is_none());
+            cache.insert(time, origin, vr::TrackedDevicePose_t::default());
+        }
+        assert_eq!(cache.poses.len(), MAX_CACHED_POSES);
+        assert!(
+            cache
+                .get(display_time, xr::Time::from_nanos(0), origin)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn controller_profile_name() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions.json");
+        assert_eq!(f.input.get_controller_profile_name(Hand::Left), None);
+
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        let frame = || {
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        frame();
+        frame();
+
+        assert_eq!(
+            f.input.get_controller_profile_name(Hand::Left),
+            Some("Valve Index Controller")
+        );
+        assert_eq!(f.input.get_controller_profile_name(Hand::Right), None);
+    }
+
+    #[test]
+    fn property_overrides() {
+        let mut f = Fixture::new();
+        let overrides = format!(
+            r#"{{
+                "{}": {{
+                    "controller_type": "oculus_touch",
+                    "render_model_name": {{
+                        "left": "custom_left",
+                        "right": "custom_right"
+                    }}
+                }}
+            }}"#,
+            Knuckles::profile_path()
+        );
+        *f.input.property_overrides.write().unwrap() =
+            PropertyOverrides::from_slice(overrides.as_bytes()).unwrap();
+
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+
+        let frame = || {
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        frame();
+        frame();
+
+        let index = f.input.get_controller_device_index(Hand::Left).unwrap();
+        let get = |property| {
+            f.input
+                .get_device_string_tracked_property(index, property)
+                .unwrap()
+        };
+
+        // Controller type overrides are ignored, since they wouldn't change the bindings.
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::ControllerType_String).as_c_str(),
+            c"knuckles"
+        );
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::RenderModelName_String).as_c_str(),
+            c"custom_left"
+        );
+        // Properties that aren't overridden still come from the profile
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::ModelNumber_String).as_c_str(),
+            c"Knuckles Left"
+        );
+    }
+
+    #[test]
+    fn grip_offset_override() {
+        let overrides = format!(
+            r#"{{
+                "{}": {{
+                    "grip_offset": {{
+                        "left": {{ "translation": [0.01, 0.0, -0.02], "rotation": [-15.0, 0.0, 5.0] }},
+                        "right": {{ "rotation": [-15.0, 0.0, -5.0] }}
+                    }}
+                }}
+            }}"#,
+            Knuckles::profile_path()
+        );
+        let overrides = PropertyOverrides::from_slice(overrides.as_bytes()).unwrap();
+
+        let mut data = ProfileData::new::<Knuckles>();
+        data.overrides = overrides.get(Knuckles::profile_path());
+
+        let expected = [
+            (
+                Hand::Left,
+                Mat4::from_rotation_translation(
+                    Quat::from_euler(EulerRot::XYZ, -15f32.to_radians(), 0.0, 5f32.to_radians()),
+                    Vec3::new(0.01, 0.0, -0.02),
+                ),
+            ),
+            (
+                Hand::Right,
+                Mat4::from_quat(Quat::from_euler(
+                    EulerRot::XYZ,
+                    -15f32.to_radians(),
+                    0.0,
+                    -5f32.to_radians(),
+                )),
+            ),
+        ];
+        for (hand, offset) in expected {
+            let expected = Knuckles::offset_grip_pose(hand) * offset;
+            assert!(
+                data.hand_offset(hand).abs_diff_eq(expected, 1e-6),
+                "{hand:?}: {} != {expected}",
+                data.hand_offset(hand)
+            );
+        }
+
+        // Profiles without an override keep their normal offset.
+        assert_eq!(
+            ProfileData::new::<Knuckles>().hand_offset(Hand::Left),
+            Knuckles::offset_grip_pose(Hand::Left)
+        );
+    }
+
+    #[test]
+    fn knuckles_axis_types() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+
+        let frame = || {
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        frame();
+        frame();
+
+        let index = f.input.get_controller_device_index(Hand::Left).unwrap();
+        let get = |property| {
+            f.input
+                .get_device_int_tracked_property(index, property)
+                .unwrap()
+        };
+
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::Axis0Type_Int32),
+            vr::EVRControllerAxisType::Joystick as i32
+        );
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::Axis2Type_Int32),
+            vr::EVRControllerAxisType::Trigger as i32
+        );
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::Axis3Type_Int32),
+            vr::EVRControllerAxisType::TrackPad as i32
+        );
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::Axis4Type_Int32),
+            vr::EVRControllerAxisType::None as i32
+        );
+    }
 
     #[test]
     #[cfg_attr(not(feature = "monado"), ignore)]
@@ -613,7 +1651,10 @@ mod tests {
         let mut f = Fixture::new();
         f.load_actions(c"actions.json");
         f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
-        fakexr::add_trackers(f.input.openxr.session_data.get().session.as_raw());
+        fakexr::add_trackers(
+            f.input.openxr.session_data.get().session.as_raw(),
+            &[c"FAKEXR-TRACKER"],
+        );
 
         let frame = || {
             f.input.openxr.poll_events();
@@ -635,13 +1676,65 @@ mod tests {
         assert!(pose2.is_some());
     }
 
+    #[test]
+    #[cfg(feature = "monado")]
+    fn tracker_pose_velocity() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        let session = f.input.openxr.session_data.get().session.as_raw();
+        fakexr::add_trackers(session, &[c"FAKEXR-TRACKER"]);
+
+        let frame = |time: i64| {
+            f.input
+                .openxr
+                .display_time
+                .set(xr::Time::from_nanos(time * 100_000_000));
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        // Fake xdev spaces follow the view.
+        let set_pose = |z: f32| {
+            fakexr::set_view(
+                session,
+                xr::Posef {
+                    position: xr::Vector3f { x: 0.0, y: 1.0, z },
+                    orientation: xr::Quaternionf::IDENTITY,
+                },
+            );
+        };
+        let get_pose = || {
+            let pose = f
+                .input
+                .get_device_pose(2, Some(vr::ETrackingUniverseOrigin::Seated))
+                .unwrap();
+            assert!(pose.bPoseIsValid);
+            pose
+        };
+
+        set_pose(0.0);
+        frame(1);
+        frame(2);
+        assert_eq!(get_pose().vVelocity.v, [0.0; 3]);
+
+        // Move 0.1m forward over 0.1 seconds.
+        frame(3);
+        set_pose(-0.1);
+        let [x, y, z] = get_pose().vVelocity.v;
+        assert!((z + 1.0).abs() < 1e-3, "{:?}", [x, y, z]);
+        assert!(x.abs() < 1e-3 && y.abs() < 1e-3, "{:?}", [x, y, z]);
+    }
+
     #[test]
     #[cfg_attr(not(feature = "monado"), ignore)]
     fn get_tracker_serial() {
         let mut f = Fixture::new();
         f.load_actions(c"actions.json");
         f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
-        fakexr::add_trackers(f.input.openxr.session_data.get().session.as_raw());
+        fakexr::add_trackers(
+            f.input.openxr.session_data.get().session.as_raw(),
+            &[c"FAKEXR-TRACKER"],
+        );
 
         let frame = || {
             f.input.openxr.poll_events();
@@ -663,4 +1756,251 @@ mod tests {
             .unwrap();
         assert_eq!(serial.to_str().unwrap(), "FAKEXR-SERIAL");
     }
+
+    #[test]
+    #[cfg(feature = "monado")]
+    fn tracker_hotplug_events() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        let session = f.input.openxr.session_data.get().session.as_raw();
+        fakexr::add_trackers(session, &[c"FAKEXR-TRACKER"]);
+
+        let frame = || {
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        // The xdev list is only checked every so often.
+        let poll_trackers = || {
+            for _ in 0..crate::input::TRACKER_POLL_INTERVAL {
+                frame();
+            }
+        };
+        let activated = vr::EVREventType::TrackedDeviceActivated as u32;
+        let deactivated = vr::EVREventType::TrackedDeviceDeactivated as u32;
+        let events = || {
+            let mut event = vr::VREvent_t::default();
+            let mut events = Vec::new();
+            while f
+                .input
+                .get_next_event(std::mem::size_of_val(&event) as u32, &mut event)
+            {
+                if [activated, deactivated].contains(&event.eventType) {
+                    events.push((event.eventType, event.trackedDeviceIndex));
+                }
+            }
+            events
+        };
+
+        frame();
+        frame();
+        assert!(events().contains(&(activated, 2)));
+
+        // A second tracker appears
+        fakexr::add_trackers(session, &[c"FAKEXR-TRACKER", c"FAKEXR-TRACKER"]);
+        poll_trackers();
+        assert_eq!(
+            f.input.device_index_to_tracked_device_class(3),
+            Some(vr::ETrackedDeviceClass::GenericTracker)
+        );
+        assert_eq!(events(), [(activated, 3)]);
+
+        // ...and goes away again, without moving the first one
+        fakexr::add_trackers(session, &[c"FAKEXR-TRACKER"]);
+        poll_trackers();
+        assert_eq!(events(), [(deactivated, 3)]);
+        let serial = f
+            .input
+            .get_device_string_tracked_property(2, vr::ETrackedDeviceProperty::SerialNumber_String)
+            .unwrap();
+        assert_eq!(serial.to_str().unwrap(), "FAKEXR-SERIAL");
+
+        // Nothing changed
+        poll_trackers();
+        assert!(events().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "monado")]
+    fn trackers_with_duplicate_serials() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        let session = f.input.openxr.session_data.get().session.as_raw();
+        fakexr::add_trackers_with_serials(
+            session,
+            &[
+                (c"Tracker A", c"SAME-SERIAL"),
+                (c"Tracker B", c"SAME-SERIAL"),
+            ],
+        );
+
+        let frame = || {
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        frame();
+        frame();
+
+        let serial = |index| {
+            f.input
+                .get_device_string_tracked_property(
+                    index,
+                    vr::ETrackedDeviceProperty::SerialNumber_String,
+                )
+                .unwrap()
+                .into_string()
+                .unwrap()
+        };
+        assert_eq!(serial(2), "SAME-SERIAL");
+        assert_eq!(serial(3), "SAME-SERIAL#2");
+
+        // Losing one of them only disconnects that one.
+        fakexr::add_trackers_with_serials(session, &[(c"Tracker A", c"SAME-SERIAL")]);
+        for _ in 0..crate::input::TRACKER_POLL_INTERVAL {
+            frame();
+        }
+        let devices = f
+            .input
+            .openxr
+            .session_data
+            .get()
+            .input_data
+            .devices
+            .read()
+            .unwrap();
+        assert!(devices.get_device(2).unwrap().connected);
+        assert!(!devices.get_device(3).unwrap().connected);
+    }
+
+    #[test]
+    #[cfg(feature = "monado")]
+    fn tracker_roles() {
+        use crate::input::tracker_role::TrackerRoleOverrides;
+
+        let mut f = Fixture::new();
+        *f.input.tracker_roles.write().unwrap() =
+            TrackerRoleOverrides::parse("FAKEXR-SERIAL-2=right_elbow");
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        fakexr::add_trackers(
+            f.input.openxr.session_data.get().session.as_raw(),
+            &[c"Waist Tracker", c"Left Foot Tracker", c"Generic Tracker"],
+        );
+
+        let frame = || {
+            f.input.openxr.poll_events();
+            f.input.frame_start_update();
+        };
+        frame();
+        frame();
+
+        let get = |index, property| {
+            f.input
+                .get_device_string_tracked_property(index, property)
+                .unwrap()
+                .into_string()
+                .unwrap()
+        };
+        let controller_type = vr::ETrackedDeviceProperty::ControllerType_String;
+        assert_eq!(get(2, controller_type), "vive_tracker_waist");
+        assert_eq!(get(3, controller_type), "vive_tracker_left_foot");
+        assert_eq!(get(4, controller_type), "vive_tracker_right_elbow");
+
+        let registered_type = get(2, vr::ETrackedDeviceProperty::RegisteredDeviceType_String);
+        assert_eq!(registered_type, "htc/vive_trackerFAKEXR-SERIAL");
+        assert_eq!(
+            f.input.get_tracker_role_setting(&registered_type),
+            Some(c"TrackerRole_Waist")
+        );
+        assert_eq!(
+            f.input
+                .get_tracker_role_setting("htc/vive_trackerFAKEXR-SERIAL-1"),
+            Some(c"TrackerRole_LeftFoot")
+        );
+    }
+
+    #[test]
+    fn htcx_vive_trackers() {
+        let mut f = Fixture::new();
+        fakexr::set_vive_trackers(
+            f.input.openxr.instance.as_raw(),
+            &[(
+                "/devices/htc/vive_tracker_htcx/LHR-FAKEXR",
+                "/user/vive_tracker_htcx/role/left_foot",
+            )],
+        );
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: f.get_action_set_handle(c"/actions/set1"),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            f.input.device_index_to_tracked_device_class(2),
+            Some(vr::ETrackedDeviceClass::GenericTracker)
+        );
+        let get = |property| {
+            f.input
+                .get_device_string_tracked_property(2, property)
+                .unwrap()
+                .into_string()
+                .unwrap()
+        };
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::SerialNumber_String),
+            "LHR-FAKEXR"
+        );
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::ControllerType_String),
+            "vive_tracker_left_foot"
+        );
+        assert_eq!(
+            get(vr::ETrackedDeviceProperty::RenderModelName_String),
+            "vr_tracker_vive_3_0"
+        );
+        assert!(
+            f.input
+                .get_device_pose(2, Some(vr::ETrackingUniverseOrigin::Seated))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn htcx_vive_tracker_connected_later() {
+        let mut f = Fixture::new();
+        f.load_actions(c"actions.json");
+        f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: f.get_action_set_handle(c"/actions/set1"),
+            ..Default::default()
+        });
+        assert_eq!(f.input.device_index_to_tracked_device_class(2), None);
+
+        // A tracker that already has a role connecting doesn't change any interaction profiles.
+        fakexr::set_vive_trackers(
+            f.input.openxr.instance.as_raw(),
+            &[(
+                "/devices/htc/vive_tracker_htcx/LHR-FAKEXR",
+                "/user/vive_tracker_htcx/role/waist",
+            )],
+        );
+        f.input.openxr.poll_events();
+        assert_eq!(
+            f.input.device_index_to_tracked_device_class(2),
+            Some(vr::ETrackedDeviceClass::GenericTracker)
+        );
+        assert_eq!(
+            f.input
+                .get_device_string_tracked_property(
+                    2,
+                    vr::ETrackedDeviceProperty::ControllerType_String
+                )
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "vive_tracker_waist"
+        );
+    }
 }
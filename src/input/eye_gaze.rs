@@ -0,0 +1,99 @@
+use super::{Input, SpaceReadGuard};
+use crate::openxr_data::{self, SessionData};
+use log::trace;
+use openvr as vr;
+use openxr as xr;
+use std::sync::RwLock;
+
+pub const EYE_GAZE_INTERACTION_PROFILE: &str = "/interaction_profiles/ext/eye_gaze_interaction";
+const EYES_USER_PATH: &str = "/user/eyes_ext";
+const GAZE_POSE_PATH: &str = "/user/eyes_ext/input/gaze_ext/pose";
+
+/// Gaze from XR_EXT_eye_gaze_interaction. The pose's origin is between the eyes, and it points
+/// along the gaze direction.
+pub(super) struct EyeGazeData {
+    pub action: xr::Action<xr::Posef>,
+    pub user_path: xr::Path,
+    space: RwLock<Option<xr::Space>>,
+}
+
+impl EyeGazeData {
+    /// Creates the gaze action in the given set, and suggests its only binding.
+    pub fn new(instance: &xr::Instance, set: &xr::ActionSet) -> Self {
+        let user_path = instance.string_to_path(EYES_USER_PATH).unwrap();
+        let action = set
+            .create_action("eye-gaze-pose", "Eye Gaze Pose", &[user_path])
+            .unwrap();
+
+        instance
+            .suggest_interaction_profile_bindings(
+                instance
+                    .string_to_path(EYE_GAZE_INTERACTION_PROFILE)
+                    .unwrap(),
+                &[xr::Binding::new(
+                    &action,
+                    instance.string_to_path(GAZE_POSE_PATH).unwrap(),
+                )],
+            )
+            .unwrap();
+
+        Self {
+            action,
+            user_path,
+            space: RwLock::default(),
+        }
+    }
+
+    pub fn try_get_or_init_space(&self, session_data: &SessionData) -> Option<SpaceReadGuard<'_>> {
+        {
+            let space = self.space.read().unwrap();
+            if space.is_some() {
+                return Some(SpaceReadGuard(space));
+            }
+        }
+
+        let space = self
+            .action
+            .create_space(&session_data.session, self.user_path, xr::Posef::IDENTITY)
+            .inspect_err(|e| trace!("Failed to create eye gaze space: {e}"))
+            .ok()?;
+        *self.space.write().unwrap() = Some(space);
+
+        Some(SpaceReadGuard(self.space.read().unwrap()))
+    }
+
+    pub fn reset_space(&self) {
+        *self.space.write().unwrap() = None;
+    }
+}
+
+impl<C: openxr_data::Compositor> Input<C> {
+    /// The eye gaze pose, if the runtime is providing it. The gaze is only exposed through pose
+    /// actions, as SteamVR has no tracked device for it.
+    pub fn get_eye_gaze_pose(
+        &self,
+        origin: Option<vr::ETrackingUniverseOrigin>,
+    ) -> Option<vr::TrackedDevicePose_t> {
+        let session_data = self.openxr.session_data.get();
+        let eye_gaze = session_data.input_data.pose_data.get()?.eye_gaze.as_ref()?;
+        // The runtime only provides the gaze while the eye gaze profile is active.
+        if session_data
+            .session
+            .current_interaction_profile(eye_gaze.user_path)
+            .ok()?
+            == xr::Path::NULL
+        {
+            return None;
+        }
+
+        let origin = origin.unwrap_or(session_data.current_origin);
+        let (location, velocity) = eye_gaze
+            .try_get_or_init_space(&session_data)?
+            .relate(
+                session_data.get_space_for_origin(origin),
+                self.openxr.display_time.get(),
+            )
+            .ok()?;
+        Some(vr::space_relation_to_openvr_pose(location, velocity))
+    }
+}
@@ -9,21 +9,56 @@ use crate::{
 use log::{debug, trace, warn};
 use openvr as vr;
 use openxr as xr;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Default)]
 pub(super) struct LegacyState {
-    packet_num: AtomicU32,
     got_state_this_frame: [AtomicBool; 2],
+    packets: [Mutex<HandPacket>; 2],
+}
+
+/// Like SteamVR, the packet number for a hand only goes up when its reported state changes.
+#[derive(Default)]
+struct HandPacket {
+    num: u32,
+    last_inputs: Option<ControllerInputs>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct ControllerInputs {
+    pressed: u64,
+    touched: u64,
+    axes: [[f32; 2]; 5],
+}
+
+impl ControllerInputs {
+    fn new(state: &vr::VRControllerState_t) -> Self {
+        // VRControllerState_t is packed, so copy the fields out before using them.
+        Self {
+            pressed: state.ulButtonPressed,
+            touched: state.ulButtonTouched,
+            axes: { state.rAxis }.map(|axis| [axis.x, axis.y]),
+        }
+    }
 }
 
 impl LegacyState {
     pub fn on_action_sync(&self) {
-        self.packet_num.fetch_add(1, Ordering::Relaxed);
         for state in &self.got_state_this_frame {
             state.store(false, Ordering::Relaxed);
         }
     }
+
+    fn packet_num(&self, hand: openxr_data::Hand, state: &vr::VRControllerState_t) -> u32 {
+        let inputs = ControllerInputs::new(state);
+        let mut packet = self.packets[hand as usize - 1].lock().unwrap();
+        if packet.last_inputs != Some(inputs) {
+            packet.num = packet.num.wrapping_add(1);
+            packet.last_inputs = Some(inputs);
+        }
+        packet.num
+    }
 }
 
 // Adapted from openvr.h
@@ -64,6 +99,9 @@ impl<C: openxr_data::Compositor> Input<C> {
 
         impl RunWithProfile for Runner<'_> {
             fn run<P: super::InteractionProfile>(&mut self) {
+                if !P::has_required_extensions(self.instance.exts()) {
+                    return;
+                }
                 let conv = super::profiles::InputToXrPath::new(self.instance);
                 let bindings = P::legacy_bindings(&conv);
                 self.instance
@@ -108,7 +146,7 @@ impl<C: openxr_data::Compositor> Input<C> {
             debug!("tried triggering haptic on invalid device index: {device_index}");
             return;
         };
-        let hand_path = self.get_subaction_path(hand);
+        let hand_path = self.get_subaction_path(self.physical_hand(hand));
 
         let data = self.openxr.session_data.get();
         if let Some(manifest_actions) = data.input_data.get_loaded_actions() {
@@ -207,12 +245,10 @@ impl<C: openxr_data::Compositor> Input<C> {
             return false;
         };
 
-        let hand_path = self.get_subaction_path(hand);
+        let hand_path = self.get_subaction_path(self.physical_hand(hand));
 
         let data = self.openxr.session_data.get();
 
-        state.unPacketNum = self.legacy_state.packet_num.load(Ordering::Relaxed);
-
         // Only send the input event if we haven't already.
         let mut events = self.legacy_state.got_state_this_frame[hand as usize - 1]
             .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
@@ -238,7 +274,9 @@ impl<C: openxr_data::Compositor> Input<C> {
                                 vr::EVREventType::ButtonUntouch
                             },
                             index: device_index,
-                            data: vr::VREvent_Controller_t { button: id as u32 },
+                            data: vr::VREvent_Data_t {
+                                controller: vr::VREvent_Controller_t { button: id as u32 },
+                            },
                         });
                     }
                     if click_state.changed_since_last_sync {
@@ -249,7 +287,9 @@ impl<C: openxr_data::Compositor> Input<C> {
                                 vr::EVREventType::ButtonUnpress
                             },
                             index: device_index,
-                            data: vr::VREvent_Controller_t { button: id as u32 },
+                            data: vr::VREvent_Data_t {
+                                controller: vr::VREvent_Controller_t { button: id as u32 },
+                            },
                         });
                     }
                 }
@@ -270,24 +310,48 @@ impl<C: openxr_data::Compositor> Input<C> {
         read_button(vr::EVRButtonId::Grip, &actions.squeeze_click, None);
         read_button(vr::EVRButtonId::Axis2, &actions.squeeze_click, None);
 
+        // The simple controller has no analog inputs, so approximate them with the clicks.
+        let (analog_from_click, has_joystick) = data
+            .input_data
+            .devices
+            .read()
+            .unwrap()
+            .get_controller(self.physical_hand(hand))
+            .and_then(|c| c.profile_data.as_ref())
+            .map_or((false, false), |p| (p.analog_from_click, p.has_joystick()));
+
+        // Controllers with a thumbstick have it bound to the main axis.
         let j = actions.main_xy.state(&data.session, hand_path).unwrap();
-        state.rAxis[0] = vr::VRControllerAxis_t {
-            x: j.current_state.x,
-            y: j.current_state.y,
+        let j = if has_joystick {
+            self.apply_thumbstick_deadzone(j.current_state)
+        } else {
+            j.current_state
+        };
+        state.rAxis[0] = vr::VRControllerAxis_t { x: j.x, y: j.y };
+        let analog_value = |analog: &xr::Action<f32>, click: &xr::Action<bool>| {
+            let value = analog
+                .state(&data.session, hand_path)
+                .unwrap()
+                .current_state;
+            if analog_from_click && click.state(&data.session, hand_path).unwrap().current_state {
+                1.0
+            } else {
+                value
+            }
         };
 
-        let t = actions.trigger.state(&data.session, hand_path).unwrap();
         state.rAxis[1] = vr::VRControllerAxis_t {
-            x: t.current_state,
+            x: analog_value(&actions.trigger, &actions.trigger_click),
             y: 0.0,
         };
 
-        let s = actions.squeeze.state(&data.session, hand_path).unwrap();
         state.rAxis[2] = vr::VRControllerAxis_t {
-            x: s.current_state,
+            x: analog_value(&actions.squeeze, &actions.squeeze_click),
             y: 0.0,
         };
 
+        state.unPacketNum = self.legacy_state.packet_num(hand, state);
+
         true
     }
 }
@@ -420,10 +484,13 @@ impl LegacyActionData {
 
 #[cfg(test)]
 mod tests {
-    use crate::input::profiles::{knuckles::Knuckles, simple_controller::SimpleController};
+    use crate::input::profiles::{
+        InteractionProfile, knuckles::Knuckles, oculus_touch::OculusTouch,
+        simple_controller::SimpleController, vive_controller::ViveWands,
+    };
     use crate::input::tests::{Fixture, compare_pose};
     use crate::openxr_data::Hand;
-    use openvr as vr;
+    use openvr::{self as vr, IVRInput010_Interface};
     use openxr as xr;
 
     #[repr(C)]
@@ -703,6 +770,115 @@ mod tests {
     );
     test_button!(a, vr::EVRButtonId::A);
 
+    #[test]
+    fn packet_num_changes_with_hand_state() {
+        use fakexr::UserPath::*;
+        let mut f = Fixture::new();
+        f.input.openxr.restart_session();
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        f.set_interaction_profile::<Knuckles>(RightHand);
+        f.input.frame_start_update();
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+
+        let trigger_click = f
+            .input
+            .openxr
+            .session_data
+            .get()
+            .input_data
+            .get_legacy_actions()
+            .unwrap()
+            .actions
+            .trigger_click
+            .as_raw();
+
+        let get_packet_num = |device_index| {
+            let mut state = vr::VRControllerState_t::default();
+            assert!(f.input.get_legacy_controller_state(
+                device_index,
+                &mut state,
+                std::mem::size_of_val(&state) as u32
+            ));
+            state.unPacketNum
+        };
+
+        let left = get_packet_num(1);
+        let right = get_packet_num(2);
+
+        // Nothing changed
+        f.input.frame_start_update();
+        assert_eq!(get_packet_num(1), left);
+        assert_eq!(get_packet_num(2), right);
+
+        fakexr::set_action_state(trigger_click, fakexr::ActionState::Bool(true), LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_packet_num(1), left + 1);
+        assert_eq!(get_packet_num(2), right);
+
+        // Reading the same state again shouldn't bump it
+        assert_eq!(get_packet_num(1), left + 1);
+        f.input.frame_start_update();
+        assert_eq!(get_packet_num(1), left + 1);
+
+        fakexr::set_action_state(trigger_click, fakexr::ActionState::Bool(false), LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_packet_num(1), left + 2);
+        assert_eq!(get_packet_num(2), right);
+    }
+
+    #[test]
+    fn thumbstick_deadzone() {
+        use fakexr::UserPath::*;
+        let mut f = Fixture::new();
+        f.input.openxr.restart_session();
+        f.set_interaction_profile::<Knuckles>(LeftHand);
+        f.input.frame_start_update();
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+
+        let main_xy = f
+            .input
+            .openxr
+            .session_data
+            .get()
+            .input_data
+            .get_legacy_actions()
+            .unwrap()
+            .actions
+            .main_xy
+            .as_raw();
+
+        let get_axis = |f: &Fixture, x, y| {
+            fakexr::set_action_state(main_xy, fakexr::ActionState::Vector2(x, y), LeftHand);
+            f.input.frame_start_update();
+            let mut state = vr::VRControllerState_t::default();
+            assert!(f.input.get_legacy_controller_state(
+                1,
+                &mut state,
+                std::mem::size_of_val(&state) as u32
+            ));
+            let axis = { state.rAxis }[0];
+            (axis.x, axis.y)
+        };
+
+        // Off by default
+        assert_eq!(get_axis(&f, 0.1, 0.1), (0.1, 0.1));
+
+        f.input.set_thumbstick_deadzone(0.2);
+        assert_eq!(get_axis(&f, 0.1, 0.1), (0.0, 0.0));
+        // Outside of the deadzone, the stick ramps up from zero
+        let (x, y) = get_axis(&f, 0.6, 0.0);
+        assert!((x - 0.5).abs() < 1e-6, "{x}");
+        assert_eq!(y, 0.0);
+        assert_eq!(get_axis(&f, 1.0, 0.0), (1.0, 0.0));
+
+        // Trackpads don't drift, so they're left alone.
+        f.set_interaction_profile::<ViveWands>(LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_axis(&f, 0.1, 0.1), (0.1, 0.1));
+    }
+
     #[test]
     fn no_legacy_input_with_manifest() {
         let mut f = Fixture::new();
@@ -720,6 +896,7 @@ mod tests {
             &mut state,
             std::mem::size_of_val(&state) as u32
         ));
+        assert!(f.input.IsUsingLegacyInput());
 
         f.load_actions(c"actions.json");
         f.input.openxr.poll_events();
@@ -729,6 +906,169 @@ mod tests {
             &mut state,
             std::mem::size_of_val(&state) as u32
         ));
+        assert!(!f.input.IsUsingLegacyInput());
+    }
+
+    #[test]
+    fn simple_controller_trigger_axis_from_click() {
+        use fakexr::UserPath::*;
+        let mut f = Fixture::new();
+        f.input.openxr.restart_session();
+        f.set_interaction_profile::<SimpleController>(LeftHand);
+        f.input.frame_start_update();
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+
+        let trigger_click = f
+            .input
+            .openxr
+            .session_data
+            .get()
+            .input_data
+            .get_legacy_actions()
+            .unwrap()
+            .actions
+            .trigger_click
+            .as_raw();
+
+        let get_trigger = || {
+            let mut state = vr::VRControllerState_t::default();
+            assert!(f.input.get_legacy_controller_state(
+                1,
+                &mut state,
+                std::mem::size_of_val(&state) as u32
+            ));
+            // Copy the axes out, since VRControllerState_t is packed.
+            let axes = { state.rAxis };
+            axes[1].x
+        };
+
+        assert_eq!(get_trigger(), 0.0);
+
+        fakexr::set_action_state(trigger_click, fakexr::ActionState::Bool(true), LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_trigger(), 1.0);
+
+        fakexr::set_action_state(trigger_click, fakexr::ActionState::Bool(false), LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_trigger(), 0.0);
+    }
+
+    #[test]
+    fn simple_controller_app_menu() {
+        use fakexr::UserPath::*;
+        let mut f = Fixture::new();
+        f.input.openxr.restart_session();
+        f.set_interaction_profile::<SimpleController>(LeftHand);
+        f.input.frame_start_update();
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+
+        let app_menu = f
+            .input
+            .openxr
+            .session_data
+            .get()
+            .input_data
+            .get_legacy_actions()
+            .unwrap()
+            .actions
+            .app_menu
+            .as_raw();
+
+        let profile = f
+            .input
+            .openxr
+            .instance
+            .string_to_path(SimpleController::profile_path())
+            .unwrap();
+        let bindings = fakexr::get_suggested_bindings(app_menu, profile);
+        assert!(
+            bindings.contains(&"/user/hand/left/input/menu/click".to_string()),
+            "{bindings:#?}"
+        );
+
+        let get_pressed = || {
+            let mut state = vr::VRControllerState_t::default();
+            assert!(f.input.get_legacy_controller_state(
+                1,
+                &mut state,
+                std::mem::size_of_val(&state) as u32
+            ));
+            state.ulButtonPressed
+        };
+
+        let menu = super::button_mask_from_id(vr::EVRButtonId::ApplicationMenu);
+        assert_eq!(get_pressed() & menu, 0);
+
+        fakexr::set_action_state(app_menu, fakexr::ActionState::Bool(true), LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_pressed() & menu, menu);
+    }
+
+    #[test]
+    fn touch_b_y_app_menu() {
+        use fakexr::UserPath::*;
+        let mut f = Fixture::new();
+        f.input.openxr.restart_session();
+        f.set_interaction_profile::<OculusTouch>(LeftHand);
+        f.set_interaction_profile::<OculusTouch>(RightHand);
+        f.input.frame_start_update();
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+
+        let (app_menu, a) = {
+            let data = f.input.openxr.session_data.get();
+            let actions = &data.input_data.get_legacy_actions().unwrap().actions;
+            (actions.app_menu.as_raw(), actions.a.as_raw())
+        };
+
+        let profile = f
+            .input
+            .openxr
+            .instance
+            .string_to_path(OculusTouch::profile_path())
+            .unwrap();
+        let bindings = fakexr::get_suggested_bindings(app_menu, profile);
+        for path in [
+            "/user/hand/left/input/y/click",
+            "/user/hand/right/input/b/click",
+        ] {
+            assert!(bindings.contains(&path.to_string()), "{bindings:#?}");
+        }
+        let bindings = fakexr::get_suggested_bindings(a, profile);
+        for path in [
+            "/user/hand/left/input/x/click",
+            "/user/hand/right/input/a/click",
+        ] {
+            assert!(bindings.contains(&path.to_string()), "{bindings:#?}");
+        }
+
+        let get_pressed = |index| {
+            let mut state = vr::VRControllerState_t::default();
+            assert!(f.input.get_legacy_controller_state(
+                index,
+                &mut state,
+                std::mem::size_of_val(&state) as u32
+            ));
+            state.ulButtonPressed
+        };
+
+        let menu = super::button_mask_from_id(vr::EVRButtonId::ApplicationMenu);
+        assert_eq!(get_pressed(2), 0);
+
+        // B
+        fakexr::set_action_state(app_menu, fakexr::ActionState::Bool(true), RightHand);
+        f.input.frame_start_update();
+        assert_eq!(get_pressed(2), menu);
+        assert_eq!(get_pressed(1), 0);
+
+        // Y
+        fakexr::set_action_state(app_menu, fakexr::ActionState::Bool(false), RightHand);
+        fakexr::set_action_state(app_menu, fakexr::ActionState::Bool(true), LeftHand);
+        f.input.frame_start_update();
+        assert_eq!(get_pressed(1), menu);
+        assert_eq!(get_pressed(2), 0);
     }
 
     #[test]
@@ -1,9 +1,11 @@
+pub mod hp_motion_controller;
 pub mod knuckles;
 pub mod oculus_touch;
+pub mod oculus_touch_pro;
+pub mod pico4;
 pub mod simple_controller;
 pub mod vive_controller;
 pub mod vive_focus3;
-#[cfg(feature = "monado")]
 pub mod vive_tracker;
 use super::{
     action_manifest::ControllerType, legacy::LegacyBindings, skeletal::SkeletalInputBindings,
@@ -11,9 +13,12 @@ use super::{
 use crate::input::profiles::typemagic::ContainsPath;
 use crate::openxr_data::Hand;
 use glam::Mat4;
+use hp_motion_controller::HpMotionController;
 use knuckles::Knuckles;
 use oculus_touch::OculusTouch;
+use oculus_touch_pro::OculusTouchPro;
 use openxr as xr;
+use pico4::Pico4;
 use simple_controller::SimpleController;
 use std::ffi::CStr;
 use std::marker::PhantomData;
@@ -34,6 +39,12 @@ pub trait InteractionProfile: SupportedProfile + Sized + 'static {
     fn skeletal_input_bindings(converter: &InputToXrPath<Self>) -> SkeletalInputBindings;
     /// Can be extracted from SteamVR rendermodel files, it is the inverse of the "grip" or "openxr_grip" value
     fn offset_grip_pose(_: Hand) -> Mat4;
+    /// Can be extracted from SteamVR rendermodel files, it is the "gdc2015" component. The Vive
+    /// wands' raw pose already is the GDC 2015 coordinate system, and profiles without the
+    /// component values use the raw pose as well.
+    fn offset_gdc2015_pose(_: Hand) -> Mat4 {
+        Mat4::IDENTITY
+    }
 }
 
 pub(super) trait RunWithProfile {
@@ -51,7 +62,12 @@ impl ControllerType {
                 runner.run::<ViveWands>();
                 runner.run::<SimpleController>();
             }
-            Self::OculusTouch => runner.run::<OculusTouch>(),
+            Self::OculusTouch => {
+                runner.run::<OculusTouch>();
+                runner.run::<OculusTouchPro>();
+                runner.run::<Pico4>();
+                runner.run::<HpMotionController>();
+            }
             Self::Knuckles => runner.run::<Knuckles>(),
             Self::ViveFocus3 => runner.run::<ViveFocus3>(),
             Self::Unknown(_) => {}
@@ -69,7 +85,6 @@ pub trait SupportedProfile {}
 // Vive tracker profile is like a fake profile, and when we're doing something in the context
 // of all profiles, like suggesting bindings, we typically don't want to do it with the
 // tracker profile.
-#[cfg(feature = "monado")]
 impl SupportedProfile for vive_tracker::ViveTracker {}
 
 pub fn run_for_all_profiles(runner: &mut impl RunWithProfile) {
@@ -88,7 +103,10 @@ pub fn run_for_all_profiles(runner: &mut impl RunWithProfile) {
     profile!(ViveWands);
     profile!(Knuckles);
     profile!(OculusTouch);
+    profile!(OculusTouchPro);
     profile!(ViveFocus3);
+    profile!(Pico4);
+    profile!(HpMotionController);
     profile!(SimpleController);
 }
 
@@ -266,6 +284,8 @@ impl<T> Property<T> {
 }
 
 pub struct ProfileProperties {
+    /// A human readable name for the controller, for logs and UIs.
+    pub display_name: &'static str,
     /// Corresponds to Prop_ModelNumber_String
     /// Can be pulled from a SteamVR System Report
     pub model: Property<&'static CStr>,
@@ -275,7 +295,11 @@ pub struct ProfileProperties {
     /// Corresponds to RenderModelName_String
     /// Can be found in SteamVR under resources/rendermodels (some are in driver subdirs)
     pub render_model_name: Property<&'static CStr>,
-    pub main_axis: MainAxisType,
+    /// Whether the controller has a thumbstick. If it does, this is reported as the main axis.
+    pub has_joystick: bool,
+    /// Whether the controller has a trackpad. If it also has a thumbstick, the trackpad is
+    /// reported as Axis3.
+    pub has_trackpad: bool,
     /// Corresponds to Prop_RegisteredDeviceType_String
     pub registered_device_type: Property<&'static CStr>,
     /// Corresponds to Prop_SerialNumber_String
@@ -289,11 +313,6 @@ pub struct ProfileProperties {
     pub legacy_buttons_mask: u64,
 }
 
-pub enum MainAxisType {
-    Thumbstick,
-    Trackpad,
-}
-
 // Some strong typing for representing input paths.
 
 pub struct Left<Sub = (), Comp = ()>(PhantomData<(Sub, Comp)>);
@@ -467,7 +486,9 @@ pub mod paths {
         Value::<f32>,
         Force::<f32>,
         Vec2X::<f32>,
-        Vec2Y::<f32>
+        Vec2Y::<f32>,
+        CurlFb::<f32>,
+        SlideFb::<f32>
     );
 
     impl DynComponent {
@@ -476,6 +497,9 @@ pub mod paths {
                 "click" => Some(Self::Click),
                 "touch" => Some(Self::Touch),
                 "value" | "pull" => Some(Self::Value),
+                "force" => Some(Self::Force),
+                "curl" | "curl_fb" => Some(Self::CurlFb),
+                "slide" | "slide_fb" => Some(Self::SlideFb),
                 _ => None,
             }
         }
@@ -490,6 +514,8 @@ pub mod paths {
                 Self::Force => "force",
                 Self::Vec2X => "x",
                 Self::Vec2Y => "y",
+                Self::CurlFb => "curl_fb",
+                Self::SlideFb => "slide_fb",
             };
 
             f.write_str(s)
@@ -520,7 +546,7 @@ pub mod paths {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                     paste::paste! {
                         let s = match self {
-                            $(Self::$name => stringify!([<$name:lower>]),)+
+                            $(Self::$name => stringify!([<$name:snake>]),)+
                         };
                     }
                     f.write_str(s)
@@ -537,11 +563,12 @@ pub mod paths {
         Y::<Click, Touch>,
         Menu::<Click>,
         Select::<Click>,
-        Trigger::<Click, Value, Touch>,
+        Trigger::<Click, Value, Touch, CurlFb, SlideFb>,
         Squeeze::<Click, Value, Force, Touch>,
         Thumbstick::<Click, Touch, Vec2X, Vec2Y>,
         Trackpad::<Click, Touch, Force, Vec2X, Vec2Y>,
-        Thumbrest::<Touch>
+        Thumbrest::<Touch, Force>,
+        StylusFb::<Force>
     );
 
     // Vec2 impls
@@ -560,8 +587,135 @@ pub mod paths {
                 "grip" => Some(Self::Squeeze),
                 "thumbstick" | "joystick" => Some(Self::Thumbstick),
                 "trackpad" => Some(Self::Trackpad),
+                "thumbrest" => Some(Self::Thumbrest),
+                "stylus" | "stylus_fb" => Some(Self::StylusFb),
                 _ => None,
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::paths::{DynComponent, DynSubpath};
+    use super::*;
+
+    const SUBPATHS: [DynSubpath; 12] = [
+        DynSubpath::A,
+        DynSubpath::B,
+        DynSubpath::X,
+        DynSubpath::Y,
+        DynSubpath::Menu,
+        DynSubpath::Select,
+        DynSubpath::Trigger,
+        DynSubpath::Squeeze,
+        DynSubpath::Thumbstick,
+        DynSubpath::Trackpad,
+        DynSubpath::Thumbrest,
+        DynSubpath::StylusFb,
+    ];
+    const COMPONENTS: [DynComponent; 8] = [
+        DynComponent::Click,
+        DynComponent::Touch,
+        DynComponent::Value,
+        DynComponent::Force,
+        DynComponent::Vec2X,
+        DynComponent::Vec2Y,
+        DynComponent::CurlFb,
+        DynComponent::SlideFb,
+    ];
+
+    #[test]
+    fn subpath_names() {
+        let names: Vec<String> = SUBPATHS.iter().map(ToString::to_string).collect();
+        // Single word subpaths keep the names they had before multi-word ones were added.
+        assert_eq!(
+            names,
+            [
+                "a",
+                "b",
+                "x",
+                "y",
+                "menu",
+                "select",
+                "trigger",
+                "squeeze",
+                "thumbstick",
+                "trackpad",
+                "thumbrest",
+                "stylus_fb"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_vendor_components() {
+        let parse = |s: &str| s.parse::<DynInputPath>().unwrap();
+        let path = parse("/user/hand/left/input/trigger/curl");
+        assert!(
+            path.subpath == DynSubpath::Trigger && path.component == Some(DynComponent::CurlFb)
+        );
+        let path = parse("/user/hand/left/input/trigger/slide_fb");
+        assert!(path.component == Some(DynComponent::SlideFb));
+        let path = parse("/user/hand/right/input/stylus/force");
+        assert!(
+            path.subpath == DynSubpath::StylusFb && path.component == Some(DynComponent::Force)
+        );
+        let path = parse("/user/hand/right/input/grip/force");
+        assert!(path.subpath == DynSubpath::Squeeze && path.component == Some(DynComponent::Force));
+    }
+
+    /// Only the Touch Pro profile has the vendor specific inputs, so every other profile's
+    /// legal paths are the same as they were before those were added.
+    #[test]
+    fn vendor_paths_only_on_touch_pro() {
+        struct Collect(Vec<(&'static str, Vec<String>)>);
+        impl RunWithProfile for Collect {
+            fn run<P: InteractionProfile>(&mut self) {
+                let paths =
+                    [Hand::Left, Hand::Right]
+                        .into_iter()
+                        .flat_map(|hand| {
+                            SUBPATHS.into_iter().flat_map(move |subpath| {
+                                COMPONENTS.into_iter().map(Some).chain([None]).map(
+                                    move |component| DynInputPath {
+                                        hand,
+                                        subpath,
+                                        component,
+                                    },
+                                )
+                            })
+                        })
+                        .filter(|path| P::LegalPaths::is_legal(*path))
+                        .map(|path| path.to_string())
+                        .collect();
+                self.0.push((P::profile_path(), paths));
+            }
+        }
+
+        let mut collect = Collect(Vec::new());
+        run_for_all_profiles(&mut collect);
+        for (profile, paths) in collect.0 {
+            let vendor: Vec<_> = paths.iter().filter(|p| p.contains("_fb")).collect();
+            if profile == OculusTouchPro::profile_path() {
+                assert_eq!(
+                    vendor,
+                    [
+                        "/user/hand/left/input/trigger/curl_fb",
+                        "/user/hand/left/input/trigger/slide_fb",
+                        "/user/hand/left/input/stylus_fb/force",
+                        "/user/hand/right/input/trigger/curl_fb",
+                        "/user/hand/right/input/trigger/slide_fb",
+                        "/user/hand/right/input/stylus_fb/force",
+                    ]
+                );
+            } else {
+                assert!(vendor.is_empty(), "{profile}: {vendor:?}");
+                assert!(
+                    !paths.iter().any(|p| p.contains("thumbrest/force")),
+                    "{profile}: {paths:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use super::{
+    InteractionProfile, Left, ProfileProperties, Property, Right, SkeletalInputBindings,
+    legal_paths, oculus_touch::OculusTouch, paths::*,
+};
+use crate::button_mask_from_ids;
+use crate::input::legacy::{self, LegacyBindings, button_mask_from_id};
+use crate::input::profiles::InputToXrPath;
+use crate::openxr_data::Hand;
+use glam::Mat4;
+
+pub struct HpMotionController;
+
+impl InteractionProfile for HpMotionController {
+    type LegalPaths = legal_paths![
+        Both::<
+            (Menu, Click),
+            (Squeeze, Value),
+            (Trigger, Value),
+            (Thumbstick, ()),
+            (Thumbstick, Click),
+        >,
+        Left::<(X, Click), (Y, Click)>,
+        Right::<(A, Click), (B, Click)>
+    ];
+    fn properties() -> &'static ProfileProperties {
+        use openvr::EVRButtonId::*;
+        static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "HP Reverb G2 Controller",
+            model: Property::PerHand {
+                left: c"HP Reverb G2 Controller (Left)",
+                right: c"HP Reverb G2 Controller (Right)",
+            },
+            // The face buttons and thumbstick match the Touch layout, and games rarely ship
+            // bindings for these, so reuse the Touch bindings.
+            openvr_controller_type: c"oculus_touch",
+            // The generic WMR controller models have a trackpad, which these don't.
+            render_model_name: Property::PerHand {
+                left: c"oculus_rifts_controller_left",
+                right: c"oculus_rifts_controller_right",
+            },
+            registered_device_type: Property::PerHand {
+                left: c"hp/HP_Reverb_G2_Controller_Left",
+                right: c"hp/HP_Reverb_G2_Controller_Right",
+            },
+            serial_number: Property::PerHand {
+                left: c"HP_Reverb_G2_Controller_Left",
+                right: c"HP_Reverb_G2_Controller_Right",
+            },
+            tracking_system_name: c"holographic",
+            manufacturer_name: c"HP",
+            has_joystick: true,
+            has_trackpad: false,
+            legacy_buttons_mask: button_mask_from_ids!(
+                System,
+                ApplicationMenu,
+                Grip,
+                A,
+                Axis0,
+                Axis1,
+                Axis2
+            ),
+        };
+        &DEVICE_PROPERTIES
+    }
+    fn profile_path() -> &'static str {
+        "/interaction_profiles/hp/mixed_reality_controller"
+    }
+    fn has_required_extensions(enabled_extensions: &openxr::ExtensionSet) -> bool {
+        enabled_extensions.ext_hp_mixed_reality_controller
+    }
+
+    fn legacy_bindings(c: &InputToXrPath<Self>) -> LegacyBindings {
+        LegacyBindings {
+            extra: legacy::Bindings {
+                grip_pose: c.pose(),
+            },
+            trigger: c.leftright::<Trigger, Value, _, _>(),
+            trigger_click: c.leftright::<Trigger, Value, _, _>(),
+            // Unlike Touch, both controllers have a dedicated menu button.
+            app_menu: c.leftright::<Menu, Click, _, _>(),
+            a: [
+                c.into::<Left<X, Click>, _>(),
+                c.into::<Right<A, Click>, _>(),
+            ]
+            .concat(),
+            squeeze_click: c.leftright::<Squeeze, Value, _, _>(),
+            squeeze: c.leftright::<Squeeze, Value, _, _>(),
+            main_xy: c.leftright::<Thumbstick, (), _, _>(),
+            main_xy_click: c.leftright::<Thumbstick, Click, _, _>(),
+            main_xy_touch: vec![],
+            haptic: c.haptics(),
+        }
+    }
+
+    fn skeletal_input_bindings(c: &InputToXrPath<Self>) -> SkeletalInputBindings {
+        // No capacitive sensors, so the closest we can get is whether things are pressed.
+        SkeletalInputBindings {
+            thumb_touch: [
+                c.leftright::<Thumbstick, Click, _, _>(),
+                c.into::<Left<X, Click>, _>(),
+                c.into::<Left<Y, Click>, _>(),
+                c.into::<Right<A, Click>, _>(),
+                c.into::<Right<B, Click>, _>(),
+            ]
+            .concat(),
+            index_touch: c.leftright::<Trigger, Value, _, _>(),
+            index_curl: c.leftright::<Trigger, Value, _, _>(),
+            rest_curl: c.leftright::<Squeeze, Value, _, _>(),
+        }
+    }
+
+    fn offset_grip_pose(hand: Hand) -> Mat4 {
+        OculusTouch::offset_grip_pose(hand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HpMotionController, InteractionProfile};
+    use crate::input::tests::Fixture;
+    use openxr as xr;
+
+    #[test]
+    fn verify_bindings() {
+        let f = Fixture::new();
+        f.load_actions(c"actions.json");
+
+        let path = HpMotionController::profile_path();
+        f.verify_bindings::<bool>(
+            path,
+            c"/actions/set1/in/boolact",
+            [
+                "/user/hand/left/input/x/click".into(),
+                "/user/hand/left/input/y/click".into(),
+                "/user/hand/right/input/a/click".into(),
+                "/user/hand/right/input/b/click".into(),
+                "/user/hand/right/input/thumbstick/click".into(),
+                "/user/hand/left/input/menu/click".into(),
+            ],
+        );
+
+        f.verify_bindings::<f32>(
+            path,
+            c"/actions/set1/boolact_asfloat",
+            [
+                "/user/hand/left/input/squeeze/value".into(),
+                "/user/hand/right/input/squeeze/value".into(),
+                "/user/hand/left/input/trigger/value".into(),
+                "/user/hand/right/input/trigger/value".into(),
+            ],
+        );
+
+        f.verify_bindings::<f32>(
+            path,
+            c"/actions/set1/in/vec1act",
+            [
+                "/user/hand/left/input/trigger/value".into(),
+                "/user/hand/right/input/trigger/value".into(),
+            ],
+        );
+
+        f.verify_bindings::<xr::Vector2f>(
+            path,
+            c"/actions/set1/in/vec2act",
+            [
+                "/user/hand/left/input/thumbstick".into(),
+                "/user/hand/right/input/thumbstick".into(),
+            ],
+        );
+
+        f.verify_bindings::<xr::Haptic>(
+            path,
+            c"/actions/set1/in/vib",
+            [
+                "/user/hand/left/output/haptic".into(),
+                "/user/hand/right/output/haptic".into(),
+            ],
+        );
+    }
+}
@@ -1,6 +1,5 @@
 use super::{
-    InteractionProfile, MainAxisType, ProfileProperties, Property, SkeletalInputBindings,
-    legal_paths, paths::*,
+    InteractionProfile, ProfileProperties, Property, SkeletalInputBindings, legal_paths, paths::*,
 };
 use crate::button_mask_from_ids;
 use crate::input::legacy::{self, LegacyBindings, button_mask_from_id};
@@ -41,6 +40,7 @@ impl InteractionProfile for Knuckles {
     }
     fn properties() -> &'static ProfileProperties {
         static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Valve Index Controller",
             model: Property::PerHand {
                 left: c"Knuckles Left",
                 right: c"Knuckles Right",
@@ -50,7 +50,8 @@ impl InteractionProfile for Knuckles {
                 left: c"{indexcontroller}valve_controller_knu_1_0_left",
                 right: c"{indexcontroller}valve_controller_knu_1_0_right",
             },
-            main_axis: MainAxisType::Thumbstick,
+            has_joystick: true,
+            has_trackpad: true,
             registered_device_type: Property::PerHand {
                 left: c"valve/index_controllerLHR-FFFFFFF1",
                 right: c"valve/index_controllerLHR-FFFFFFF2",
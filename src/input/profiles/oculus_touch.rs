@@ -1,6 +1,6 @@
 use super::{
-    InteractionProfile, Left, MainAxisType, ProfileProperties, Property, Right,
-    SkeletalInputBindings, legal_paths, paths::*,
+    InteractionProfile, Left, ProfileProperties, Property, Right, SkeletalInputBindings,
+    legal_paths, paths::*,
 };
 use crate::button_mask_from_ids;
 use crate::input::legacy::{self, LegacyBindings, button_mask_from_id};
@@ -27,6 +27,7 @@ impl InteractionProfile for OculusTouch {
     fn properties() -> &'static ProfileProperties {
         use openvr::EVRButtonId::*;
         static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Oculus Touch Controller",
             model: Property::PerHand {
                 left: c"Oculus Quest2 (Left Controller)",
                 right: c"Oculus Quest2 (Right Controller)",
@@ -46,7 +47,8 @@ impl InteractionProfile for OculusTouch {
             },
             tracking_system_name: c"oculus",
             manufacturer_name: c"Oculus",
-            main_axis: MainAxisType::Thumbstick,
+            has_joystick: true,
+            has_trackpad: false,
             legacy_buttons_mask: button_mask_from_ids!(
                 System,
                 ApplicationMenu,
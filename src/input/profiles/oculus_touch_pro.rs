@@ -0,0 +1,211 @@
+use super::{
+    InteractionProfile, Left, ProfileProperties, Property, Right, SkeletalInputBindings,
+    legal_paths, oculus_touch::OculusTouch, paths::*,
+};
+use crate::button_mask_from_ids;
+use crate::input::legacy::{self, LegacyBindings, button_mask_from_id};
+use crate::input::profiles::{DynInputPath, InputToXrPath};
+use crate::openxr_data::Hand;
+use glam::Mat4;
+
+pub struct OculusTouchPro;
+
+impl InteractionProfile for OculusTouchPro {
+    type LegalPaths = legal_paths![
+        Both::<
+            (Squeeze, Value),
+            (Trigger, Value),
+            (Trigger, Touch),
+            (Trigger, CurlFb),
+            (Trigger, SlideFb),
+            (Thumbstick, ()),
+            (Thumbstick, Click),
+            (Thumbstick, Touch),
+            (Thumbrest, Touch),
+            (Thumbrest, Force),
+            (StylusFb, Force),
+        >,
+        Left::<(X, Click), (X, Touch), (Y, Click), (Y, Touch), (Menu, Click)>,
+        Right::<(A, Click), (A, Touch), (B, Click), (B, Touch)>
+    ];
+    fn properties() -> &'static ProfileProperties {
+        use openvr::EVRButtonId::*;
+        static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Meta Quest Touch Pro Controller",
+            model: Property::PerHand {
+                left: c"Meta Quest Pro (Left Controller)",
+                right: c"Meta Quest Pro (Right Controller)",
+            },
+            // Games only ship bindings for the regular Touch controllers.
+            openvr_controller_type: c"oculus_touch",
+            render_model_name: Property::PerHand {
+                left: c"oculus_quest_pro_controller_left",
+                right: c"oculus_quest_pro_controller_right",
+            },
+            registered_device_type: Property::PerHand {
+                left: c"oculus/1WMHHA000L0000_Controller_Left",
+                right: c"oculus/1WMHHA000R0000_Controller_Right",
+            },
+            serial_number: Property::PerHand {
+                left: c"1WMHHA000L0000_Controller_Left",
+                right: c"1WMHHA000R0000_Controller_Right",
+            },
+            tracking_system_name: c"oculus",
+            manufacturer_name: c"Oculus",
+            has_joystick: true,
+            has_trackpad: false,
+            legacy_buttons_mask: button_mask_from_ids!(
+                System,
+                ApplicationMenu,
+                Grip,
+                A,
+                Axis0,
+                Axis1,
+                Axis2
+            ),
+        };
+        &DEVICE_PROPERTIES
+    }
+    fn profile_path() -> &'static str {
+        "/interaction_profiles/facebook/touch_controller_pro"
+    }
+    fn has_required_extensions(enabled_extensions: &openxr::ExtensionSet) -> bool {
+        enabled_extensions.fb_touch_controller_pro
+    }
+    fn translate_path(path: DynInputPath) -> Option<DynInputPath> {
+        match path {
+            // The stylus tip is a pressure sensor, so treat any analog read as force.
+            path @ DynInputPath {
+                subpath: DynSubpath::StylusFb,
+                component: Some(DynComponent::Value | DynComponent::Click),
+                ..
+            } => Some(path.with_component(DynComponent::Force)),
+            _ => None,
+        }
+    }
+
+    fn legacy_bindings(c: &InputToXrPath<Self>) -> LegacyBindings {
+        LegacyBindings {
+            extra: legacy::Bindings {
+                grip_pose: c.pose(),
+            },
+            trigger: c.leftright::<Trigger, Value, _, _>(),
+            trigger_click: c.leftright::<Trigger, Value, _, _>(),
+            app_menu: [
+                c.into::<Left<Y, Click>, _>(),
+                c.into::<Right<B, Click>, _>(),
+            ]
+            .concat(),
+            a: [
+                c.into::<Left<X, Click>, _>(),
+                c.into::<Right<A, Click>, _>(),
+            ]
+            .concat(),
+            squeeze_click: c.leftright::<Squeeze, Value, _, _>(),
+            squeeze: c.leftright::<Squeeze, Value, _, _>(),
+            main_xy: c.leftright::<Thumbstick, (), _, _>(),
+            main_xy_click: c.leftright::<Thumbstick, Click, _, _>(),
+            main_xy_touch: c.leftright::<Thumbstick, Touch, _, _>(),
+            haptic: c.haptics(),
+        }
+    }
+
+    fn skeletal_input_bindings(c: &InputToXrPath<Self>) -> SkeletalInputBindings {
+        SkeletalInputBindings {
+            thumb_touch: [
+                c.leftright::<Thumbstick, Touch, _, _>(),
+                c.into::<Left<X, Touch>, _>(),
+                c.into::<Left<Y, Touch>, _>(),
+                c.into::<Right<A, Touch>, _>(),
+                c.into::<Right<B, Touch>, _>(),
+                c.leftright::<Thumbrest, Touch, _, _>(),
+            ]
+            .concat(),
+            index_touch: c.leftright::<Trigger, Touch, _, _>(),
+            // The Pro controllers can tell how far the index finger is curled, even off the trigger.
+            index_curl: [
+                c.leftright::<Trigger, Value, _, _>(),
+                c.leftright::<Trigger, CurlFb, _, _>(),
+            ]
+            .concat(),
+            rest_curl: c.leftright::<Squeeze, Value, _, _>(),
+        }
+    }
+
+    fn offset_grip_pose(hand: Hand) -> Mat4 {
+        // The grip pose sits in roughly the same spot as on the regular Touch controllers.
+        OculusTouch::offset_grip_pose(hand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InteractionProfile, OculusTouchPro};
+    use crate::input::tests::Fixture;
+    use openxr as xr;
+
+    #[test]
+    fn verify_bindings() {
+        let f = Fixture::new();
+        f.load_actions(c"actions.json");
+
+        let path = OculusTouchPro::profile_path();
+        f.verify_bindings::<bool>(
+            path,
+            c"/actions/set1/in/boolact",
+            [
+                "/user/hand/left/input/x/click".into(),
+                "/user/hand/left/input/y/click".into(),
+                "/user/hand/right/input/a/click".into(),
+                "/user/hand/right/input/b/click".into(),
+                "/user/hand/right/input/thumbstick/click".into(),
+                "/user/hand/right/input/thumbstick/touch".into(),
+                "/user/hand/left/input/menu/click".into(),
+            ],
+        );
+
+        f.verify_bindings::<f32>(
+            path,
+            c"/actions/set1/in/vec1act",
+            [
+                "/user/hand/left/input/trigger/value".into(),
+                "/user/hand/right/input/trigger/value".into(),
+            ],
+        );
+
+        f.verify_bindings::<xr::Vector2f>(
+            path,
+            c"/actions/set1/in/vec2act",
+            [
+                "/user/hand/left/input/thumbstick".into(),
+                "/user/hand/right/input/thumbstick".into(),
+            ],
+        );
+
+        f.verify_bindings::<xr::Haptic>(
+            path,
+            c"/actions/set1/in/vib",
+            [
+                "/user/hand/left/output/haptic".into(),
+                "/user/hand/right/output/haptic".into(),
+            ],
+        );
+    }
+
+    #[test]
+    fn verify_stylus_bindings() {
+        let f = Fixture::new();
+        f.load_actions(c"actions_touch_pro.json");
+
+        let path = OculusTouchPro::profile_path();
+        f.verify_bindings::<f32>(
+            path,
+            c"/actions/set1/in/vec1act",
+            [
+                "/user/hand/left/input/trigger/value".into(),
+                "/user/hand/left/input/stylus_fb/force".into(),
+                "/user/hand/right/input/stylus_fb/force".into(),
+            ],
+        );
+    }
+}
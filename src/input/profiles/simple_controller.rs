@@ -1,6 +1,6 @@
 use super::{
-    DynInputPath, InteractionProfile, MainAxisType, ProfileProperties, Property,
-    SkeletalInputBindings, legal_paths, paths::*,
+    DynInputPath, InteractionProfile, ProfileProperties, Property, SkeletalInputBindings,
+    legal_paths, paths::*,
 };
 use crate::button_mask_from_ids;
 use crate::input::legacy::{Bindings, LegacyBindings, button_mask_from_id};
@@ -14,10 +14,12 @@ impl InteractionProfile for SimpleController {
     type LegalPaths = legal_paths![Both::<(Select, Click), (Menu, Click)>];
     fn properties() -> &'static ProfileProperties {
         static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Simple Controller",
             model: Property::BothHands(c"generic"),
             openvr_controller_type: c"<unknown>",
             render_model_name: Property::BothHands(c"generic_controller"),
-            main_axis: MainAxisType::Thumbstick,
+            has_joystick: true,
+            has_trackpad: false,
             // TODO: These are just from the vive_controller. I'm not certain whether that's correct here
             registered_device_type: Property::PerHand {
                 left: c"htc/vive_controllerLHR-00000001",
@@ -1,7 +1,4 @@
-use super::{
-    DynInputPath, InteractionProfile, MainAxisType, ProfileProperties, Property,
-    SkeletalInputBindings,
-};
+use super::{DynInputPath, InteractionProfile, ProfileProperties, Property, SkeletalInputBindings};
 use crate::button_mask_from_ids;
 use crate::input::legacy::{self, LegacyBindings, button_mask_from_id};
 use crate::input::profiles::{legal_paths, paths::*};
@@ -28,10 +25,12 @@ impl InteractionProfile for ViveWands {
 
     fn properties() -> &'static ProfileProperties {
         static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Vive Controller",
             model: Property::BothHands(c"Vive. MV"),
             openvr_controller_type: c"vive_controller",
             render_model_name: Property::BothHands(c"vr_controller_vive_1_5"),
-            main_axis: MainAxisType::Trackpad,
+            has_joystick: false,
+            has_trackpad: true,
             registered_device_type: Property::PerHand {
                 left: c"htc/vive_controllerLHR-00000001",
                 right: c"htc/vive_controllerLHR-00000002",
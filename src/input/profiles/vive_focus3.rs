@@ -1,6 +1,5 @@
 use super::{
-    InteractionProfile, MainAxisType, ProfileProperties, Property, SkeletalInputBindings,
-    legal_paths, paths::*,
+    InteractionProfile, ProfileProperties, Property, SkeletalInputBindings, legal_paths, paths::*,
 };
 use crate::button_mask_from_ids;
 use crate::input::legacy::{self, LegacyBindings, button_mask_from_id};
@@ -31,6 +30,7 @@ impl InteractionProfile for ViveFocus3 {
 
     fn properties() -> &'static ProfileProperties {
         static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Vive Focus 3 Controller",
             model: Property::BothHands(c"vive_focus3_controller"),
             openvr_controller_type: c"vive_focus3_controller",
             render_model_name: Property::PerHand {
@@ -46,7 +46,8 @@ impl InteractionProfile for ViveFocus3 {
             },
             tracking_system_name: c"htc_eyes",
             manufacturer_name: c"htc_rr",
-            main_axis: MainAxisType::Thumbstick,
+            has_joystick: true,
+            has_trackpad: false,
             legacy_buttons_mask: button_mask_from_ids!(
                 btn::System,
                 btn::ApplicationMenu,
@@ -1,4 +1,4 @@
-use super::{InteractionProfile, MainAxisType, ProfileProperties, Property, SkeletalInputBindings};
+use super::{InteractionProfile, ProfileProperties, Property, SkeletalInputBindings};
 use crate::{input::legacy::LegacyBindings, openxr_data::Hand};
 use glam::Mat4;
 
@@ -14,10 +14,12 @@ impl InteractionProfile for ViveTracker {
     }
     fn properties() -> &'static ProfileProperties {
         static DEVICE_PROPERTIES: ProfileProperties = ProfileProperties {
+            display_name: "Vive Tracker",
             model: Property::BothHands(c"Vive Tracker Handheld Object"),
             openvr_controller_type: c"vive_tracker_handheld_object",
             render_model_name: Property::BothHands(c"vr_tracker_vive_3_0"),
-            main_axis: MainAxisType::Thumbstick,
+            has_joystick: true,
+            has_trackpad: false,
             registered_device_type: Property::BothHands(c"vive_tracker"),
             serial_number: Property::BothHands(c"vive_tracker"), // This gets replaced
             tracking_system_name: c"lighthouse",
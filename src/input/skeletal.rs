@@ -1,7 +1,7 @@
 #[path = "skeletal_generated.rs"]
 mod generated;
 
-use super::Input;
+use super::{ActionData, Input, devices::HandSkeleton};
 use crate::openxr_data::{self, Hand, SessionData};
 use HandSkeletonBone::*;
 use glam::{Affine3A, Quat, Vec3};
@@ -11,6 +11,7 @@ use openxr::{self as xr};
 use paste::paste;
 use std::cell::RefCell;
 use std::f32::consts::{FRAC_PI_2, PI};
+use std::ffi::c_void;
 use std::time::Instant;
 
 impl<C: openxr_data::Compositor> Input<C> {
@@ -18,6 +19,7 @@ impl<C: openxr_data::Compositor> Input<C> {
         &self,
         session_data: &SessionData,
         space: vr::EVRSkeletalTransformSpace,
+        motion_range: vr::EVRSkeletalMotionRange,
         hand: Hand,
         transforms: &mut [vr::VRBoneTransform_t],
     ) {
@@ -27,7 +29,7 @@ impl<C: openxr_data::Compositor> Input<C> {
         let devices = session_data.input_data.devices.read().unwrap();
 
         let Some(controller) = devices.get_controller(hand) else {
-            self.get_estimated_bones(session_data, space, hand, transforms);
+            self.get_estimated_bones(session_data, space, motion_range, hand, transforms);
             return;
         };
 
@@ -36,12 +38,16 @@ impl<C: openxr_data::Compositor> Input<C> {
             Hand::Right => &pose_data.right_space,
         }
         .try_get_or_init_raw(&controller.profile_data, session_data, pose_data) else {
-            self.get_estimated_bones(session_data, space, hand, transforms);
+            self.get_estimated_bones(session_data, space, motion_range, hand, transforms);
             return;
         };
 
-        let Some(joints) = controller.get_hand_skeleton(&self.openxr, &raw) else {
-            self.get_estimated_bones(session_data, space, hand, transforms);
+        let Some(HandSkeleton {
+            joints,
+            tracking_level,
+        }) = controller.get_hand_skeleton(&self.openxr, &raw)
+        else {
+            self.get_estimated_bones(session_data, space, motion_range, hand, transforms);
             return;
         };
 
@@ -145,6 +151,10 @@ impl<C: openxr_data::Compositor> Input<C> {
             xr_joint_to_vr_bone(&joints[joint], &mut transforms[bone as usize])
         }
 
+        if matches!(motion_range, vr::EVRSkeletalMotionRange::WithController) {
+            limit_to_grip(hand, transforms);
+        }
+
         // Convert back to model space if needed
         // it is unnecessary to convert back and forth, but it works and it's easy
         if space == vr::EVRSkeletalTransformSpace::Model {
@@ -159,7 +169,9 @@ impl<C: openxr_data::Compositor> Input<C> {
             }
         }
 
-        *self.skeletal_tracking_level.write().unwrap() = vr::EVRSkeletalTrackingLevel::Full;
+        session_data
+            .input_data
+            .set_skeletal_tracking_level(hand, tracking_level);
     }
 
     pub(super) fn get_bone_summary_from_hand_tracking(
@@ -186,7 +198,8 @@ impl<C: openxr_data::Compositor> Input<C> {
             return;
         };
 
-        let Some(joints) = controller.get_hand_skeleton(&self.openxr, &raw) else {
+        let Some(HandSkeleton { joints, .. }) = controller.get_hand_skeleton(&self.openxr, &raw)
+        else {
             self.get_estimated_bone_summary(session_data, summary_type, summary_data, hand);
             return;
         };
@@ -203,8 +216,42 @@ impl<C: openxr_data::Compositor> Input<C> {
             })
             .collect();
 
-        // FIXME: calculate splay
-        summary_data.flFingerSplay.fill(0.2);
+        // Splay is the angle between adjacent fingers, as seen from the back of the hand
+        let palm_normal = joints[xr::HandJoint::PALM].1 * Vec3::Y;
+        let finger_direction = |base: xr::HandJoint, next: xr::HandJoint| {
+            let dir = joints[next].0 - joints[base].0;
+            (dir - palm_normal * dir.dot(palm_normal)).normalize_or_zero()
+        };
+        let fingers = [
+            finger_direction(xr::HandJoint::THUMB_PROXIMAL, xr::HandJoint::THUMB_DISTAL),
+            finger_direction(
+                xr::HandJoint::INDEX_PROXIMAL,
+                xr::HandJoint::INDEX_INTERMEDIATE,
+            ),
+            finger_direction(
+                xr::HandJoint::MIDDLE_PROXIMAL,
+                xr::HandJoint::MIDDLE_INTERMEDIATE,
+            ),
+            finger_direction(
+                xr::HandJoint::RING_PROXIMAL,
+                xr::HandJoint::RING_INTERMEDIATE,
+            ),
+            finger_direction(
+                xr::HandJoint::LITTLE_PROXIMAL,
+                xr::HandJoint::LITTLE_INTERMEDIATE,
+            ),
+        ];
+        for (i, out_splay) in summary_data.flFingerSplay.iter_mut().enumerate() {
+            let (a, b) = (fingers[i], fingers[i + 1]);
+            if a == Vec3::ZERO || b == Vec3::ZERO {
+                *out_splay = DEFAULT_SPLAY;
+                continue;
+            }
+            // The thumb can spread much further from the index finger than the fingers can
+            // from each other.
+            let max_splay = if i == 0 { FRAC_PI_2 } else { MAX_FINGER_SPLAY };
+            *out_splay = (a.angle_between(b) / max_splay).clamp(0.0, 1.0);
+        }
 
         for (i, out_curl) in summary_data.flFingerCurl.iter_mut().enumerate() {
             if i == 0
@@ -282,16 +329,28 @@ impl<C: openxr_data::Compositor> Input<C> {
         &self,
         session_data: &SessionData,
         space: vr::EVRSkeletalTransformSpace,
+        motion_range: vr::EVRSkeletalMotionRange,
         hand: Hand,
         transforms: &mut [vr::VRBoneTransform_t],
     ) {
         let finger_state = self.get_finger_state(session_data, hand);
-        let (open, fist) = match hand {
-            Hand::Left => (&generated::left_hand::OPENHAND, &generated::left_hand::FIST),
-            Hand::Right => (
+        let with_controller = matches!(motion_range, vr::EVRSkeletalMotionRange::WithController);
+
+        // Holding a controller, the fingers can only close as far as its grip.
+        let (open, closed) = match (hand, with_controller) {
+            (Hand::Left, false) => (&generated::left_hand::OPENHAND, &generated::left_hand::FIST),
+            (Hand::Left, true) => (
+                &generated::left_hand::OPENHAND,
+                &generated::left_hand::GRIPLIMIT,
+            ),
+            (Hand::Right, false) => (
                 &generated::right_hand::OPENHAND,
                 &generated::right_hand::FIST,
             ),
+            (Hand::Right, true) => (
+                &generated::right_hand::OPENHAND,
+                &generated::right_hand::GRIPLIMIT,
+            ),
         };
 
         const fn constrain<'a, F, G>(f: F) -> F
@@ -304,7 +363,7 @@ impl<C: openxr_data::Compositor> Input<C> {
         let bone_transform_map = constrain(|start_data: &[vr::VRBoneTransform_t], state| {
             move |idx| {
                 let (start_pos, start_rot) = bone_transform_to_glam(start_data[idx]);
-                let (closed_pos, closed_rot) = bone_transform_to_glam(fist[idx]);
+                let (closed_pos, closed_rot) = bone_transform_to_glam(closed[idx]);
 
                 let pos = start_pos.lerp(closed_pos, state);
                 let rot = start_rot.slerp(closed_rot, state);
@@ -322,7 +381,9 @@ impl<C: openxr_data::Compositor> Input<C> {
         });
 
         finalize_transforms(bone_it, space, transforms);
-        *self.skeletal_tracking_level.write().unwrap() = vr::EVRSkeletalTrackingLevel::Estimated;
+        session_data
+            .input_data
+            .set_skeletal_tracking_level(hand, vr::EVRSkeletalTrackingLevel::Estimated);
     }
 
     pub(super) fn get_estimated_bone_summary(
@@ -333,16 +394,20 @@ impl<C: openxr_data::Compositor> Input<C> {
         hand: Hand,
     ) {
         let state = self.get_finger_state(session_data, hand);
-
+        let curl = [
+            state.thumb,
+            state.index,
+            state.middle,
+            state.ring,
+            state.pinky,
+        ];
+
+        // Fingers are pulled together as they curl into a fist
         *summary_data = vr::VRSkeletalSummaryData_t {
-            flFingerSplay: [0.2; 4],
-            flFingerCurl: [
-                state.thumb,
-                state.index,
-                state.middle,
-                state.ring,
-                state.pinky,
-            ],
+            flFingerSplay: std::array::from_fn(|i| {
+                DEFAULT_SPLAY * (1.0 - curl[i].max(curl[i + 1]))
+            }),
+            flFingerCurl: curl,
         };
     }
 
@@ -394,7 +459,7 @@ impl<C: openxr_data::Compositor> Input<C> {
             .lock()
             .unwrap();
 
-        let current_time = std::time::Instant::now();
+        let current_time = crate::clock::now();
 
         let target = FingerState {
             index,
@@ -414,6 +479,47 @@ impl<C: openxr_data::Compositor> Input<C> {
         *state
     }
 
+    pub(super) fn get_compressed_bones(
+        &self,
+        action: vr::VRActionHandle_t,
+        space: vr::EVRSkeletalTransformSpace,
+        motion_range: vr::EVRSkeletalMotionRange,
+        compressed_data: *mut c_void,
+        compressed_size: u32,
+        required_compressed_size: *mut u32,
+    ) -> vr::EVRInputError {
+        let session_data = self.openxr.session_data.get();
+        let Some(loaded) = session_data.input_data.get_loaded_actions() else {
+            return vr::EVRInputError::InvalidHandle;
+        };
+        let hand = match loaded.try_get_action(action) {
+            Ok(ActionData::Skeleton(hand)) => *hand,
+            Ok(_) => return vr::EVRInputError::WrongType,
+            Err(e) => return e,
+        };
+
+        if let Some(required) = unsafe { required_compressed_size.as_mut() } {
+            *required = COMPRESSED_BONES_SIZE as u32;
+        }
+        if compressed_data.is_null() || (compressed_size as usize) < COMPRESSED_BONES_SIZE {
+            return vr::EVRInputError::BufferTooSmall;
+        }
+
+        let mut transforms = [vr::VRBoneTransform_t::default(); HandSkeletonBone::Count as usize];
+        self.get_bones_from_hand_tracking(
+            &session_data,
+            vr::EVRSkeletalTransformSpace::Parent,
+            motion_range,
+            hand,
+            &mut transforms,
+        );
+
+        let data = compress_bones(space, &transforms);
+        let out = unsafe { std::slice::from_raw_parts_mut(compressed_data as *mut u8, data.len()) };
+        out.copy_from_slice(&data);
+        vr::EVRInputError::None
+    }
+
     pub(super) fn get_reference_transforms(
         &self,
         hand: Hand,
@@ -443,6 +549,11 @@ impl<C: openxr_data::Compositor> Input<C> {
     }
 }
 
+/// Splay reported for a relaxed, open hand.
+const DEFAULT_SPLAY: f32 = 0.2;
+/// Angle between two adjacent (non-thumb) fingers that is considered fully splayed.
+const MAX_FINGER_SPLAY: f32 = PI / 9.0;
+
 /// trait alias
 trait PoseIterator: Iterator<Item = (Vec3, Quat)> {}
 impl<T: Iterator<Item = (Vec3, Quat)>> PoseIterator for T {}
@@ -490,6 +601,37 @@ fn parent_to_model_space_bone_data(it: impl PoseIterator) -> impl PoseIterator {
     )
 }
 
+/// Keeps parent space finger bones from bending further away from the open hand than the grip
+/// limit does, since a hand holding a controller can only close around it.
+fn limit_to_grip(hand: Hand, transforms: &mut [vr::VRBoneTransform_t]) {
+    let (open, limit) = match hand {
+        Hand::Left => (
+            &generated::left_hand::OPENHAND,
+            &generated::left_hand::GRIPLIMIT,
+        ),
+        Hand::Right => (
+            &generated::right_hand::OPENHAND,
+            &generated::right_hand::GRIPLIMIT,
+        ),
+    };
+
+    for (_, bone) in JOINTS_TO_BONES[1..].iter().flat_map(|list| list.iter()) {
+        let idx = *bone as usize;
+        let (pos, rot) = bone_transform_to_glam(transforms[idx]);
+        let (_, open_rot) = bone_transform_to_glam(open[idx]);
+        let (_, limit_rot) = bone_transform_to_glam(limit[idx]);
+
+        let angle = open_rot.angle_between(rot);
+        let max_angle = open_rot.angle_between(limit_rot);
+        if angle > max_angle {
+            transforms[idx] = vr::VRBoneTransform_t {
+                position: pos.into(),
+                orientation: open_rot.slerp(rot, max_angle / angle).into(),
+            };
+        }
+    }
+}
+
 fn bone_transform_to_glam(transform: vr::VRBoneTransform_t) -> (Vec3, Quat) {
     let rot = transform.orientation;
     (
@@ -533,6 +675,98 @@ fn finalize_transforms(
     }
 }
 
+/// Compressed skeletons are laid out as a header (magic, transform space, bone count),
+/// followed by each bone's position and orientation quantized to i16s.
+const COMPRESSED_MAGIC: [u8; 4] = *b"XRZB";
+const COMPRESSED_HEADER_SIZE: usize = 10;
+const COMPRESSED_BONE_SIZE: usize = 7 * size_of::<i16>();
+pub(super) const COMPRESSED_BONES_SIZE: usize =
+    COMPRESSED_HEADER_SIZE + COMPRESSED_BONE_SIZE * HandSkeletonBone::Count as usize;
+/// Bone positions are relative to their parent (or the hand), so this is plenty.
+const COMPRESSED_POSITION_RANGE: f32 = 1.0;
+
+fn quantize(value: f32, range: f32) -> [u8; 2] {
+    (((value / range).clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16).to_le_bytes()
+}
+
+fn dequantize(bytes: &[u8], range: f32) -> f32 {
+    i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / i16::MAX as f32 * range
+}
+
+/// Compresses parent space bone transforms, recording the space they should be decompressed to.
+pub(super) fn compress_bones(
+    space: vr::EVRSkeletalTransformSpace,
+    transforms: &[vr::VRBoneTransform_t],
+) -> Vec<u8> {
+    let mut data = Vec::with_capacity(COMPRESSED_BONES_SIZE);
+    data.extend_from_slice(&COMPRESSED_MAGIC);
+    data.extend_from_slice(&(space as u32).to_le_bytes());
+    data.extend_from_slice(&(transforms.len() as u16).to_le_bytes());
+
+    for transform in transforms {
+        let (pos, rot) = bone_transform_to_glam(*transform);
+        let rot = rot.normalize();
+        for v in pos.to_array() {
+            data.extend_from_slice(&quantize(v, COMPRESSED_POSITION_RANGE));
+        }
+        for v in rot.to_array() {
+            data.extend_from_slice(&quantize(v, 1.0));
+        }
+    }
+
+    data
+}
+
+/// Decompresses bones produced by [`compress_bones`] into the given space, or the space recorded
+/// during compression if none is given. Returns the space the bones were written in.
+pub(super) fn decompress_bones(
+    data: &[u8],
+    space: Option<vr::EVRSkeletalTransformSpace>,
+    transforms: &mut [vr::VRBoneTransform_t],
+) -> Result<vr::EVRSkeletalTransformSpace, vr::EVRInputError> {
+    if data.len() < COMPRESSED_HEADER_SIZE || data[..4] != COMPRESSED_MAGIC {
+        debug!("Invalid compressed skeleton data");
+        return Err(vr::EVRInputError::InvalidCompressedData);
+    }
+
+    let stored_space = match u32::from_le_bytes(data[4..8].try_into().unwrap()) {
+        x if x == vr::EVRSkeletalTransformSpace::Model as u32 => {
+            vr::EVRSkeletalTransformSpace::Model
+        }
+        x if x == vr::EVRSkeletalTransformSpace::Parent as u32 => {
+            vr::EVRSkeletalTransformSpace::Parent
+        }
+        _ => return Err(vr::EVRInputError::InvalidCompressedData),
+    };
+    let count = u16::from_le_bytes([data[8], data[9]]) as usize;
+    let bones = &data[COMPRESSED_HEADER_SIZE..];
+    if bones.len() < count * COMPRESSED_BONE_SIZE {
+        return Err(vr::EVRInputError::InvalidCompressedData);
+    }
+    if transforms.len() < count {
+        return Err(vr::EVRInputError::BufferTooSmall);
+    }
+
+    let bone_it = bones
+        .chunks_exact(COMPRESSED_BONE_SIZE)
+        .take(count)
+        .map(|bone| {
+            let mut values = bone.chunks_exact(size_of::<i16>());
+            let mut next = |range| dequantize(values.next().unwrap(), range);
+            let pos = Vec3::new(
+                next(COMPRESSED_POSITION_RANGE),
+                next(COMPRESSED_POSITION_RANGE),
+                next(COMPRESSED_POSITION_RANGE),
+            );
+            let rot = Quat::from_xyzw(next(1.0), next(1.0), next(1.0), next(1.0)).normalize();
+            (pos, rot)
+        });
+
+    let space = space.unwrap_or(stored_space);
+    finalize_transforms(bone_it, space, transforms);
+    Ok(space)
+}
+
 macro_rules! joints_for_finger {
     ($xr_finger:ident, $vr_finger:ident) => {
         paste! {[
@@ -585,7 +819,7 @@ impl FingerState {
             ring: 0.0,
             pinky: 0.0,
             thumb: 0.0,
-            time: Instant::now(),
+            time: crate::clock::now(),
         }
     }
 
@@ -673,6 +907,90 @@ pub(super) enum HandSkeletonBone {
     Count,
 }
 
+/// SteamVR's names for each [`HandSkeletonBone`], with `{h}` standing in for the hand (`l`/`r`).
+static BONE_NAMES: [&str; HandSkeletonBone::Count as usize] = [
+    "root",
+    "wrist_{h}",
+    "finger_thumb_0_{h}",
+    "finger_thumb_1_{h}",
+    "finger_thumb_2_{h}",
+    "finger_thumb_{h}_end",
+    "finger_index_meta_{h}",
+    "finger_index_0_{h}",
+    "finger_index_1_{h}",
+    "finger_index_2_{h}",
+    "finger_index_{h}_end",
+    "finger_middle_meta_{h}",
+    "finger_middle_0_{h}",
+    "finger_middle_1_{h}",
+    "finger_middle_2_{h}",
+    "finger_middle_{h}_end",
+    "finger_ring_meta_{h}",
+    "finger_ring_0_{h}",
+    "finger_ring_1_{h}",
+    "finger_ring_2_{h}",
+    "finger_ring_{h}_end",
+    "finger_pinky_meta_{h}",
+    "finger_pinky_0_{h}",
+    "finger_pinky_1_{h}",
+    "finger_pinky_2_{h}",
+    "finger_pinky_{h}_end",
+    "finger_thumb_{h}_aux",
+    "finger_index_{h}_aux",
+    "finger_middle_{h}_aux",
+    "finger_ring_{h}_aux",
+    "finger_pinky_{h}_aux",
+];
+
+/// The parent of each [`HandSkeletonBone`]. Aux bones hang off the root, like in SteamVR.
+static BONE_PARENTS: [Option<HandSkeletonBone>; HandSkeletonBone::Count as usize] = [
+    None,
+    Some(Root),
+    Some(Wrist),
+    Some(Thumb0),
+    Some(Thumb1),
+    Some(Thumb2),
+    Some(Wrist),
+    Some(IndexFinger0),
+    Some(IndexFinger1),
+    Some(IndexFinger2),
+    Some(IndexFinger3),
+    Some(Wrist),
+    Some(MiddleFinger0),
+    Some(MiddleFinger1),
+    Some(MiddleFinger2),
+    Some(MiddleFinger3),
+    Some(Wrist),
+    Some(RingFinger0),
+    Some(RingFinger1),
+    Some(RingFinger2),
+    Some(RingFinger3),
+    Some(Wrist),
+    Some(PinkyFinger0),
+    Some(PinkyFinger1),
+    Some(PinkyFinger2),
+    Some(PinkyFinger3),
+    Some(Root),
+    Some(Root),
+    Some(Root),
+    Some(Root),
+    Some(Root),
+];
+
+pub(super) fn bone_name(index: usize, hand: Hand) -> Option<String> {
+    let hand = match hand {
+        Hand::Left => "l",
+        Hand::Right => "r",
+    };
+    BONE_NAMES.get(index).map(|name| name.replace("{h}", hand))
+}
+
+pub(super) fn bone_hierarchy() -> impl Iterator<Item = vr::BoneIndex_t> {
+    BONE_PARENTS
+        .iter()
+        .map(|parent| parent.map_or(vr::k_unInvalidBoneIndex, |bone| bone as vr::BoneIndex_t))
+}
+
 macro_rules! skeletal_input_actions {
     ($($field:ident: $ty:ty),+$(,)?) => {
         pub struct SkeletalInputActions {
@@ -6,7 +6,7 @@ use super::{
     },
 };
 use crate::{
-    input::ActionKey,
+    input::{ActionKey, ActionSetKey},
     openxr_data::{FakeCompositor, Hand, OpenXrData},
     vr::{self, IVRInput010_Interface},
 };
@@ -16,7 +16,8 @@ use openxr as xr;
 use slotmap::KeyData;
 use std::collections::HashSet;
 use std::f32::consts::FRAC_PI_4;
-use std::ffi::CStr;
+use std::ffi::{CStr, c_char};
+use std::sync::atomic::Ordering;
 use std::sync::{Arc, Barrier};
 
 static ACTIONS_JSONS_DIR: &CStr = unsafe {
@@ -41,7 +42,7 @@ impl std::fmt::Debug for ActionData {
 pub(super) struct Fixture {
     pub input: Arc<Input<FakeCompositor>>,
     pending_profile_change: bool,
-    _comp: Arc<FakeCompositor>,
+    comp: Arc<FakeCompositor>,
 }
 
 pub(super) trait ActionType: xr::ActionTy {
@@ -76,6 +77,8 @@ pub enum ExtraActionType {
     DpadDirection,
     ToggleAction,
     Double,
+    Scroll,
+    LongPress,
 }
 
 impl Fixture {
@@ -87,7 +90,7 @@ impl Fixture {
         let ret = Self {
             input: Input::new(xr.clone()).into(),
             pending_profile_change: false,
-            _comp: comp,
+            comp,
         };
         xr.input.set(Arc::downgrade(&ret.input));
 
@@ -291,6 +294,8 @@ impl Fixture {
             ExtraActionType::DpadDirection => extras.vector2_action.as_ref()?.as_raw(),
             ExtraActionType::ToggleAction => extras.toggle_action.as_ref()?.as_raw(),
             ExtraActionType::Double => extras.double_action.as_ref()?.as_raw(),
+            ExtraActionType::Scroll => extras.scroll_action.as_ref()?.as_raw(),
+            ExtraActionType::LongPress => extras.long_press_action.as_ref()?.as_raw(),
         })
     }
 
@@ -450,6 +455,184 @@ fn input_state_flow() {
     assert!(state.bChanged);
 }
 
+#[test]
+fn action_set_restricted_to_device() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+
+    f.load_actions(c"actions.json");
+
+    for hand in [LeftHand, RightHand] {
+        fakexr::set_action_state(
+            f.get_action::<bool>(boolact),
+            fakexr::ActionState::Bool(true),
+            hand,
+        );
+    }
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ulRestrictedToDevice: right,
+        ..Default::default()
+    });
+
+    let state = f.get_bool_state_hand(boolact, left).unwrap();
+    assert!(!state.bActive);
+    assert!(!state.bState);
+
+    let state = f.get_bool_state_hand(boolact, right).unwrap();
+    assert!(state.bActive);
+    assert!(state.bState);
+
+    let state = f.get_bool_state(boolact).unwrap();
+    assert!(state.bActive);
+    assert!(state.bState);
+    assert_eq!(state.activeOrigin, right);
+
+    // Lifting the restriction makes the left hand available again
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let state = f.get_bool_state_hand(boolact, left).unwrap();
+    assert!(state.bActive);
+    assert!(state.bState);
+}
+
+#[test]
+fn dominant_hand() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+
+    let get_dominant_hand = |f: &Fixture| {
+        let mut role = vr::ETrackedControllerRole::Invalid;
+        assert_eq!(f.input.GetDominantHand(&mut role), vr::EVRInputError::None);
+        role
+    };
+    assert_eq!(get_dominant_hand(&f), vr::ETrackedControllerRole::RightHand);
+    assert_eq!(
+        f.input.SetDominantHand(vr::ETrackedControllerRole::Invalid),
+        vr::EVRInputError::InvalidParam
+    );
+
+    f.load_actions(c"actions_toggle.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+    f.set_interaction_profile::<Knuckles>(RightHand);
+    let toggle = f
+        .get_extra_action(boolact, ExtraActionType::ToggleAction)
+        .unwrap();
+    for state in [false, true] {
+        for hand in [LeftHand, RightHand] {
+            fakexr::set_action_state(toggle, state.into(), hand);
+        }
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        });
+    }
+
+    // Both hands are toggled on, and with no dominant hand set the left hand wins.
+    let state = f.get_bool_state(boolact).unwrap();
+    assert!(state.bState);
+    assert_eq!(state.activeOrigin, left);
+
+    // Once set, the dominant hand should be reported.
+    assert_eq!(
+        f.input
+            .SetDominantHand(vr::ETrackedControllerRole::RightHand),
+        vr::EVRInputError::None
+    );
+    assert_eq!(get_dominant_hand(&f), vr::ETrackedControllerRole::RightHand);
+    let state = f.get_bool_state(boolact).unwrap();
+    assert!(state.bState);
+    assert_eq!(state.activeOrigin, right);
+
+    assert_eq!(
+        f.input
+            .SetDominantHand(vr::ETrackedControllerRole::LeftHand),
+        vr::EVRInputError::None
+    );
+    assert_eq!(get_dominant_hand(&f), vr::ETrackedControllerRole::LeftHand);
+    let state = f.get_bool_state(boolact).unwrap();
+    assert!(state.bState);
+    assert_eq!(state.activeOrigin, left);
+
+    f.input.openxr.restart_session();
+    assert_eq!(get_dominant_hand(&f), vr::ETrackedControllerRole::LeftHand);
+}
+
+#[test]
+fn swapped_hands() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    let pose_action = f.get_action_handle(c"/actions/set1/in/pose");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+    f.set_interaction_profile::<Knuckles>(RightHand);
+    f.input.set_hands_swapped(true);
+
+    let press_right = |f: &mut Fixture| {
+        fakexr::set_action_state(
+            f.get_action::<bool>(boolact),
+            fakexr::ActionState::Bool(true),
+            RightHand,
+        );
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        });
+    };
+    press_right(&mut f);
+
+    // The right controller drives the left hand
+    let state = f.get_bool_state_hand(boolact, left).unwrap();
+    assert!(state.bState);
+    let state = f.get_bool_state_hand(boolact, right).unwrap();
+    assert!(!state.bState);
+
+    // So does its pose.
+    for (hand, x) in [(LeftHand, -0.5), (RightHand, 0.5)] {
+        let pose = xr::Posef {
+            orientation: xr::Quaternionf::IDENTITY,
+            position: xr::Vector3f { x, y: 0.0, z: 0.0 },
+        };
+        fakexr::set_grip(f.raw_session(), hand, pose);
+        fakexr::set_aim(f.raw_session(), hand, pose);
+    }
+    f.input.openxr.poll_events();
+    f.input.frame_start_update();
+    let pose_x = |handle| {
+        let pose = f.get_pose(pose_action, handle).unwrap().pose;
+        assert!(pose.bPoseIsValid);
+        pose.mDeviceToAbsoluteTracking.m[0][3]
+    };
+    assert!(pose_x(left) > 0.4, "{}", pose_x(left));
+    assert!(pose_x(right) < -0.4, "{}", pose_x(right));
+
+    f.input.openxr.restart_session();
+    assert!(f.input.hands_swapped());
+    press_right(&mut f);
+    let state = f.get_bool_state_hand(boolact, left).unwrap();
+    assert!(state.bState);
+
+    f.input.set_hands_swapped(false);
+    let state = f.get_bool_state_hand(boolact, left).unwrap();
+    assert!(!state.bState);
+}
+
 #[test]
 fn reload_manifest_on_session_restart() {
     let mut f = Fixture::new();
@@ -586,134 +769,888 @@ fn raw_pose_waitgetposes_and_skeletal_pose_identical() {
 }
 
 #[test]
-fn actions_with_bad_paths() {
+fn eye_gaze_pose() {
     let mut f = Fixture::new();
-    let spaces = f.get_action_handle(c"/actions/set1/in/action with spaces");
-    let commas = f.get_action_handle(c"/actions/set1/in/action,with,commas");
-    let mixed = f.get_action_handle(c"/actions/set1/in/mixed, action");
-    let paren = f.get_action_handle(c"/actions/set1/in/(action)(with)(parenthesis)");
-    let brackets = f.get_action_handle(c"/actions/set1/in/[action][with][brackets]");
-    let long_bad1 = f.get_action_handle(c"/actions/set1/in/ThisActionHasAReallyLongNameThatIsMostCertainlyLongerThanTheOpenXRLimit,However,ItWillBeGivenASimpleLocalizedName");
-    let long_bad2 = f.get_action_handle(c"/actions/set1/in/ThisActionWillAlsoHaveAReallyLongNameAndAShortLocalizedName,MuchLikeThePreviousAction");
-    let long_exact = f.get_action_handle(
-        c"/actions/set1/in/this right here is an action that is exactly 64 characters long!",
-    );
-
-    let set1 = f.get_action_set_handle(c"/actions/set1");
-    f.load_actions(c"actions_malformed_paths.json");
-
-    fakexr::set_action_state(
-        f.get_action::<bool>(spaces),
-        fakexr::ActionState::Bool(true),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<f32>(commas),
-        fakexr::ActionState::Float(0.5),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<bool>(mixed),
-        fakexr::ActionState::Bool(true),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<bool>(long_bad1),
-        fakexr::ActionState::Bool(false),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<bool>(long_bad2),
-        fakexr::ActionState::Bool(false),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<bool>(long_exact),
-        fakexr::ActionState::Bool(false),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<bool>(paren),
-        fakexr::ActionState::Bool(false),
-        LeftHand,
-    );
-    fakexr::set_action_state(
-        f.get_action::<bool>(brackets),
-        fakexr::ActionState::Bool(false),
-        LeftHand,
-    );
-    f.sync(vr::VRActiveActionSet_t {
-        ulActionSet: set1,
-        ..Default::default()
-    });
-
-    let s = f.get_bool_state(spaces).unwrap();
-    assert!(s.bActive);
-    assert!(s.bState);
-    assert!(s.bChanged);
-
-    let s = f.get_bool_state(mixed).unwrap();
-    assert!(s.bActive);
-    assert!(s.bState);
-    assert!(s.bChanged);
-
-    let s = f.get_bool_state(paren).unwrap();
-    assert!(s.bActive);
-    assert!(!s.bState);
-    assert!(!s.bChanged);
+    let left_hand = f.get_input_source_handle(c"/user/hand/left");
+    let gaze = f.get_action_handle(c"/actions/set1/in/gaze");
+    f.load_actions(c"actions_eye_gaze.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
 
-    let s = f.get_bool_state(brackets).unwrap();
-    assert!(s.bActive);
-    assert!(!s.bState);
-    assert!(!s.bChanged);
+    let eye_gaze_profile = f
+        .input
+        .openxr
+        .instance
+        .string_to_path(super::EYE_GAZE_INTERACTION_PROFILE)
+        .unwrap();
+    fakexr::set_interaction_profile(f.raw_session(), Eyes, eye_gaze_profile);
 
-    let s = f.get_bool_state(long_bad1).unwrap();
-    assert!(s.bActive);
-    assert!(!s.bState);
-    assert!(!s.bChanged);
+    let rot = Quat::from_rotation_y(FRAC_PI_4);
+    let pose = xr::Posef {
+        position: xr::Vector3f {
+            x: 0.0,
+            y: 1.5,
+            z: 0.0,
+        },
+        orientation: xr::Quaternionf {
+            x: rot.x,
+            y: rot.y,
+            z: rot.z,
+            w: rot.w,
+        },
+    };
+    fakexr::set_gaze(f.raw_session(), pose);
 
-    let s = f.get_bool_state(long_bad2).unwrap();
-    assert!(s.bActive);
-    assert!(!s.bState);
-    assert!(!s.bChanged);
+    for _ in 0..2 {
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+    }
 
-    let s = f.get_bool_state(long_exact).unwrap();
-    assert!(s.bActive);
-    assert!(!s.bState);
-    assert!(!s.bChanged);
+    let data = f.input.openxr.session_data.get();
+    let eye_gaze = data
+        .input_data
+        .pose_data
+        .get()
+        .unwrap()
+        .eye_gaze
+        .as_ref()
+        .expect("eye gaze should be set up");
+    assert_eq!(
+        fakexr::get_suggested_bindings(eye_gaze.action.as_raw(), eye_gaze_profile),
+        vec!["/user/eyes_ext/input/gaze_ext/pose"]
+    );
+    drop(data);
 
-    let mut s = vr::InputAnalogActionData_t::default();
-    let ret = f
+    let seated_origin = vr::ETrackingUniverseOrigin::Seated;
+    let expected = f
         .input
-        .GetAnalogActionData(commas, &mut s, std::mem::size_of_val(&s) as u32, 0);
+        .get_eye_gaze_pose(Some(seated_origin))
+        .expect("eye tracker should be connected");
+    let expected_pose: xr::Posef = expected.mDeviceToAbsoluteTracking.into();
+    assert!((expected_pose.position.y - pose.position.y).abs() < 1e-6);
+
+    let mut gaze_pose = vr::InputPoseActionData_t::default();
+    let ret = f.input.GetPoseActionDataForNextFrame(
+        gaze,
+        seated_origin,
+        &mut gaze_pose,
+        std::mem::size_of_val(&gaze_pose) as u32,
+        0,
+    );
     assert_eq!(ret, vr::EVRInputError::None);
+    assert!(gaze_pose.bActive);
+    compare_pose(
+        expected.mDeviceToAbsoluteTracking.into(),
+        gaze_pose.pose.mDeviceToAbsoluteTracking.into(),
+    );
 
-    assert!(s.bActive);
-    assert_eq!(s.x, 0.5);
+    // The gaze doesn't belong to either hand.
+    let ret = f.input.GetPoseActionDataForNextFrame(
+        gaze,
+        seated_origin,
+        &mut gaze_pose,
+        std::mem::size_of_val(&gaze_pose) as u32,
+        left_hand,
+    );
+    assert_eq!(ret, vr::EVRInputError::None);
+    assert!(!gaze_pose.bActive);
+
+    // The gaze is only available through the action, not as a tracked device.
+    let data = f.input.openxr.session_data.get();
+    let devices = data.input_data.devices.read().unwrap();
+    assert!(devices.iter().all(|device| {
+        matches!(
+            device.get_device_class(),
+            vr::ETrackedDeviceClass::HMD | vr::ETrackedDeviceClass::Controller
+        )
+    }));
 }
 
 #[test]
-fn pose_action_no_restrict() {
+fn controller_velocity_estimated_from_previous_pose() {
     let mut f = Fixture::new();
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
 
-    let set1 = f.get_action_set_handle(c"/actions/set1");
-    let posel = f.get_action_handle(c"/actions/set1/in/posel");
-    let poser = f.get_action_handle(c"/actions/set1/in/poser");
+    let frame = |time: i64| {
+        f.input
+            .openxr
+            .display_time
+            .set(xr::Time::from_nanos(time * 100_000_000));
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+    };
+    let set_pose = |x: f32, angle: f32| {
+        let rot = Quat::from_rotation_y(angle);
+        let pose = xr::Posef {
+            position: xr::Vector3f { x, y: 1.0, z: 0.0 },
+            orientation: xr::Quaternionf {
+                x: rot.x,
+                y: rot.y,
+                z: rot.z,
+                w: rot.w,
+            },
+        };
+        fakexr::set_grip(f.raw_session(), LeftHand, pose);
+        fakexr::set_aim(f.raw_session(), LeftHand, pose);
+    };
+    set_pose(0.0, 0.0);
+    // we need to wait two frames for the controller to be connected.
+    frame(1);
+    frame(2);
 
-    f.load_actions(c"actions.json");
-    f.set_interaction_profile::<SimpleController>(LeftHand);
-    f.set_interaction_profile::<SimpleController>(RightHand);
-    let session = f.input.openxr.session_data.get().session.as_raw();
-    let pose_left = xr::Posef {
-        position: xr::Vector3f {
-            x: 0.5,
-            y: 0.5,
-            z: 0.5,
-        },
-        orientation: xr::Quaternionf::IDENTITY,
+    let get_pose = || {
+        // Locating the controller for another origin in between shouldn't affect the estimate.
+        f.input
+            .get_controller_pose(
+                super::Hand::Left,
+                Some(vr::ETrackingUniverseOrigin::Standing),
+            )
+            .unwrap();
+        let pose = f
+            .input
+            .get_controller_pose(super::Hand::Left, Some(vr::ETrackingUniverseOrigin::Seated))
+            .unwrap();
+        assert!(pose.bPoseIsValid);
+        pose
     };
-    fakexr::set_grip(session, LeftHand, pose_left);
 
+    let pose = get_pose();
+    assert_eq!(pose.vVelocity.v, [0.0; 3]);
+    assert_eq!(pose.vAngularVelocity.v, [0.0; 3]);
+
+    // Move 0.1m to the right over 0.1 seconds.
+    frame(3);
+    set_pose(0.1, 0.0);
+    let pose = get_pose();
+    let [x, y, z] = pose.vVelocity.v;
+    assert!((x - 1.0).abs() < 1e-3, "{:?}", pose.vVelocity.v);
+    assert!(y.abs() < 1e-3 && z.abs() < 1e-3, "{:?}", pose.vVelocity.v);
+    let angular = pose.vAngularVelocity.v;
+    assert!(angular.iter().all(|v| v.abs() < 1e-3), "{angular:?}");
+
+    // Turn 0.1 radians over 0.1 seconds.
+    frame(4);
+    set_pose(0.1, 0.1);
+    let pose = get_pose();
+    let [x, y, z] = pose.vAngularVelocity.v;
+    assert!((y - 1.0).abs() < 1e-3, "{:?}", pose.vAngularVelocity.v);
+    assert!(
+        x.abs() < 1e-3 && z.abs() < 1e-3,
+        "{:?}",
+        pose.vAngularVelocity.v
+    );
+}
+
+#[test]
+fn profile_change_sends_role_changed_event() {
+    let mut f = Fixture::new();
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+
+    let frame = || {
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
+    };
+    let events = || {
+        let mut events = Vec::new();
+        let mut event = vr::VREvent_t::default();
+        while f
+            .input
+            .get_next_event(std::mem::size_of_val(&event) as u32, &mut event)
+        {
+            events.push((event.eventType, event.trackedDeviceIndex));
+        }
+        events
+    };
+
+    frame();
+    frame();
+    let index = f
+        .input
+        .get_controller_device_index(super::Hand::Left)
+        .unwrap();
+    let role_changed = (vr::EVREventType::TrackedDeviceRoleChanged as u32, index);
+    assert!(!events().contains(&role_changed));
+
+    f.set_interaction_profile::<OculusTouch>(LeftHand);
+    frame();
+    frame();
+    assert_eq!(
+        f.input.get_controller_device_index(super::Hand::Left),
+        Some(index)
+    );
+    assert_eq!(events(), [role_changed]);
+}
+
+#[test]
+fn show_bindings_is_noop() {
+    let f = Fixture::new();
+    let set = f.get_action_set_handle(c"/actions/set1");
+    let action = f.get_action_handle(c"/actions/set1/in/boolact");
+    f.load_actions(c"actions.json");
+
+    let mut active = vr::VRActiveActionSet_t {
+        ulActionSet: set,
+        ..Default::default()
+    };
+    assert_eq!(
+        f.input.ShowBindingsForActionSet(
+            &mut active,
+            std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
+            1,
+            0
+        ),
+        vr::EVRInputError::None
+    );
+    assert_eq!(
+        f.input.ShowActionOrigins(set, action),
+        vr::EVRInputError::None
+    );
+}
+
+#[test]
+fn compressed_skeleton_round_trip() {
+    let f = Fixture::new();
+    let skel_handle = f.get_action_handle(c"/actions/set1/in/skellyl");
+    f.load_actions(c"actions.json");
+
+    let count = super::skeletal::HandSkeletonBone::Count as usize;
+    let mut required_size = 0;
+    assert_eq!(
+        f.input.GetSkeletalBoneDataCompressed(
+            skel_handle,
+            vr::EVRSkeletalMotionRange::WithoutController,
+            std::ptr::null_mut(),
+            0,
+            &mut required_size,
+        ),
+        vr::EVRInputError::BufferTooSmall
+    );
+
+    let mut compressed = vec![0u8; required_size as usize];
+    assert_eq!(
+        f.input.GetSkeletalBoneDataCompressed(
+            skel_handle,
+            vr::EVRSkeletalMotionRange::WithoutController,
+            compressed.as_mut_ptr().cast(),
+            compressed.len() as u32,
+            &mut required_size,
+        ),
+        vr::EVRInputError::None
+    );
+
+    for space in [
+        vr::EVRSkeletalTransformSpace::Parent,
+        vr::EVRSkeletalTransformSpace::Model,
+    ] {
+        let mut expected = vec![vr::VRBoneTransform_t::default(); count];
+        assert_eq!(
+            f.input.GetSkeletalBoneData(
+                skel_handle,
+                space,
+                vr::EVRSkeletalMotionRange::WithoutController,
+                expected.as_mut_ptr(),
+                count as u32,
+            ),
+            vr::EVRInputError::None
+        );
+
+        let mut decompressed = vec![vr::VRBoneTransform_t::default(); count];
+        assert_eq!(
+            f.input.DecompressSkeletalBoneData(
+                compressed.as_ptr().cast(),
+                compressed.len() as u32,
+                space,
+                decompressed.as_mut_ptr(),
+                count as u32,
+            ),
+            vr::EVRInputError::None
+        );
+
+        for (idx, (expected, actual)) in expected.iter().zip(&decompressed).enumerate() {
+            let (epos, apos) = (expected.position.v, actual.position.v);
+            let (erot, arot) = (expected.orientation, actual.orientation);
+            let erot = Quat::from_xyzw(erot.x, erot.y, erot.z, erot.w);
+            let arot = Quat::from_xyzw(arot.x, arot.y, arot.z, arot.w);
+            for i in 0..3 {
+                assert!(
+                    (epos[i] - apos[i]).abs() < 0.001,
+                    "bone {idx} position mismatch in {space:?}: {epos:?} vs {apos:?}"
+                );
+            }
+            // q and -q are the same rotation
+            assert!(
+                erot.dot(arot).abs() > 0.9999,
+                "bone {idx} orientation mismatch in {space:?}: {erot:?} vs {arot:?}"
+            );
+        }
+    }
+
+    let mut garbage = vec![0u8; required_size as usize];
+    let mut decompressed = vec![vr::VRBoneTransform_t::default(); count];
+    assert_eq!(
+        f.input.DecompressSkeletalBoneData(
+            garbage.as_mut_ptr().cast(),
+            garbage.len() as u32,
+            vr::EVRSkeletalTransformSpace::Parent,
+            decompressed.as_mut_ptr(),
+            count as u32,
+        ),
+        vr::EVRInputError::InvalidCompressedData
+    );
+}
+
+#[test]
+fn skeletal_summary_follows_trigger() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let skel_handle = f.get_action_handle(c"/actions/set1/in/skellyl");
+    f.load_actions(c"actions.json");
+
+    let index_curl = f
+        .input
+        .openxr
+        .session_data
+        .get()
+        .input_data
+        .estimated_skeleton_actions
+        .get()
+        .unwrap()
+        .actions
+        .index_curl
+        .as_raw();
+
+    let mut get_summary = |trigger| {
+        fakexr::set_action_state(index_curl, fakexr::ActionState::Float(trigger), LeftHand);
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        });
+        // Let the estimated fingers settle
+        crate::clock::advance(std::time::Duration::from_millis(100));
+
+        let mut summary = vr::VRSkeletalSummaryData_t::default();
+        assert_eq!(
+            f.input.GetSkeletalSummaryData(
+                skel_handle,
+                vr::EVRSummaryType::FromDevice,
+                &mut summary
+            ),
+            vr::EVRInputError::None
+        );
+        summary
+    };
+
+    let open = get_summary(0.0);
+    let pulled = get_summary(1.0);
+    assert!(
+        pulled.flFingerCurl[1] > open.flFingerCurl[1],
+        "index curl didn't increase: {} -> {}",
+        open.flFingerCurl[1],
+        pulled.flFingerCurl[1]
+    );
+    assert!(pulled.flFingerSplay[1] < open.flFingerSplay[1]);
+}
+
+#[test]
+fn skeletal_motion_range() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let skel_handle = f.get_action_handle(c"/actions/set1/in/skellyl");
+    f.load_actions(c"actions.json");
+
+    let index_curl = f
+        .input
+        .openxr
+        .session_data
+        .get()
+        .input_data
+        .estimated_skeleton_actions
+        .get()
+        .unwrap()
+        .actions
+        .index_curl
+        .as_raw();
+
+    fakexr::set_action_state(index_curl, fakexr::ActionState::Float(1.0), LeftHand);
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+    // Let the estimated fingers settle
+    crate::clock::advance(std::time::Duration::from_millis(100));
+
+    let count = super::skeletal::HandSkeletonBone::Count as usize;
+    let get_bones = |motion_range| {
+        let mut bones = vec![vr::VRBoneTransform_t::default(); count];
+        assert_eq!(
+            f.input.GetSkeletalBoneData(
+                skel_handle,
+                vr::EVRSkeletalTransformSpace::Parent,
+                motion_range,
+                bones.as_mut_ptr(),
+                count as u32,
+            ),
+            vr::EVRInputError::None
+        );
+        bones
+    };
+
+    let without = get_bones(vr::EVRSkeletalMotionRange::WithoutController);
+    let with = get_bones(vr::EVRSkeletalMotionRange::WithController);
+
+    // A fully pulled trigger curls the index finger into a fist without the controller, but only
+    // wraps it around the trigger with it.
+    let index = super::skeletal::HandSkeletonBone::IndexFinger1 as usize;
+    let rotation = |bone: vr::VRBoneTransform_t| {
+        let q = bone.orientation;
+        Quat::from_xyzw(q.x, q.y, q.z, q.w)
+    };
+    let difference = rotation(without[index]).angle_between(rotation(with[index]));
+    assert!(
+        difference > 0.01,
+        "index finger curl doesn't depend on the motion range"
+    );
+}
+
+#[test]
+fn bone_names_and_hierarchy() {
+    let f = Fixture::new();
+    let skel_left = f.get_action_handle(c"/actions/set1/in/skellyl");
+    let skel_right = f.get_action_handle(c"/actions/set1/in/skellyr");
+    f.load_actions(c"actions.json");
+
+    let mut count = 0;
+    assert_eq!(
+        f.input.GetBoneCount(skel_left, &mut count),
+        vr::EVRInputError::None
+    );
+    assert_eq!(count, 31);
+
+    let get_name = |handle, index| {
+        let mut name = [0 as c_char; 64];
+        assert_eq!(
+            f.input
+                .GetBoneName(handle, index, name.as_mut_ptr(), name.len() as u32),
+            vr::EVRInputError::None
+        );
+        unsafe { CStr::from_ptr(name.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_owned()
+    };
+    assert_eq!(get_name(skel_left, 0), "root");
+    assert_eq!(get_name(skel_right, 1), "wrist_r");
+    assert_eq!(get_name(skel_left, 6), "finger_index_meta_l");
+    assert_eq!(get_name(skel_right, 25), "finger_pinky_r_end");
+    assert_eq!(get_name(skel_right, 30), "finger_pinky_r_aux");
+
+    let mut name = [0 as c_char; 64];
+    assert_eq!(
+        f.input
+            .GetBoneName(skel_left, count as i32, name.as_mut_ptr(), 64),
+        vr::EVRInputError::InvalidBoneIndex
+    );
+    assert_eq!(
+        f.input.GetBoneName(skel_left, 1, name.as_mut_ptr(), 4),
+        vr::EVRInputError::BufferTooSmall
+    );
+
+    let mut parents = vec![0; count as usize];
+    assert_eq!(
+        f.input
+            .GetBoneHierarchy(skel_left, parents.as_mut_ptr(), count),
+        vr::EVRInputError::None
+    );
+    assert_eq!(parents[0], -1);
+    assert_eq!(parents[1], 0);
+    // index finger chain
+    assert_eq!(&parents[6..11], &[1, 6, 7, 8, 9]);
+    assert!(
+        parents[1..]
+            .iter()
+            .all(|parent| (0..count as i32).contains(parent))
+    );
+}
+
+#[test]
+fn skeletal_reference_transforms() {
+    let f = Fixture::new();
+    let skel_left = f.get_action_handle(c"/actions/set1/in/skellyl");
+    let skel_right = f.get_action_handle(c"/actions/set1/in/skellyr");
+    f.load_actions(c"actions.json");
+
+    let count = super::skeletal::HandSkeletonBone::Count as usize;
+    let get_transforms = |handle, space, pose| {
+        let mut transforms = vec![vr::VRBoneTransform_t::default(); count];
+        assert_eq!(
+            f.input.GetSkeletalReferenceTransforms(
+                handle,
+                space,
+                pose,
+                transforms.as_mut_ptr(),
+                count as u32,
+            ),
+            vr::EVRInputError::None
+        );
+        transforms
+    };
+    let assert_pos = |actual: vr::HmdVector4_t, expected: [f32; 3], what: &str| {
+        for i in 0..3 {
+            assert!(
+                (actual.v[i] - expected[i]).abs() < 0.001,
+                "{what}: {:?} vs {expected:?}",
+                actual.v
+            );
+        }
+    };
+
+    // Values from SteamVR's reference poses - the wrist doesn't move between them.
+    for (handle, mirror) in [(skel_left, -1.0), (skel_right, 1.0)] {
+        for pose in [
+            vr::EVRSkeletalReferencePose::BindPose,
+            vr::EVRSkeletalReferencePose::OpenHand,
+            vr::EVRSkeletalReferencePose::Fist,
+            vr::EVRSkeletalReferencePose::GripLimit,
+        ] {
+            for space in [
+                vr::EVRSkeletalTransformSpace::Parent,
+                vr::EVRSkeletalTransformSpace::Model,
+            ] {
+                let wrist = get_transforms(handle, space, pose)[1];
+                let what = format!("{pose:?} {space:?} wrist ({mirror})");
+                assert_pos(wrist.position, [0.03404 * mirror, 0.0365, 0.16472], &what);
+
+                let rot = wrist.orientation;
+                let actual = Quat::from_xyzw(rot.x, rot.y, rot.z, rot.w);
+                let expected =
+                    Quat::from_xyzw(-0.07861, 0.92028 * mirror, -0.3793 * mirror, -0.05515);
+                assert!(actual.dot(expected).abs() > 0.9999, "{what}: {actual:?}");
+            }
+        }
+    }
+
+    // SteamVR's aux bones sit on the distal finger joints in model space, so walking the chain
+    // from the wrist through the metacarpals should land in the same spot.
+    const INDEX_DISTAL: usize = 9;
+    const AUX_INDEX: usize = 27;
+    for handle in [skel_left, skel_right] {
+        for pose in [
+            vr::EVRSkeletalReferencePose::OpenHand,
+            vr::EVRSkeletalReferencePose::Fist,
+        ] {
+            let transforms = get_transforms(handle, vr::EVRSkeletalTransformSpace::Model, pose);
+            let aux = transforms[AUX_INDEX].position.v;
+            assert_pos(
+                transforms[INDEX_DISTAL].position,
+                [aux[0], aux[1], aux[2]],
+                &format!("{pose:?} index distal"),
+            );
+        }
+    }
+}
+
+#[test]
+fn actions_with_bad_paths() {
+    let mut f = Fixture::new();
+    let spaces = f.get_action_handle(c"/actions/set1/in/action with spaces");
+    let commas = f.get_action_handle(c"/actions/set1/in/action,with,commas");
+    let mixed = f.get_action_handle(c"/actions/set1/in/mixed, action");
+    let paren = f.get_action_handle(c"/actions/set1/in/(action)(with)(parenthesis)");
+    let brackets = f.get_action_handle(c"/actions/set1/in/[action][with][brackets]");
+    let long_bad1 = f.get_action_handle(c"/actions/set1/in/ThisActionHasAReallyLongNameThatIsMostCertainlyLongerThanTheOpenXRLimit,However,ItWillBeGivenASimpleLocalizedName");
+    let long_bad2 = f.get_action_handle(c"/actions/set1/in/ThisActionWillAlsoHaveAReallyLongNameAndAShortLocalizedName,MuchLikeThePreviousAction");
+    let long_exact = f.get_action_handle(
+        c"/actions/set1/in/this right here is an action that is exactly 64 characters long!",
+    );
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    f.load_actions(c"actions_malformed_paths.json");
+
+    fakexr::set_action_state(
+        f.get_action::<bool>(spaces),
+        fakexr::ActionState::Bool(true),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<f32>(commas),
+        fakexr::ActionState::Float(0.5),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(mixed),
+        fakexr::ActionState::Bool(true),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(long_bad1),
+        fakexr::ActionState::Bool(false),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(long_bad2),
+        fakexr::ActionState::Bool(false),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(long_exact),
+        fakexr::ActionState::Bool(false),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(paren),
+        fakexr::ActionState::Bool(false),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(brackets),
+        fakexr::ActionState::Bool(false),
+        LeftHand,
+    );
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let s = f.get_bool_state(spaces).unwrap();
+    assert!(s.bActive);
+    assert!(s.bState);
+    assert!(s.bChanged);
+
+    let s = f.get_bool_state(mixed).unwrap();
+    assert!(s.bActive);
+    assert!(s.bState);
+    assert!(s.bChanged);
+
+    let s = f.get_bool_state(paren).unwrap();
+    assert!(s.bActive);
+    assert!(!s.bState);
+    assert!(!s.bChanged);
+
+    let s = f.get_bool_state(brackets).unwrap();
+    assert!(s.bActive);
+    assert!(!s.bState);
+    assert!(!s.bChanged);
+
+    let s = f.get_bool_state(long_bad1).unwrap();
+    assert!(s.bActive);
+    assert!(!s.bState);
+    assert!(!s.bChanged);
+
+    let s = f.get_bool_state(long_bad2).unwrap();
+    assert!(s.bActive);
+    assert!(!s.bState);
+    assert!(!s.bChanged);
+
+    let s = f.get_bool_state(long_exact).unwrap();
+    assert!(s.bActive);
+    assert!(!s.bState);
+    assert!(!s.bChanged);
+
+    let mut s = vr::InputAnalogActionData_t::default();
+    let ret = f
+        .input
+        .GetAnalogActionData(commas, &mut s, std::mem::size_of_val(&s) as u32, 0);
+    assert_eq!(ret, vr::EVRInputError::None);
+
+    assert!(s.bActive);
+    assert_eq!(s.x, 0.5);
+}
+
+#[test]
+fn pose_action_no_restrict() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let posel = f.get_action_handle(c"/actions/set1/in/posel");
+    let poser = f.get_action_handle(c"/actions/set1/in/poser");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<SimpleController>(LeftHand);
+    f.set_interaction_profile::<SimpleController>(RightHand);
+    let session = f.input.openxr.session_data.get().session.as_raw();
+    let pose_left = xr::Posef {
+        position: xr::Vector3f {
+            x: 0.5,
+            y: 0.5,
+            z: 0.5,
+        },
+        orientation: xr::Quaternionf::IDENTITY,
+    };
+    fakexr::set_grip(session, LeftHand, pose_left);
+
+    let pose_right = xr::Posef {
+        position: xr::Vector3f {
+            x: 0.6,
+            y: 0.6,
+            z: 0.6,
+        },
+        orientation: xr::Quaternionf::IDENTITY,
+    };
+    fakexr::set_grip(session, RightHand, pose_right);
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    for (handle, expected) in [(posel, pose_left), (poser, pose_right)] {
+        let actual = f.get_pose(handle, 0).unwrap();
+        assert!(actual.bActive);
+        let p = actual.pose;
+        assert!(p.bPoseIsValid);
+        compare_pose(expected, p.mDeviceToAbsoluteTracking.into());
+    }
+}
+
+#[test]
+fn pose_action_prediction() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let posel = f.get_action_handle(c"/actions/set1/in/posel");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<SimpleController>(LeftHand);
+    fakexr::set_grip_velocity(
+        f.raw_session(),
+        LeftHand,
+        xr::Vector3f {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        },
+    );
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let get_x = |seconds_from_now| {
+        let mut state = vr::InputPoseActionData_t::default();
+        assert_eq!(
+            f.input.GetPoseActionDataRelativeToNow(
+                posel,
+                vr::ETrackingUniverseOrigin::Seated,
+                seconds_from_now,
+                &mut state,
+                std::mem::size_of_val(&state) as u32,
+                0,
+            ),
+            vr::EVRInputError::None
+        );
+        assert!(state.bActive);
+        assert!(state.pose.bPoseIsValid);
+        state.pose.mDeviceToAbsoluteTracking.m[0][3]
+    };
+
+    let next_frame = f
+        .get_pose(posel, 0)
+        .unwrap()
+        .pose
+        .mDeviceToAbsoluteTracking
+        .m[0][3];
+
+    // Predictions are relative to the runtime's current time, not the display time.
+    let display_time = f.input.openxr.display_time.get();
+    fakexr::set_now(
+        f.input.openxr.instance.as_raw(),
+        xr::Time::from_nanos(display_time.as_nanos() - 20_000_000),
+    );
+
+    // The controller moves 1m/s along x, so it should be 2cm short of the display time pose now.
+    let now = get_x(0.0);
+    assert!((next_frame - now - 0.02).abs() < 1e-3, "{now} {next_frame}");
+    let predicted = get_x(0.02);
+    assert!(
+        (predicted - next_frame).abs() < 1e-3,
+        "{next_frame} {predicted}"
+    );
+    let past = get_x(-0.05);
+    assert!((now - past - 0.05).abs() < 1e-3, "{now} {past}");
+    let far = get_x(1.0);
+    assert!((far - now - 1.0).abs() < 1e-3, "{now} {far}");
+
+    // Out of range offsets are clamped, and NaN is treated as no offset.
+    assert_eq!(get_x(f32::INFINITY), far);
+    let far_past = get_x(f32::NEG_INFINITY);
+    assert!((now - far_past - 1.0).abs() < 1e-3, "{now} {far_past}");
+    assert_eq!(get_x(f32::NAN), now);
+
+    // The next frame's pose stays at the display time.
+    assert_eq!(
+        f.get_pose(posel, 0)
+            .unwrap()
+            .pose
+            .mDeviceToAbsoluteTracking
+            .m[0][3],
+        next_frame
+    );
+}
+
+#[test]
+fn pose_action_without_location() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let posel = f.get_action_handle(c"/actions/set1/in/posel");
+    let poser = f.get_action_handle(c"/actions/set1/in/poser");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<SimpleController>(LeftHand);
+    let session = f.input.openxr.session_data.get().session.as_raw();
+    fakexr::set_tracked(session, LeftHand, false);
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    // The controller is there, but its space can't be located.
+    let actual = f.get_pose(posel, 0).unwrap();
+    assert!(actual.bActive);
+    assert!(!actual.pose.bPoseIsValid);
+    assert_eq!(
+        actual.pose.eTrackingResult,
+        vr::ETrackingResult::Uninitialized
+    );
+    assert_eq!(actual.pose.mDeviceToAbsoluteTracking.m, [[0.0; 4]; 3]);
+
+    // There's no right controller at all.
+    let actual = f.get_pose(poser, 0).unwrap();
+    assert!(!actual.bActive);
+    assert!(!actual.pose.bPoseIsValid);
+    assert_eq!(
+        actual.pose.eTrackingResult,
+        vr::ETrackingResult::Uninitialized
+    );
+
+    // Once it's tracked again, the pose is valid.
+    fakexr::set_tracked(session, LeftHand, true);
+    f.input
+        .openxr
+        .session_data
+        .get()
+        .input_data
+        .clear_pose_caches();
+    let actual = f.get_pose(posel, 0).unwrap();
+    assert!(actual.bActive);
+    assert!(actual.pose.bPoseIsValid);
+}
+
+#[test]
+fn pose_action_restricted_to_unbound_hand() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let poser = f.get_action_handle(c"/actions/set1/in/poser");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<SimpleController>(RightHand);
+    let session = f.input.openxr.session_data.get().session.as_raw();
     let pose_right = xr::Posef {
         position: xr::Vector3f {
             x: 0.6,
@@ -722,366 +1659,1463 @@ fn pose_action_no_restrict() {
         },
         orientation: xr::Quaternionf::IDENTITY,
     };
-    fakexr::set_grip(session, RightHand, pose_right);
+    fakexr::set_grip(session, RightHand, pose_right);
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let actual = f.get_pose(poser, right).unwrap();
+    assert!(actual.bActive);
+    compare_pose(pose_right, actual.pose.mDeviceToAbsoluteTracking.into());
+
+    // The left hand has no profile, so it shouldn't borrow the right hand's pose.
+    let actual = f.get_pose(poser, left).unwrap();
+    assert!(!actual.bActive);
+    assert!(!actual.pose.bPoseIsValid);
+}
+
+#[test]
+fn raw_pose_switch_profile() {
+    let mut f = Fixture::new();
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let posel = f.get_action_handle(c"/actions/set1/in/posel");
+    let poser = f.get_action_handle(c"/actions/set1/in/poser");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<SimpleController>(LeftHand);
+    f.set_interaction_profile::<SimpleController>(RightHand);
+    let session = f.input.openxr.session_data.get().session.as_raw();
+    let pose_left = xr::Posef {
+        position: xr::Vector3f {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        orientation: xr::Quaternionf::IDENTITY,
+    };
+    fakexr::set_grip(session, LeftHand, pose_left);
+
+    let pose_right = xr::Posef {
+        position: xr::Vector3f {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        orientation: xr::Quaternionf::IDENTITY,
+    };
+    fakexr::set_grip(session, RightHand, pose_right);
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    fn offset_to_pose(offset: &Mat4) -> xr::Posef {
+        let translation = offset.w_axis.truncate();
+        let rotation = Quat::from_mat4(offset);
+
+        xr::Posef {
+            orientation: xr::Quaternionf {
+                x: rotation.x,
+                y: rotation.y,
+                z: rotation.z,
+                w: rotation.w,
+            },
+            position: xr::Vector3f {
+                x: translation.x,
+                y: translation.y,
+                z: translation.z,
+            },
+        }
+    }
+
+    for (handle, hand) in [(posel, Hand::Left), (poser, Hand::Right)] {
+        let expected = &SimpleController::offset_grip_pose(hand);
+        let actual = f.get_pose(handle, 0).unwrap();
+        assert!(actual.bActive, "{hand:?} not active");
+        let p = actual.pose;
+        assert!(p.bPoseIsValid, "{hand:?} invalid pose");
+        compare_pose(offset_to_pose(expected), p.mDeviceToAbsoluteTracking.into());
+    }
+
+    f.set_interaction_profile::<OculusTouch>(LeftHand);
+    f.set_interaction_profile::<OculusTouch>(RightHand);
+
+    // Cached poses don't reset until next frame
+    f.input.openxr.poll_events();
+    f.input.frame_start_update();
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    for (handle, expected) in [
+        (posel, &OculusTouch::offset_grip_pose(Hand::Left)),
+        (poser, &OculusTouch::offset_grip_pose(Hand::Right)),
+    ] {
+        let actual = f.get_pose(handle, 0).unwrap();
+        assert!(actual.bActive);
+        let p = actual.pose;
+        assert!(p.bPoseIsValid);
+        compare_pose(offset_to_pose(expected), p.mDeviceToAbsoluteTracking.into());
+    }
+}
+
+#[test]
+fn windows_style_binding_url() {
+    let f = Fixture::new();
+    f.load_actions(c"actions_windows_paths.json");
+
+    f.verify_bindings::<xr::Haptic>(
+        ViveWands::profile_path(),
+        c"/actions/set1/in/vib",
+        [
+            "/user/hand/left/output/haptic".into(),
+            "/user/hand/right/output/haptic".into(),
+        ],
+    );
+}
+
+#[test]
+fn case_colliding_actions() {
+    let f = Fixture::new();
+    let upper = f.get_action_handle(c"/actions/set1/in/BoolAct");
+    let lower = f.get_action_handle(c"/actions/set1/in/boolact");
+    assert_eq!(upper, lower);
+
+    f.load_actions(c"actions_case_collision.json");
+
+    // The first declaration wins, so the action stays a boolean.
+    f.verify_bindings::<bool>(
+        ViveWands::profile_path(),
+        c"/actions/set1/in/boolact",
+        ["/user/hand/left/input/squeeze/click".into()],
+    );
+}
+
+#[test]
+fn cased_actions() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    f.load_actions(c"actions_cased.json");
+
+    let path = ViveWands::profile_path();
+    f.verify_bindings::<bool>(
+        path,
+        c"/actions/set1/in/BoolAct",
+        ["/user/hand/left/input/squeeze/click".into()],
+    );
+    f.verify_bindings::<f32>(
+        path,
+        c"/actions/set1/in/Vec1Act",
+        ["/user/hand/left/input/trigger/value".into()],
+    );
+    f.verify_bindings::<xr::Vector2f>(
+        path,
+        c"/actions/set1/in/Vec2Act",
+        ["/user/hand/left/input/trackpad".into()],
+    );
+    f.verify_bindings::<xr::Haptic>(
+        path,
+        c"/actions/set1/in/VibAct",
+        ["/user/hand/left/output/haptic".into()],
+    );
+
+    f.set_interaction_profile::<ViveWands>(LeftHand);
+    let session = f.input.openxr.session_data.get().session.as_raw();
+    fakexr::set_grip(session, LeftHand, xr::Posef::IDENTITY);
+    fakexr::set_aim(session, LeftHand, xr::Posef::IDENTITY);
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let poseact = f.get_action_handle(c"/actions/set1/in/PoseAct");
+    let pose = f.get_pose(poseact, 0).unwrap();
+    assert!(pose.bActive);
+    assert!(pose.pose.bPoseIsValid);
+
+    let skelact = f.get_action_handle(c"/actions/set1/in/SkelAct");
+    let pose = f.get_pose(skelact, 0).unwrap();
+    assert!(pose.bActive);
+    assert!(pose.pose.bPoseIsValid);
+}
+
+#[test]
+fn digital_action_initalize_on_failure() {
+    let f = Fixture::new();
+    f.load_actions(c"actions.json");
+    let bad_state = vr::InputDigitalActionData_t {
+        bActive: true,
+        bState: true,
+        activeOrigin: 12345,
+        bChanged: true,
+        fUpdateTime: -999.0,
+    };
+
+    let mut state = bad_state;
+    let bad_handle = 3434343343;
+    assert_ne!(
+        f.input.GetDigitalActionData(
+            bad_handle,
+            &mut state,
+            std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
+            0
+        ),
+        vr::EVRInputError::None
+    );
+    assert!(!state.bActive);
+    assert!(!state.bState);
+    assert_eq!(state.activeOrigin, 0);
+    assert!(!state.bChanged);
+    assert_eq!(state.fUpdateTime, 0.0);
+
+    let mut state = bad_state;
+    let vecact = f.get_action_handle(c"/actions/set1/in/vec1act");
+    assert_ne!(
+        f.input.GetDigitalActionData(
+            vecact,
+            &mut state,
+            std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
+            0
+        ),
+        vr::EVRInputError::None
+    );
+
+    assert!(!state.bActive);
+    assert!(!state.bState);
+    assert_eq!(state.activeOrigin, 0);
+    assert!(!state.bChanged);
+    assert_eq!(state.fUpdateTime, 0.0);
+}
+
+#[test]
+fn analog_action_initialize_on_failure() {
+    let f = Fixture::new();
+    f.load_actions(c"actions.json");
+
+    let bad_state = vr::InputAnalogActionData_t {
+        bActive: true,
+        activeOrigin: 12345,
+        x: 300.0,
+        y: 600.0,
+        z: 900.0,
+        deltaX: 1000.0,
+        deltaY: 3000.0,
+        deltaZ: -999.999,
+        fUpdateTime: 395.0,
+    };
+
+    let check_state = |state: vr::InputAnalogActionData_t, desc: &str| {
+        assert!(!state.bActive, "{desc}");
+        assert_eq!(state.activeOrigin, 0, "{desc}");
+        assert_eq!(state.x, 0.0, "{desc}");
+        assert_eq!(state.deltaX, 0.0, "{desc}");
+        assert_eq!(state.y, 0.0, "{desc}");
+        assert_eq!(state.deltaY, 0.0, "{desc}");
+        assert_eq!(state.z, 0.0, "{desc}");
+        assert_eq!(state.deltaZ, 0.0, "{desc}");
+        assert_eq!(state.fUpdateTime, 0.0, "{desc}");
+    };
+
+    let mut state = bad_state;
+    let bad_handle = 3434343343;
+    assert_ne!(
+        f.input.GetAnalogActionData(
+            bad_handle,
+            &mut state,
+            std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
+            0
+        ),
+        vr::EVRInputError::None
+    );
+    check_state(state, "bad handle");
+
+    let mut state = bad_state;
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    assert_ne!(
+        f.input.GetAnalogActionData(
+            boolact,
+            &mut state,
+            std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
+            0
+        ),
+        vr::EVRInputError::None
+    );
+    check_state(state, "wrong type");
+}
+
+#[test]
+fn implicit_action_sets() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    f.load_actions(c"actions_missing_sets.json");
+
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let res = f.get_bool_state(boolact);
+    assert!(res.is_ok(), "{res:?}");
+}
+
+#[test]
+fn detect_controller_after_manifest_load() {
+    let mut f = Fixture::new();
+    f.load_actions(c"actions.json");
+
+    let input = f.input.clone();
+    let frame = || {
+        input.openxr.poll_events();
+        input.frame_start_update();
+    };
+
+    frame();
+    assert!(f.input.get_controller_device_index(Hand::Left).is_none());
+
+    f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
+    frame();
+    // Profile won't be set for this frame - we call sync after events have already been polled
+    assert!(f.input.get_controller_device_index(Hand::Left).is_none());
+
+    frame();
+    let index = f.input.get_controller_device_index(Hand::Left);
+    assert!(index.is_some_and(|i| f.input.is_device_connected(i)));
+}
+
+#[test]
+fn empty_manifest() {
+    let f = Fixture::new();
+    f.input
+        .SetActionManifestPath(c"empty_manifest.json".as_ptr() as _);
+
+    f.input.openxr.restart_session();
+    assert!(f.input.action_map.read().unwrap().is_empty());
+}
+
+#[test]
+fn load_actions_race() {
+    let mut f = Fixture::new();
+    f.input.openxr.restart_session(); // get to real session
+
+    f.set_interaction_profile::<OculusTouch>(LeftHand);
+    f.set_interaction_profile::<OculusTouch>(RightHand);
+
+    let mut f = Arc::new(f);
+    f.input.frame_start_update(); // load legacy
+    f.input.openxr.poll_events();
+    let got_input = f.input.get_legacy_controller_state(
+        1,
+        &mut vr::VRControllerState_t::default(),
+        std::mem::size_of::<vr::VRControllerState_t>() as _,
+    );
+    assert!(got_input);
+
+    let restarting = Arc::new(Barrier::new(2));
+    f.comp.set_restart_barrier(restarting.clone());
+    std::thread::scope(|scope| {
+        {
+            let input = f.input.clone();
+            scope.spawn(move || {
+                // Wait for the restart to start, so we get the frame start update right after it
+                restarting.wait();
+                input.frame_start_update();
+            });
+        }
+        {
+            let f = f.clone();
+            scope.spawn(move || {
+                f.load_actions(c"actions.json");
+            });
+        }
+    });
+
+    let got_input = f.input.get_legacy_controller_state(
+        0,
+        &mut vr::VRControllerState_t::default(),
+        std::mem::size_of::<vr::VRControllerState_t>() as _,
+    );
+    assert!(!got_input);
+
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    Arc::get_mut(&mut f).unwrap().sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+
+    let res = f.get_bool_state(boolact);
+    assert!(res.is_ok(), "{res:?}");
+}
+
+#[test]
+fn action_origins() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let set2 = f.get_action_set_handle(c"/actions/set2");
+    let pose = f.get_action_handle(c"/actions/set1/in/pose");
+    let posel = f.get_action_handle(c"/actions/set1/in/posel");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<OculusTouch>(LeftHand);
+    f.set_interaction_profile::<OculusTouch>(RightHand);
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+    f.input.frame_start_update();
+
+    let get_origins = |set, action| {
+        let mut origins = [vr::k_ulInvalidInputValueHandle; 3];
+        let ret = f
+            .input
+            .GetActionOrigins(set, action, origins.as_mut_ptr(), origins.len() as u32);
+        (ret, origins)
+    };
+
+    assert_eq!(
+        get_origins(set1, pose),
+        (vr::EVRInputError::None, [left, right, 0])
+    );
+    assert_eq!(
+        get_origins(set1, posel),
+        (vr::EVRInputError::None, [left, 0, 0])
+    );
+    assert_eq!(get_origins(set2, pose).0, vr::EVRInputError::InvalidHandle);
+}
+
+#[test]
+fn origin_localized_name() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+    let left_trigger = f.get_input_source_handle(c"/user/hand/left/input/trigger");
+
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+    f.input.frame_start_update();
+
+    let get_name = |origin, sections, size: usize| {
+        let mut buf = vec![0 as std::ffi::c_char; size];
+        let ret = f
+            .input
+            .GetOriginLocalizedName(origin, buf.as_mut_ptr(), size as u32, sections);
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        (ret, name)
+    };
+
+    let ok = |name: &str| (vr::EVRInputError::None, name.to_string());
+    assert_eq!(get_name(left, 0x01, 64), ok("Left Hand"));
+    assert_eq!(get_name(right, 0x01, 64), ok("Right Hand"));
+    assert_eq!(
+        get_name(left, 0x01 | 0x02, 64),
+        ok("Left Hand Valve Index Controller")
+    );
+    // No controller is connected on the right hand
+    assert_eq!(get_name(right, 0x01 | 0x02, 64), ok("Right Hand"));
+    assert_eq!(get_name(left_trigger, 0x04, 64), ok("trigger"));
+    assert_eq!(
+        get_name(left_trigger, -1, 64),
+        ok("Left Hand Valve Index Controller trigger")
+    );
+    assert_eq!(
+        get_name(left, 0x01, 5),
+        (vr::EVRInputError::BufferTooSmall, "Left".to_string())
+    );
+
+    // With swapped hands, the application's right hand is the left controller.
+    f.input.set_hands_swapped(true);
+    assert_eq!(
+        get_name(right, 0x01 | 0x02, 64),
+        ok("Left Hand Valve Index Controller")
+    );
+    assert_eq!(get_name(left, 0x01 | 0x02, 64), ok("Right Hand"));
+}
+
+#[test]
+fn action_binding_info() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+
+    f.load_actions(c"actions_dpad.json");
+    f.set_interaction_profile::<ViveWands>(LeftHand);
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+    f.input.frame_start_update();
+
+    let mut infos = [vr::InputBindingInfo_t {
+        rchDevicePathName: [0; 128],
+        rchInputPathName: [0; 128],
+        rchModeName: [0; 128],
+        rchSlotName: [0; 128],
+        rchInputSourceType: [0; 32],
+    }; 2];
+    let mut count = 0;
+    assert_eq!(
+        f.input.GetActionBindingInfo(
+            boolact,
+            infos.as_mut_ptr(),
+            std::mem::size_of::<vr::InputBindingInfo_t>() as u32,
+            infos.len() as u32,
+            &mut count,
+        ),
+        vr::EVRInputError::None
+    );
+    assert_eq!(count, 1);
+
+    let to_str = |s: &[std::ffi::c_char]| {
+        unsafe { CStr::from_ptr(s.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    };
+    let info = &infos[0];
+    assert_eq!(to_str(&info.rchDevicePathName), "/user/hand/left");
+    assert_eq!(to_str(&info.rchInputPathName), "/input/trackpad");
+    assert_eq!(to_str(&info.rchModeName), "dpad");
+    assert_eq!(to_str(&info.rchSlotName), "north");
+    assert_eq!(to_str(&info.rchInputSourceType), "trackpad");
+}
+
+#[test]
+fn component_state_for_binding() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+
+    fn to_array<const N: usize>(s: &str) -> [c_char; N] {
+        let mut ret = [0; N];
+        for (c, b) in ret.iter_mut().zip(s.bytes()) {
+            *c = b as c_char;
+        }
+        ret
+    }
+    let binding = |input, slot| vr::InputBindingInfo_t {
+        rchDevicePathName: to_array("/user/hand/left"),
+        rchInputPathName: to_array(input),
+        rchModeName: to_array("button"),
+        rchSlotName: to_array(slot),
+        rchInputSourceType: to_array("button"),
+    };
+    let a_click = binding("/input/a", "click");
+    let trigger_touch = binding("/input/trigger", "touch");
+
+    fn get_component_properties(
+        f: &Fixture,
+        component: &CStr,
+        infos: &[vr::InputBindingInfo_t],
+    ) -> u32 {
+        let mut state = unsafe { std::mem::zeroed::<vr::RenderModel_ComponentState_t>() };
+        assert_eq!(
+            f.input.GetComponentStateForBinding(
+                c"{indexcontroller}valve_controller_knu_1_0_left".as_ptr(),
+                component.as_ptr(),
+                infos.as_ptr(),
+                std::mem::size_of::<vr::InputBindingInfo_t>() as u32,
+                infos.len() as u32,
+                &mut state,
+            ),
+            vr::EVRInputError::None
+        );
+        state.uProperties
+    }
+    fn get_properties(f: &Fixture, info: &vr::InputBindingInfo_t) -> u32 {
+        get_component_properties(f, c"xrizer_component", std::slice::from_ref(info))
+    }
+    let has = |props: u32, flag: vr::EVRComponentProperty| props & flag.0 != 0;
 
     f.sync(vr::VRActiveActionSet_t {
         ulActionSet: set1,
         ..Default::default()
     });
+    let props = get_properties(&f, &a_click);
+    assert!(has(props, vr::EVRComponentProperty::IsVisible));
+    assert!(!has(props, vr::EVRComponentProperty::IsPressed));
 
-    for (handle, expected) in [(posel, pose_left), (poser, pose_right)] {
-        let actual = f.get_pose(handle, 0).unwrap();
-        assert!(actual.bActive);
-        let p = actual.pose;
-        assert!(p.bPoseIsValid);
-        compare_pose(expected, p.mDeviceToAbsoluteTracking.into());
-    }
+    fakexr::set_action_state(
+        f.get_action::<bool>(boolact),
+        fakexr::ActionState::Bool(true),
+        LeftHand,
+    );
+    f.sync(vr::VRActiveActionSet_t {
+        ulActionSet: set1,
+        ..Default::default()
+    });
+    let props = get_properties(&f, &a_click);
+    assert!(has(props, vr::EVRComponentProperty::IsVisible));
+    assert!(has(props, vr::EVRComponentProperty::IsPressed));
+    assert!(!has(props, vr::EVRComponentProperty::IsTouched));
+
+    let props = get_properties(&f, &trigger_touch);
+    assert!(has(props, vr::EVRComponentProperty::IsTouched));
+    assert!(!has(props, vr::EVRComponentProperty::IsPressed));
+
+    // Named components only show the state of their own input.
+    let both = [a_click, trigger_touch];
+    let props = get_component_properties(&f, c"trigger", &both);
+    assert!(has(props, vr::EVRComponentProperty::IsVisible));
+    assert!(has(props, vr::EVRComponentProperty::IsTouched));
+    assert!(!has(props, vr::EVRComponentProperty::IsPressed));
+    let props = get_component_properties(&f, c"button_a", &both);
+    assert!(has(props, vr::EVRComponentProperty::IsPressed));
+    assert!(!has(props, vr::EVRComponentProperty::IsTouched));
+    let props = get_component_properties(&f, c"thumbstick", &both);
+    assert!(has(props, vr::EVRComponentProperty::IsVisible));
+    assert!(!has(props, vr::EVRComponentProperty::IsPressed));
+    assert!(!has(props, vr::EVRComponentProperty::IsTouched));
+
+    // Unknown components have no state
+    let mut state = unsafe { std::mem::zeroed::<vr::RenderModel_ComponentState_t>() };
+    assert_eq!(
+        f.input.GetComponentStateForBinding(
+            c"{indexcontroller}valve_controller_knu_1_0_left".as_ptr(),
+            c"not_a_component".as_ptr(),
+            &a_click,
+            std::mem::size_of::<vr::InputBindingInfo_t>() as u32,
+            1,
+            &mut state,
+        ),
+        vr::EVRInputError::InvalidParam
+    );
 }
 
 #[test]
-fn raw_pose_switch_profile() {
+fn open_binding_ui_writes_bindings() {
+    let f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let set2 = f.get_action_set_handle(c"/actions/set2");
+    f.get_action_handle(c"/actions/set1/in/boolact");
+    f.load_actions(c"actions_dpad.json");
+
+    let read_dump = |set| {
+        assert_eq!(
+            f.input.OpenBindingUI(c"".as_ptr(), set, 0, false),
+            vr::EVRInputError::None
+        );
+        let path = Input::<FakeCompositor>::binding_dump_path();
+        let dump = std::fs::read_to_string(&path).unwrap().to_lowercase();
+        std::fs::remove_file(path).unwrap();
+        dump
+    };
+
+    let dump = read_dump(set1);
+    assert!(
+        dump.contains("/interaction_profiles/htc/vive_controller"),
+        "{dump}"
+    );
+    assert!(
+        dump.contains("/actions/set1/in/boolact: /user/hand/left/input/trackpad (dpad, north)"),
+        "{dump}"
+    );
+
+    let dump = read_dump(set2);
+    assert!(!dump.contains("/actions/set1/in/boolact"), "{dump}");
+
+    assert_eq!(
+        f.input.OpenBindingUI(c"".as_ptr(), 12345, 0, false),
+        vr::EVRInputError::InvalidHandle
+    );
+}
+
+#[test]
+fn trigger_deadzone_and_gain() {
     let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let vec1act = f.get_action_handle(c"/actions/set1/in/vec1act");
+    let left = f.get_input_source_handle(c"/user/hand/left");
+    let right = f.get_input_source_handle(c"/user/hand/right");
+    f.load_actions(c"actions_analog_remap.json");
+    f.set_interaction_profile::<ViveWands>(LeftHand);
+    f.set_interaction_profile::<ViveWands>(RightHand);
+
+    let action = f.get_action::<f32>(vec1act);
+    let sync = |f: &mut Fixture| {
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        });
+        f.input.frame_start_update();
+    };
+    sync(&mut f);
+    sync(&mut f);
+
+    // Left trigger has a 20% deadzone and a gain of 2, right trigger is untouched.
+    // Expected values are for the left hand, the right hand, and both hands.
+    // Negative values (i.e. from an axis bound as a vector1) are remapped by their magnitude, and
+    // both hands are combined by the largest magnitude.
+    let cases: [(f32, f32, [f32; 3]); 7] = [
+        (0.1, 0.1, [0.0, 0.1, 0.1]),
+        (0.2, 0.0, [0.0, 0.0, 0.0]),
+        (0.4, 0.3, [0.5, 0.3, 0.5]),
+        (0.6, 0.3, [1.0, 0.3, 1.0]),
+        (1.0, 1.0, [1.0, 1.0, 1.0]),
+        (-0.4, 0.3, [-0.5, 0.3, -0.5]),
+        (-0.1, -0.3, [0.0, -0.3, -0.3]),
+    ];
+    for (left_pull, right_pull, expected) in cases {
+        fakexr::set_action_state(action, fakexr::ActionState::Float(left_pull), LeftHand);
+        fakexr::set_action_state(action, fakexr::ActionState::Float(right_pull), RightHand);
+        sync(&mut f);
+
+        for (restrict, expected) in [left, right, 0].into_iter().zip(expected) {
+            let mut state = vr::InputAnalogActionData_t::default();
+            assert_eq!(
+                f.input.GetAnalogActionData(
+                    vec1act,
+                    &mut state,
+                    std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
+                    restrict,
+                ),
+                vr::EVRInputError::None
+            );
+            assert!(state.bActive);
+            assert!(
+                (state.x - expected).abs() < 0.0001,
+                "expected {expected}, got {} (pulls {left_pull}/{right_pull}, restrict {restrict})",
+                state.x
+            );
+        }
+    }
+}
 
+#[test]
+fn thumbstick_deadzone() {
+    let mut f = Fixture::new();
     let set1 = f.get_action_set_handle(c"/actions/set1");
-    let posel = f.get_action_handle(c"/actions/set1/in/posel");
-    let poser = f.get_action_handle(c"/actions/set1/in/poser");
+    let vec2act = f.get_action_handle(c"/actions/set1/in/vec2act");
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+    f.input.set_thumbstick_deadzone(0.2);
+
+    let action = f.get_action::<xr::Vector2f>(vec2act);
+    let get_state = |f: &mut Fixture, x, y| {
+        fakexr::set_action_state(action, fakexr::ActionState::Vector2(x, y), LeftHand);
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: set1,
+            ..Default::default()
+        });
+        let mut state = vr::InputAnalogActionData_t::default();
+        assert_eq!(
+            f.input.GetAnalogActionData(
+                vec2act,
+                &mut state,
+                std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
+                0,
+            ),
+            vr::EVRInputError::None
+        );
+        state
+    };
+
+    let state = get_state(&mut f, 0.1, -0.1);
+    assert!(state.bActive);
+    assert_eq!((state.x, state.y), (0.0, 0.0));
+
+    let state = get_state(&mut f, 0.0, 0.6);
+    assert_eq!(state.x, 0.0);
+    assert!((state.y - 0.5).abs() < 1e-6, "{}", state.y);
+    assert!((state.deltaY - 0.5).abs() < 1e-6, "{}", state.deltaY);
 
+    // Trackpads don't drift, so they're left alone.
+    f.set_interaction_profile::<ViveWands>(LeftHand);
+    let state = get_state(&mut f, 0.1, -0.1);
+    assert_eq!((state.x, state.y), (0.1, -0.1));
+}
+
+#[test]
+fn activate_all_sets_without_active_sets() {
+    let f = Fixture::new();
     f.load_actions(c"actions.json");
-    f.set_interaction_profile::<SimpleController>(LeftHand);
-    f.set_interaction_profile::<SimpleController>(RightHand);
-    let session = f.input.openxr.session_data.get().session.as_raw();
-    let pose_left = xr::Posef {
-        position: xr::Vector3f {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        },
-        orientation: xr::Quaternionf::IDENTITY,
+    let session = f.raw_session();
+    let update = || {
+        f.input.UpdateActionState(
+            std::ptr::null_mut(),
+            std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
+            0,
+        )
     };
-    fakexr::set_grip(session, LeftHand, pose_left);
 
-    let pose_right = xr::Posef {
-        position: xr::Vector3f {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        },
-        orientation: xr::Quaternionf::IDENTITY,
+    let start = fakexr::sync_actions_count(session);
+    assert_eq!(update(), vr::EVRInputError::NoActiveActionSet);
+    assert_eq!(fakexr::sync_actions_count(session), start);
+
+    f.input.activate_all_sets.store(true, Ordering::Relaxed);
+    assert_eq!(update(), vr::EVRInputError::None);
+    assert_eq!(fakexr::sync_actions_count(session) - start, 1);
+
+    let set1 = ActionSetKey::from(KeyData::from_ffi(f.get_action_set_handle(c"/actions/set1")));
+    let active_sets = f.input.active_sets.lock().unwrap();
+    assert!(active_sets.contains(&set1));
+    let data = f.input.openxr.session_data.get();
+    let loaded = data.input_data.get_loaded_actions().unwrap();
+    assert_eq!(active_sets.len(), loaded.sets.len());
+    assert!(loaded.sets.keys().all(|key| active_sets.contains(&key)));
+}
+
+#[test]
+fn info_set_sync_throttled() {
+    let mut f = Fixture::new();
+    f.load_actions(c"actions.json");
+    let session = f.raw_session();
+
+    let frame = |f: &Fixture| {
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
     };
-    fakexr::set_grip(session, RightHand, pose_right);
 
+    let start = fakexr::sync_actions_count(session);
+    for _ in 0..super::INFO_SET_SYNC_INTERVAL {
+        frame(&f);
+    }
+    assert_eq!(fakexr::sync_actions_count(session) - start, 1);
+
+    // The profile is picked up by the next sync, and reported on the frame after.
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+    frame(&f);
+    assert_eq!(fakexr::sync_actions_count(session) - start, 2);
+    frame(&f);
+    assert!(f.input.get_controller_device_index(Hand::Left).is_some());
+
+    // Once a controller is connected, the info set no longer needs to be synced.
+    let synced = fakexr::sync_actions_count(session);
+    for _ in 0..super::INFO_SET_SYNC_INTERVAL {
+        frame(&f);
+    }
+    assert_eq!(fakexr::sync_actions_count(session), synced);
+}
+
+#[test]
+fn analog_delta_reset_on_set_reactivation() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let set2 = f.get_action_set_handle(c"/actions/set2");
+    let vec2act = f.get_action_handle(c"/actions/set1/in/vec2act");
+    f.load_actions(c"actions.json");
+    f.set_interaction_profile::<Knuckles>(LeftHand);
+
+    let action = f.get_action::<xr::Vector2f>(vec2act);
+    let mut get_state = |set, x, y| {
+        fakexr::set_action_state(action, fakexr::ActionState::Vector2(x, y), LeftHand);
+        f.sync(vr::VRActiveActionSet_t {
+            ulActionSet: set,
+            ..Default::default()
+        });
+        let mut state = vr::InputAnalogActionData_t::default();
+        assert_eq!(
+            f.input.GetAnalogActionData(
+                vec2act,
+                &mut state,
+                std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
+                0,
+            ),
+            vr::EVRInputError::None
+        );
+        state
+    };
+
+    let state = get_state(set1, 0.5, 0.5);
+    assert!(state.bActive);
+    assert_eq!((state.deltaX, state.deltaY), (0.0, 0.0));
+
+    let state = get_state(set1, 0.75, 0.25);
+    assert_eq!((state.deltaX, state.deltaY), (0.25, -0.25));
+
+    get_state(set2, 0.0, 0.0);
+
+    // Reactivating the set shouldn't produce a delta relative to the value before deactivation.
+    let state = get_state(set1, 1.0, 1.0);
+    assert_eq!((state.x, state.y), (1.0, 1.0));
+    assert_eq!((state.deltaX, state.deltaY), (0.0, 0.0));
+
+    let state = get_state(set1, 0.5, 1.0);
+    assert_eq!((state.deltaX, state.deltaY), (-0.5, 0.0));
+}
+
+#[test]
+fn action_set_priority() {
+    let mut f = Fixture::new();
+    let set1 = f.get_action_set_handle(c"/actions/set1");
+    let set2 = f.get_action_set_handle(c"/actions/set2");
+    let act1 = f.get_action_handle(c"/actions/set1/in/boolact");
+    let act2 = f.get_action_handle(c"/actions/set2/in/boolact");
+    f.load_actions(c"actions_priority.json");
+    f.set_interaction_profile::<ViveWands>(LeftHand);
     f.sync(vr::VRActiveActionSet_t {
         ulActionSet: set1,
         ..Default::default()
     });
+    f.input.frame_start_update();
+    f.input.frame_start_update();
 
-    fn offset_to_pose(offset: &Mat4) -> xr::Posef {
-        let translation = offset.w_axis.truncate();
-        let rotation = Quat::from_mat4(offset);
+    fakexr::set_action_state(
+        f.get_action::<bool>(act1),
+        fakexr::ActionState::Bool(true),
+        LeftHand,
+    );
+    fakexr::set_action_state(
+        f.get_action::<bool>(act2),
+        fakexr::ActionState::Bool(true),
+        LeftHand,
+    );
 
-        xr::Posef {
-            orientation: xr::Quaternionf {
-                x: rotation.x,
-                y: rotation.y,
-                z: rotation.z,
-                w: rotation.w,
+    let sync_with_priorities = |priority1, priority2| {
+        let mut sets = [
+            vr::VRActiveActionSet_t {
+                ulActionSet: set1,
+                nPriority: priority1,
+                ..Default::default()
             },
-            position: xr::Vector3f {
-                x: translation.x,
-                y: translation.y,
-                z: translation.z,
+            vr::VRActiveActionSet_t {
+                ulActionSet: set2,
+                nPriority: priority2,
+                ..Default::default()
             },
-        }
-    }
+        ];
+        assert_eq!(
+            f.input.UpdateActionState(
+                sets.as_mut_ptr(),
+                std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
+                sets.len() as u32
+            ),
+            vr::EVRInputError::None
+        );
+    };
+    let is_active = |action| {
+        let state = f.get_bool_state(action).unwrap();
+        state.bActive && state.bState
+    };
 
-    for (handle, hand) in [(posel, Hand::Left), (poser, Hand::Right)] {
-        let expected = &SimpleController::offset_grip_pose(hand);
-        let actual = f.get_pose(handle, 0).unwrap();
-        assert!(actual.bActive, "{hand:?} not active");
-        let p = actual.pose;
-        assert!(p.bPoseIsValid, "{hand:?} invalid pose");
-        compare_pose(offset_to_pose(expected), p.mDeviceToAbsoluteTracking.into());
+    sync_with_priorities(0, 0);
+    assert!(is_active(act1));
+    assert!(is_active(act2));
+
+    sync_with_priorities(10, 0);
+    assert!(is_active(act1));
+    assert!(!is_active(act2));
+
+    sync_with_priorities(0, 10);
+    assert!(!is_active(act1));
+    assert!(is_active(act2));
+}
+
+/// A directory under the system temp dir that's removed when dropped, even if the test fails.
+struct TempDir(std::path::PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "xrizer-{name}-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
     }
+}
 
-    f.set_interaction_profile::<OculusTouch>(LeftHand);
-    f.set_interaction_profile::<OculusTouch>(RightHand);
+impl std::ops::Deref for TempDir {
+    type Target = std::path::Path;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
 
-    // Cached poses don't reset until next frame
-    f.input.openxr.poll_events();
-    f.input.frame_start_update();
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
 
-    f.sync(vr::VRActiveActionSet_t {
-        ulActionSet: set1,
-        ..Default::default()
-    });
+#[test]
+fn manifest_cached_across_restarts() {
+    use super::action_manifest::MANIFEST_READS;
 
-    for (handle, expected) in [
-        (posel, &OculusTouch::offset_grip_pose(Hand::Left)),
-        (poser, &OculusTouch::offset_grip_pose(Hand::Right)),
-    ] {
-        let actual = f.get_pose(handle, 0).unwrap();
-        assert!(actual.bActive);
-        let p = actual.pose;
-        assert!(p.bPoseIsValid);
-        compare_pose(offset_to_pose(expected), p.mDeviceToAbsoluteTracking.into());
+    let f = Fixture::new();
+    let dir = TempDir::new("manifest-cache");
+    let data_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/input_data");
+    for file in ["actions_toggle.json", "knuckles_toggle.json"] {
+        std::fs::copy(data_dir.join(file), dir.join(file)).unwrap();
     }
+    let manifest = dir.join("actions_toggle.json");
+    let manifest_c = std::ffi::CString::new(manifest.to_str().unwrap()).unwrap();
+
+    let reads = || MANIFEST_READS.with(|reads| reads.get());
+    let reads_before = reads();
+    assert_eq!(
+        f.input.SetActionManifestPath(manifest_c.as_ptr()),
+        vr::EVRInputError::None
+    );
+    assert_eq!(reads(), reads_before + 1);
+
+    let restart = || {
+        f.input.openxr.restart_session();
+        assert!(
+            f.input
+                .openxr
+                .session_data
+                .get()
+                .input_data
+                .get_loaded_actions()
+                .is_some()
+        );
+    };
+    let touch = |file: &str, secs: u64| {
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join(file))
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(secs))
+            .unwrap();
+    };
+
+    restart();
+    assert_eq!(reads(), reads_before + 1);
+
+    // Changing the manifest should cause it to be read again.
+    touch("actions_toggle.json", 10);
+    restart();
+    assert_eq!(reads(), reads_before + 2);
+
+    // So should changing one of its bindings.
+    touch("knuckles_toggle.json", 20);
+    restart();
+    assert_eq!(reads(), reads_before + 3);
+
+    restart();
+    assert_eq!(reads(), reads_before + 3);
 }
 
 #[test]
-fn cased_actions() {
-    let mut f = Fixture::new();
-    let set1 = f.get_action_set_handle(c"/actions/set1");
-    f.load_actions(c"actions_cased.json");
+fn merged_action_manifests() {
+    let f = Fixture::new();
+    f.input
+        .merge_action_manifests
+        .store(true, Ordering::Relaxed);
+    f.load_actions(c"actions_merge_game.json");
+    f.load_actions(c"actions_merge_mod.json");
 
-    let path = ViveWands::profile_path();
+    let path = Knuckles::profile_path();
+    // The game's bindings for its own set win over the mod's.
     f.verify_bindings::<bool>(
         path,
-        c"/actions/set1/in/BoolAct",
-        ["/user/hand/left/input/squeeze/click".into()],
-    );
-    f.verify_bindings::<f32>(
-        path,
-        c"/actions/set1/in/Vec1Act",
-        ["/user/hand/left/input/trigger/value".into()],
-    );
-    f.verify_bindings::<xr::Vector2f>(
-        path,
-        c"/actions/set1/in/Vec2Act",
-        ["/user/hand/left/input/trackpad".into()],
-    );
-    f.verify_bindings::<xr::Haptic>(
-        path,
-        c"/actions/set1/in/VibAct",
-        ["/user/hand/left/output/haptic".into()],
+        c"/actions/game/in/boolact",
+        ["/user/hand/left/input/a/click".into()],
+    );
+    f.verify_bindings::<bool>(
+        path,
+        c"/actions/mod/in/modact",
+        ["/user/hand/right/input/b/click".into()],
     );
 
-    f.set_interaction_profile::<ViveWands>(LeftHand);
-    let session = f.input.openxr.session_data.get().session.as_raw();
-    fakexr::set_grip(session, LeftHand, xr::Posef::IDENTITY);
-    fakexr::set_aim(session, LeftHand, xr::Posef::IDENTITY);
-    f.sync(vr::VRActiveActionSet_t {
-        ulActionSet: set1,
-        ..Default::default()
-    });
-
-    let poseact = f.get_action_handle(c"/actions/set1/in/PoseAct");
-    let pose = f.get_pose(poseact, 0).unwrap();
-    assert!(pose.bActive);
-    assert!(pose.pose.bPoseIsValid);
-
-    let skelact = f.get_action_handle(c"/actions/set1/in/SkelAct");
-    let pose = f.get_pose(skelact, 0).unwrap();
-    assert!(pose.bActive);
-    assert!(pose.pose.bPoseIsValid);
+    // Loading either manifest again shouldn't change anything.
+    f.load_actions(c"actions_merge_game.json");
+    f.load_actions(c"actions_merge_mod.json");
+    assert_eq!(f.input.loaded_actions_paths.lock().unwrap().len(), 2);
 }
 
 #[test]
-fn digital_action_initalize_on_failure() {
+fn action_manifests_not_merged_by_default() {
     let f = Fixture::new();
-    f.load_actions(c"actions.json");
-    let bad_state = vr::InputDigitalActionData_t {
-        bActive: true,
-        bState: true,
-        activeOrigin: 12345,
-        bChanged: true,
-        fUpdateTime: -999.0,
-    };
+    f.load_actions(c"actions_merge_game.json");
+
+    let path = [
+        ACTIONS_JSONS_DIR.to_bytes(),
+        c"actions_merge_mod.json".to_bytes_with_nul(),
+    ]
+    .concat();
+    assert_eq!(
+        f.input.SetActionManifestPath(path.as_ptr() as _),
+        vr::EVRInputError::MismatchedActionManifest
+    );
+    assert_eq!(f.input.loaded_actions_paths.lock().unwrap().len(), 1);
+}
 
-    let mut state = bad_state;
-    let bad_handle = 3434343343;
-    assert_ne!(
-        f.input.GetDigitalActionData(
-            bad_handle,
-            &mut state,
-            std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
-            0
-        ),
+#[test]
+fn merged_action_manifest_with_same_sets_replaces() {
+    let f = Fixture::new();
+    f.input
+        .merge_action_manifests
+        .store(true, Ordering::Relaxed);
+    f.load_actions(c"actions_merge_game.json");
+
+    // Another copy of the game's manifest declares the same sets, so it replaces the first one.
+    let dir = TempDir::new("replaced-manifest");
+    let source = std::path::Path::new(ACTIONS_JSONS_DIR.to_str().unwrap());
+    for file in ["actions_merge_game.json", "knuckles_merge_game.json"] {
+        std::fs::copy(source.join(file), dir.join(file)).unwrap();
+    }
+    let manifest_path = dir.join("actions_merge_game.json");
+    let path = std::ffi::CString::new(manifest_path.to_str().unwrap()).unwrap();
+    assert_eq!(
+        f.input.SetActionManifestPath(path.as_ptr()),
         vr::EVRInputError::None
     );
-    assert!(!state.bActive);
-    assert!(!state.bState);
-    assert_eq!(state.activeOrigin, 0);
-    assert!(!state.bChanged);
-    assert_eq!(state.fUpdateTime, 0.0);
 
-    let mut state = bad_state;
-    let vecact = f.get_action_handle(c"/actions/set1/in/vec1act");
-    assert_ne!(
-        f.input.GetDigitalActionData(
-            vecact,
-            &mut state,
-            std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
-            0
-        ),
-        vr::EVRInputError::None
+    assert_eq!(
+        *f.input.loaded_actions_paths.lock().unwrap(),
+        [manifest_path]
+    );
+    f.verify_bindings::<bool>(
+        Knuckles::profile_path(),
+        c"/actions/game/in/boolact",
+        ["/user/hand/left/input/a/click".into()],
     );
+}
 
-    assert!(!state.bActive);
-    assert!(!state.bState);
-    assert_eq!(state.activeOrigin, 0);
-    assert!(!state.bChanged);
-    assert_eq!(state.fUpdateTime, 0.0);
+#[test]
+fn large_manifest_bindings() {
+    const SETS: usize = 4;
+    const ACTIONS_PER_SET: usize = 64;
+
+    let dir = TempDir::new("large-manifest");
+
+    let action_name = |set: usize, action: usize| format!("/actions/set{set}/in/act{action}");
+    let action_sets: Vec<_> = (0..SETS)
+        .map(|set| {
+            serde_json::json!({
+                "name": format!("/actions/set{set}"),
+                "usage": "leftright",
+            })
+        })
+        .collect();
+    let actions: Vec<_> = (0..SETS)
+        .flat_map(|set| {
+            (0..ACTIONS_PER_SET).map(move |action| {
+                serde_json::json!({
+                    "name": action_name(set, action),
+                    "requirement": "mandatory",
+                    "type": if action % 2 == 0 { "boolean" } else { "vector1" },
+                })
+            })
+        })
+        .collect();
+    // Bool actions are bound to a button, float actions to the trigger.
+    let bindings = |button: &str| {
+        let sets: serde_json::Map<_, _> = (0..SETS)
+            .map(|set| {
+                let sources: Vec<_> = (0..ACTIONS_PER_SET)
+                    .flat_map(|action| {
+                        ["left", "right"].map(|hand| {
+                            let (mode, input, slot) = if action % 2 == 0 {
+                                ("button", button, "click")
+                            } else {
+                                ("trigger", "trigger", "pull")
+                            };
+                            serde_json::json!({
+                                "mode": mode,
+                                "path": format!("/user/hand/{hand}/input/{input}"),
+                                "inputs": { slot: { "output": action_name(set, action) } },
+                            })
+                        })
+                    })
+                    .collect();
+                (
+                    format!("/actions/set{set}"),
+                    serde_json::json!({ "sources": sources }),
+                )
+            })
+            .collect();
+        serde_json::json!({ "bindings": sets }).to_string()
+    };
+    std::fs::write(dir.join("wands.json"), bindings("menu")).unwrap();
+    std::fs::write(dir.join("knuckles.json"), bindings("a")).unwrap();
+    let wands =
+        serde_json::json!({ "controller_type": "vive_controller", "binding_url": "wands.json" });
+    let knuckles =
+        serde_json::json!({ "controller_type": "knuckles", "binding_url": "knuckles.json" });
+    let load = |name: &str, default_bindings: serde_json::Value| {
+        let manifest = serde_json::json!({
+            "action_sets": action_sets,
+            "actions": actions,
+            "default_bindings": default_bindings,
+        });
+        let manifest_path = dir.join(name);
+        std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+        let f = Fixture::new();
+        let path = std::ffi::CString::new(manifest_path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            f.input.SetActionManifestPath(path.as_ptr()),
+            vr::EVRInputError::None
+        );
+        f
+    };
+    let suggested = |f: &Fixture, profile: &str, set: usize, action: usize| {
+        let name = std::ffi::CString::new(action_name(set, action)).unwrap();
+        let handle = f.get_action_handle(&name);
+        let action = if action % 2 == 0 {
+            f.get_action::<bool>(handle)
+        } else {
+            f.get_action::<f32>(handle)
+        };
+        let profile = f.input.openxr.instance.string_to_path(profile).unwrap();
+        let mut bindings = fakexr::get_suggested_bindings(action, profile);
+        bindings.sort();
+        bindings
+    };
+
+    let f = load("actions.json", serde_json::json!([wands, knuckles]));
+
+    // Manifests with a single controller only read one bindings file at a time, so they give
+    // the sequential result.
+    for (profile, controller, name) in [
+        (ViveWands::profile_path(), &wands, "wands_actions.json"),
+        (Knuckles::profile_path(), &knuckles, "knuckles_actions.json"),
+    ] {
+        let sequential = load(name, serde_json::json!([controller]));
+        for set in 0..SETS {
+            for action in 0..ACTIONS_PER_SET {
+                assert_eq!(
+                    suggested(&f, profile, set, action),
+                    suggested(&sequential, profile, set, action),
+                    "{profile} bindings for {}",
+                    action_name(set, action)
+                );
+            }
+        }
+    }
+
+    for (profile, button) in [
+        (ViveWands::profile_path(), "menu"),
+        (Knuckles::profile_path(), "a"),
+    ] {
+        for set in 0..SETS {
+            for action in 0..ACTIONS_PER_SET {
+                let name = std::ffi::CString::new(action_name(set, action)).unwrap();
+                if action % 2 == 0 {
+                    f.verify_bindings::<bool>(
+                        profile,
+                        &name,
+                        [
+                            format!("/user/hand/left/input/{button}/click"),
+                            format!("/user/hand/right/input/{button}/click"),
+                        ],
+                    );
+                } else {
+                    f.verify_bindings::<f32>(
+                        profile,
+                        &name,
+                        [
+                            "/user/hand/left/input/trigger/value".into(),
+                            "/user/hand/right/input/trigger/value".into(),
+                        ],
+                    );
+                }
+            }
+        }
+    }
 }
 
 #[test]
-fn analog_action_initialize_on_failure() {
-    let f = Fixture::new();
+fn skeletal_tracking_level_from_data_source() {
+    let mut f = Fixture::new();
+    let skel_handle = f.get_action_handle(c"/actions/set1/in/skellyl");
     f.load_actions(c"actions.json");
+    f.set_interaction_profile::<ViveWands>(LeftHand);
 
-    let bad_state = vr::InputAnalogActionData_t {
-        bActive: true,
-        activeOrigin: 12345,
-        x: 300.0,
-        y: 600.0,
-        z: 900.0,
-        deltaX: 1000.0,
-        deltaY: 3000.0,
-        deltaZ: -999.999,
-        fUpdateTime: 395.0,
+    let frame = || {
+        f.input.openxr.poll_events();
+        f.input.frame_start_update();
     };
+    frame();
+    frame();
 
-    let check_state = |state: vr::InputAnalogActionData_t, desc: &str| {
-        assert!(!state.bActive, "{desc}");
-        assert_eq!(state.activeOrigin, 0, "{desc}");
-        assert_eq!(state.x, 0.0, "{desc}");
-        assert_eq!(state.deltaX, 0.0, "{desc}");
-        assert_eq!(state.y, 0.0, "{desc}");
-        assert_eq!(state.deltaY, 0.0, "{desc}");
-        assert_eq!(state.z, 0.0, "{desc}");
-        assert_eq!(state.deltaZ, 0.0, "{desc}");
-        assert_eq!(state.fUpdateTime, 0.0, "{desc}");
+    let get_level = |source| {
+        fakexr::set_hand_tracking_source(f.raw_session(), LeftHand, source);
+        frame();
+
+        let mut bones = [vr::VRBoneTransform_t::default(); 31];
+        assert_eq!(
+            f.input.GetSkeletalBoneData(
+                skel_handle,
+                vr::EVRSkeletalTransformSpace::Parent,
+                vr::EVRSkeletalMotionRange::WithoutController,
+                bones.as_mut_ptr(),
+                bones.len() as u32,
+            ),
+            vr::EVRInputError::None
+        );
+
+        let mut level = vr::EVRSkeletalTrackingLevel::Estimated;
+        assert_eq!(
+            f.input.GetSkeletalTrackingLevel(skel_handle, &mut level),
+            vr::EVRInputError::None
+        );
+        level
     };
 
-    let mut state = bad_state;
-    let bad_handle = 3434343343;
-    assert_ne!(
-        f.input.GetAnalogActionData(
-            bad_handle,
-            &mut state,
-            std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
-            0
-        ),
-        vr::EVRInputError::None
+    assert_eq!(
+        get_level(Some(xr::sys::HandTrackingDataSourceEXT::UNOBSTRUCTED)),
+        vr::EVRSkeletalTrackingLevel::Full
     );
-    check_state(state, "bad handle");
-
-    let mut state = bad_state;
-    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
-    assert_ne!(
-        f.input.GetAnalogActionData(
-            boolact,
-            &mut state,
-            std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
-            0
-        ),
-        vr::EVRInputError::None
+    assert_eq!(
+        get_level(Some(xr::sys::HandTrackingDataSourceEXT::CONTROLLER)),
+        vr::EVRSkeletalTrackingLevel::Partial
     );
-    check_state(state, "wrong type");
+    assert_eq!(get_level(None), vr::EVRSkeletalTrackingLevel::Estimated);
 }
 
 #[test]
-fn implicit_action_sets() {
+fn gdc2015_pose_offset_from_raw() {
     let mut f = Fixture::new();
     let set1 = f.get_action_set_handle(c"/actions/set1");
-    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
-    f.load_actions(c"actions_missing_sets.json");
+    let raw = f.get_action_handle(c"/actions/set1/in/rawpose");
+    let gdc = f.get_action_handle(c"/actions/set1/in/gdcpose");
+    f.load_actions(c"actions_gdc2015.json");
+    f.set_interaction_profile::<ViveWands>(LeftHand);
 
+    let session = f.input.openxr.session_data.get().session.as_raw();
+    let rotation = Quat::from_rotation_y(FRAC_PI_4);
+    fakexr::set_grip(
+        session,
+        LeftHand,
+        xr::Posef {
+            position: xr::Vector3f {
+                x: 0.2,
+                y: 1.0,
+                z: -0.3,
+            },
+            orientation: xr::Quaternionf {
+                x: rotation.x,
+                y: rotation.y,
+                z: rotation.z,
+                w: rotation.w,
+            },
+        },
+    );
     f.sync(vr::VRActiveActionSet_t {
         ulActionSet: set1,
         ..Default::default()
     });
 
-    let res = f.get_bool_state(boolact);
-    assert!(res.is_ok(), "{res:?}");
+    let get_pose = |handle| {
+        let data = f.get_pose(handle, 0).unwrap();
+        assert!(data.bActive);
+        assert!(data.pose.bPoseIsValid);
+        let pose = xr::Posef::from(data.pose.mDeviceToAbsoluteTracking);
+        let orientation = pose.orientation;
+        let position = pose.position;
+        Mat4::from_rotation_translation(
+            Quat::from_xyzw(orientation.x, orientation.y, orientation.z, orientation.w),
+            glam::Vec3::new(position.x, position.y, position.z),
+        )
+    };
+    // The wands' raw pose is the GDC 2015 coordinate system.
+    let expected = Mat4::from_rotation_translation(rotation, glam::Vec3::new(0.2, 1.0, -0.3));
+    for (name, pose) in [("raw", get_pose(raw)), ("gdc2015", get_pose(gdc))] {
+        assert!(
+            pose.abs_diff_eq(expected, 1e-5),
+            "{name} expected: {expected}\nactual: {pose}"
+        );
+    }
 }
 
 #[test]
-fn detect_controller_after_manifest_load() {
-    let mut f = Fixture::new();
-    f.load_actions(c"actions.json");
-
-    let input = f.input.clone();
-    let frame = || {
-        input.openxr.poll_events();
-        input.frame_start_update();
+fn offset_pose_moves_velocity() {
+    let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+    let mut pose = vr::TrackedDevicePose_t {
+        mDeviceToAbsoluteTracking: xr::Posef {
+            position: xr::Vector3f {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            orientation: xr::Quaternionf {
+                x: rotation.x,
+                y: rotation.y,
+                z: rotation.z,
+                w: rotation.w,
+            },
+        }
+        .into(),
+        vVelocity: vr::HmdVector3_t { v: [0.5, 0.0, 0.0] },
+        vAngularVelocity: vr::HmdVector3_t { v: [0.0, 2.0, 0.0] },
+        bPoseIsValid: true,
+        ..Default::default()
     };
 
-    frame();
-    assert!(f.input.get_controller_device_index(Hand::Left).is_none());
+    // 10cm forward of the device, pitched up 90 degrees.
+    super::offset_pose(
+        &mut pose,
+        Mat4::from_rotation_translation(
+            Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+            glam::Vec3::new(0.0, 0.0, -0.1),
+        ),
+    );
 
-    f.set_interaction_profile::<Knuckles>(fakexr::UserPath::LeftHand);
-    frame();
-    // Profile won't be set for this frame - we call sync after events have already been polled
-    assert!(f.input.get_controller_device_index(Hand::Left).is_none());
+    let offset = xr::Posef::from(pose.mDeviceToAbsoluteTracking);
+    let position = offset.position;
+    let orientation = offset.orientation;
+    let expected = [-0.1, 1.0, 0.0];
+    let actual = [position.x, position.y, position.z];
+    assert!(
+        expected
+            .iter()
+            .zip(actual)
+            .all(|(e, a)| (e - a).abs() < 1e-5),
+        "{actual:?}"
+    );
+    let expected = [0.5, 0.5, -0.5, 0.5];
+    let actual = [orientation.x, orientation.y, orientation.z, orientation.w];
+    assert!(
+        expected
+            .iter()
+            .zip(actual)
+            .all(|(e, a)| (e - a).abs() < 1e-5),
+        "{actual:?}"
+    );
 
-    frame();
-    let index = f.input.get_controller_device_index(Hand::Left);
-    assert!(index.is_some_and(|i| f.input.is_device_connected(i)));
+    // Spinning around the raw origin sweeps the offset point sideways.
+    let expected = [0.5, 0.0, 0.2];
+    let actual = pose.vVelocity.v;
+    assert!(
+        expected
+            .iter()
+            .zip(actual)
+            .all(|(e, a)| (e - a).abs() < 1e-5),
+        "{actual:?}"
+    );
+    assert_eq!(pose.vAngularVelocity.v, [0.0, 2.0, 0.0]);
 }
 
 #[test]
-fn empty_manifest() {
+fn binding_report_lists_unmatched_bindings() {
     let f = Fixture::new();
-    f.input
-        .SetActionManifestPath(c"empty_manifest.json".as_ptr() as _);
-
-    f.input.openxr.restart_session();
-    assert!(f.input.action_map.read().unwrap().is_empty());
-}
-
-#[test]
-fn load_actions_race() {
-    let mut f = Fixture::new();
-    f.input.openxr.restart_session(); // get to real session
-
-    f.set_interaction_profile::<OculusTouch>(LeftHand);
-    f.set_interaction_profile::<OculusTouch>(RightHand);
-
-    let mut f = Arc::new(f);
-    f.input.frame_start_update(); // load legacy
-    f.input.openxr.poll_events();
-    let got_input = f.input.get_legacy_controller_state(
-        1,
-        &mut vr::VRControllerState_t::default(),
-        std::mem::size_of::<vr::VRControllerState_t>() as _,
+    assert!(f.input.binding_report().is_none());
+    f.load_actions(c"actions_report.json");
+
+    let report = f.input.binding_report().unwrap();
+    let profile = ViveWands::profile_path().to_string();
+    assert_eq!(
+        report.unbound_actions,
+        ["/actions/set1/in/vec1act".to_string()].into()
     );
-    assert!(got_input);
-
-    std::thread::scope(|scope| {
-        let barrier = Arc::new(Barrier::new(2));
-        {
-            let input = f.input.clone();
-            let barrier = barrier.clone();
-            scope.spawn(move || {
-                barrier.wait();
-                // arbitrary delay, so we get the frame start update right after restart
-                std::thread::sleep(std::time::Duration::from_micros(500));
-                input.frame_start_update();
-            });
-        }
-        {
-            let f = f.clone();
-            scope.spawn(move || {
-                barrier.wait();
-                f.load_actions(c"actions.json");
-            });
-        }
-    });
-
-    let got_input = f.input.get_legacy_controller_state(
-        0,
-        &mut vr::VRControllerState_t::default(),
-        std::mem::size_of::<vr::VRControllerState_t>() as _,
+    // The wand bindings are also used for the simple controller, so only check the wands.
+    assert_eq!(
+        report.unknown_actions[&profile],
+        ["/actions/set1/in/vec1atc".to_string()].into()
+    );
+    assert_eq!(
+        report.invalid_inputs[&profile],
+        ["/user/hand/left/input/thumbstick/click".to_string()].into()
     );
-    assert!(!got_input);
-
-    let set1 = f.get_action_set_handle(c"/actions/set1");
-    let boolact = f.get_action_handle(c"/actions/set1/in/boolact");
-    Arc::get_mut(&mut f).unwrap().sync(vr::VRActiveActionSet_t {
-        ulActionSet: set1,
-        ..Default::default()
-    });
-
-    let res = f.get_bool_state(boolact);
-    assert!(res.is_ok(), "{res:?}");
 }
@@ -0,0 +1,129 @@
+use crate::settings::XrizerSettings;
+use std::collections::HashMap;
+use std::ffi::CStr;
+
+/// The body part a generic tracker is attached to, as assigned in SteamVR's tracker management.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrackerRole {
+    Unassigned,
+    LeftFoot,
+    RightFoot,
+    LeftShoulder,
+    RightShoulder,
+    LeftElbow,
+    RightElbow,
+    LeftKnee,
+    RightKnee,
+    Waist,
+    Chest,
+    Camera,
+    Keyboard,
+}
+
+impl TrackerRole {
+    /// Infers a role from a device name or serial, such as "Left Foot Tracker" or "waist".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace(['_', '-'], " ");
+        let left = name.contains("left");
+        let right = name.contains("right");
+        let sided = |left_role, right_role| match (left, right) {
+            (true, false) => Some(left_role),
+            (false, true) => Some(right_role),
+            _ => None,
+        };
+
+        if name.contains("waist") || name.contains("hip") || name.contains("pelvis") {
+            Some(Self::Waist)
+        } else if name.contains("chest") {
+            Some(Self::Chest)
+        } else if name.contains("foot") || name.contains("feet") || name.contains("ankle") {
+            sided(Self::LeftFoot, Self::RightFoot)
+        } else if name.contains("knee") {
+            sided(Self::LeftKnee, Self::RightKnee)
+        } else if name.contains("elbow") {
+            sided(Self::LeftElbow, Self::RightElbow)
+        } else if name.contains("shoulder") {
+            sided(Self::LeftShoulder, Self::RightShoulder)
+        } else if name.contains("camera") {
+            Some(Self::Camera)
+        } else if name.contains("keyboard") {
+            Some(Self::Keyboard)
+        } else {
+            None
+        }
+    }
+
+    /// The value of ControllerType_String for trackers with this role.
+    pub fn controller_type(self) -> &'static CStr {
+        match self {
+            Self::Unassigned => c"vive_tracker_handheld_object",
+            Self::LeftFoot => c"vive_tracker_left_foot",
+            Self::RightFoot => c"vive_tracker_right_foot",
+            Self::LeftShoulder => c"vive_tracker_left_shoulder",
+            Self::RightShoulder => c"vive_tracker_right_shoulder",
+            Self::LeftElbow => c"vive_tracker_left_elbow",
+            Self::RightElbow => c"vive_tracker_right_elbow",
+            Self::LeftKnee => c"vive_tracker_left_knee",
+            Self::RightKnee => c"vive_tracker_right_knee",
+            Self::Waist => c"vive_tracker_waist",
+            Self::Chest => c"vive_tracker_chest",
+            Self::Camera => c"vive_tracker_camera",
+            Self::Keyboard => c"vive_tracker_keyboard",
+        }
+    }
+
+    /// The name SteamVR stores for this role in the "trackers" settings section.
+    pub fn setting_name(self) -> &'static CStr {
+        match self {
+            Self::Unassigned => c"TrackerRole_None",
+            Self::LeftFoot => c"TrackerRole_LeftFoot",
+            Self::RightFoot => c"TrackerRole_RightFoot",
+            Self::LeftShoulder => c"TrackerRole_LeftShoulder",
+            Self::RightShoulder => c"TrackerRole_RightShoulder",
+            Self::LeftElbow => c"TrackerRole_LeftElbow",
+            Self::RightElbow => c"TrackerRole_RightElbow",
+            Self::LeftKnee => c"TrackerRole_LeftKnee",
+            Self::RightKnee => c"TrackerRole_RightKnee",
+            Self::Waist => c"TrackerRole_Waist",
+            Self::Chest => c"TrackerRole_Chest",
+            Self::Camera => c"TrackerRole_Camera",
+            Self::Keyboard => c"TrackerRole_Keyboard",
+        }
+    }
+}
+
+/// User provided tracker roles, keyed by serial.
+pub struct TrackerRoleOverrides(HashMap<String, TrackerRole>);
+
+impl TrackerRoleOverrides {
+    /// Loads overrides from [`XrizerSettings::tracker_roles`].
+    pub fn load() -> Self {
+        Self::parse(&XrizerSettings::get().tracker_roles)
+    }
+
+    pub fn parse(roles: &str) -> Self {
+        Self(
+            roles
+                .split(';')
+                .filter_map(|entry| {
+                    let (serial, role_name) = entry.split_once('=')?;
+                    let Some(role) = TrackerRole::from_name(role_name) else {
+                        log::warn!("Unknown tracker role {role_name:?} for serial {serial:?}");
+                        return None;
+                    };
+                    Some((serial.trim().to_string(), role))
+                })
+                .collect(),
+        )
+    }
+
+    /// Determines the role of a tracker, preferring user overrides over its name and serial.
+    pub fn get_role(&self, name: &str, serial: &str) -> TrackerRole {
+        self.0
+            .get(serial)
+            .copied()
+            .or_else(|| TrackerRole::from_name(name))
+            .or_else(|| TrackerRole::from_name(serial))
+            .unwrap_or(TrackerRole::Unassigned)
+    }
+}
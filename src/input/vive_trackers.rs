@@ -0,0 +1,135 @@
+use super::InteractionProfile;
+use super::profiles::vive_tracker::ViveTracker;
+use log::trace;
+use openxr as xr;
+
+/// Every role a tracker can be assigned under XR_HTCX_vive_tracker_interaction.
+const ROLE_PATHS: [&str; 13] = [
+    "/user/vive_tracker_htcx/role/handheld_object",
+    "/user/vive_tracker_htcx/role/left_foot",
+    "/user/vive_tracker_htcx/role/right_foot",
+    "/user/vive_tracker_htcx/role/left_shoulder",
+    "/user/vive_tracker_htcx/role/right_shoulder",
+    "/user/vive_tracker_htcx/role/left_elbow",
+    "/user/vive_tracker_htcx/role/right_elbow",
+    "/user/vive_tracker_htcx/role/left_knee",
+    "/user/vive_tracker_htcx/role/right_knee",
+    "/user/vive_tracker_htcx/role/waist",
+    "/user/vive_tracker_htcx/role/chest",
+    "/user/vive_tracker_htcx/role/camera",
+    "/user/vive_tracker_htcx/role/keyboard",
+];
+
+/// Trackers from XR_HTCX_vive_tracker_interaction. Trackers are only usable through actions once
+/// they've been assigned a role, so the pose action is bound to all of them.
+pub(super) struct ViveTrackerData {
+    action: xr::Action<xr::Posef>,
+}
+
+impl ViveTrackerData {
+    /// Creates the tracker pose action in the given set, and suggests its bindings.
+    pub fn new(instance: &xr::Instance, set: &xr::ActionSet) -> Self {
+        let role_paths: Vec<xr::Path> = ROLE_PATHS
+            .iter()
+            .map(|path| instance.string_to_path(path).unwrap())
+            .collect();
+        let action = set
+            .create_action("vive-tracker-pose", "Vive Tracker Pose", &role_paths)
+            .unwrap();
+
+        let bindings: Vec<_> = ROLE_PATHS
+            .iter()
+            .map(|path| {
+                xr::Binding::new(
+                    &action,
+                    instance
+                        .string_to_path(&format!("{path}/input/grip/pose"))
+                        .unwrap(),
+                )
+            })
+            .collect();
+        instance
+            .suggest_interaction_profile_bindings(
+                instance
+                    .string_to_path(ViveTracker::profile_path())
+                    .unwrap(),
+                &bindings,
+            )
+            .unwrap();
+
+        Self { action }
+    }
+
+    pub fn create_space(
+        &self,
+        session: &xr::Session<xr::AnyGraphics>,
+        role_path: xr::Path,
+    ) -> xr::Result<xr::Space> {
+        self.action
+            .create_space(session, role_path, xr::Posef::IDENTITY)
+    }
+}
+
+/// A connected tracker that has been assigned a role.
+pub(super) struct ViveTrackerInfo {
+    /// The last component of the tracker's persistent path.
+    pub serial: String,
+    pub role_path: xr::Path,
+    /// The last component of the role path, e.g. "left_foot".
+    pub role_name: String,
+}
+
+/// Lists the connected trackers with xrEnumerateViveTrackerPathsHTCX. Trackers without a role are
+/// skipped, since there's no path to create their space from.
+pub(super) fn enumerate_trackers(instance: &xr::Instance) -> xr::Result<Vec<ViveTrackerInfo>> {
+    let Some(fp) = instance.exts().htcx_vive_tracker_interaction.as_ref() else {
+        return Err(xr::sys::Result::ERROR_EXTENSION_NOT_PRESENT);
+    };
+
+    let mut count = 0;
+    let ret = unsafe {
+        (fp.enumerate_vive_tracker_paths)(instance.as_raw(), 0, &mut count, std::ptr::null_mut())
+    };
+    if ret.into_raw() < 0 {
+        return Err(ret);
+    }
+
+    let mut paths = vec![
+        xr::sys::ViveTrackerPathsHTCX {
+            ty: xr::sys::ViveTrackerPathsHTCX::TYPE,
+            next: std::ptr::null_mut(),
+            persistent_path: xr::Path::NULL,
+            role_path: xr::Path::NULL,
+        };
+        count as usize
+    ];
+    let ret = unsafe {
+        (fp.enumerate_vive_tracker_paths)(
+            instance.as_raw(),
+            paths.len() as u32,
+            &mut count,
+            paths.as_mut_ptr(),
+        )
+    };
+    if ret.into_raw() < 0 {
+        return Err(ret);
+    }
+    paths.truncate(count as usize);
+
+    let last_component = |path: String| path.rsplit('/').next().unwrap_or_default().to_owned();
+    let mut trackers = Vec::with_capacity(paths.len());
+    for paths in paths {
+        let persistent_path = instance.path_to_string(paths.persistent_path)?;
+        if paths.role_path == xr::Path::NULL {
+            trace!("Vive tracker {persistent_path} has no role, skipping");
+            continue;
+        }
+        trackers.push(ViveTrackerInfo {
+            serial: last_component(persistent_path),
+            role_path: paths.role_path,
+            role_name: last_component(instance.path_to_string(paths.role_path)?),
+        });
+    }
+
+    Ok(trackers)
+}
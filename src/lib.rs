@@ -3,6 +3,7 @@
 mod applications;
 mod chaperone;
 mod clientcore;
+mod clock;
 mod compositor;
 mod graphics_backends;
 mod input;
@@ -24,7 +25,7 @@ use std::ffi::{CStr, c_char, c_void};
 use std::sync::OnceLock;
 use std::sync::{
     Arc,
-    atomic::{AtomicU32, AtomicU64, Ordering},
+    atomic::{AtomicU32, Ordering},
 };
 
 macro_rules! warn_unimplemented {
@@ -97,7 +98,6 @@ macro_rules! atomic_float {
 }
 
 atomic_float!(AtomicF32, f32, AtomicU32);
-atomic_float!(AtomicF64, f64, AtomicU64);
 
 fn init_logging() {
     static ONCE: std::sync::Once = std::sync::Once::new();
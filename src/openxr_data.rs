@@ -1,18 +1,21 @@
 use crate::{
+    AtomicF32,
     clientcore::{Injected, Injector},
     graphics_backends::{GraphicsBackend, VulkanData, supported_apis_enum},
+    settings::XrizerSettings,
 };
 use derive_more::Deref;
 use glam::f32::{Quat, Vec3};
 use log::{info, warn};
 use openvr as vr;
 use openxr as xr;
+use std::collections::VecDeque;
 use std::mem::ManuallyDrop;
 use std::sync::{
-    RwLock,
-    atomic::{AtomicI64, Ordering},
+    Mutex, RwLock,
+    atomic::{AtomicBool, AtomicI64, Ordering},
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "monado")]
 use openxr_mndx_xdev_space::XR_MNDX_XDEV_SPACE_EXTENSION_NAME;
@@ -42,7 +45,16 @@ pub struct OpenXrData<C: Compositor> {
     pub session_data: SessionReadGuard,
     pub display_time: AtomicXrTime,
     pub display_period_nanos: AtomicI64,
+    pub frame_timings: Mutex<FrameTimings>,
+    /// The last refresh rate requested by the application, reapplied on session restart.
+    requested_refresh_rate: Mutex<Option<f32>>,
+    /// Whether the application has asked for passthrough, reapplied on session restart.
+    passthrough_requested: AtomicBool,
+    /// Multiplier applied to the recommended render target size.
+    render_target_scale: AtomicF32,
     pub enabled_extensions: xr::ExtensionSet,
+    /// Whether the user is wearing the headset, going by the session's visibility.
+    user_present: AtomicBool,
 
     /// should only be externally accessed for testing
     pub(crate) input: Injected<crate::input::Input<C>>,
@@ -127,14 +139,22 @@ impl<C: Compositor> OpenXrData<C> {
         exts.khr_opengl_enable = supported_exts.khr_opengl_enable;
         exts.khr_convert_timespec_time = supported_exts.khr_convert_timespec_time;
         exts.ext_hand_tracking = supported_exts.ext_hand_tracking;
+        exts.ext_hand_tracking_data_source =
+            supported_exts.ext_hand_tracking && supported_exts.ext_hand_tracking_data_source;
         exts.khr_visibility_mask = supported_exts.khr_visibility_mask;
         exts.khr_composition_layer_cylinder = supported_exts.khr_composition_layer_cylinder;
         exts.khr_composition_layer_equirect2 = supported_exts.khr_composition_layer_equirect2;
+        exts.khr_composition_layer_depth = supported_exts.khr_composition_layer_depth;
         exts.khr_composition_layer_color_scale_bias =
             supported_exts.khr_composition_layer_color_scale_bias;
         exts.htc_vive_focus3_controller_interaction =
             supported_exts.htc_vive_focus3_controller_interaction;
+        exts.fb_touch_controller_pro = supported_exts.fb_touch_controller_pro;
+        exts.bd_controller_interaction = supported_exts.bd_controller_interaction;
+        exts.ext_hp_mixed_reality_controller = supported_exts.ext_hp_mixed_reality_controller;
         exts.fb_display_refresh_rate = supported_exts.fb_display_refresh_rate;
+        exts.fb_passthrough = supported_exts.fb_passthrough;
+        exts.ext_eye_gaze_interaction = supported_exts.ext_eye_gaze_interaction;
 
         // Extension that enables simple full body tracking support via generic tracked devices.
         // Available only in the Monado OpenXR runtime.
@@ -146,6 +166,8 @@ impl<C: Compositor> OpenXrData<C> {
             exts.other
                 .push(XR_MNDX_XDEV_SPACE_EXTENSION_NAME.to_string());
         }
+        // Vive trackers on runtimes without the xdev extension, i.e. SteamVR.
+        exts.htcx_vive_tracker_interaction = supported_exts.htcx_vive_tracker_interaction;
 
         let instance = entry
             .create_instance(
@@ -188,7 +210,15 @@ impl<C: Compositor> OpenXrData<C> {
             session_data,
             display_time: AtomicXrTime(display_time.into()), // This will get replaced on the first WaitGetPoses
             display_period_nanos: 11111111.into(), // This will get replaced on the first WaitGetPoses
+            frame_timings: Mutex::default(),
+            requested_refresh_rate: Mutex::new(None),
+            passthrough_requested: false.into(),
+            render_target_scale: Self::clamp_render_target_scale(
+                XrizerSettings::get().render_scale,
+            )
+            .into(),
             enabled_extensions: exts,
+            user_present: true.into(),
             input: injector.inject(),
             compositor: injector.inject(),
         })
@@ -197,11 +227,76 @@ impl<C: Compositor> OpenXrData<C> {
     pub fn poll_events(&self) {
         let data = self.session_data.get();
         if let Some(state) = self.poll_events_impl(&data) {
+            // Sessions we stop ourselves (i.e., for restarts) don't go through here,
+            // so this is the runtime asking us to exit. Stopping on its own is a normal
+            // transition (the session may become ready again), so only exiting means quit.
+            let is_stopped = |state| {
+                matches!(
+                    state,
+                    xr::SessionState::STOPPING
+                        | xr::SessionState::LOSS_PENDING
+                        | xr::SessionState::EXITING
+                )
+            };
+            if state == xr::SessionState::EXITING && data.state != xr::SessionState::EXITING {
+                info!("Runtime requested exit, sending quit event");
+                if let Some(input) = self.input.get() {
+                    input.queue_event(vr::EVREventType::Quit, vr::k_unTrackedDeviceIndexInvalid);
+                }
+            } else if !is_stopped(state) {
+                self.update_user_presence(data.state, state);
+            }
             drop(data);
             self.session_data.0.write().unwrap().state = state;
         }
     }
 
+    /// Runtimes hide the session (dropping it out of VISIBLE/FOCUSED) when the headset is
+    /// taken off, which is the closest thing to a proximity sensor core OpenXR gives us.
+    fn update_user_presence(&self, old_state: xr::SessionState, state: xr::SessionState) {
+        let present = matches!(state, xr::SessionState::VISIBLE | xr::SessionState::FOCUSED);
+        // Only report removal once the session has actually been shown, so the startup
+        // transitions through SYNCHRONIZED don't look like the user taking the headset off.
+        let was_present = self.user_present.load(Ordering::Relaxed);
+        let shown_before = matches!(
+            old_state,
+            xr::SessionState::VISIBLE | xr::SessionState::FOCUSED
+        );
+        if present == was_present || (!present && !shown_before) {
+            return;
+        }
+
+        self.user_present.store(present, Ordering::Relaxed);
+        info!(
+            "User {} the headset",
+            if present { "put on" } else { "took off" }
+        );
+        if let Some(input) = self.input.get() {
+            input.queue_event(
+                if present {
+                    vr::EVREventType::TrackedDeviceUserInteractionStarted
+                } else {
+                    vr::EVREventType::TrackedDeviceUserInteractionEnded
+                },
+                vr::k_unTrackedDeviceIndex_Hmd,
+            );
+        }
+    }
+
+    pub fn is_user_present(&self) -> bool {
+        self.user_present.load(Ordering::Relaxed)
+    }
+
+    /// The runtime's current time. Without XR_KHR_convert_timespec_time there's no way to ask
+    /// for it, so this falls back to the display time.
+    pub fn now(&self) -> xr::Time {
+        self.enabled_extensions
+            .khr_convert_timespec_time
+            .then(|| self.instance.now().ok())
+            .flatten()
+            .unwrap_or_else(|| self.display_time.get())
+    }
+
     fn poll_events_impl(&self, session_data: &SessionData) -> Option<xr::SessionState> {
         let mut buf = xr::EventDataBuffer::new();
         let mut state = None;
@@ -216,6 +311,22 @@ impl<C: Compositor> OpenXrData<C> {
                         input.interaction_profile_changed(session_data);
                     }
                 }
+                xr::Event::ViveTrackerConnectedHTCX(_) => {
+                    if let Some(input) = self.input.get() {
+                        input.vive_tracker_connected(session_data);
+                    }
+                }
+                xr::Event::VisibilityMaskChangedKHR(event) => {
+                    info!("Visibility mask changed for view {}", event.view_index());
+                    session_data.system_data.invalidate_hidden_area_meshes();
+                }
+                xr::Event::DisplayRefreshRateChangedFB(event) => {
+                    info!(
+                        "Display refresh rate changed: {} -> {}",
+                        event.from_display_refresh_rate(),
+                        event.to_display_refresh_rate()
+                    );
+                }
                 _ => {
                     info!("unknown event");
                 }
@@ -244,6 +355,14 @@ impl<C: Compositor> OpenXrData<C> {
             SessionData::new(&self.instance, self.system_id, origin, Some(&info))
                 .expect("Failed to initalize new session");
 
+        if let Some(rate) = *self.requested_refresh_rate.lock().unwrap() {
+            Self::request_session_refresh_rate(&session, rate);
+        }
+
+        if self.passthrough_requested.load(Ordering::Relaxed) {
+            session.set_passthrough(true);
+        }
+
         comp.post_session_restart(&session, waiter, stream);
 
         if let Some(input) = self.input.get() {
@@ -308,21 +427,86 @@ impl<C: Compositor> OpenXrData<C> {
                 .unwrap();
         };
 
-        match origin {
+        let event = match origin {
             vr::ETrackingUniverseOrigin::RawAndUncalibrated => {
                 // RawAndUncalibrated has no calibration to reset
+                return;
+            }
+            vr::ETrackingUniverseOrigin::Standing => {
+                reset_space(
+                    stage_space_reference,
+                    stage_space_adjusted,
+                    xr::ReferenceSpaceType::STAGE,
+                );
+                vr::EVREventType::StandingZeroPoseReset
+            }
+            vr::ETrackingUniverseOrigin::Seated => {
+                reset_space(
+                    local_space_reference,
+                    local_space_adjusted,
+                    xr::ReferenceSpaceType::LOCAL,
+                );
+                vr::EVREventType::SeatedZeroPoseReset
             }
-            vr::ETrackingUniverseOrigin::Standing => reset_space(
-                stage_space_reference,
-                stage_space_adjusted,
-                xr::ReferenceSpaceType::STAGE,
-            ),
-            vr::ETrackingUniverseOrigin::Seated => reset_space(
-                local_space_reference,
-                local_space_adjusted,
-                xr::ReferenceSpaceType::LOCAL,
-            ),
         };
+        guard.input_data.clear_pose_caches();
+        drop(guard);
+
+        // Let the app know so it can move its world to match. The first field overlaps
+        // VREvent_SeatedZeroPoseReset_t::bResetBySystemMenu, which stays false.
+        if let Some(input) = self.input.get() {
+            input.queue_event_with_data(
+                event,
+                vr::k_unTrackedDeviceIndexInvalid,
+                vr::VREvent_Data_t {
+                    chaperone: vr::VREvent_Chaperone_t {
+                        m_nCurrentUniverse: origin as u64,
+                        ..Default::default()
+                    },
+                },
+            );
+        }
+    }
+
+    /// The seated zero pose, as set by the last seated reset, in the standing space.
+    pub fn seated_zero_pose_in_standing(&self) -> xr::Posef {
+        let data = self.session_data.get();
+        // Locate the standing space in the seated space and invert it, instead of locating the
+        // seated space directly, because some runtimes (and fakexr) don't like STAGE as a base.
+        let Some(xr::Posef {
+            position,
+            orientation,
+        }) = data
+            .stage_space_adjusted
+            .locate(&data.local_space_adjusted, self.display_time.get())
+            .ok()
+            .filter(|loc| {
+                loc.location_flags.contains(
+                    xr::SpaceLocationFlags::POSITION_VALID
+                        | xr::SpaceLocationFlags::ORIENTATION_VALID,
+                )
+            })
+            .map(|loc| loc.pose)
+        else {
+            return xr::Posef::IDENTITY;
+        };
+
+        let rotation =
+            Quat::from_xyzw(orientation.x, orientation.y, orientation.z, orientation.w).inverse();
+        let position = -(rotation * Vec3::new(position.x, position.y, position.z));
+        xr::Posef {
+            position: xr::Vector3f {
+                x: position.x,
+                y: position.y,
+                z: position.z,
+            },
+            orientation: xr::Quaternionf {
+                x: rotation.x,
+                y: rotation.y,
+                z: rotation.z,
+                w: rotation.w,
+            },
+        }
     }
 
     pub fn get_refresh_rate(&self) -> f32 {
@@ -334,6 +518,7 @@ impl<C: Compositor> OpenXrData<C> {
                     .unwrap(),
             )
             .as_secs_f32()
+            .recip()
         };
         if !self.enabled_extensions.fb_display_refresh_rate {
             return get_fallback_rate();
@@ -346,12 +531,107 @@ impl<C: Compositor> OpenXrData<C> {
             .unwrap_or_else(|_| get_fallback_rate())
     }
 
+    /// Requests the runtime switch to the supported refresh rate closest to `rate`.
+    /// Returns the rate that was requested, or None if the refresh rate can't be changed.
+    pub fn request_refresh_rate(&self, rate: f32) -> Option<f32> {
+        if !self.enabled_extensions.fb_display_refresh_rate {
+            return None;
+        }
+
+        let rate = Self::request_session_refresh_rate(&self.session_data.get(), rate)?;
+        *self.requested_refresh_rate.lock().unwrap() = Some(rate);
+        Some(rate)
+    }
+
+    fn request_session_refresh_rate(session_data: &SessionData, rate: f32) -> Option<f32> {
+        let closest = session_data
+            .refresh_rates
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - rate).abs().total_cmp(&(b - rate).abs()))?;
+
+        session_data
+            .session
+            .request_display_refresh_rate(closest)
+            .inspect_err(|e| warn!("Failed to request refresh rate of {closest}: {e}"))
+            .ok()?;
+        info!("Requested refresh rate of {closest} (wanted {rate})");
+        Some(closest)
+    }
+
+    /// Enables or disables compositing the passthrough camera feed under the projection layer.
+    /// Returns false if passthrough isn't supported by the runtime.
+    pub fn set_passthrough(&self, enabled: bool) -> bool {
+        if !self.enabled_extensions.fb_passthrough {
+            return false;
+        }
+
+        if !self.session_data.get().set_passthrough(enabled) {
+            return false;
+        }
+
+        let was_enabled = self.passthrough_requested.swap(enabled, Ordering::Relaxed);
+        if was_enabled != enabled {
+            if let Some(input) = self.input.get() {
+                input.queue_event(
+                    if enabled {
+                        vr::EVREventType::RoomViewShown
+                    } else {
+                        vr::EVREventType::RoomViewHidden
+                    },
+                    vr::k_unTrackedDeviceIndexInvalid,
+                );
+            }
+        }
+        true
+    }
+
+    pub fn passthrough_enabled(&self) -> bool {
+        self.passthrough_requested.load(Ordering::Relaxed)
+    }
+
+    fn clamp_render_target_scale(scale: f32) -> f32 {
+        if scale.is_finite() && scale > 0.0 {
+            scale.clamp(0.1, 4.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn render_target_scale(&self) -> f32 {
+        self.render_target_scale.load()
+    }
+
+    /// Sets the multiplier for the recommended render target size. Only applications that query
+    /// the size again will pick it up.
+    pub fn set_render_target_scale(&self, scale: f32) {
+        let scale = Self::clamp_render_target_scale(scale);
+        info!("Setting render target scale to {scale}");
+        self.render_target_scale.store(scale);
+    }
+
+    /// Ends the session once the application has acknowledged that it's quitting, so the runtime
+    /// isn't left waiting on it.
+    pub fn end_session_for_quit(&self) {
+        let mut session_guard = self.session_data.0.write().unwrap();
+        self.end_session(&mut session_guard);
+        session_guard.comp_data.clear();
+    }
+
     fn end_session(&self, session_data: &mut SessionData) {
-        session_data.session.request_exit().unwrap();
         let mut state = session_data.state;
-        while state != xr::SessionState::STOPPING {
-            if let Some(s) = self.poll_events_impl(session_data) {
-                state = s;
+        match state {
+            // Already ended, or lost - there's nothing left to end.
+            xr::SessionState::EXITING | xr::SessionState::LOSS_PENDING => return,
+            // The runtime already asked us to stop.
+            xr::SessionState::STOPPING => {}
+            _ => {
+                session_data.session.request_exit().unwrap();
+                while state != xr::SessionState::STOPPING {
+                    if let Some(s) = self.poll_events_impl(session_data) {
+                        state = s;
+                    }
+                }
             }
         }
         #[cfg(test)]
@@ -364,6 +644,7 @@ impl<C: Compositor> OpenXrData<C> {
                 state = s;
             }
         }
+        session_data.state = state;
     }
 }
 
@@ -381,6 +662,83 @@ impl AtomicXrTime {
     }
 }
 
+/// Timing information for a frame presented by the application.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameTiming {
+    pub index: u32,
+    /// Seconds since the first frame timing was recorded.
+    pub system_time: f64,
+    /// The time since the previous frame was presented, or the display period for the first frame.
+    pub interval_ms: f32,
+    /// The time spent in xrEndFrame.
+    pub present_ms: f32,
+    /// Whether the frame didn't make it to the display, either because xrEndFrame failed or
+    /// because it came too late for its display refresh.
+    pub dropped: bool,
+}
+
+/// Ring buffer of the most recently presented frames.
+pub struct FrameTimings {
+    start: Instant,
+    last_present: Option<Instant>,
+    next_index: u32,
+    frames: VecDeque<FrameTiming>,
+}
+
+impl Default for FrameTimings {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            last_present: None,
+            next_index: 1,
+            frames: VecDeque::with_capacity(Self::CAPACITY),
+        }
+    }
+}
+
+impl FrameTimings {
+    pub const CAPACITY: usize = 128;
+
+    /// Records a frame that was presented at `now`. `ended` is whether xrEndFrame succeeded.
+    pub fn push(&mut self, now: Instant, present_ms: f32, display_period_nanos: i64, ended: bool) {
+        let period_ms = display_period_nanos as f32 / 1_000_000.0;
+        let interval_ms = self
+            .last_present
+            .replace(now)
+            .map(|last| now.duration_since(last).as_secs_f32() * 1000.0)
+            .unwrap_or(period_ms);
+        // Frames are paced to the display, so a frame that took over half a period longer than
+        // that missed its refresh and the runtime had to show an older one again.
+        let dropped = !ended || interval_ms > period_ms * 1.5;
+
+        if self.frames.len() == Self::CAPACITY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(FrameTiming {
+            index: self.next_index,
+            system_time: now.duration_since(self.start).as_secs_f64(),
+            interval_ms,
+            present_ms,
+            dropped,
+        });
+        self.next_index = self.next_index.wrapping_add(1);
+    }
+
+    /// Gets the timing for the frame presented `frames_ago` frames before the most recent one, if
+    /// we still have it.
+    pub fn get(&self, frames_ago: u32) -> Option<FrameTiming> {
+        let index = self.frames.len().checked_sub(frames_ago as usize + 1)?;
+        self.frames.get(index).copied()
+    }
+
+    /// The `count` most recent frames, oldest first.
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &FrameTiming> {
+        self.frames
+            .iter()
+            .skip(self.frames.len().saturating_sub(count))
+    }
+}
+
 pub struct SessionReadGuard(RwLock<ManuallyDrop<SessionData>>);
 impl SessionReadGuard {
     pub fn get(&self) -> std::sync::RwLockReadGuard<'_, ManuallyDrop<SessionData>> {
@@ -420,6 +778,12 @@ impl SessionCreateInfo {
     }
 }
 
+pub struct PassthroughData {
+    pub layer: xr::PassthroughLayer,
+    // Dropped after the layer, since the layer was created from it.
+    _passthrough: xr::Passthrough,
+}
+
 pub struct SessionData {
     pub session: xr::Session<xr::AnyGraphics>,
     session_graphics: GraphicalSession,
@@ -434,10 +798,15 @@ pub struct SessionData {
     stage_space_reference: xr::Space,
     stage_space_adjusted: xr::Space,
     pub current_origin: vr::ETrackingUniverseOrigin,
+    /// Refresh rates supported by XR_FB_display_refresh_rate. Empty if unsupported.
+    refresh_rates: Box<[f32]>,
+    /// The XR_FB_passthrough layer, present while passthrough is enabled.
+    pub passthrough: Mutex<Option<PassthroughData>>,
 
     pub input_data: crate::input::InputSessionData,
     pub comp_data: crate::compositor::CompositorSessionData,
     pub overlay_data: crate::overlay::OverlaySessionData,
+    pub system_data: crate::system::SystemSessionData,
     /// OpenXR requires graphics information before creating a session, but OpenVR clients don't
     /// have to provide that information until they actually submit a frame. Yet, we need some
     /// information only available behind a session (i.e., calling xrLocateViews for
@@ -559,6 +928,16 @@ impl SessionData {
             .map_err(SessionCreationError::BeginSessionFailed)?;
         info!("Began OpenXR session.");
 
+        let refresh_rates = if instance.exts().fb_display_refresh_rate.is_some() {
+            session
+                .enumerate_display_refresh_rates()
+                .inspect_err(|e| warn!("Failed to enumerate display refresh rates: {e}"))
+                .unwrap_or_default()
+                .into_boxed_slice()
+        } else {
+            Default::default()
+        };
+
         Ok((
             SessionData {
                 temp_vulkan,
@@ -573,13 +952,54 @@ impl SessionData {
                 input_data: Default::default(),
                 comp_data: Default::default(),
                 overlay_data: Default::default(),
+                system_data: Default::default(),
                 current_origin,
+                refresh_rates,
+                passthrough: Mutex::new(None),
             },
             waiter,
             stream,
         ))
     }
 
+    fn set_passthrough(&self, enabled: bool) -> bool {
+        let mut passthrough = self.passthrough.lock().unwrap();
+        if !enabled {
+            if passthrough.take().is_some() {
+                info!("Disabled passthrough");
+            }
+            return true;
+        }
+        if passthrough.is_some() {
+            return true;
+        }
+
+        let flags = xr::PassthroughFlagsFB::IS_RUNNING_AT_CREATION;
+        let data = self.session.create_passthrough(flags).and_then(|pt| {
+            let layer = self.session.create_passthrough_layer(
+                &pt,
+                flags,
+                xr::PassthroughLayerPurposeFB::RECONSTRUCTION,
+            )?;
+            Ok(PassthroughData {
+                layer,
+                _passthrough: pt,
+            })
+        });
+
+        match data {
+            Ok(data) => {
+                *passthrough = Some(data);
+                info!("Enabled passthrough");
+                true
+            }
+            Err(e) => {
+                warn!("Failed to create passthrough layer: {e}");
+                false
+            }
+        }
+    }
+
     pub fn create_swapchain<G: xr::Graphics>(
         &self,
         info: &xr::SwapchainCreateInfo<G>,
@@ -599,6 +1019,33 @@ impl SessionData {
             .create_swapchain(info)
     }
 
+    /// The runtime's most preferred swapchain format.
+    pub fn preferred_swapchain_format<G: GraphicsBackend>(&self) -> <G::Api as xr::Graphics>::Format
+    where
+        for<'a> &'a GraphicalSession: TryInto<&'a Session<G::Api>, Error: std::fmt::Display>,
+    {
+        let session: &Session<G::Api> = (&self.session_graphics).try_into().unwrap_or_else(|e| {
+            panic!(
+                "Session was not using API {}: {e}",
+                std::any::type_name::<G::Api>()
+            )
+        });
+        session.swapchain_formats[0]
+    }
+
+    pub fn supports_format<G: GraphicsBackend>(
+        &self,
+        format: <G::Api as xr::Graphics>::Format,
+    ) -> bool
+    where
+        for<'a> &'a GraphicalSession: TryInto<&'a Session<G::Api>, Error: std::fmt::Display>,
+        <G::Api as xr::Graphics>::Format: PartialEq,
+    {
+        (&self.session_graphics)
+            .try_into()
+            .is_ok_and(|session: &Session<G::Api>| session.swapchain_formats.contains(&format))
+    }
+
     pub fn check_format<G: GraphicsBackend>(&self, info: &mut xr::SwapchainCreateInfo<G::Api>)
     where
         for<'a> &'a GraphicalSession: TryInto<&'a Session<G::Api>, Error: std::fmt::Display>,
@@ -635,10 +1082,8 @@ impl SessionData {
         match origin {
             vr::ETrackingUniverseOrigin::Seated => &self.local_space_adjusted,
             vr::ETrackingUniverseOrigin::Standing => &self.stage_space_adjusted,
-            vr::ETrackingUniverseOrigin::RawAndUncalibrated => {
-                crate::warn_unimplemented!("RawAndUncalibrated tracking space");
-                &self.stage_space_reference
-            }
+            // The raw stage, unaffected by any resets
+            vr::ETrackingUniverseOrigin::RawAndUncalibrated => &self.stage_space_reference,
         }
     }
 
@@ -656,11 +1101,8 @@ impl SessionData {
     pub fn current_origin_as_reference_space(&self) -> xr::ReferenceSpaceType {
         match self.current_origin {
             vr::ETrackingUniverseOrigin::Seated => xr::ReferenceSpaceType::LOCAL,
-            vr::ETrackingUniverseOrigin::Standing => xr::ReferenceSpaceType::STAGE,
-            vr::ETrackingUniverseOrigin::RawAndUncalibrated => {
-                crate::warn_unimplemented!("RawAndUncalibrated tracking space");
-                xr::ReferenceSpaceType::STAGE
-            }
+            vr::ETrackingUniverseOrigin::Standing
+            | vr::ETrackingUniverseOrigin::RawAndUncalibrated => xr::ReferenceSpaceType::STAGE,
         }
     }
 
@@ -747,7 +1189,7 @@ pub use tests::FakeCompositor;
 
 #[cfg(test)]
 mod tests {
-    use super::{FrameStream, GraphicsBackend, OpenXrData, SessionCreateInfo};
+    use super::{FrameStream, FrameTimings, GraphicsBackend, OpenXrData, SessionCreateInfo};
     use crate::clientcore::Injector;
     use openxr as xr;
     use std::ffi::CStr;
@@ -764,6 +1206,11 @@ mod tests {
             Self::new_with_barrier(xr, None)
         }
 
+        /// Makes the next session restart wait on the barrier, while it holds the session.
+        pub fn set_restart_barrier(&self, barrier: Arc<Barrier>) {
+            *self.barrier.lock().unwrap() = Some(barrier);
+        }
+
         fn new_with_barrier(xr: &OpenXrData<Self>, barrier: Option<Arc<Barrier>>) -> Self {
             Self {
                 backend: crate::graphics_backends::VulkanData::new_temporary(
@@ -839,4 +1286,44 @@ mod tests {
         drop(data); // Session must be dropped before Vulkan data.
         drop(comp);
     }
+
+    #[test]
+    fn now_from_runtime() {
+        let data = OpenXrData::<FakeCompositor>::new(&Injector::default()).unwrap();
+        assert!(data.enabled_extensions.khr_convert_timespec_time);
+        let time = xr::Time::from_nanos(data.display_time.get().as_nanos() - 5_000_000);
+        fakexr::set_now(data.instance.as_raw(), time);
+        assert_eq!(data.now().as_nanos(), time.as_nanos());
+    }
+
+    #[test]
+    fn now_without_convert_timespec_time() {
+        fakexr::disable_extensions(&[c"XR_KHR_convert_timespec_time"]);
+        let data = OpenXrData::<FakeCompositor>::new(&Injector::default()).unwrap();
+        assert!(!data.enabled_extensions.khr_convert_timespec_time);
+        assert_eq!(data.now().as_nanos(), data.display_time.get().as_nanos());
+    }
+
+    #[test]
+    fn frame_timings_dropped_frames() {
+        const PERIOD_NANOS: i64 = 10_000_000;
+        let start = std::time::Instant::now();
+        let at_ms = |ms| start + std::time::Duration::from_millis(ms);
+
+        let mut timings = FrameTimings::default();
+        timings.push(at_ms(0), 1.0, PERIOD_NANOS, true);
+        timings.push(at_ms(10), 1.0, PERIOD_NANOS, true);
+        // Missed a refresh.
+        timings.push(at_ms(30), 1.0, PERIOD_NANOS, true);
+        // On time, but xrEndFrame failed.
+        timings.push(at_ms(40), 1.0, PERIOD_NANOS, false);
+
+        let dropped: Vec<_> = (0..4)
+            .rev()
+            .map(|frames_ago| timings.get(frames_ago).unwrap().dropped)
+            .collect();
+        assert_eq!(dropped, [false, false, true, true]);
+        assert_eq!(timings.get(0).unwrap().interval_ms, 10.0);
+        assert!(timings.get(4).is_none());
+    }
 }
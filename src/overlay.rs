@@ -30,6 +30,8 @@ pub struct OverlayMan {
     overlays: RwLock<SlotMap<OverlayKey, Overlay>>,
     key_to_overlay: RwLock<HashMap<CString, OverlayKey>>,
     skybox: RwLock<Vec<OverlayKey>>,
+    /// The dashboard overlay currently being shown, if any.
+    active_dashboard: RwLock<Option<OverlayKey>>,
 }
 
 #[derive(derive_more::Deref)]
@@ -44,9 +46,20 @@ impl OverlayMan {
             overlays: Default::default(),
             key_to_overlay: Default::default(),
             skybox: Default::default(),
+            active_dashboard: Default::default(),
         }
     }
 
+    fn create_overlay(&self, key: &CStr, name: &CStr) -> OverlayKey {
+        let mut overlays = self.overlays.write().unwrap();
+        let ret_key = overlays.insert(Overlay::new(key.into(), name.into()));
+        let mut key_to_overlay = self.key_to_overlay.write().unwrap();
+        key_to_overlay.insert(key.into(), ret_key);
+
+        debug!("created overlay {name:?} with key {key:?}");
+        ret_key
+    }
+
     fn get_real_session_data(
         &self,
         texture: &vr::Texture_t,
@@ -87,6 +100,8 @@ impl OverlayMan {
                 let texture = textures.first().unwrap();
                 let name = CString::new("__xrizer_skybox").unwrap();
                 let key = overlays.insert(Overlay::new(name.clone(), name));
+                // Track the overlay right away, so it's cleaned up even if setting it up fails.
+                skybox.push(key);
                 let overlay = overlays.get_mut(key).unwrap();
 
                 self.get_real_session_data(texture, overlay.bounds)
@@ -96,13 +111,13 @@ impl OverlayMan {
                 overlay.width = SKYBOX_SIZE; // for equirect this becomes radius
                 overlay.kind = OverlayKind::Sphere;
                 overlay.z_order = SKYBOX_Z_ORDER;
-                skybox.push(key);
             }
             6 => {
                 for (idx, texture) in textures.iter().enumerate() {
                     // 6 quads forming a cursed box
                     let name = CString::new(format!("__xrizer_skybox_{idx}")).unwrap();
                     let key = overlays.insert(Overlay::new(name.clone(), name));
+                    skybox.push(key);
                     let overlay = overlays.get_mut(key).unwrap();
                     self.get_real_session_data(texture, overlay.bounds)
                         .and_then(|data| overlay.set_texture(key, data, *texture))
@@ -140,12 +155,10 @@ impl OverlayMan {
                         },
                     ];
 
-                    overlay.transform = Some((
-                        vr::ETrackingUniverseOrigin::Standing,
-                        QUAD_POSES[idx].into(),
-                    ));
-
-                    skybox.push(key);
+                    overlay.transform = Some(OverlayTransform::Absolute {
+                        origin: vr::ETrackingUniverseOrigin::Standing,
+                        transform: QUAD_POSES[idx].into(),
+                    });
                 }
             }
             _ => unreachable!(),
@@ -181,11 +194,19 @@ impl OverlayMan {
             )
         });
 
+        let active_dashboard = *self.active_dashboard.read().unwrap();
         let mut layers = Vec::with_capacity(overlays.len());
         for (key, overlay) in overlays.iter_mut() {
             if !overlay.visible {
                 continue;
             }
+            // We don't have a dashboard to show thumbnails in, and dashboard overlays are only
+            // visible while they're the active dashboard.
+            match overlay.dashboard {
+                Some(DashboardRole::Thumbnail) => continue,
+                Some(DashboardRole::Main { .. }) if active_dashboard != Some(key) => continue,
+                _ => {}
+            }
             if overlay.z_order == SKYBOX_Z_ORDER && !render_skybox {
                 continue;
             }
@@ -194,29 +215,14 @@ impl OverlayMan {
             };
 
             let SwapchainData { swapchain, .. } = swapchains.get(key).unwrap();
-            let space = session.get_space_for_origin(
-                overlay
-                    .transform
-                    .as_ref()
-                    .map(|(o, _)| *o)
-                    .unwrap_or(session.current_origin),
-            );
+            let Some((origin, pose)) = self.resolve_transform(session, overlay.transform) else {
+                trace!("couldn't resolve transform for overlay {:?}", overlay.name);
+                continue;
+            };
+            let space = session.get_space_for_origin(origin);
 
             trace!("overlay rect: {rect:#?}");
 
-            let pose = overlay
-                .transform
-                .as_ref()
-                .map(|(_, t)| (*t).into())
-                .unwrap_or(xr::Posef {
-                    position: xr::Vector3f {
-                        x: 0.0,
-                        y: 0.0,
-                        z: -0.5,
-                    },
-                    orientation: xr::Quaternionf::IDENTITY,
-                });
-
             macro_rules! layer_init {
                 ($ty:ident) => {{
                     $ty::new()
@@ -332,6 +338,35 @@ impl OverlayMan {
         sorted_layers
     }
 
+    /// Returns the space origin and pose an overlay should be placed at.
+    fn resolve_transform(
+        &self,
+        session: &SessionData,
+        transform: Option<OverlayTransform>,
+    ) -> Option<(vr::ETrackingUniverseOrigin, xr::Posef)> {
+        match transform {
+            None => Some((session.current_origin, DEFAULT_OVERLAY_POSE)),
+            Some(OverlayTransform::Absolute { origin, transform }) => {
+                Some((origin, transform.into()))
+            }
+            Some(OverlayTransform::TrackedDeviceRelative { device, transform }) => {
+                let origin = session.current_origin;
+                let device_pose = self
+                    .openxr
+                    .input
+                    .get()?
+                    .get_device_pose_in_session(session, device, origin)?;
+                if !device_pose.bPoseIsValid {
+                    return None;
+                }
+
+                let device_pose: xr::Posef = device_pose.mDeviceToAbsoluteTracking.into();
+                let relative: xr::Posef = transform.into();
+                Some((origin, compose_poses(device_pose, relative)))
+            }
+        }
+    }
+
     pub fn get_next_overlay_event(
         &self,
         _event: *mut vr::VREvent_t,
@@ -445,6 +480,106 @@ new_key_type!(
     pub(crate) struct OverlayKey;
 );
 
+/// Where overlays without a transform are placed, relative to the current origin.
+const DEFAULT_OVERLAY_POSE: xr::Posef = xr::Posef {
+    position: xr::Vector3f {
+        x: 0.0,
+        y: 0.0,
+        z: -0.5,
+    },
+    orientation: xr::Quaternionf::IDENTITY,
+};
+
+fn compose_poses(parent: xr::Posef, child: xr::Posef) -> xr::Posef {
+    let to_glam = |pose: xr::Posef| {
+        let (p, o) = (pose.position, pose.orientation);
+        (
+            Vec3::new(p.x, p.y, p.z),
+            Quat::from_xyzw(o.x, o.y, o.z, o.w),
+        )
+    };
+    let (parent_pos, parent_rot) = to_glam(parent);
+    let (child_pos, child_rot) = to_glam(child);
+
+    let position = parent_pos + parent_rot * child_pos;
+    let orientation = (parent_rot * child_rot).normalize();
+    xr::Posef {
+        position: xr::Vector3f {
+            x: position.x,
+            y: position.y,
+            z: position.z,
+        },
+        orientation: xr::Quaternionf {
+            x: orientation.x,
+            y: orientation.y,
+            z: orientation.z,
+            w: orientation.w,
+        },
+    }
+}
+
+/// Casts a ray against a quad of the given size centered on `pose`, facing its +Z axis.
+/// UVs have their origin at the bottom left of the quad.
+fn intersect_quad(
+    pose: xr::Posef,
+    width: f32,
+    height: f32,
+    source: Vec3,
+    direction: Vec3,
+) -> Option<vr::VROverlayIntersectionResults_t> {
+    let (p, o) = (pose.position, pose.orientation);
+    let position = Vec3::new(p.x, p.y, p.z);
+    let rotation = Quat::from_xyzw(o.x, o.y, o.z, o.w).normalize();
+    let direction = direction.try_normalize()?;
+
+    // Work in the quad's local space, where it lies on the XY plane.
+    let local_source = rotation.inverse() * (source - position);
+    let local_direction = rotation.inverse() * direction;
+    if local_direction.z.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let distance = -local_source.z / local_direction.z;
+    if distance < 0.0 {
+        return None;
+    }
+
+    let hit = local_source + local_direction * distance;
+    let u = hit.x / width + 0.5;
+    let v = hit.y / height + 0.5;
+    if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+        return None;
+    }
+
+    Some(vr::VROverlayIntersectionResults_t {
+        vPoint: vr::HmdVector3_t {
+            v: (source + direction * distance).to_array(),
+        },
+        vNormal: vr::HmdVector3_t {
+            v: (rotation * Vec3::Z).to_array(),
+        },
+        vUVs: vr::HmdVector2_t { v: [u, v] },
+        fDistance: distance,
+    })
+}
+
+/// Normalizes the rotation of an application provided transform.
+fn normalize_transform(transform: vr::HmdMatrix34_t) -> vr::HmdMatrix34_t {
+    let xr_transform: xr::Posef = transform.into();
+    let o = xr_transform.orientation;
+    let q = Quat::from_xyzw(o.x, o.y, o.z, o.w).normalize();
+    xr::Posef {
+        position: xr_transform.position,
+        orientation: xr::Quaternionf {
+            x: q.x,
+            y: q.y,
+            z: q.z,
+            w: q.w,
+        },
+    }
+    .into()
+}
+
 pub(crate) struct SwapchainData<G: xr::Graphics> {
     swapchain: xr::Swapchain<G>,
     info: xr::SwapchainCreateInfo<G>,
@@ -465,6 +600,24 @@ enum OverlayKind {
     Sphere,
 }
 
+#[derive(Copy, Clone)]
+enum OverlayTransform {
+    Absolute {
+        origin: vr::ETrackingUniverseOrigin,
+        transform: vr::HmdMatrix34_t,
+    },
+    TrackedDeviceRelative {
+        device: vr::TrackedDeviceIndex_t,
+        transform: vr::HmdMatrix34_t,
+    },
+}
+
+#[derive(Copy, Clone)]
+enum DashboardRole {
+    Main { thumbnail: OverlayKey },
+    Thumbnail,
+}
+
 struct Overlay {
     key: CString,
     name: CString,
@@ -475,9 +628,14 @@ struct Overlay {
     kind: OverlayKind,
     z_order: i64,
     bounds: vr::VRTextureBounds_t,
-    transform: Option<(vr::ETrackingUniverseOrigin, vr::HmdMatrix34_t)>,
+    transform: Option<OverlayTransform>,
     compositor: Option<SupportedBackend>,
     rect: Option<xr::Rect2Di>,
+    /// Size of the last submitted texture, before applying the texture bounds
+    texture_size: Option<(u32, u32)>,
+    /// Bitmask of VROverlayFlags
+    flags: u32,
+    dashboard: Option<DashboardRole>,
 }
 
 impl Overlay {
@@ -499,7 +657,24 @@ impl Overlay {
             transform: None,
             compositor: None,
             rect: None,
+            texture_size: None,
+            flags: 0,
+            dashboard: None,
+        }
+    }
+
+    /// The height of the overlay relative to its width, based on the last submitted texture and
+    /// the current texture bounds.
+    fn aspect_ratio(&self) -> f32 {
+        let Some((width, height)) = self.texture_size else {
+            return 1.0;
+        };
+        let width = width as f32 * (self.bounds.uMax - self.bounds.uMin).abs();
+        let height = height as f32 * (self.bounds.vMax - self.bounds.vMin).abs();
+        if width == 0.0 {
+            return 1.0;
         }
+        height / width
     }
 
     fn set_texture(
@@ -536,7 +711,7 @@ impl Overlay {
             map: &mut AnySwapchainMap,
             key: OverlayKey,
             texture: vr::Texture_t,
-        ) -> Result<xr::Extent2Di, vr::EVROverlayError>
+        ) -> Result<(xr::Extent2Di, (u32, u32)), vr::EVROverlayError>
         where
             for<'a> &'a mut SwapchainMap<G::Api>:
                 TryFrom<&'a mut AnySwapchainMap, Error: std::fmt::Display>,
@@ -555,6 +730,16 @@ impl Overlay {
             };
             let tex_swapchain_info =
                 backend.swapchain_info_for_texture(b_texture, texture_bounds, texture.eColorSpace);
+            let full_info = backend.swapchain_info_for_texture(
+                b_texture,
+                vr::VRTextureBounds_t {
+                    uMin: 0.0,
+                    vMin: 0.0,
+                    uMax: 1.0,
+                    vMax: 1.0,
+                },
+                texture.eColorSpace,
+            );
             let mut create_swapchain = || {
                 let mut info = backend.swapchain_info_for_texture(
                     b_texture,
@@ -590,11 +775,11 @@ impl Overlay {
             let extent = backend.copy_overlay_to_swapchain(b_texture, texture_bounds, idx as usize);
             swapchain.release_image().unwrap();
 
-            Ok(extent)
+            Ok((extent, (full_info.width, full_info.height)))
         }
 
         let backend = self.compositor.as_mut().unwrap();
-        let extent = backend.with_any_graphics_mut::<set_swapchain_texture>((
+        let (extent, texture_size) = backend.with_any_graphics_mut::<set_swapchain_texture>((
             &session_data,
             self.bounds,
             swapchains,
@@ -605,6 +790,7 @@ impl Overlay {
             extent,
             offset: xr::Offset2Di::default(),
         });
+        self.texture_size = Some(texture_size);
         Ok(())
     }
 }
@@ -638,16 +824,12 @@ impl vr::IVROverlay028_Interface for OverlayMan {
             return vr::EVROverlayError::InvalidParameter;
         }
 
-        let mut overlays = self.overlays.write().unwrap();
-        let ret_key = overlays.insert(Overlay::new(key.into(), name.into()));
-        let mut key_to_overlay = self.key_to_overlay.write().unwrap();
-        key_to_overlay.insert(key.into(), ret_key);
+        let ret_key = self.create_overlay(key, name);
 
         unsafe {
             handle.write(ret_key.data().as_ffi());
         }
 
-        debug!("created overlay {name:?} with key {key:?}");
         vr::EVROverlayError::None
     }
 
@@ -834,8 +1016,24 @@ impl vr::IVROverlay028_Interface for OverlayMan {
     fn GetPrimaryDashboardDevice(&self) -> vr::TrackedDeviceIndex_t {
         todo!()
     }
-    fn ShowDashboard(&self, _: *const c_char) {
-        todo!()
+    fn ShowDashboard(&self, overlay_to_show: *const c_char) {
+        let overlays = self.overlays.read().unwrap();
+        let is_dashboard =
+            |key: &OverlayKey| matches!(overlays[*key].dashboard, Some(DashboardRole::Main { .. }));
+
+        let requested = (!overlay_to_show.is_null())
+            .then(|| unsafe { CStr::from_ptr(overlay_to_show) })
+            .and_then(|key| self.key_to_overlay.read().unwrap().get(key).copied())
+            .filter(is_dashboard);
+
+        // If the requested overlay isn't a dashboard overlay, just show any dashboard overlay.
+        let Some(key) = requested.or_else(|| overlays.keys().find(is_dashboard)) else {
+            debug!("ShowDashboard called without any dashboard overlays");
+            return;
+        };
+
+        debug!("showing dashboard overlay {:?}", overlays[key].name);
+        *self.active_dashboard.write().unwrap() = Some(key);
     }
     fn GetDashboardOverlaySceneProcess(
         &self,
@@ -851,20 +1049,46 @@ impl vr::IVROverlay028_Interface for OverlayMan {
     ) -> vr::EVROverlayError {
         todo!()
     }
-    fn IsActiveDashboardOverlay(&self, _: vr::VROverlayHandle_t) -> bool {
-        todo!()
+    fn IsActiveDashboardOverlay(&self, handle: vr::VROverlayHandle_t) -> bool {
+        *self.active_dashboard.read().unwrap() == Some(OverlayKey::from(KeyData::from_ffi(handle)))
     }
     fn IsDashboardVisible(&self) -> bool {
-        false
+        self.active_dashboard.read().unwrap().is_some()
     }
     fn CreateDashboardOverlay(
         &self,
-        _: *const c_char,
-        _: *const c_char,
-        _: *mut vr::VROverlayHandle_t,
-        _: *mut vr::VROverlayHandle_t,
+        key: *const c_char,
+        name: *const c_char,
+        main_handle: *mut vr::VROverlayHandle_t,
+        thumbnail_handle: *mut vr::VROverlayHandle_t,
     ) -> vr::EVROverlayError {
-        todo!()
+        let key = unsafe { CStr::from_ptr(key) };
+        let name = unsafe { CStr::from_ptr(name) };
+
+        if main_handle.is_null() || thumbnail_handle.is_null() {
+            return vr::EVROverlayError::InvalidParameter;
+        }
+
+        let main = self.create_overlay(key, name);
+
+        // The thumbnail isn't findable by key, so it doesn't go into key_to_overlay.
+        let mut overlays = self.overlays.write().unwrap();
+        let thumbnail = overlays.insert(Overlay {
+            dashboard: Some(DashboardRole::Thumbnail),
+            ..Overlay::new(key.into(), name.into())
+        });
+        let main_overlay = &mut overlays[main];
+        main_overlay.dashboard = Some(DashboardRole::Main { thumbnail });
+        // Dashboard overlays are visible whenever their dashboard is shown.
+        main_overlay.visible = true;
+
+        unsafe {
+            main_handle.write(main.data().as_ffi());
+            thumbnail_handle.write(thumbnail.data().as_ffi());
+        }
+
+        debug!("created dashboard overlay {name:?} with key {key:?}");
+        vr::EVROverlayError::None
     }
     fn GetOverlayTextureSize(
         &self,
@@ -958,12 +1182,57 @@ impl vr::IVROverlay028_Interface for OverlayMan {
     }
     fn ComputeOverlayIntersection(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: *const vr::VROverlayIntersectionParams_t,
-        _: *mut vr::VROverlayIntersectionResults_t,
+        handle: vr::VROverlayHandle_t,
+        params: *const vr::VROverlayIntersectionParams_t,
+        results: *mut vr::VROverlayIntersectionResults_t,
     ) -> bool {
-        crate::warn_unimplemented!("ComputeOverlayIntersection");
-        false
+        let (Some(params), Some(results)) =
+            (unsafe { params.as_ref() }, unsafe { results.as_mut() })
+        else {
+            return false;
+        };
+        // Lock the session data before the overlays, in the same order as the compositor does when
+        // it collects the overlay layers.
+        let session = self.openxr.session_data.get();
+        let overlays = self.overlays.read().unwrap();
+        let Some(overlay) = overlays.get(OverlayKey::from(KeyData::from_ffi(handle))) else {
+            return false;
+        };
+        if !matches!(overlay.kind, OverlayKind::Quad) {
+            crate::warn_once!("Intersections are only supported for quad overlays");
+            return false;
+        }
+
+        let Some((origin, mut pose)) = self.resolve_transform(&session, overlay.transform) else {
+            return false;
+        };
+        if origin != params.eOrigin {
+            let Ok(location) = session.get_space_for_origin(origin).locate(
+                session.get_space_for_origin(params.eOrigin),
+                self.openxr.display_time.get(),
+            ) else {
+                return false;
+            };
+            if !location.location_flags.contains(
+                xr::SpaceLocationFlags::POSITION_VALID | xr::SpaceLocationFlags::ORIENTATION_VALID,
+            ) {
+                return false;
+            }
+            pose = compose_poses(location.pose, pose);
+        }
+
+        let vec = |v: vr::HmdVector3_t| Vec3::from_array(v.v);
+        let Some(intersection) = intersect_quad(
+            pose,
+            overlay.width,
+            overlay.width * overlay.aspect_ratio(),
+            vec(params.vSource),
+            vec(params.vDirection),
+        ) else {
+            return false;
+        };
+        *results = intersection;
+        true
     }
     fn SetOverlayMouseScale(
         &self,
@@ -1078,28 +1347,75 @@ impl vr::IVROverlay028_Interface for OverlayMan {
     }
     fn GetOverlayTransformTrackedDeviceRelative(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: *mut vr::TrackedDeviceIndex_t,
-        _: *mut vr::HmdMatrix34_t,
+        handle: vr::VROverlayHandle_t,
+        device_index: *mut vr::TrackedDeviceIndex_t,
+        transform: *mut vr::HmdMatrix34_t,
     ) -> vr::EVROverlayError {
-        todo!()
+        get_overlay!(self, handle, overlay);
+        let Some(OverlayTransform::TrackedDeviceRelative {
+            device,
+            transform: relative,
+        }) = overlay.transform
+        else {
+            return vr::EVROverlayError::WrongTransformType;
+        };
+        if device_index.is_null() || transform.is_null() {
+            return vr::EVROverlayError::InvalidParameter;
+        }
+
+        unsafe {
+            device_index.write(device);
+            transform.write(relative);
+        }
+        vr::EVROverlayError::None
     }
     fn SetOverlayTransformTrackedDeviceRelative(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: vr::TrackedDeviceIndex_t,
-        _: *const vr::HmdMatrix34_t,
+        handle: vr::VROverlayHandle_t,
+        device_index: vr::TrackedDeviceIndex_t,
+        transform: *const vr::HmdMatrix34_t,
     ) -> vr::EVROverlayError {
-        crate::warn_unimplemented!("SetOverlayTransformTrackedDeviceRelative");
+        get_overlay!(self, handle, mut overlay);
+        if transform.is_null() || device_index >= vr::k_unMaxTrackedDeviceCount {
+            return vr::EVROverlayError::InvalidParameter;
+        }
+
+        let transform = normalize_transform(unsafe { transform.read() });
+        overlay.transform = Some(OverlayTransform::TrackedDeviceRelative {
+            device: device_index,
+            transform,
+        });
+        debug!(
+            "set overlay transform relative to device {device_index} for {:?} ({:?})",
+            overlay.name,
+            xr::Posef::from(transform)
+        );
         vr::EVROverlayError::None
     }
     fn GetOverlayTransformAbsolute(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: *mut vr::ETrackingUniverseOrigin,
-        _: *mut vr::HmdMatrix34_t,
+        handle: vr::VROverlayHandle_t,
+        origin: *mut vr::ETrackingUniverseOrigin,
+        transform: *mut vr::HmdMatrix34_t,
     ) -> vr::EVROverlayError {
-        todo!()
+        get_overlay!(self, handle, overlay);
+        let (abs_origin, abs_transform) = match overlay.transform {
+            Some(OverlayTransform::Absolute { origin, transform }) => (origin, transform),
+            None => (
+                self.openxr.get_tracking_space(),
+                DEFAULT_OVERLAY_POSE.into(),
+            ),
+            Some(_) => return vr::EVROverlayError::WrongTransformType,
+        };
+        if origin.is_null() || transform.is_null() {
+            return vr::EVROverlayError::InvalidParameter;
+        }
+
+        unsafe {
+            origin.write(abs_origin);
+            transform.write(abs_transform);
+        }
+        vr::EVROverlayError::None
     }
     fn SetOverlayTransformAbsolute(
         &self,
@@ -1111,33 +1427,33 @@ impl vr::IVROverlay028_Interface for OverlayMan {
         if transform.is_null() {
             vr::EVROverlayError::InvalidParameter
         } else {
-            let transform = unsafe { transform.read() };
-            let xr_transform: xr::Posef = transform.into();
-            let o = xr_transform.orientation;
-            let q = Quat::from_xyzw(o.x, o.y, o.z, o.w).normalize();
-            let transform = xr::Posef {
-                position: xr_transform.position,
-                orientation: xr::Quaternionf {
-                    x: q.x,
-                    y: q.y,
-                    z: q.z,
-                    w: q.w,
-                },
-            };
-            overlay.transform = Some((origin, transform.into()));
+            let transform = normalize_transform(unsafe { transform.read() });
+            overlay.transform = Some(OverlayTransform::Absolute { origin, transform });
             debug!(
-                "set overlay transform origin to {origin:?} for {:?} ({transform:?})",
-                overlay.name
+                "set overlay transform origin to {origin:?} for {:?} ({:?})",
+                overlay.name,
+                xr::Posef::from(transform)
             );
             vr::EVROverlayError::None
         }
     }
     fn GetOverlayTransformType(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: *mut vr::VROverlayTransformType,
+        handle: vr::VROverlayHandle_t,
+        transform_type: *mut vr::VROverlayTransformType,
     ) -> vr::EVROverlayError {
-        todo!()
+        get_overlay!(self, handle, overlay);
+        let Some(transform_type) = (unsafe { transform_type.as_mut() }) else {
+            return vr::EVROverlayError::InvalidParameter;
+        };
+
+        *transform_type = match overlay.transform {
+            None | Some(OverlayTransform::Absolute { .. }) => vr::VROverlayTransformType::Absolute,
+            Some(OverlayTransform::TrackedDeviceRelative { .. }) => {
+                vr::VROverlayTransformType::TrackedDeviceRelative
+            }
+        };
+        vr::EVROverlayError::None
     }
     fn GetOverlayTextureBounds(
         &self,
@@ -1291,24 +1607,50 @@ impl vr::IVROverlay028_Interface for OverlayMan {
         crate::warn_unimplemented!("SetOverlayColor");
         vr::EVROverlayError::None
     }
-    fn GetOverlayFlags(&self, _: vr::VROverlayHandle_t, _: *mut u32) -> vr::EVROverlayError {
-        todo!()
+    fn GetOverlayFlags(
+        &self,
+        handle: vr::VROverlayHandle_t,
+        flags: *mut u32,
+    ) -> vr::EVROverlayError {
+        get_overlay!(self, handle, overlay);
+        let Some(flags) = (unsafe { flags.as_mut() }) else {
+            return vr::EVROverlayError::InvalidParameter;
+        };
+
+        *flags = overlay.flags;
+        vr::EVROverlayError::None
     }
     fn GetOverlayFlag(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: vr::VROverlayFlags,
-        _: *mut bool,
+        handle: vr::VROverlayHandle_t,
+        flag: vr::VROverlayFlags,
+        enabled: *mut bool,
     ) -> vr::EVROverlayError {
-        todo!()
+        get_overlay!(self, handle, overlay);
+        let Some(enabled) = (unsafe { enabled.as_mut() }) else {
+            return vr::EVROverlayError::InvalidParameter;
+        };
+
+        *enabled = overlay.flags & flag as u32 != 0;
+        vr::EVROverlayError::None
     }
     fn SetOverlayFlag(
         &self,
-        _: vr::VROverlayHandle_t,
-        _: vr::VROverlayFlags,
-        _: bool,
+        handle: vr::VROverlayHandle_t,
+        flag: vr::VROverlayFlags,
+        enabled: bool,
     ) -> vr::EVROverlayError {
-        crate::warn_unimplemented!("SetOverlayFlag");
+        get_overlay!(self, handle, mut overlay);
+
+        debug!(
+            "setting overlay flag {flag:?} on {:?} to {enabled}",
+            overlay.name
+        );
+        if enabled {
+            overlay.flags |= flag as u32;
+        } else {
+            overlay.flags &= !(flag as u32);
+        }
         vr::EVROverlayError::None
     }
     fn GetOverlayRenderingPid(&self, _: vr::VROverlayHandle_t) -> u32 {
@@ -1383,9 +1725,20 @@ impl vr::IVROverlay028_Interface for OverlayMan {
         let key = OverlayKey::from(KeyData::from_ffi(handle));
 
         let mut overlays = self.overlays.write().unwrap();
+        if let Some(Some(DashboardRole::Thumbnail)) = overlays.get(key).map(|o| o.dashboard) {
+            return vr::EVROverlayError::ThumbnailCantBeDestroyed;
+        }
+
         if let Some(overlay) = overlays.remove(key) {
             let mut map = self.key_to_overlay.write().unwrap();
             map.remove(&overlay.key);
+            if let Some(DashboardRole::Main { thumbnail }) = overlay.dashboard {
+                overlays.remove(thumbnail);
+                let mut active = self.active_dashboard.write().unwrap();
+                if *active == Some(key) {
+                    *active = None;
+                }
+            }
         }
         vr::EVROverlayError::None
     }
@@ -1593,3 +1946,247 @@ impl vr::IVROverlay007On010 for OverlayMan {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vr::IVROverlay028_Interface;
+
+    fn create_overlay(overlays: &OverlayMan) -> vr::VROverlayHandle_t {
+        let mut handle = 0;
+        assert_eq!(
+            overlays.CreateOverlay(c"test_overlay".as_ptr(), c"Test".as_ptr(), &mut handle),
+            vr::EVROverlayError::None
+        );
+        handle
+    }
+
+    fn test_pose() -> xr::Posef {
+        let q = Quat::from_rotation_y(0.5);
+        xr::Posef {
+            position: xr::Vector3f {
+                x: 0.1,
+                y: 1.2,
+                z: -0.7,
+            },
+            orientation: xr::Quaternionf {
+                x: q.x,
+                y: q.y,
+                z: q.z,
+                w: q.w,
+            },
+        }
+    }
+
+    #[track_caller]
+    fn assert_matrix_eq(actual: vr::HmdMatrix34_t, expected: vr::HmdMatrix34_t) {
+        for (a, e) in actual.m.iter().flatten().zip(expected.m.iter().flatten()) {
+            assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual.m, expected.m);
+        }
+    }
+
+    #[test]
+    fn absolute_transform() {
+        let xr = Arc::new(OpenXrData::new(&Injector::default()).unwrap());
+        let overlays = OverlayMan::new(xr, &Injector::default());
+        let handle = create_overlay(&overlays);
+
+        let mut transform_type = vr::VROverlayTransformType::TrackedDeviceRelative;
+        assert_eq!(
+            overlays.GetOverlayTransformType(handle, &mut transform_type),
+            vr::EVROverlayError::None
+        );
+        assert_eq!(transform_type, vr::VROverlayTransformType::Absolute);
+
+        let transform: vr::HmdMatrix34_t = test_pose().into();
+        assert_eq!(
+            overlays.SetOverlayTransformAbsolute(
+                handle,
+                vr::ETrackingUniverseOrigin::Seated,
+                &transform
+            ),
+            vr::EVROverlayError::None
+        );
+
+        let mut origin = vr::ETrackingUniverseOrigin::RawAndUncalibrated;
+        let mut actual = vr::HmdMatrix34_t::default();
+        assert_eq!(
+            overlays.GetOverlayTransformAbsolute(handle, &mut origin, &mut actual),
+            vr::EVROverlayError::None
+        );
+        assert_eq!(origin, vr::ETrackingUniverseOrigin::Seated);
+        assert_matrix_eq(actual, transform);
+
+        let mut device = 0;
+        assert_eq!(
+            overlays.GetOverlayTransformTrackedDeviceRelative(handle, &mut device, &mut actual),
+            vr::EVROverlayError::WrongTransformType
+        );
+    }
+
+    #[test]
+    fn ray_intersection() {
+        let xr = Arc::new(OpenXrData::new(&Injector::default()).unwrap());
+        let overlays = OverlayMan::new(xr, &Injector::default());
+        let handle = create_overlay(&overlays);
+
+        let transform: vr::HmdMatrix34_t = xr::Posef {
+            position: xr::Vector3f {
+                x: 0.0,
+                y: 1.0,
+                z: -1.0,
+            },
+            orientation: xr::Quaternionf::IDENTITY,
+        }
+        .into();
+        assert_eq!(
+            overlays.SetOverlayTransformAbsolute(
+                handle,
+                vr::ETrackingUniverseOrigin::Seated,
+                &transform
+            ),
+            vr::EVROverlayError::None
+        );
+
+        let intersect = |source: [f32; 3], direction: [f32; 3]| {
+            let params = vr::VROverlayIntersectionParams_t {
+                vSource: vr::HmdVector3_t { v: source },
+                vDirection: vr::HmdVector3_t { v: direction },
+                eOrigin: vr::ETrackingUniverseOrigin::Seated,
+            };
+            let mut results = vr::VROverlayIntersectionResults_t::default();
+            overlays
+                .ComputeOverlayIntersection(handle, &params, &mut results)
+                .then_some(results)
+        };
+
+        let results = intersect([0.0, 1.0, 0.0], [0.0, 0.0, -2.0]).expect("ray should hit");
+        assert_eq!(results.vUVs.v, [0.5, 0.5]);
+        assert_eq!(results.vPoint.v, [0.0, 1.0, -1.0]);
+        assert_eq!(results.vNormal.v, [0.0, 0.0, 1.0]);
+        assert_eq!(results.fDistance, 1.0);
+
+        let results = intersect([0.25, 0.75, 0.0], [0.0, 0.0, -1.0]).expect("ray should hit");
+        assert_eq!(results.vUVs.v, [0.75, 0.25]);
+
+        assert!(intersect([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]).is_none());
+        assert!(intersect([0.0, 1.0, 0.0], [1.0, 0.0, 0.0]).is_none());
+        assert!(intersect([1.0, 1.0, 0.0], [0.0, 0.0, -1.0]).is_none());
+
+        assert_eq!(
+            overlays.SetOverlayWidthInMeters(handle, 4.0),
+            vr::EVROverlayError::None
+        );
+        assert!(intersect([1.0, 1.0, 0.0], [0.0, 0.0, -1.0]).is_some());
+    }
+
+    #[test]
+    fn aspect_ratio_uses_texture_bounds() {
+        let mut overlay = Overlay::new(c"test".into(), c"test".into());
+        assert_eq!(overlay.aspect_ratio(), 1.0);
+
+        overlay.texture_size = Some((200, 100));
+        assert_eq!(overlay.aspect_ratio(), 0.5);
+
+        // Bounds changed after the texture was submitted still apply.
+        overlay.bounds = vr::VRTextureBounds_t {
+            uMin: 0.0,
+            vMin: 1.0,
+            uMax: 0.5,
+            vMax: 0.0,
+        };
+        assert_eq!(overlay.aspect_ratio(), 1.0);
+    }
+
+    #[test]
+    fn tracked_device_relative_transform() {
+        use vr::IVRInput010_Interface;
+
+        let f = crate::system::tests::Fixture::new();
+        let overlays = OverlayMan::new(f.xr.clone(), &Injector::default());
+        f.xr.set_tracking_space(vr::ETrackingUniverseOrigin::Seated);
+        f.load_actions();
+        let index = f.connect_controller();
+        let session = f.xr.session_data.get().session.as_raw();
+        fakexr::set_grip(session, fakexr::UserPath::LeftHand, test_pose());
+
+        let mut set = 0;
+        assert_eq!(
+            f.input
+                .GetActionSetHandle(c"/actions/set1".as_ptr(), &mut set),
+            vr::EVRInputError::None
+        );
+        let mut active = vr::VRActiveActionSet_t {
+            ulActionSet: set,
+            ..Default::default()
+        };
+        assert_eq!(
+            f.input.UpdateActionState(
+                &mut active,
+                std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
+                1
+            ),
+            vr::EVRInputError::None
+        );
+
+        let handle = create_overlay(&overlays);
+        let relative: vr::HmdMatrix34_t = xr::Posef {
+            position: xr::Vector3f {
+                x: 0.0,
+                y: 0.0,
+                z: -0.1,
+            },
+            orientation: xr::Quaternionf::IDENTITY,
+        }
+        .into();
+        assert_eq!(
+            overlays.SetOverlayTransformTrackedDeviceRelative(handle, index, &relative),
+            vr::EVROverlayError::None
+        );
+
+        let mut transform_type = vr::VROverlayTransformType::Absolute;
+        assert_eq!(
+            overlays.GetOverlayTransformType(handle, &mut transform_type),
+            vr::EVROverlayError::None
+        );
+        assert_eq!(
+            transform_type,
+            vr::VROverlayTransformType::TrackedDeviceRelative
+        );
+
+        let mut device = 0;
+        let mut actual = vr::HmdMatrix34_t::default();
+        assert_eq!(
+            overlays.GetOverlayTransformTrackedDeviceRelative(handle, &mut device, &mut actual),
+            vr::EVROverlayError::None
+        );
+        assert_eq!(device, index);
+        assert_matrix_eq(actual, relative);
+
+        let transform = overlays.overlays.read().unwrap()
+            [OverlayKey::from(KeyData::from_ffi(handle))]
+        .transform;
+        let (origin, pose) = overlays
+            .resolve_transform(&f.xr.session_data.get(), transform)
+            .expect("transform should resolve");
+        assert_eq!(origin, vr::ETrackingUniverseOrigin::Seated);
+
+        let device_pose = f
+            .input
+            .get_device_pose(index, Some(vr::ETrackingUniverseOrigin::Seated))
+            .unwrap();
+        assert!(device_pose.bPoseIsValid);
+        let device_pose: xr::Posef = device_pose.mDeviceToAbsoluteTracking.into();
+        let o = device_pose.orientation;
+        let expected = Vec3::new(
+            device_pose.position.x,
+            device_pose.position.y,
+            device_pose.position.z,
+        ) + Quat::from_xyzw(o.x, o.y, o.z, o.w) * vec3(0.0, 0.0, -0.1);
+        let actual = Vec3::new(pose.position.x, pose.position.y, pose.position.z);
+        assert!(
+            actual.abs_diff_eq(expected, 1e-5),
+            "{actual:?} != {expected:?}"
+        );
+    }
+}
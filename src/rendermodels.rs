@@ -20,6 +20,31 @@ const RENDER_MODEL_COLOR: [u8; 4] = [32, 32, 32, 255];
 // use a single static component for all rendermodels
 static DEFAULT_COMPONENT: &CStr = c"xrizer_component";
 
+/// Component names used by SteamVR's rendermodels, and the OpenVR input they show. Our models
+/// don't have these as separate parts, but games still ask for their state by name.
+static INPUT_COMPONENTS: &[(&CStr, &str)] = &[
+    (c"trigger", "trigger"),
+    (c"trackpad", "trackpad"),
+    (c"thumbstick", "thumbstick"),
+    (c"button_a", "a"),
+    (c"button_b", "b"),
+    (c"button_x", "x"),
+    (c"button_y", "y"),
+    (c"button", "application_menu"),
+    (c"grip", "grip"),
+    (c"lgrip", "grip"),
+    (c"rgrip", "grip"),
+];
+
+/// The OpenVR input (i.e., "trigger" in /input/trigger) shown by a rendermodel component, or
+/// None if it doesn't show a single input.
+pub fn component_input(component_name: &CStr) -> Option<&'static str> {
+    INPUT_COMPONENTS
+        .iter()
+        .find(|(name, _)| *name == component_name)
+        .map(|(_, input)| *input)
+}
+
 struct RenderModelObjLz4 {
     /// lz4-compressed obj data
     bytes: &'static [u8],
@@ -135,6 +160,26 @@ fn get_render_model_data(render_model_name: &CStr) -> Option<&RenderModelObjLz4>
     RENDER_MODELS.get(&model_name)
 }
 
+/// The state of a component of one of our render models, or None if the model or component
+/// doesn't exist.
+pub fn static_component_state(
+    render_model_name: &CStr,
+    component_name: &CStr,
+) -> Option<vr::RenderModel_ComponentState_t> {
+    if (component_name != DEFAULT_COMPONENT && component_input(component_name).is_none())
+        || get_render_model_data(render_model_name).is_none()
+    {
+        return None;
+    }
+
+    // all of our models are static and have offsets baked in
+    Some(vr::RenderModel_ComponentState_t {
+        mTrackingToComponentRenderModel: xr::Posef::IDENTITY.into(),
+        mTrackingToComponentLocal: xr::Posef::IDENTITY.into(),
+        uProperties: (vr::EVRComponentProperty::IsVisible | vr::EVRComponentProperty::IsStatic).0,
+    })
+}
+
 #[derive(Default, macros::InterfaceImpl)]
 #[interface = "IVRRenderModels"]
 #[versions(006, 005, 004)]
@@ -210,18 +255,14 @@ impl vr::IVRRenderModels006_Interface for RenderModels {
         let render_model_name = unsafe { CStr::from_ptr(render_model_name) };
         let component_name = unsafe { CStr::from_ptr(component_name) };
 
-        if component_name != DEFAULT_COMPONENT || get_render_model_data(render_model_name).is_none()
-        {
+        let Some(component_state) = static_component_state(render_model_name, component_name)
+        else {
             // unsupported model or component
             return false;
-        }
+        };
 
-        // all of our models are static and have offsets baked in
         unsafe {
-            (*state).mTrackingToComponentRenderModel = xr::Posef::IDENTITY.into();
-            (*state).mTrackingToComponentLocal = xr::Posef::IDENTITY.into();
-            (*state).uProperties =
-                (vr::EVRComponentProperty::IsVisible | vr::EVRComponentProperty::IsStatic).0;
+            state.write(component_state);
         }
 
         true
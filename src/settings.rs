@@ -1,14 +1,155 @@
-use log::debug;
+use crate::openxr_data::RealOpenXrData;
+use log::{debug, warn};
 use openvr as vr;
 use openvr::EVRSettingsError;
 use std::ffi::CStr;
 use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
 
-#[derive(Default, macros::InterfaceImpl)]
+const STEAMVR_SECTION: &CStr = c"steamvr";
+const PREFERRED_REFRESH_RATE: &CStr = c"preferredRefreshRate";
+/// Multiplier for the recommended render target size.
+const SUPERSAMPLE_SCALE: &CStr = c"supersampleScale";
+const CAMERA_SECTION: &CStr = c"camera";
+/// Nonzero when the camera feed ("room view") should be shown behind the scene.
+const ROOM_VIEW: &CStr = c"roomView";
+const XRIZER_SECTION: &CStr = c"xrizer";
+/// Whether the left hand's input and pose come from the right controller, and vice versa.
+/// Defaults to [`XrizerSettings::swap_hands`].
+const SWAP_HANDS: &CStr = c"swapHands";
+/// Radius of the radial deadzone applied to thumbsticks, from 0 (off) to 1.
+/// Defaults to [`XrizerSettings::thumbstick_deadzone`].
+const THUMBSTICK_DEADZONE: &CStr = c"thumbstickDeadzone";
+/// Tracker roles are stored in this section, keyed by "/devices/<registered device type>".
+const TRACKERS_SECTION: &CStr = c"trackers";
+
+/// xrizer's own options, read once from `XRIZER_*` environment variables (see the README).
+/// Flags are enabled with `1` or `true`, and invalid values are ignored with a warning.
+#[derive(Debug, PartialEq)]
+pub struct XrizerSettings {
+    /// `XRIZER_SWAP_HANDS`
+    pub swap_hands: bool,
+    /// `XRIZER_THUMBSTICK_DEADZONE`
+    pub thumbstick_deadzone: f32,
+    /// `XRIZER_ACTIVATE_ALL_SETS`
+    pub activate_all_sets: bool,
+    /// `XRIZER_CANTED_VIEWS`
+    pub canted_views: bool,
+    /// `XRIZER_MERGE_ACTION_MANIFESTS`
+    pub merge_action_manifests: bool,
+    /// `XRIZER_RENDER_SCALE`
+    pub render_scale: f32,
+    /// `XRIZER_TRACKER_SERIALS`, semi-colon separated.
+    pub tracker_serials: Vec<String>,
+    /// `XRIZER_TRACKER_ROLES`, semi-colon separated `serial=role` pairs.
+    pub tracker_roles: String,
+    /// `XRIZER_CUSTOM_BINDINGS_DIR`, or `xrizer` in the working directory if unset.
+    pub custom_bindings_dir: Option<PathBuf>,
+    /// `XRIZER_PROPERTY_OVERRIDES`, or `xrizer/property_overrides.json` in the working
+    /// directory if unset.
+    pub property_overrides: Option<PathBuf>,
+}
+
+impl Default for XrizerSettings {
+    fn default() -> Self {
+        Self {
+            swap_hands: false,
+            thumbstick_deadzone: 0.0,
+            activate_all_sets: false,
+            canted_views: false,
+            merge_action_manifests: false,
+            render_scale: 1.0,
+            tracker_serials: Vec::new(),
+            tracker_roles: String::new(),
+            custom_bindings_dir: None,
+            property_overrides: None,
+        }
+    }
+}
+
+impl XrizerSettings {
+    pub fn get() -> &'static Self {
+        static SETTINGS: OnceLock<XrizerSettings> = OnceLock::new();
+        SETTINGS.get_or_init(|| Self::from_vars(|name| std::env::var(name).ok()))
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let flag = |name: &str| {
+            var(name).and_then(|value| match value.as_str() {
+                "1" | "true" => Some(true),
+                "0" | "false" | "" => Some(false),
+                _ => {
+                    warn!("Ignoring invalid {name} {value:?}, expected 1 or 0");
+                    None
+                }
+            })
+        };
+        let float = |name: &str| {
+            var(name).and_then(|value| {
+                value
+                    .parse::<f32>()
+                    .inspect_err(|e| warn!("Ignoring invalid {name} {value:?}: {e}"))
+                    .ok()
+            })
+        };
+
+        let default = Self::default();
+        Self {
+            swap_hands: flag("XRIZER_SWAP_HANDS").unwrap_or(default.swap_hands),
+            thumbstick_deadzone: float("XRIZER_THUMBSTICK_DEADZONE")
+                .unwrap_or(default.thumbstick_deadzone),
+            activate_all_sets: flag("XRIZER_ACTIVATE_ALL_SETS")
+                .unwrap_or(default.activate_all_sets),
+            canted_views: flag("XRIZER_CANTED_VIEWS").unwrap_or(default.canted_views),
+            merge_action_manifests: flag("XRIZER_MERGE_ACTION_MANIFESTS")
+                .unwrap_or(default.merge_action_manifests),
+            render_scale: float("XRIZER_RENDER_SCALE").unwrap_or(default.render_scale),
+            tracker_serials: var("XRIZER_TRACKER_SERIALS").map_or(default.tracker_serials, |s| {
+                s.split(';').map(str::to_string).collect()
+            }),
+            tracker_roles: var("XRIZER_TRACKER_ROLES").unwrap_or(default.tracker_roles),
+            custom_bindings_dir: var("XRIZER_CUSTOM_BINDINGS_DIR").map(PathBuf::from),
+            property_overrides: var("XRIZER_PROPERTY_OVERRIDES").map(PathBuf::from),
+        }
+    }
+}
+
+#[derive(macros::InterfaceImpl)]
 #[interface = "IVRSettings"]
 #[versions(003, 002)]
 pub struct Settings {
     vtables: Vtables,
+    openxr: Arc<RealOpenXrData>,
+}
+
+impl Settings {
+    pub fn new(openxr: Arc<RealOpenXrData>) -> Self {
+        Self {
+            vtables: Default::default(),
+            openxr,
+        }
+    }
+
+    fn is_refresh_rate_key(section: &CStr, key: &CStr) -> bool {
+        section == STEAMVR_SECTION && key == PREFERRED_REFRESH_RATE
+    }
+
+    fn is_supersample_scale_key(section: &CStr, key: &CStr) -> bool {
+        section == STEAMVR_SECTION && key == SUPERSAMPLE_SCALE
+    }
+
+    fn is_room_view_key(section: &CStr, key: &CStr) -> bool {
+        section == CAMERA_SECTION && key == ROOM_VIEW
+    }
+
+    fn is_swap_hands_key(section: &CStr, key: &CStr) -> bool {
+        section == XRIZER_SECTION && key == SWAP_HANDS
+    }
+
+    fn is_thumbstick_deadzone_key(section: &CStr, key: &CStr) -> bool {
+        section == XRIZER_SECTION && key == THUMBSTICK_DEADZONE
+    }
 }
 
 impl vr::IVRSettings003_Interface for Settings {
@@ -34,9 +175,14 @@ impl vr::IVRSettings003_Interface for Settings {
         value: bool,
         error: *mut EVRSettingsError,
     ) {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
-        debug!("Setting bool on {section}/{key} to {value}");
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
+        debug!("Setting bool on {section:?}/{key:?} to {value}");
+        if Self::is_swap_hands_key(section, key)
+            && let Some(input) = self.openxr.input.get()
+        {
+            input.set_hands_swapped(value);
+        }
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
@@ -49,9 +195,15 @@ impl vr::IVRSettings003_Interface for Settings {
         value: i32,
         error: *mut EVRSettingsError,
     ) {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
-        debug!("Setting int on {section}/{key} to {value}");
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
+        debug!("Setting int on {section:?}/{key:?} to {value}");
+        if Self::is_refresh_rate_key(section, key) {
+            self.openxr.request_refresh_rate(value as f32);
+        }
+        if Self::is_room_view_key(section, key) {
+            self.openxr.set_passthrough(value != 0);
+        }
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
@@ -64,9 +216,20 @@ impl vr::IVRSettings003_Interface for Settings {
         value: f32,
         error: *mut EVRSettingsError,
     ) {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
-        debug!("Setting float on {section}/{key} to {value}");
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
+        debug!("Setting float on {section:?}/{key:?} to {value}");
+        if Self::is_refresh_rate_key(section, key) {
+            self.openxr.request_refresh_rate(value);
+        }
+        if Self::is_supersample_scale_key(section, key) {
+            self.openxr.set_render_target_scale(value);
+        }
+        if Self::is_thumbstick_deadzone_key(section, key)
+            && let Some(input) = self.openxr.input.get()
+        {
+            input.set_thumbstick_deadzone(value);
+        }
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
@@ -94,12 +257,19 @@ impl vr::IVRSettings003_Interface for Settings {
         settings_key: *const c_char,
         error: *mut EVRSettingsError,
     ) -> bool {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
-        debug!("Getting bool on {section}/{key}");
+        debug!("Getting bool on {section:?}/{key:?}");
+        if Self::is_swap_hands_key(section, key) {
+            return self
+                .openxr
+                .input
+                .get()
+                .is_some_and(|input| input.hands_swapped());
+        }
         false
     }
 
@@ -109,12 +279,18 @@ impl vr::IVRSettings003_Interface for Settings {
         settings_key: *const c_char,
         error: *mut EVRSettingsError,
     ) -> i32 {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
-        debug!("Getting int on {section}/{key}");
+        debug!("Getting int on {section:?}/{key:?}");
+        if Self::is_refresh_rate_key(section, key) {
+            return self.openxr.get_refresh_rate().round() as i32;
+        }
+        if Self::is_room_view_key(section, key) {
+            return self.openxr.passthrough_enabled() as i32;
+        }
         0
     }
 
@@ -124,12 +300,25 @@ impl vr::IVRSettings003_Interface for Settings {
         settings_key: *const c_char,
         error: *mut EVRSettingsError,
     ) -> f32 {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
-        debug!("Getting float on {section}/{key}");
+        debug!("Getting float on {section:?}/{key:?}");
+        if Self::is_refresh_rate_key(section, key) {
+            return self.openxr.get_refresh_rate();
+        }
+        if Self::is_supersample_scale_key(section, key) {
+            return self.openxr.render_target_scale();
+        }
+        if Self::is_thumbstick_deadzone_key(section, key) {
+            return self
+                .openxr
+                .input
+                .get()
+                .map_or(0.0, |input| input.thumbstick_deadzone());
+        }
         0.0
     }
 
@@ -141,8 +330,8 @@ impl vr::IVRSettings003_Interface for Settings {
         value_len: u32,
         error: *mut EVRSettingsError,
     ) {
-        let section = unsafe { CStr::from_ptr(section) }.to_string_lossy();
-        let key = unsafe { CStr::from_ptr(settings_key) }.to_string_lossy();
+        let section = unsafe { CStr::from_ptr(section) };
+        let key = unsafe { CStr::from_ptr(settings_key) };
         if let Some(error) = unsafe { error.as_mut() } {
             *error = EVRSettingsError::None;
         }
@@ -151,7 +340,23 @@ impl vr::IVRSettings003_Interface for Settings {
                 *value = 0;
             }
         }
-        debug!("Getting string on {section}/{key}");
+        debug!("Getting string on {section:?}/{key:?}");
+
+        if section == TRACKERS_SECTION {
+            let role = key
+                .to_str()
+                .ok()
+                .and_then(|key| key.strip_prefix("/devices/"))
+                .and_then(|ty| self.openxr.input.get()?.get_tracker_role_setting(ty));
+            if let Some(role) = role {
+                let role = role.to_bytes_with_nul();
+                if role.len() <= value_len as usize {
+                    unsafe {
+                        value.copy_from_nonoverlapping(role.as_ptr().cast(), role.len());
+                    }
+                }
+            }
+        }
     }
 
     fn RemoveSection(&self, section: *const c_char, error: *mut EVRSettingsError) {
@@ -185,3 +390,57 @@ impl vr::IVRSettings002On003 for Settings {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn from_vars(vars: &[(&str, &str)]) -> XrizerSettings {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        XrizerSettings::from_vars(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn xrizer_settings_defaults() {
+        assert_eq!(from_vars(&[]), XrizerSettings::default());
+    }
+
+    #[test]
+    fn xrizer_settings_from_vars() {
+        let settings = from_vars(&[
+            ("XRIZER_SWAP_HANDS", "1"),
+            ("XRIZER_ACTIVATE_ALL_SETS", "true"),
+            ("XRIZER_CANTED_VIEWS", "0"),
+            ("XRIZER_THUMBSTICK_DEADZONE", "0.2"),
+            ("XRIZER_RENDER_SCALE", "1.5"),
+            ("XRIZER_TRACKER_SERIALS", "ABC;DEF"),
+            ("XRIZER_TRACKER_ROLES", "ABC=waist"),
+            ("XRIZER_PROPERTY_OVERRIDES", "/tmp/overrides.json"),
+        ]);
+        assert_eq!(
+            settings,
+            XrizerSettings {
+                swap_hands: true,
+                thumbstick_deadzone: 0.2,
+                activate_all_sets: true,
+                canted_views: false,
+                merge_action_manifests: false,
+                render_scale: 1.5,
+                tracker_serials: vec!["ABC".into(), "DEF".into()],
+                tracker_roles: "ABC=waist".into(),
+                custom_bindings_dir: None,
+                property_overrides: Some("/tmp/overrides.json".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn xrizer_settings_invalid_values() {
+        let settings = from_vars(&[
+            ("XRIZER_SWAP_HANDS", "yes please"),
+            ("XRIZER_RENDER_SCALE", "big"),
+        ]);
+        assert_eq!(settings, XrizerSettings::default());
+    }
+}
@@ -3,14 +3,50 @@ use crate::{
     input::Input,
     openxr_data::{Hand, RealOpenXrData, SessionData},
     overlay::OverlayMan,
+    settings::XrizerSettings,
     tracy_span,
 };
-use glam::{Mat3, Quat, Vec3};
-use log::{debug, error, trace, warn};
+use glam::{Affine3A, Mat3, Quat, Vec3};
+use log::{debug, error, info, trace, warn};
 use openvr as vr;
 use openxr as xr;
+use std::collections::{HashMap, HashSet};
 use std::ffi::{CStr, CString};
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+static AXIS_TYPE_NAMES: &[(vr::EVRControllerAxisType, &CStr)] = &[
+    (vr::EVRControllerAxisType::None, c"none"),
+    (vr::EVRControllerAxisType::TrackPad, c"trackpad"),
+    (vr::EVRControllerAxisType::Joystick, c"joystick"),
+    (vr::EVRControllerAxisType::Trigger, c"trigger"),
+];
+
+/// The names SteamVR uses for buttons, which prefers the aliases for well known controllers.
+static BUTTON_NAMES: &[(vr::EVRButtonId, &CStr)] = &[
+    (vr::EVRButtonId::System, c"k_EButton_System"),
+    (
+        vr::EVRButtonId::ApplicationMenu,
+        c"k_EButton_ApplicationMenu",
+    ),
+    (vr::EVRButtonId::Grip, c"k_EButton_Grip"),
+    (vr::EVRButtonId::DPad_Left, c"k_EButton_DPad_Left"),
+    (vr::EVRButtonId::DPad_Up, c"k_EButton_DPad_Up"),
+    (vr::EVRButtonId::DPad_Right, c"k_EButton_DPad_Right"),
+    (vr::EVRButtonId::DPad_Down, c"k_EButton_DPad_Down"),
+    (vr::EVRButtonId::A, c"k_EButton_A"),
+    (
+        vr::EVRButtonId::ProximitySensor,
+        c"k_EButton_ProximitySensor",
+    ),
+    (vr::EVRButtonId::Axis0, c"k_EButton_SteamVR_Touchpad"),
+    (vr::EVRButtonId::Axis1, c"k_EButton_SteamVR_Trigger"),
+    (vr::EVRButtonId::Axis2, c"k_EButton_Axis2"),
+    (vr::EVRButtonId::Axis3, c"k_EButton_Axis3"),
+    (vr::EVRButtonId::Axis4, c"k_EButton_Axis4"),
+];
 
 #[derive(Copy, Clone)]
 pub struct ViewData {
@@ -24,9 +60,15 @@ struct ViewDataViewSpace {
     original_orientations: [Quat; 2],
 }
 
+/// Views are cached per frame, since the application may ask for them many times.
+///
+/// By default the views are made parallel, removing any display canting, since many games assume
+/// parallel projections. With canted views enabled the runtime's views are passed through as is, and
+/// the cant is reported through GetEyeToHeadTransform instead.
 #[derive(Default)]
 struct ViewCache {
     view: Option<ViewDataViewSpace>,
+    canted_view: Option<ViewData>,
     local: Option<ViewData>,
     stage: Option<ViewData>,
 }
@@ -37,7 +79,18 @@ impl ViewCache {
         session: &SessionData,
         display_time: xr::Time,
         ty: xr::ReferenceSpaceType,
+        canted: bool,
     ) -> ViewData {
+        if canted {
+            let view = match ty {
+                xr::ReferenceSpaceType::VIEW => &mut self.canted_view,
+                xr::ReferenceSpaceType::LOCAL => &mut self.local,
+                xr::ReferenceSpaceType::STAGE => &mut self.stage,
+                other => panic!("unexpected reference space type: {other:?}"),
+            };
+            return *view.get_or_insert_with(|| Self::locate_views(session, display_time, ty));
+        }
+
         match ty {
             xr::ReferenceSpaceType::VIEW => {
                 self.view
@@ -101,6 +154,28 @@ impl ViewCache {
         }
     }
 
+    fn locate_views(
+        session: &SessionData,
+        display_time: xr::Time,
+        ty: xr::ReferenceSpaceType,
+    ) -> ViewData {
+        let (flags, views) = session
+            .session
+            .locate_views(
+                xr::ViewConfigurationType::PRIMARY_STEREO,
+                display_time,
+                session.get_space_from_type(ty),
+            )
+            .expect("Couldn't locate views");
+
+        ViewData {
+            flags,
+            views: views
+                .try_into()
+                .unwrap_or_else(|v: Vec<xr::View>| panic!("Expected 2 views, got {}", v.len())),
+        }
+    }
+
     fn get_views_other_space(
         session: &SessionData,
         display_time: xr::Time,
@@ -147,6 +222,79 @@ impl ViewCache {
     }
 }
 
+type HiddenAreaMeshKey = (vr::EVREye, vr::EHiddenAreaMeshType);
+
+#[derive(Default)]
+pub struct SystemSessionData {
+    /// Bumped whenever the runtime tells us the visibility mask changed.
+    visibility_mask_generation: AtomicU64,
+}
+
+impl SystemSessionData {
+    pub fn invalidate_hidden_area_meshes(&self) {
+        self.visibility_mask_generation
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// GetHiddenAreaMesh hands out a pointer without any way for the application to free it, so the
+/// vertices are never freed. When the mask changes, a buffer of the same size is overwritten in
+/// place instead of allocating a new one, so this only grows with the number of distinct mesh sizes.
+#[derive(Default)]
+struct HiddenAreaMeshes {
+    /// The session and mask generation the current meshes were built for.
+    source: Option<(xr::sys::Session, u64)>,
+    meshes: HashMap<HiddenAreaMeshKey, HiddenAreaMeshBuffers>,
+}
+
+#[derive(Default)]
+struct HiddenAreaMeshBuffers {
+    /// Index into `buffers` of the mesh for the current visibility mask.
+    current: Option<usize>,
+    buffers: Vec<Box<[vr::HmdVector2_t]>>,
+}
+
+impl HiddenAreaMeshes {
+    /// Forgets the current meshes if they were built for a different session or visibility mask.
+    fn validate(&mut self, source: (xr::sys::Session, u64)) {
+        if self.source != Some(source) {
+            self.source = Some(source);
+            for mesh in self.meshes.values_mut() {
+                mesh.current = None;
+            }
+        }
+    }
+
+    fn get(&self, key: HiddenAreaMeshKey) -> Option<&[vr::HmdVector2_t]> {
+        let mesh = self.meshes.get(&key)?;
+        mesh.current.map(|idx| &*mesh.buffers[idx])
+    }
+
+    fn insert(
+        &mut self,
+        key: HiddenAreaMeshKey,
+        vertices: Vec<vr::HmdVector2_t>,
+    ) -> &[vr::HmdVector2_t] {
+        let mesh = self.meshes.entry(key).or_default();
+        let idx = match mesh
+            .buffers
+            .iter()
+            .position(|buffer| buffer.len() == vertices.len())
+        {
+            Some(idx) => {
+                mesh.buffers[idx].copy_from_slice(&vertices);
+                idx
+            }
+            None => {
+                mesh.buffers.push(vertices.into_boxed_slice());
+                mesh.buffers.len() - 1
+            }
+        };
+        mesh.current = Some(idx);
+        &mesh.buffers[idx]
+    }
+}
+
 #[derive(macros::InterfaceImpl)]
 #[interface = "IVRSystem"]
 #[versions(026, 023, 022, 021, 020, 019, 017, 016, 015, 014, 012, 011, 009)]
@@ -156,10 +304,16 @@ pub struct System {
     overlay: Injected<OverlayMan>,
     vtables: Vtables,
     views: Mutex<ViewCache>,
+    /// Pass canted views through instead of making them parallel.
+    canted_views: bool,
+    hidden_area_meshes: Mutex<HiddenAreaMeshes>,
+    /// Properties we've answered with UnknownProperty, along with the device they were queried on.
+    unknown_properties: Mutex<HashSet<(vr::TrackedDeviceIndex_t, vr::ETrackedDeviceProperty)>>,
 }
 
 mod log_tags {
     pub const TRACKED_PROP: &str = "tracked_property";
+    pub const UNKNOWN_PROP: &str = "unknown_tracked_property";
 }
 
 impl System {
@@ -170,19 +324,65 @@ impl System {
             overlay: injector.inject(),
             vtables: Default::default(),
             views: Mutex::default(),
+            canted_views: XrizerSettings::get().canted_views,
+            hidden_area_meshes: Mutex::default(),
+            unknown_properties: Mutex::default(),
+        }
+    }
+
+    /// Records a property we couldn't answer, logging it the first time it's seen for a device so
+    /// it's easy to tell what a game is missing without the log being flooded every frame.
+    fn unknown_property(
+        &self,
+        device_index: vr::TrackedDeviceIndex_t,
+        prop: vr::ETrackedDeviceProperty,
+    ) {
+        if self
+            .unknown_properties
+            .lock()
+            .unwrap()
+            .insert((device_index, prop))
+        {
+            debug!(target: log_tags::UNKNOWN_PROP, "unknown property: {prop:?} ({device_index})");
         }
     }
 
+    /// There's no DXGI to ask on Linux, so find the adapter for D3D applications by the LUID of
+    /// the runtime's Vulkan device.
+    fn adapter_index(&self) -> i32 {
+        crate::graphics_backends::physical_device_index(
+            &self.openxr.instance,
+            self.openxr.system_id,
+        )
+        .unwrap_or_else(|| {
+            warn!("Couldn't find the adapter with the runtime's LUID, using the first adapter");
+            0
+        }) as i32
+    }
+
+    #[cfg(test)]
+    fn unknown_properties(
+        &self,
+    ) -> HashSet<(vr::TrackedDeviceIndex_t, vr::ETrackedDeviceProperty)> {
+        self.unknown_properties.lock().unwrap().clone()
+    }
+
     pub fn reset_views(&self) {
         std::mem::take(&mut *self.views.lock().unwrap());
         let session = self.openxr.session_data.get();
         let display_time = self.openxr.display_time.get();
         let mut views = self.views.lock().unwrap();
-        views.get_views(&session, display_time, xr::ReferenceSpaceType::VIEW);
+        views.get_views(
+            &session,
+            display_time,
+            xr::ReferenceSpaceType::VIEW,
+            self.canted_views,
+        );
         views.get_views(
             &session,
             display_time,
             session.current_origin_as_reference_space(),
+            self.canted_views,
         );
     }
 
@@ -190,7 +390,12 @@ impl System {
         tracy_span!();
         let session = self.openxr.session_data.get();
         let mut views = self.views.lock().unwrap();
-        views.get_views(&session, self.openxr.display_time.get(), ty)
+        views.get_views(
+            &session,
+            self.openxr.display_time.get(),
+            ty,
+            self.canted_views,
+        )
     }
 }
 
@@ -205,12 +410,28 @@ impl vr::IVRSystem026_Interface for System {
             )
             .unwrap();
 
+        // Scaling the resolution doesn't change the FOV, so GetProjectionRaw is unaffected.
+        let scale = self.openxr.render_target_scale();
+        let scaled = |recommended: u32, max: u32| {
+            ((recommended as f32 * scale).round() as u32).clamp(1, max.max(1))
+        };
+
         if !width.is_null() {
-            unsafe { *width = views[0].recommended_image_rect_width };
+            unsafe {
+                *width = scaled(
+                    views[0].recommended_image_rect_width,
+                    views[0].max_image_rect_width,
+                )
+            };
         }
 
         if !height.is_null() {
-            unsafe { *height = views[0].recommended_image_rect_height };
+            unsafe {
+                *height = scaled(
+                    views[0].recommended_image_rect_height,
+                    views[0].max_image_rect_height,
+                )
+            };
         }
     }
     fn GetProjectionMatrix(&self, eye: vr::EVREye, near_z: f32, far_z: f32) -> vr::HmdMatrix44_t {
@@ -259,12 +480,21 @@ impl vr::IVRSystem026_Interface for System {
     fn ComputeDistortion(
         &self,
         _: vr::EVREye,
-        _: f32,
-        _: f32,
-        _: *mut vr::DistortionCoordinates_t,
+        u: f32,
+        v: f32,
+        coords: *mut vr::DistortionCoordinates_t,
     ) -> bool {
-        crate::warn_unimplemented!("ComputeDistortion");
-        false
+        let Some(coords) = (unsafe { coords.as_mut() }) else {
+            return false;
+        };
+
+        // The OpenXR runtime takes care of lens distortion, so there's nothing to correct here.
+        *coords = vr::DistortionCoordinates_t {
+            rfRed: [u, v],
+            rfGreen: [u, v],
+            rfBlue: [u, v],
+        };
+        true
     }
     fn ComputeDistortionSet(
         &self,
@@ -313,11 +543,35 @@ impl vr::IVRSystem026_Interface for System {
     fn SetSDKVersion(&self, _: u32, _: u32, _: u32) -> vr::EVRInitError {
         vr::EVRInitError::None
     }
-    fn GetAppContainerFilePaths(&self, _: *mut std::os::raw::c_char, _: u32) -> u32 {
-        todo!()
+    fn GetAppContainerFilePaths(&self, buffer: *mut std::os::raw::c_char, size: u32) -> u32 {
+        let paths = self
+            .input
+            .get()
+            .map(|input| input.app_container_file_paths())
+            .unwrap_or_default();
+        if paths.is_empty() {
+            if !buffer.is_null() && size > 0 {
+                unsafe { *buffer = 0 };
+            }
+            return 0;
+        }
+
+        let list = paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(";");
+        let list = CString::new(list).unwrap();
+        let list = list.to_bytes_with_nul();
+        if !buffer.is_null() && size as usize >= list.len() {
+            unsafe { buffer.copy_from_nonoverlapping(list.as_ptr().cast(), list.len()) };
+        }
+
+        list.len() as u32
     }
     fn AcknowledgeQuit_Exiting(&self) {
-        todo!()
+        info!("Application acknowledged quit, ending session");
+        self.openxr.end_session_for_quit();
     }
     fn PerformFirmwareUpdate(&self, _: vr::TrackedDeviceIndex_t) -> vr::EVRFirmwareError {
         todo!()
@@ -329,23 +583,28 @@ impl vr::IVRSystem026_Interface for System {
         false
     }
     fn IsSteamVRDrawingControllers(&self) -> bool {
-        todo!()
+        // We never draw controller models, so the application should draw its own.
+        false
     }
     fn IsInputAvailable(&self) -> bool {
         true
     }
     fn GetControllerAxisTypeNameFromEnum(
         &self,
-        _: vr::EVRControllerAxisType,
+        axis_type: vr::EVRControllerAxisType,
     ) -> *const std::os::raw::c_char {
-        crate::warn_unimplemented!("GetControllerAxisTypeNameFromEnum");
-        static NAME: &CStr = c"Unknown";
-        NAME.as_ptr()
-    }
-    fn GetButtonIdNameFromEnum(&self, _: vr::EVRButtonId) -> *const std::os::raw::c_char {
-        crate::warn_unimplemented!("GetButtonIdNameFromEnum");
-        static NAME: &CStr = c"Unknown";
-        NAME.as_ptr()
+        AXIS_TYPE_NAMES
+            .iter()
+            .find_map(|(ty, name)| (*ty == axis_type).then_some(*name))
+            .unwrap_or(c"Unknown")
+            .as_ptr()
+    }
+    fn GetButtonIdNameFromEnum(&self, button: vr::EVRButtonId) -> *const std::os::raw::c_char {
+        BUTTON_NAMES
+            .iter()
+            .find_map(|(id, name)| (*id == button).then_some(*name))
+            .unwrap_or(c"Unknown")
+            .as_ptr()
     }
     fn TriggerHapticPulse(
         &self,
@@ -416,6 +675,26 @@ impl vr::IVRSystem026_Interface for System {
         };
 
         let session_data = self.openxr.session_data.get();
+        let mut meshes = self.hidden_area_meshes.lock().unwrap();
+        meshes.validate((
+            session_data.session.as_raw(),
+            session_data
+                .system_data
+                .visibility_mask_generation
+                .load(Ordering::Relaxed),
+        ));
+        // A line loop's "triangle count" is actually its number of vertices.
+        let to_mesh = |vertices: &[vr::HmdVector2_t]| vr::HiddenAreaMesh_t {
+            pVertexData: vertices.as_ptr(),
+            unTriangleCount: match ty {
+                vr::EHiddenAreaMeshType::LineLoop => vertices.len(),
+                _ => vertices.len() / 3,
+            } as u32,
+        };
+        if let Some(vertices) = meshes.get((eye, ty)) {
+            return to_mesh(vertices);
+        }
+
         let mask = session_data
             .session
             .get_visibility_mask_khr(
@@ -448,17 +727,7 @@ impl vr::IVRSystem026_Interface for System {
             .collect();
 
         trace!("vertices: {vertices:#?}");
-        let count = vertices.len() / 3;
-        // XXX: what are we supposed to do here? pVertexData is a random pointer and there's no
-        // clear way for the application to deallocate it
-        // fortunately it seems like applications don't call this often, so this leakage isn't a
-        // huge deal.
-        let vertices = Vec::leak(vertices).as_ptr();
-
-        vr::HiddenAreaMesh_t {
-            pVertexData: vertices,
-            unTriangleCount: count as u32,
-        }
+        to_mesh(meshes.insert((eye, ty), vertices))
     }
 
     fn GetEyeTrackedFoveationCenter(
@@ -524,7 +793,12 @@ impl vr::IVRSystem026_Interface for System {
         if got_event && !pose.is_null() {
             unsafe {
                 let index = (&raw const (*event).trackedDeviceIndex).read();
-                pose.write(input.get_device_pose(index, Some(origin)).unwrap());
+                // Not all events are associated with a device (i.e., Quit)
+                pose.write(
+                    input
+                        .get_device_pose(index, Some(origin))
+                        .unwrap_or_default(),
+                );
             }
         }
         got_event
@@ -591,6 +865,7 @@ impl vr::IVRSystem026_Interface for System {
         };
 
         let Some(data) = data else {
+            self.unknown_property(device_index, prop);
             if let Some(error) = unsafe { error.as_mut() } {
                 *error = vr::ETrackedPropertyError::UnknownProperty;
             }
@@ -611,14 +886,68 @@ impl vr::IVRSystem026_Interface for System {
     }
     fn GetArrayTrackedDeviceProperty(
         &self,
-        _: vr::TrackedDeviceIndex_t,
-        _: vr::ETrackedDeviceProperty,
-        _: vr::PropertyTypeTag_t,
-        _: *mut std::os::raw::c_void,
-        _: u32,
-        _: *mut vr::ETrackedPropertyError,
+        device_index: vr::TrackedDeviceIndex_t,
+        prop: vr::ETrackedDeviceProperty,
+        tag: vr::PropertyTypeTag_t,
+        buffer: *mut std::os::raw::c_void,
+        size: u32,
+        error: *mut vr::ETrackedPropertyError,
     ) -> u32 {
-        todo!()
+        debug!(target: log_tags::TRACKED_PROP, "requesting array property: {prop:?} (tag {tag}, {device_index})");
+
+        fn to_bytes<T: Copy>(value: T) -> Vec<u8> {
+            unsafe { std::slice::from_raw_parts((&raw const value).cast::<u8>(), size_of::<T>()) }
+                .to_vec()
+        }
+
+        // We don't have any properties that are actually arrays, but SteamVR will happily return
+        // a single value property as an array with one element, so do the same.
+        let mut prop_err = vr::ETrackedPropertyError::Success;
+        let data = match tag {
+            vr::k_unInt32PropertyTag => {
+                to_bytes(self.GetInt32TrackedDeviceProperty(device_index, prop, &mut prop_err))
+            }
+            vr::k_unFloatPropertyTag => {
+                to_bytes(self.GetFloatTrackedDeviceProperty(device_index, prop, &mut prop_err))
+            }
+            vr::k_unUint64PropertyTag => {
+                to_bytes(self.GetUint64TrackedDeviceProperty(device_index, prop, &mut prop_err))
+            }
+            vr::k_unBoolPropertyTag => {
+                to_bytes(self.GetBoolTrackedDeviceProperty(device_index, prop, &mut prop_err))
+            }
+            vr::k_unHmdMatrix34PropertyTag => {
+                to_bytes(self.GetMatrix34TrackedDeviceProperty(device_index, prop, &mut prop_err))
+            }
+            _ => {
+                debug!(target: log_tags::TRACKED_PROP, "unsupported array property tag: {tag}");
+                prop_err = vr::ETrackedPropertyError::WrongDataType;
+                Vec::new()
+            }
+        };
+
+        if prop_err != vr::ETrackedPropertyError::Success {
+            if let Some(error) = unsafe { error.as_mut() } {
+                *error = prop_err;
+            }
+            return 0;
+        }
+
+        if buffer.is_null() || (size as usize) < data.len() {
+            if let Some(error) = unsafe { error.as_mut() } {
+                *error = vr::ETrackedPropertyError::BufferTooSmall;
+            }
+        } else {
+            unsafe {
+                std::slice::from_raw_parts_mut(buffer.cast::<u8>(), data.len())
+                    .copy_from_slice(&data);
+            }
+            if let Some(error) = unsafe { error.as_mut() } {
+                *error = vr::ETrackedPropertyError::Success;
+            }
+        }
+
+        data.len() as u32
     }
     fn GetMatrix34TrackedDeviceProperty(
         &self,
@@ -634,6 +963,7 @@ impl vr::IVRSystem026_Interface for System {
             return Default::default();
         }
 
+        self.unknown_property(device_index, prop);
         if let Some(err) = unsafe { err.as_mut() } {
             *err = vr::ETrackedPropertyError::UnknownProperty;
         }
@@ -661,6 +991,7 @@ impl vr::IVRSystem026_Interface for System {
             .get()
             .and_then(|input| input.get_device_uint_tracked_property(device_index, prop))
             .unwrap_or_else(|| {
+                self.unknown_property(device_index, prop);
                 if let Some(err) = unsafe { err.as_mut() } {
                     *err = vr::ETrackedPropertyError::UnknownProperty;
                 }
@@ -688,6 +1019,7 @@ impl vr::IVRSystem026_Interface for System {
             .get()
             .and_then(|input| input.get_device_int_tracked_property(device_index, prop))
             .unwrap_or_else(|| {
+                self.unknown_property(device_index, prop);
                 if let Some(err) = unsafe { err.as_mut() } {
                     *err = vr::ETrackedPropertyError::UnknownProperty;
                 }
@@ -702,10 +1034,27 @@ impl vr::IVRSystem026_Interface for System {
     ) -> f32 {
         debug!(target: log_tags::TRACKED_PROP, "requesting float property: {prop:?} ({device_index})");
         if device_index != vr::k_unTrackedDeviceIndex_Hmd {
+            if !self.IsTrackedDeviceConnected(device_index) {
+                if let Some(error) = unsafe { error.as_mut() } {
+                    *error = vr::ETrackedPropertyError::InvalidDevice;
+                }
+                return 0.0;
+            }
+
             if let Some(error) = unsafe { error.as_mut() } {
-                *error = vr::ETrackedPropertyError::UnknownProperty;
+                *error = vr::ETrackedPropertyError::Success;
             }
-            return 0.0;
+            return self
+                .input
+                .get()
+                .and_then(|input| input.get_device_float_tracked_property(device_index, prop))
+                .unwrap_or_else(|| {
+                    self.unknown_property(device_index, prop);
+                    if let Some(error) = unsafe { error.as_mut() } {
+                        *error = vr::ETrackedPropertyError::UnknownProperty;
+                    }
+                    0.0
+                });
         }
 
         match prop {
@@ -715,6 +1064,7 @@ impl vr::IVRSystem026_Interface for System {
             }
             vr::ETrackedDeviceProperty::DisplayFrequency_Float => self.openxr.get_refresh_rate(),
             _ => {
+                self.unknown_property(device_index, prop);
                 if let Some(error) = unsafe { error.as_mut() } {
                     *error = vr::ETrackedPropertyError::UnknownProperty;
                 }
@@ -729,10 +1079,48 @@ impl vr::IVRSystem026_Interface for System {
         err: *mut vr::ETrackedPropertyError,
     ) -> bool {
         debug!(target: log_tags::TRACKED_PROP, "requesting bool property: {prop:?} ({device_index})");
+        if device_index == vr::k_unTrackedDeviceIndex_Hmd {
+            let value = match prop {
+                // We have no way of knowing if the headset runs on battery
+                vr::ETrackedDeviceProperty::DeviceProvidesBatteryStatus_Bool
+                | vr::ETrackedDeviceProperty::DeviceCanPowerOff_Bool
+                | vr::ETrackedDeviceProperty::Firmware_UpdateAvailable_Bool => Some(false),
+                // Presence is derived from the session's visibility, see is_user_present
+                vr::ETrackedDeviceProperty::ContainsProximitySensor_Bool => Some(true),
+                _ => None,
+            };
+            if value.is_none() {
+                self.unknown_property(device_index, prop);
+            }
+            if let Some(err) = unsafe { err.as_mut() } {
+                *err = if value.is_some() {
+                    vr::ETrackedPropertyError::Success
+                } else {
+                    vr::ETrackedPropertyError::UnknownProperty
+                };
+            }
+            return value.unwrap_or(false);
+        }
+        if !self.IsTrackedDeviceConnected(device_index) {
+            if let Some(err) = unsafe { err.as_mut() } {
+                *err = vr::ETrackedPropertyError::InvalidDevice;
+            }
+            return false;
+        }
+
         if let Some(err) = unsafe { err.as_mut() } {
-            *err = vr::ETrackedPropertyError::UnknownProperty;
+            *err = vr::ETrackedPropertyError::Success;
         }
-        false
+        self.input
+            .get()
+            .and_then(|input| input.get_device_bool_tracked_property(device_index, prop))
+            .unwrap_or_else(|| {
+                self.unknown_property(device_index, prop);
+                if let Some(err) = unsafe { err.as_mut() } {
+                    *err = vr::ETrackedPropertyError::UnknownProperty;
+                }
+                false
+            })
     }
 
     fn IsTrackedDeviceConnected(&self, device_index: vr::TrackedDeviceIndex_t) -> bool {
@@ -784,18 +1172,54 @@ impl vr::IVRSystem026_Interface for System {
     }
     fn ApplyTransform(
         &self,
-        _: *mut vr::TrackedDevicePose_t,
-        _: *const vr::TrackedDevicePose_t,
-        _: *const vr::HmdMatrix34_t,
+        output: *mut vr::TrackedDevicePose_t,
+        pose: *const vr::TrackedDevicePose_t,
+        transform: *const vr::HmdMatrix34_t,
     ) {
-        todo!()
+        if output.is_null() || pose.is_null() || transform.is_null() {
+            warn!("ApplyTransform: got null pointer");
+            return;
+        }
+        // The output is allowed to be the same as the input pose, so copy everything out first.
+        let (pose, transform) = unsafe { (pose.read(), transform.read()) };
+
+        // openvr matrices are row major, glam matrices are column major
+        let to_affine = |m: vr::HmdMatrix34_t| {
+            Affine3A::from_cols_array_2d(&std::array::from_fn(|col| {
+                std::array::from_fn(|row| m.m[row][col])
+            }))
+        };
+        let transform = to_affine(transform);
+        let cols = (transform * to_affine(pose.mDeviceToAbsoluteTracking)).to_cols_array_2d();
+        let rotate = |v: vr::HmdVector3_t| vr::HmdVector3_t {
+            v: transform
+                .transform_vector3(Vec3::from_array(v.v))
+                .to_array(),
+        };
+
+        unsafe {
+            output.write(vr::TrackedDevicePose_t {
+                mDeviceToAbsoluteTracking: vr::HmdMatrix34_t {
+                    m: std::array::from_fn(|row| std::array::from_fn(|col| cols[col][row])),
+                },
+                vVelocity: rotate(pose.vVelocity),
+                vAngularVelocity: rotate(pose.vAngularVelocity),
+                ..pose
+            });
+        }
     }
     fn GetTrackedDeviceActivityLevel(
         &self,
         device_index: vr::TrackedDeviceIndex_t,
     ) -> vr::EDeviceActivityLevel {
         match device_index {
-            vr::k_unTrackedDeviceIndex_Hmd => vr::EDeviceActivityLevel::UserInteraction,
+            vr::k_unTrackedDeviceIndex_Hmd => {
+                if self.openxr.is_user_present() {
+                    vr::EDeviceActivityLevel::UserInteraction
+                } else {
+                    vr::EDeviceActivityLevel::Standby
+                }
+            }
             x if self
                 .input
                 .get()
@@ -812,18 +1236,27 @@ impl vr::IVRSystem026_Interface for System {
     }
     fn GetSortedTrackedDeviceIndicesOfClass(
         &self,
-        _: vr::ETrackedDeviceClass,
-        _: *mut vr::TrackedDeviceIndex_t,
-        _: u32,
-        _: vr::TrackedDeviceIndex_t,
+        class: vr::ETrackedDeviceClass,
+        indices: *mut vr::TrackedDeviceIndex_t,
+        count: u32,
+        relative_to: vr::TrackedDeviceIndex_t,
     ) -> u32 {
-        0
+        let sorted = self
+            .input
+            .force(|_| Input::new(self.openxr.clone()))
+            .get_sorted_device_indices_of_class(class, relative_to);
+
+        if !indices.is_null() && count as usize >= sorted.len() {
+            unsafe { std::slice::from_raw_parts_mut(indices, sorted.len()) }
+                .copy_from_slice(&sorted);
+        }
+        sorted.len() as u32
     }
     fn GetRawZeroPoseToStandingAbsoluteTrackingPose(&self) -> vr::HmdMatrix34_t {
         xr::Posef::IDENTITY.into()
     }
     fn GetSeatedZeroPoseToStandingAbsoluteTrackingPose(&self) -> vr::HmdMatrix34_t {
-        xr::Posef::IDENTITY.into()
+        self.openxr.seated_zero_pose_in_standing().into()
     }
     fn GetDeviceToAbsoluteTrackingPose(
         &self,
@@ -853,26 +1286,42 @@ impl vr::IVRSystem026_Interface for System {
         texture_type: vr::ETextureType,
         instance: *mut vr::VkInstance_T,
     ) {
-        if texture_type != vr::ETextureType::Vulkan {
-            // Proton doesn't seem to properly translate this function, but it doesn't appear to
-            // actually matter.
-            log::error!("Unsupported texture type: {texture_type:?}");
-            return;
-        }
+        let value = match texture_type {
+            vr::ETextureType::Vulkan => {
+                self.openxr
+                    .instance
+                    .vulkan_graphics_device(self.openxr.system_id, instance as _)
+                    .expect("Failed to get vulkan physical device") as u64
+            }
+            // D3D applications (i.e., under Proton) want the LUID of the adapter to render with,
+            // which is the same GPU the runtime picks for Vulkan.
+            vr::ETextureType::DirectX | vr::ETextureType::DirectX12 => {
+                crate::graphics_backends::physical_device_luid(
+                    &self.openxr.instance,
+                    self.openxr.system_id,
+                )
+                .unwrap_or_else(|| {
+                    warn!("Vulkan device has no LUID, can't report the D3D adapter");
+                    0
+                })
+            }
+            other => {
+                log::error!("Unsupported texture type: {other:?}");
+                return;
+            }
+        };
 
-        unsafe {
-            *device = self
-                .openxr
-                .instance
-                .vulkan_graphics_device(self.openxr.system_id, instance as _)
-                .expect("Failed to get vulkan physical device") as _;
+        if let Some(device) = unsafe { device.as_mut() } {
+            *device = value;
         }
     }
-    fn GetDXGIOutputInfo(&self, _: *mut i32) {
-        todo!()
+    fn GetDXGIOutputInfo(&self, adapter_index: *mut i32) {
+        if let Some(adapter_index) = unsafe { adapter_index.as_mut() } {
+            *adapter_index = self.adapter_index();
+        }
     }
     fn GetD3D9AdapterIndex(&self) -> i32 {
-        todo!()
+        self.adapter_index()
     }
 }
 
@@ -890,12 +1339,19 @@ impl vr::IVRSystem020On021 for System {
 impl vr::IVRSystem019On020 for System {
     fn DriverDebugRequest(
         &self,
-        _un_device_index: vr::TrackedDeviceIndex_t,
-        _pch_request: *const std::os::raw::c_char,
-        _pch_response_buffer: *mut std::os::raw::c_char,
-        _un_response_buffer_size: u32,
+        device_index: vr::TrackedDeviceIndex_t,
+        request: *const std::os::raw::c_char,
+        response_buffer: *mut std::os::raw::c_char,
+        response_buffer_size: u32,
     ) -> u32 {
-        unimplemented!()
+        let request = (!request.is_null()).then(|| unsafe { CStr::from_ptr(request) });
+        info!("Driver debug request for device {device_index}: {request:?}");
+
+        // There are no drivers to forward the request to, so respond with an empty string.
+        if !response_buffer.is_null() && response_buffer_size > 0 {
+            unsafe { *response_buffer = 0 };
+        }
+        1
     }
 }
 
@@ -1042,24 +1498,22 @@ impl vr::IVRSystem009On011 for System {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
-    use crate::{clientcore::Injector, openxr_data::OpenXrData};
+    use crate::{
+        clientcore::Injector,
+        openxr_data::{OpenXrData, RealOpenXrData},
+    };
     use std::ffi::CStr;
     use vr::IVRSystem022_Interface;
 
     #[test]
     fn unity_required_properties() {
-        let xr = Arc::new(OpenXrData::new(&Injector::default()).unwrap());
-        let injector = Injector::default();
-        let input = Arc::new(Input::new(xr.clone()));
-        let system = System::new(xr, &injector);
-
-        system.input.set(Arc::downgrade(&input));
+        let f = Fixture::new();
 
         let test_prop = |property| {
             let mut err = vr::ETrackedPropertyError::Success;
-            let len = system.GetStringTrackedDeviceProperty(
+            let len = f.system.GetStringTrackedDeviceProperty(
                 vr::k_unTrackedDeviceIndex_Hmd,
                 property,
                 std::ptr::null_mut(),
@@ -1070,7 +1524,7 @@ mod tests {
             assert!(len > 0);
             let mut buf = vec![0; len as usize];
 
-            let len = system.GetStringTrackedDeviceProperty(
+            let len = f.system.GetStringTrackedDeviceProperty(
                 vr::k_unTrackedDeviceIndex_Hmd,
                 property,
                 buf.as_mut_ptr(),
@@ -1089,4 +1543,1125 @@ mod tests {
         test_prop(vr::ETrackedDeviceProperty::ManufacturerName_String);
         test_prop(vr::ETrackedDeviceProperty::ControllerType_String);
     }
+
+    #[test]
+    fn no_distortion() {
+        let f = Fixture::new();
+
+        for (u, v) in [(0.0, 0.0), (0.25, 0.75), (1.0, 0.5)] {
+            let mut coords = vr::DistortionCoordinates_t::default();
+            assert!(
+                f.system
+                    .ComputeDistortion(vr::EVREye::Left, u, v, &mut coords)
+            );
+            assert_eq!(coords.rfRed, [u, v]);
+            assert_eq!(coords.rfGreen, [u, v]);
+            assert_eq!(coords.rfBlue, [u, v]);
+
+            let coords =
+                vr::IVRSystem012On014::ComputeDistortion(&f.system, vr::EVREye::Right, u, v);
+            assert_eq!(coords.rfRed, [u, v]);
+            assert_eq!(coords.rfGreen, [u, v]);
+            assert_eq!(coords.rfBlue, [u, v]);
+        }
+    }
+
+    /// A system with its own instance and input, shared by the tests that need a session.
+    pub(crate) struct Fixture {
+        pub xr: Arc<RealOpenXrData>,
+        pub input: Arc<Input<crate::compositor::Compositor>>,
+        pub system: System,
+    }
+
+    impl Fixture {
+        pub fn new() -> Self {
+            let xr = Arc::new(OpenXrData::new(&Injector::default()).unwrap());
+            let input = Arc::new(Input::new(xr.clone()));
+            xr.input.set(Arc::downgrade(&input));
+            let system = System::new(xr.clone(), &Injector::default());
+            system.input.set(Arc::downgrade(&input));
+
+            Self { xr, input, system }
+        }
+
+        /// Polls events and updates input, like the start of a frame.
+        pub fn frame(&self) {
+            self.xr.poll_events();
+            self.input.frame_start_update();
+        }
+
+        /// Loads the test action manifest.
+        pub fn load_actions(&self) {
+            use vr::IVRInput010_Interface;
+
+            let manifest = concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/input_data/actions.json\0"
+            );
+            assert_eq!(
+                self.input.SetActionManifestPath(manifest.as_ptr().cast()),
+                vr::EVRInputError::None
+            );
+        }
+
+        /// Connects a vive controller in the left hand, returning its device index.
+        pub fn connect_controller(&self) -> vr::TrackedDeviceIndex_t {
+            fakexr::set_interaction_profile(
+                self.xr.session_data.get().session.as_raw(),
+                fakexr::UserPath::LeftHand,
+                self.xr
+                    .instance
+                    .string_to_path("/interaction_profiles/htc/vive_controller")
+                    .unwrap(),
+            );
+            // The controller shows up after two frames.
+            for _ in 0..2 {
+                self.frame();
+            }
+            self.input
+                .get_controller_device_index(crate::openxr_data::Hand::Left)
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn poses_cached_within_frame() {
+        let f = Fixture::new();
+        f.load_actions();
+        let index = f.connect_controller();
+        let get_poses = |origin| {
+            let mut poses = vec![vr::TrackedDevicePose_t::default(); index as usize + 1];
+            f.system.GetDeviceToAbsoluteTrackingPose(
+                origin,
+                0.0,
+                poses.as_mut_ptr(),
+                poses.len() as u32,
+            );
+            assert!(poses[index as usize].bPoseIsValid);
+        };
+        let locate_count =
+            || fakexr::locate_space_count(f.system.openxr.session_data.get().session.as_raw());
+
+        let start = locate_count();
+        get_poses(vr::ETrackingUniverseOrigin::Seated);
+        let first = locate_count();
+        assert!(first > start);
+
+        // Asking again in the same frame should reuse the poses.
+        get_poses(vr::ETrackingUniverseOrigin::Seated);
+        assert_eq!(locate_count(), first);
+
+        // Other origins are located separately, without evicting the first.
+        get_poses(vr::ETrackingUniverseOrigin::Standing);
+        let standing = locate_count();
+        assert!(standing > first);
+        get_poses(vr::ETrackingUniverseOrigin::Seated);
+        get_poses(vr::ETrackingUniverseOrigin::Standing);
+        assert_eq!(locate_count(), standing);
+
+        // A new frame needs new poses.
+        f.frame();
+        let after_frame = locate_count();
+        get_poses(vr::ETrackingUniverseOrigin::Seated);
+        assert!(locate_count() > after_frame);
+    }
+
+    #[test]
+    fn headset_removal_sends_user_interaction_events() {
+        let f = Fixture::new();
+
+        let mut event = vr::VREvent_t::default();
+        let size = std::mem::size_of_val(&event) as u32;
+        let mut drain = || {
+            let mut events = Vec::new();
+            while f.system.PollNextEvent(&mut event, size) {
+                events.push((event.eventType, event.trackedDeviceIndex));
+            }
+            events
+        };
+
+        let session = f.xr.session_data.get().session.as_raw();
+        fakexr::set_session_state(session, xr::SessionState::FOCUSED);
+        f.xr.poll_events();
+        let interaction_events = |events: Vec<(u32, u32)>| {
+            events
+                .into_iter()
+                .filter(|(ty, _)| {
+                    *ty == vr::EVREventType::TrackedDeviceUserInteractionStarted as u32
+                        || *ty == vr::EVREventType::TrackedDeviceUserInteractionEnded as u32
+                })
+                .collect::<Vec<_>>()
+        };
+        // The headset is assumed to be worn from the start.
+        assert!(interaction_events(drain()).is_empty());
+
+        fakexr::set_session_state(session, xr::SessionState::VISIBLE);
+        fakexr::set_session_state(session, xr::SessionState::SYNCHRONIZED);
+        f.xr.poll_events();
+        assert_eq!(
+            interaction_events(drain()),
+            [(
+                vr::EVREventType::TrackedDeviceUserInteractionEnded as u32,
+                vr::k_unTrackedDeviceIndex_Hmd
+            )]
+        );
+        assert_eq!(
+            f.system
+                .GetTrackedDeviceActivityLevel(vr::k_unTrackedDeviceIndex_Hmd),
+            vr::EDeviceActivityLevel::Standby
+        );
+
+        fakexr::set_session_state(session, xr::SessionState::VISIBLE);
+        fakexr::set_session_state(session, xr::SessionState::FOCUSED);
+        f.xr.poll_events();
+        assert_eq!(
+            interaction_events(drain()),
+            [(
+                vr::EVREventType::TrackedDeviceUserInteractionStarted as u32,
+                vr::k_unTrackedDeviceIndex_Hmd
+            )]
+        );
+        assert_eq!(
+            f.system
+                .GetTrackedDeviceActivityLevel(vr::k_unTrackedDeviceIndex_Hmd),
+            vr::EDeviceActivityLevel::UserInteraction
+        );
+
+        let mut err = vr::ETrackedPropertyError::Success;
+        assert!(f.system.GetBoolTrackedDeviceProperty(
+            vr::k_unTrackedDeviceIndex_Hmd,
+            vr::ETrackedDeviceProperty::ContainsProximitySensor_Bool,
+            &mut err
+        ));
+        assert_eq!(err, vr::ETrackedPropertyError::Success);
+    }
+
+    #[test]
+    fn runtime_exit_sends_quit_event() {
+        let f = Fixture::new();
+
+        let mut event = vr::VREvent_t::default();
+        let size = std::mem::size_of_val(&event) as u32;
+        f.xr.poll_events();
+        // Drain the HMD activation event
+        while f.system.PollNextEvent(&mut event, size) {
+            assert_ne!(event.eventType, vr::EVREventType::Quit as u32);
+        }
+
+        // Stopping alone doesn't mean the runtime wants us gone.
+        let session = f.xr.session_data.get().session.as_raw();
+        fakexr::set_session_state(session, xr::SessionState::STOPPING);
+        f.xr.poll_events();
+        assert!(!f.system.PollNextEvent(&mut event, size));
+
+        fakexr::set_session_state(session, xr::SessionState::EXITING);
+        f.xr.poll_events();
+
+        assert!(f.system.PollNextEvent(&mut event, size));
+        assert_eq!(event.eventType, vr::EVREventType::Quit as u32);
+        assert_eq!(event.trackedDeviceIndex, vr::k_unTrackedDeviceIndexInvalid);
+        // Only one quit event is sent per exit.
+        assert!(!f.system.PollNextEvent(&mut event, size));
+
+        f.system.AcknowledgeQuit_Exiting();
+    }
+
+    #[test]
+    fn acknowledge_quit_ends_session() {
+        let f = Fixture::new();
+        f.xr.poll_events();
+        assert_ne!(f.xr.session_data.get().state, xr::SessionState::EXITING);
+
+        f.system.AcknowledgeQuit_Exiting();
+        assert_eq!(f.xr.session_data.get().state, xr::SessionState::EXITING);
+        // Acknowledging again (or shutting down) doesn't try to end it twice.
+        f.system.AcknowledgeQuit_Exiting();
+    }
+
+    #[test]
+    fn steamvr_not_drawing_controllers() {
+        let f = Fixture::new();
+
+        assert!(!f.system.IsSteamVRDrawingControllers());
+        assert!(!f.system.IsSteamVRDrawingControllers());
+    }
+
+    #[test]
+    fn seated_poses_follow_recenter() {
+        let f = Fixture::new();
+
+        let (yaw_sin, yaw_cos) = (45_f32.to_radians() / 2.0).sin_cos();
+        let head = xr::Posef {
+            position: xr::Vector3f {
+                x: 1.0,
+                y: 1.5,
+                z: -2.0,
+            },
+            orientation: xr::Quaternionf {
+                x: 0.0,
+                y: yaw_sin,
+                z: 0.0,
+                w: yaw_cos,
+            },
+        };
+        fakexr::set_view(f.xr.session_data.get().session.as_raw(), head);
+        f.system.ResetSeatedZeroPose();
+
+        let mut pose = vr::TrackedDevicePose_t::default();
+        f.system.GetDeviceToAbsoluteTrackingPose(
+            vr::ETrackingUniverseOrigin::Seated,
+            0.0,
+            &mut pose,
+            1,
+        );
+        assert!(pose.bPoseIsValid);
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ];
+        for (actual, expected) in pose.mDeviceToAbsoluteTracking.m.iter().zip(identity) {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!(
+                    (a - e).abs() < 1e-5,
+                    "{:?}",
+                    pose.mDeviceToAbsoluteTracking.m
+                );
+            }
+        }
+
+        let zero = f.system.GetSeatedZeroPoseToStandingAbsoluteTrackingPose();
+        let expected: vr::HmdMatrix34_t = head.into();
+        for (actual, expected) in zero.m.iter().zip(expected.m) {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!((a - e).abs() < 1e-5, "{:?}", zero.m);
+            }
+        }
+    }
+
+    #[test]
+    fn reset_zero_pose_sends_event() {
+        let f = Fixture::new();
+
+        let mut event = vr::VREvent_t::default();
+        let size = std::mem::size_of_val(&event) as u32;
+        let mut zero_pose_events = || {
+            let mut events = Vec::new();
+            while f.system.PollNextEvent(&mut event, size) {
+                if event.eventType == vr::EVREventType::SeatedZeroPoseReset as u32
+                    || event.eventType == vr::EVREventType::StandingZeroPoseReset as u32
+                {
+                    let universe = unsafe { event.data.chaperone.m_nCurrentUniverse };
+                    events.push((event.eventType, universe));
+                }
+            }
+            events
+        };
+        assert!(zero_pose_events().is_empty());
+
+        f.system.ResetSeatedZeroPose();
+        assert_eq!(
+            zero_pose_events(),
+            [(
+                vr::EVREventType::SeatedZeroPoseReset as u32,
+                vr::ETrackingUniverseOrigin::Seated as u64
+            )]
+        );
+
+        f.xr.reset_tracking_space(vr::ETrackingUniverseOrigin::Standing);
+        assert_eq!(
+            zero_pose_events(),
+            [(
+                vr::EVREventType::StandingZeroPoseReset as u32,
+                vr::ETrackingUniverseOrigin::Standing as u64
+            )]
+        );
+    }
+
+    #[test]
+    fn raw_and_uncalibrated_poses() {
+        let f = Fixture::new();
+
+        let head = xr::Posef {
+            position: xr::Vector3f {
+                x: 0.5,
+                y: 1.7,
+                z: 0.25,
+            },
+            orientation: xr::Quaternionf::IDENTITY,
+        };
+        fakexr::set_view(f.xr.session_data.get().session.as_raw(), head);
+        // Recentering shouldn't affect the raw space
+        f.system.ResetSeatedZeroPose();
+
+        let mut pose = vr::TrackedDevicePose_t::default();
+        f.system.GetDeviceToAbsoluteTrackingPose(
+            vr::ETrackingUniverseOrigin::RawAndUncalibrated,
+            0.0,
+            &mut pose,
+            1,
+        );
+        assert!(pose.bPoseIsValid);
+        let expected: vr::HmdMatrix34_t = head.into();
+        for (actual, expected) in pose.mDeviceToAbsoluteTracking.m.iter().zip(expected.m) {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!(
+                    (a - e).abs() < 1e-5,
+                    "{:?}",
+                    pose.mDeviceToAbsoluteTracking.m
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn apply_transform() {
+        let f = Fixture::new();
+
+        let pose = vr::TrackedDevicePose_t {
+            mDeviceToAbsoluteTracking: vr::HmdMatrix34_t {
+                m: [
+                    [1.0, 0.0, 0.0, 1.0],
+                    [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                ],
+            },
+            vVelocity: vr::HmdVector3_t { v: [1.0, 0.0, 0.0] },
+            vAngularVelocity: vr::HmdVector3_t { v: [0.0, 0.0, 2.0] },
+            bPoseIsValid: true,
+            ..Default::default()
+        };
+        // 90 degrees around Y, then up by one meter
+        let transform = vr::HmdMatrix34_t {
+            m: [
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0, 1.0],
+                [-1.0, 0.0, 0.0, 0.0],
+            ],
+        };
+
+        let mut output = vr::TrackedDevicePose_t::default();
+        f.system.ApplyTransform(&mut output, &pose, &transform);
+
+        let expected = [
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [-1.0, 0.0, 0.0, -1.0],
+        ];
+        for (actual, expected) in output.mDeviceToAbsoluteTracking.m.iter().zip(expected) {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!(
+                    (a - e).abs() < 1e-6,
+                    "{:?}",
+                    output.mDeviceToAbsoluteTracking.m
+                );
+            }
+        }
+        let assert_vec = |actual: vr::HmdVector3_t, expected: [f32; 3]| {
+            for (a, e) in actual.v.iter().zip(expected) {
+                assert!((a - e).abs() < 1e-6, "{:?} != {expected:?}", actual.v);
+            }
+        };
+        // Velocities are only rotated
+        assert_vec(output.vVelocity, [0.0, 0.0, -1.0]);
+        assert_vec(output.vAngularVelocity, [2.0, 0.0, 0.0]);
+        assert!(output.bPoseIsValid);
+
+        // Transforming in place works too
+        let mut in_place = pose;
+        let in_place_ptr = &raw mut in_place;
+        f.system
+            .ApplyTransform(in_place_ptr, in_place_ptr, &transform);
+        assert_eq!(
+            in_place.mDeviceToAbsoluteTracking.m,
+            output.mDeviceToAbsoluteTracking.m
+        );
+    }
+
+    #[test]
+    fn button_and_axis_names() {
+        let f = Fixture::new();
+
+        let button_name =
+            |id| unsafe { CStr::from_ptr(f.system.GetButtonIdNameFromEnum(id)) }.to_owned();
+        assert_eq!(button_name(vr::EVRButtonId::System), c"k_EButton_System");
+        assert_eq!(
+            button_name(vr::EVRButtonId::SteamVR_Trigger),
+            c"k_EButton_SteamVR_Trigger"
+        );
+        assert_eq!(
+            button_name(vr::EVRButtonId::Axis0),
+            c"k_EButton_SteamVR_Touchpad"
+        );
+        assert_eq!(button_name(vr::EVRButtonId::Reserved0), c"Unknown");
+
+        let axis_name = |ty| {
+            unsafe { CStr::from_ptr(f.system.GetControllerAxisTypeNameFromEnum(ty)) }.to_owned()
+        };
+        assert_eq!(axis_name(vr::EVRControllerAxisType::Joystick), c"joystick");
+        assert_eq!(axis_name(vr::EVRControllerAxisType::Trigger), c"trigger");
+        assert_eq!(axis_name(vr::EVRControllerAxisType::TrackPad), c"trackpad");
+    }
+
+    #[test]
+    fn controller_axis_type_array() {
+        let f = Fixture::new();
+        f.load_actions();
+        let index = f.connect_controller();
+
+        let mut axis_type = -1i32;
+        let mut err = vr::ETrackedPropertyError::Success;
+        let size = f.system.GetArrayTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::Axis0Type_Int32,
+            vr::k_unInt32PropertyTag,
+            (&raw mut axis_type).cast(),
+            0,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::BufferTooSmall);
+        assert_eq!(size, size_of::<i32>() as u32);
+
+        let size = f.system.GetArrayTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::Axis0Type_Int32,
+            vr::k_unInt32PropertyTag,
+            (&raw mut axis_type).cast(),
+            size,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::Success);
+        assert_eq!(size, size_of::<i32>() as u32);
+        assert_eq!(axis_type, vr::EVRControllerAxisType::TrackPad as i32);
+
+        f.system.GetArrayTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::Axis0Type_Int32,
+            vr::k_unStringPropertyTag,
+            (&raw mut axis_type).cast(),
+            size,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::WrongDataType);
+    }
+
+    #[test]
+    fn controller_battery_status() {
+        let f = Fixture::new();
+        f.load_actions();
+        let index = f.connect_controller();
+
+        // There's no real battery status, so controllers report being fully charged.
+        let mut err = vr::ETrackedPropertyError::UnknownProperty;
+        let battery = f.system.GetFloatTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::DeviceBatteryPercentage_Float,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::Success);
+        assert_eq!(battery, 1.0);
+
+        let mut err = vr::ETrackedPropertyError::UnknownProperty;
+        let charging = f.system.GetBoolTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::DeviceIsCharging_Bool,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::Success);
+        assert!(!charging);
+
+        let get_bool = |index, prop| {
+            let mut err = vr::ETrackedPropertyError::UnknownProperty;
+            let value = f.system.GetBoolTrackedDeviceProperty(index, prop, &mut err);
+            assert_eq!(
+                err,
+                vr::ETrackedPropertyError::Success,
+                "{prop:?} ({index})"
+            );
+            value
+        };
+        let provides_battery = vr::ETrackedDeviceProperty::DeviceProvidesBatteryStatus_Bool;
+        assert!(get_bool(index, provides_battery));
+        assert!(!get_bool(vr::k_unTrackedDeviceIndex_Hmd, provides_battery));
+        let can_power_off = vr::ETrackedDeviceProperty::DeviceCanPowerOff_Bool;
+        assert!(get_bool(index, can_power_off));
+        assert!(!get_bool(vr::k_unTrackedDeviceIndex_Hmd, can_power_off));
+        let update_available = vr::ETrackedDeviceProperty::Firmware_UpdateAvailable_Bool;
+        assert!(!get_bool(index, update_available));
+        assert!(!get_bool(vr::k_unTrackedDeviceIndex_Hmd, update_available));
+
+        let mut err = vr::ETrackedPropertyError::Success;
+        f.system.GetBoolTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::HasCamera_Bool,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::UnknownProperty);
+
+        // Disconnected devices have no properties.
+        let mut err = vr::ETrackedPropertyError::Success;
+        f.system.GetFloatTrackedDeviceProperty(
+            index + 1,
+            vr::ETrackedDeviceProperty::DeviceBatteryPercentage_Float,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::InvalidDevice);
+    }
+
+    #[test]
+    fn output_device_d3d_luid() {
+        let f = Fixture::new();
+
+        let expected = u64::from_ne_bytes(fakexr::vulkan::DEVICE_LUID);
+        for texture_type in [vr::ETextureType::DirectX, vr::ETextureType::DirectX12] {
+            let mut device = 0;
+            f.system
+                .GetOutputDevice(&mut device, texture_type, std::ptr::null_mut());
+            assert_ne!(device, 0, "{texture_type:?}");
+            assert_eq!(device, expected, "{texture_type:?}");
+        }
+    }
+
+    #[test]
+    fn d3d_adapter_index() {
+        let f = Fixture::new();
+
+        // The fake runtime's device is the second one.
+        let mut adapter_index = -1;
+        f.system.GetDXGIOutputInfo(&mut adapter_index);
+        assert_eq!(adapter_index, 1);
+        assert_eq!(f.system.GetD3D9AdapterIndex(), 1);
+    }
+
+    #[test]
+    fn unknown_properties_recorded_once() {
+        let f = Fixture::new();
+        f.load_actions();
+        let index = f.connect_controller();
+        let hmd = vr::k_unTrackedDeviceIndex_Hmd;
+
+        for _ in 0..2 {
+            let mut err = vr::ETrackedPropertyError::Success;
+            f.system.GetBoolTrackedDeviceProperty(
+                index,
+                vr::ETrackedDeviceProperty::HasCamera_Bool,
+                &mut err,
+            );
+            assert_eq!(err, vr::ETrackedPropertyError::UnknownProperty);
+
+            let mut err = vr::ETrackedPropertyError::Success;
+            f.system.GetMatrix34TrackedDeviceProperty(
+                hmd,
+                vr::ETrackedDeviceProperty::StatusDisplayTransform_Matrix34,
+                &mut err,
+            );
+            assert_eq!(err, vr::ETrackedPropertyError::UnknownProperty);
+
+            let mut err = vr::ETrackedPropertyError::Success;
+            f.system.GetFloatTrackedDeviceProperty(
+                hmd,
+                vr::ETrackedDeviceProperty::DisplayGCBlackClamp_Float,
+                &mut err,
+            );
+            assert_eq!(err, vr::ETrackedPropertyError::UnknownProperty);
+        }
+
+        // Known properties aren't recorded.
+        let mut err = vr::ETrackedPropertyError::UnknownProperty;
+        f.system.GetFloatTrackedDeviceProperty(
+            index,
+            vr::ETrackedDeviceProperty::DeviceBatteryPercentage_Float,
+            &mut err,
+        );
+        assert_eq!(err, vr::ETrackedPropertyError::Success);
+
+        let expected = HashSet::from([
+            (index, vr::ETrackedDeviceProperty::HasCamera_Bool),
+            (
+                hmd,
+                vr::ETrackedDeviceProperty::StatusDisplayTransform_Matrix34,
+            ),
+            (hmd, vr::ETrackedDeviceProperty::DisplayGCBlackClamp_Float),
+        ]);
+        assert_eq!(f.system.unknown_properties(), expected);
+    }
+
+    #[test]
+    fn sorted_controllers_right_to_left() {
+        use vr::IVRInput010_Interface;
+
+        let f = Fixture::new();
+        f.load_actions();
+        let left = f.connect_controller();
+        let session = f.xr.session_data.get().session.as_raw();
+        fakexr::set_interaction_profile(
+            session,
+            fakexr::UserPath::RightHand,
+            f.xr.instance
+                .string_to_path("/interaction_profiles/htc/vive_controller")
+                .unwrap(),
+        );
+        f.frame();
+        f.frame();
+        let right = f
+            .input
+            .get_controller_device_index(crate::openxr_data::Hand::Right)
+            .unwrap();
+        // Sorting by index alone would put the left controller first.
+        assert!(left < right);
+
+        let at_x = |x| xr::Posef {
+            position: xr::Vector3f { x, y: 1.0, z: 0.0 },
+            orientation: xr::Quaternionf::IDENTITY,
+        };
+        fakexr::set_grip(session, fakexr::UserPath::LeftHand, at_x(-0.3));
+        fakexr::set_grip(session, fakexr::UserPath::RightHand, at_x(0.3));
+
+        let mut set = 0;
+        assert_eq!(
+            f.input
+                .GetActionSetHandle(c"/actions/set1".as_ptr(), &mut set),
+            vr::EVRInputError::None
+        );
+        let sync = || {
+            f.frame();
+            let mut active = vr::VRActiveActionSet_t {
+                ulActionSet: set,
+                ..Default::default()
+            };
+            assert_eq!(
+                f.input.UpdateActionState(
+                    &mut active,
+                    std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
+                    1
+                ),
+                vr::EVRInputError::None
+            );
+        };
+        let get_sorted = |relative_to| {
+            let mut indices = [vr::k_unTrackedDeviceIndexInvalid; 2];
+            let count = f.system.GetSortedTrackedDeviceIndicesOfClass(
+                vr::ETrackedDeviceClass::Controller,
+                indices.as_mut_ptr(),
+                indices.len() as u32,
+                relative_to,
+            );
+            assert_eq!(count, 2);
+            indices
+        };
+
+        // Facing forward, the right controller is on the right.
+        sync();
+        assert_eq!(get_sorted(vr::k_unTrackedDeviceIndex_Hmd), [right, left]);
+        assert_eq!(get_sorted(vr::k_unTrackedDeviceIndexInvalid), [right, left]);
+
+        // Turning around swaps them from the headset's point of view, but not the tracking
+        // space's.
+        fakexr::set_view(
+            session,
+            xr::Posef {
+                position: xr::Vector3f {
+                    x: 0.0,
+                    y: 1.5,
+                    z: 0.0,
+                },
+                orientation: xr::Quaternionf {
+                    x: 0.0,
+                    y: 1.0,
+                    z: 0.0,
+                    w: 0.0,
+                },
+            },
+        );
+        sync();
+        assert_eq!(get_sorted(vr::k_unTrackedDeviceIndex_Hmd), [left, right]);
+        assert_eq!(get_sorted(vr::k_unTrackedDeviceIndexInvalid), [right, left]);
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "monado"), ignore)]
+    fn sorted_generic_trackers() {
+        let f = Fixture::new();
+        f.xr.set_tracking_space(vr::ETrackingUniverseOrigin::Seated);
+        f.load_actions();
+        let session = f.xr.session_data.get().session.as_raw();
+        fakexr::add_trackers(session, &[c"FAKEXR-TRACKER"; 2]);
+        // Trackers are created alongside controllers.
+        f.connect_controller();
+
+        let get_sorted = |class, relative_to| {
+            let mut indices = [vr::k_unTrackedDeviceIndexInvalid; 4];
+            let count = f.system.GetSortedTrackedDeviceIndicesOfClass(
+                class,
+                indices.as_mut_ptr(),
+                indices.len() as u32,
+                relative_to,
+            );
+            indices[..count as usize].to_vec()
+        };
+
+        let trackers = get_sorted(
+            vr::ETrackedDeviceClass::GenericTracker,
+            vr::k_unTrackedDeviceIndexInvalid,
+        );
+        assert_eq!(trackers, [2, 3]);
+        assert_eq!(
+            get_sorted(
+                vr::ETrackedDeviceClass::GenericTracker,
+                vr::k_unTrackedDeviceIndex_Hmd
+            ),
+            trackers
+        );
+        assert_eq!(
+            get_sorted(
+                vr::ETrackedDeviceClass::HMD,
+                vr::k_unTrackedDeviceIndexInvalid
+            ),
+            [vr::k_unTrackedDeviceIndex_Hmd]
+        );
+        assert_eq!(
+            get_sorted(
+                vr::ETrackedDeviceClass::Controller,
+                vr::k_unTrackedDeviceIndexInvalid
+            ),
+            [1]
+        );
+
+        // Too small of a buffer reports the required size without writing anything.
+        let mut index = vr::k_unTrackedDeviceIndexInvalid;
+        assert_eq!(
+            f.system.GetSortedTrackedDeviceIndicesOfClass(
+                vr::ETrackedDeviceClass::GenericTracker,
+                &mut index,
+                1,
+                vr::k_unTrackedDeviceIndexInvalid
+            ),
+            2
+        );
+        assert_eq!(index, vr::k_unTrackedDeviceIndexInvalid);
+    }
+
+    #[test]
+    fn hidden_area_mesh_cached() {
+        let f = Fixture::new();
+        let get_mesh = || {
+            f.system
+                .GetHiddenAreaMesh(vr::EVREye::Left, vr::EHiddenAreaMeshType::Standard)
+        };
+        let vertices = |mesh: vr::HiddenAreaMesh_t| unsafe {
+            std::slice::from_raw_parts(mesh.pVertexData, mesh.unTriangleCount as usize * 3)
+                .iter()
+                .map(|v| v.v)
+                .collect::<Vec<_>>()
+        };
+
+        let mesh = get_mesh();
+        assert_eq!(mesh.unTriangleCount, 2);
+        let expected = vertices(mesh);
+        for _ in 0..5 {
+            let again = get_mesh();
+            assert_eq!(again.pVertexData, mesh.pVertexData);
+            assert_eq!(again.unTriangleCount, mesh.unTriangleCount);
+            assert_eq!(vertices(again), expected);
+        }
+
+        // Each eye and mesh type gets its own buffer.
+        let right = f
+            .system
+            .GetHiddenAreaMesh(vr::EVREye::Right, vr::EHiddenAreaMeshType::Standard);
+        let inverse = f
+            .system
+            .GetHiddenAreaMesh(vr::EVREye::Left, vr::EHiddenAreaMeshType::Inverse);
+        assert_ne!(right.pVertexData, mesh.pVertexData);
+        assert_ne!(inverse.pVertexData, mesh.pVertexData);
+        assert_eq!(f.system.hidden_area_meshes.lock().unwrap().meshes.len(), 3);
+
+        // The old mesh must stay alive after the mask changes, since the app may still be using it.
+        // A new mask with the same number of vertices is written into the same buffer.
+        f.xr.session_data
+            .get()
+            .system_data
+            .invalidate_hidden_area_meshes();
+        let new_mesh = get_mesh();
+        assert_eq!(new_mesh.pVertexData, mesh.pVertexData);
+        assert_eq!(vertices(mesh), expected);
+        for _ in 0..5 {
+            f.xr.session_data
+                .get()
+                .system_data
+                .invalidate_hidden_area_meshes();
+            get_mesh();
+        }
+        let meshes = f.system.hidden_area_meshes.lock().unwrap();
+        assert_eq!(meshes.meshes.len(), 3);
+        assert!(meshes.meshes.values().all(|mesh| mesh.buffers.len() == 1));
+    }
+
+    #[test]
+    fn hidden_area_mesh_line_loop() {
+        let f = Fixture::new();
+
+        // The faked mask is a square, which is 4 vertices as a line loop and 2 triangles otherwise.
+        let mesh = f
+            .system
+            .GetHiddenAreaMesh(vr::EVREye::Left, vr::EHiddenAreaMeshType::LineLoop);
+        assert!(!mesh.pVertexData.is_null());
+        assert_eq!(mesh.unTriangleCount, 4);
+        let vertices =
+            unsafe { std::slice::from_raw_parts(mesh.pVertexData, mesh.unTriangleCount as usize) };
+        let unique: std::collections::HashSet<_> =
+            vertices.iter().map(|v| v.v.map(f32::to_bits)).collect();
+        assert_eq!(unique.len(), 4);
+
+        // Cached meshes keep the same interpretation.
+        let mesh = f
+            .system
+            .GetHiddenAreaMesh(vr::EVREye::Left, vr::EHiddenAreaMeshType::LineLoop);
+        assert_eq!(mesh.unTriangleCount, 4);
+
+        let mesh = f
+            .system
+            .GetHiddenAreaMesh(vr::EVREye::Left, vr::EHiddenAreaMeshType::Inverse);
+        assert_eq!(mesh.unTriangleCount, 2);
+    }
+
+    /// Eye views like a wide FOV headset's, with displays angled outwards and more FOV towards the
+    /// outside.
+    fn canted_eye_views() -> [(xr::Posef, xr::Fovf); 2] {
+        let cant = 10.0_f32.to_radians();
+        let eye_view = |sign: f32| {
+            let rot = Quat::from_rotation_y(-sign * cant);
+            let pose = xr::Posef {
+                position: xr::Vector3f {
+                    x: sign * 0.032,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                orientation: xr::Quaternionf {
+                    x: rot.x,
+                    y: rot.y,
+                    z: rot.z,
+                    w: rot.w,
+                },
+            };
+            let (outer, inner) = (70.0_f32.to_radians(), 40.0_f32.to_radians());
+            let fov = xr::Fovf {
+                angle_left: if sign < 0.0 { -outer } else { -inner },
+                angle_right: if sign < 0.0 { inner } else { outer },
+                angle_up: 45.0_f32.to_radians(),
+                angle_down: -55.0_f32.to_radians(),
+            };
+            (pose, fov)
+        };
+        [eye_view(-1.0), eye_view(1.0)]
+    }
+
+    /// Checks the projection and eye to head transform of each eye against the given views, with the
+    /// eye rotation expected to be `rotation(view_orientation)`.
+    fn check_eye_views(
+        system: &System,
+        views: [(xr::Posef, xr::Fovf); 2],
+        rotation: impl Fn(Quat) -> Quat,
+    ) {
+        for (eye, (pose, fov)) in [vr::EVREye::Left, vr::EVREye::Right].into_iter().zip(views) {
+            let [mut left, mut right, mut top, mut bottom] = [0.0; 4];
+            system.GetProjectionRaw(eye, &mut left, &mut right, &mut top, &mut bottom);
+            assert!((left - fov.angle_left.tan()).abs() < 1e-6);
+            assert!((right - fov.angle_right.tan()).abs() < 1e-6);
+            assert!((top - fov.angle_down.tan()).abs() < 1e-6);
+            assert!((bottom - fov.angle_up.tan()).abs() < 1e-6);
+
+            let proj = system.GetProjectionMatrix(eye, 0.1, 100.0);
+            let (l, r) = (fov.angle_left.tan(), fov.angle_right.tan());
+            let (u, d) = (fov.angle_up.tan(), fov.angle_down.tan());
+            assert!((proj.m[0][2] - (r + l) / (r - l)).abs() < 1e-5);
+            assert!((proj.m[1][2] - (u + d) / (u - d)).abs() < 1e-5);
+            // The frustum is off center towards the outside of each eye.
+            assert_eq!(proj.m[0][2].signum(), pose.position.x.signum());
+
+            let eye_to_head = system.GetEyeToHeadTransform(eye);
+            let expected = Mat3::from_quat(rotation(Quat::from_xyzw(
+                pose.orientation.x,
+                pose.orientation.y,
+                pose.orientation.z,
+                pose.orientation.w,
+            )));
+            for row in 0..3 {
+                for col in 0..3 {
+                    assert!(
+                        (eye_to_head.m[row][col] - expected.col(col)[row]).abs() < 1e-6,
+                        "{eye:?} eye to head: {:?}",
+                        eye_to_head.m
+                    );
+                }
+            }
+            assert!((eye_to_head.m[0][3] - pose.position.x).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn canted_views_made_parallel() {
+        let f = Fixture::new();
+
+        let views = canted_eye_views();
+        fakexr::set_eye_views(f.xr.session_data.get().session.as_raw(), views);
+
+        // The FOVs are kept, but the cant is removed.
+        check_eye_views(&f.system, views, |_| Quat::IDENTITY);
+    }
+
+    #[test]
+    fn canted_views_passed_through() {
+        let mut f = Fixture::new();
+        f.system.canted_views = true;
+
+        let views = canted_eye_views();
+        fakexr::set_eye_views(f.xr.session_data.get().session.as_raw(), views);
+
+        // The cant is carried by the eye to head transform.
+        check_eye_views(&f.system, views, |rot| rot);
+    }
+
+    #[test]
+    fn render_target_scale() {
+        use crate::settings::Settings;
+        use vr::IVRSettings003_Interface;
+
+        let f = Fixture::new();
+        let settings = Settings::new(f.xr.clone());
+
+        let get_size = || {
+            let (mut width, mut height) = (0, 0);
+            f.system
+                .GetRecommendedRenderTargetSize(&mut width, &mut height);
+            (width, height)
+        };
+        let (width, height) = fakexr::RECOMMENDED_VIEW_SIZE;
+        assert_eq!(get_size(), (width, height));
+
+        let mut err = vr::EVRSettingsError::None;
+        settings.SetFloat(
+            c"steamvr".as_ptr(),
+            c"supersampleScale".as_ptr(),
+            1.5,
+            &mut err,
+        );
+        assert_eq!(err, vr::EVRSettingsError::None);
+        assert_eq!(
+            get_size(),
+            (
+                (width as f32 * 1.5).round() as u32,
+                (height as f32 * 1.5).round() as u32
+            )
+        );
+
+        // The size can't go past what the runtime supports.
+        f.xr.set_render_target_scale(4.0);
+        assert_eq!(get_size(), fakexr::MAX_VIEW_SIZE);
+    }
+
+    #[test]
+    fn refresh_rate() {
+        use crate::settings::Settings;
+        use vr::IVRSettings003_Interface;
+
+        let f = Fixture::new();
+        let settings = Settings::new(f.xr.clone());
+
+        let get_rate = || {
+            let mut err = vr::ETrackedPropertyError::Success;
+            let rate = f.system.GetFloatTrackedDeviceProperty(
+                vr::k_unTrackedDeviceIndex_Hmd,
+                vr::ETrackedDeviceProperty::DisplayFrequency_Float,
+                &mut err,
+            );
+            assert_eq!(err, vr::ETrackedPropertyError::Success);
+            rate
+        };
+        assert_eq!(get_rate(), 90.0);
+
+        // Unsupported rates should snap to the closest supported one.
+        assert_eq!(f.xr.request_refresh_rate(118.0), Some(120.0));
+        assert_eq!(get_rate(), 120.0);
+
+        let mut err = vr::EVRSettingsError::None;
+        settings.SetFloat(
+            c"steamvr".as_ptr(),
+            c"preferredRefreshRate".as_ptr(),
+            72.0,
+            &mut err,
+        );
+        assert_eq!(err, vr::EVRSettingsError::None);
+        assert_eq!(get_rate(), 72.0);
+        assert_eq!(
+            settings.GetFloat(
+                c"steamvr".as_ptr(),
+                c"preferredRefreshRate".as_ptr(),
+                &mut err
+            ),
+            72.0
+        );
+    }
+
+    #[test]
+    fn driver_debug_request() {
+        let f = Fixture::new();
+
+        let mut buf = [b'x' as std::os::raw::c_char; 16];
+        let len = f.system.DriverDebugRequest(
+            vr::k_unTrackedDeviceIndex_Hmd,
+            c"debug request".as_ptr(),
+            buf.as_mut_ptr(),
+            buf.len() as u32,
+        );
+        assert_eq!(len, 1);
+        assert_eq!(buf[0], 0);
+        assert_eq!(buf[1], b'x' as std::os::raw::c_char);
+
+        assert_eq!(
+            f.system.DriverDebugRequest(
+                vr::k_unTrackedDeviceIndex_Hmd,
+                c"debug request".as_ptr(),
+                std::ptr::null_mut(),
+                0
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn app_container_file_paths() {
+        use vr::IVRInput011_Interface;
+
+        let f = Fixture::new();
+
+        let get_paths = || {
+            let len = f.system.GetAppContainerFilePaths(std::ptr::null_mut(), 0);
+            let mut buf = vec![0; len as usize];
+            assert_eq!(
+                f.system
+                    .GetAppContainerFilePaths(buf.as_mut_ptr(), buf.len() as u32),
+                len
+            );
+            buf
+        };
+        assert!(get_paths().is_empty());
+
+        let data_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/input_data");
+        let manifest = CString::new(data_dir.join("actions.json").to_str().unwrap()).unwrap();
+        assert_eq!(
+            f.input.SetActionManifestPath(manifest.as_ptr()),
+            vr::EVRInputError::None
+        );
+
+        let buf = get_paths();
+        let slice = unsafe { std::slice::from_raw_parts(buf.as_ptr().cast(), buf.len()) };
+        let paths = CStr::from_bytes_with_nul(slice).unwrap().to_str().unwrap();
+        assert!(
+            paths
+                .split(';')
+                .any(|path| std::path::Path::new(path) == data_dir),
+            "{paths}"
+        );
+    }
 }